@@ -67,3 +67,25 @@ pub fn try_next_enumerated_account<'a, 'b>(
 
     Ok((index, account_info))
 }
+
+/// Like [try_next_enumerated_account], but returns `None` if the next account
+/// is the program ID, which is the placeholder for an omitted optional account.
+/// The account options are only checked if the account is present.
+#[inline(always)]
+pub fn try_next_optional_enumerated_account<'a, 'b>(
+    accounts_iter: &mut EnumeratedAccountInfoIter<'a, 'b>,
+    program_id: &Pubkey,
+    opts: NextAccountOptions,
+) -> Result<Option<(usize, &'a AccountInfo<'b>)>, ProgramError> {
+    let (_, account_info) = accounts_iter
+        .clone()
+        .next()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if account_info.key == program_id {
+        accounts_iter.next();
+        return Ok(None);
+    }
+
+    try_next_enumerated_account(accounts_iter, opts).map(Some)
+}
@@ -0,0 +1,291 @@
+use std::{fmt::Display, str::FromStr};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
+
+/// Returned when parsing a decimal amount fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseAmountError {
+    /// The string is not a non-negative decimal number.
+    InvalidFormat,
+    /// The string has more fractional digits than the amount's decimals.
+    TooManyDecimals,
+    /// The amount does not fit in base units.
+    Overflow,
+}
+
+impl Display for ParseAmountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "Invalid amount format"),
+            Self::TooManyDecimals => write!(f, "Too many decimal places"),
+            Self::Overflow => write!(f, "Amount overflows"),
+        }
+    }
+}
+
+impl std::error::Error for ParseAmountError {}
+
+/// Define a token amount denominated in base units with checked arithmetic.
+/// Amounts of different tokens are distinct types, so adding lamports to 2Z
+/// tokens (or passing one where the other is expected) does not compile.
+macro_rules! define_amount {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $decimals:literal, $symbol:literal
+    ) => {
+        $(#[$meta])*
+        #[derive(
+            Debug,
+            Clone,
+            Copy,
+            Default,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+            Pod,
+            Zeroable,
+            BorshDeserialize,
+            BorshSerialize,
+        )]
+        #[repr(transparent)]
+        pub struct $name(u64);
+
+        impl $name {
+            pub const DECIMALS: u8 = $decimals;
+            pub const SYMBOL: &'static str = $symbol;
+
+            /// Base units in one whole token.
+            pub const ONE: Self = Self(10u64.pow($decimals));
+
+            pub const ZERO: Self = Self(0);
+            pub const MAX: Self = Self(u64::MAX);
+
+            pub const fn new(base_units: u64) -> Self {
+                Self(base_units)
+            }
+
+            pub const fn get(self) -> u64 {
+                self.0
+            }
+
+            pub const fn is_zero(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Convert a number of whole tokens to base units.
+            pub const fn checked_from_whole(whole: u64) -> Option<Self> {
+                match whole.checked_mul(Self::ONE.0) {
+                    Some(base_units) => Some(Self(base_units)),
+                    None => None,
+                }
+            }
+
+            pub const fn checked_add(self, other: Self) -> Option<Self> {
+                match self.0.checked_add(other.0) {
+                    Some(base_units) => Some(Self(base_units)),
+                    None => None,
+                }
+            }
+
+            pub const fn checked_sub(self, other: Self) -> Option<Self> {
+                match self.0.checked_sub(other.0) {
+                    Some(base_units) => Some(Self(base_units)),
+                    None => None,
+                }
+            }
+
+            pub const fn saturating_add(self, other: Self) -> Self {
+                Self(self.0.saturating_add(other.0))
+            }
+
+            pub const fn saturating_sub(self, other: Self) -> Self {
+                Self(self.0.saturating_sub(other.0))
+            }
+
+            pub const fn checked_mul(self, factor: u64) -> Option<Self> {
+                match self.0.checked_mul(factor) {
+                    Some(base_units) => Some(Self(base_units)),
+                    None => None,
+                }
+            }
+
+            pub const fn checked_div(self, divisor: u64) -> Option<Self> {
+                match self.0.checked_div(divisor) {
+                    Some(base_units) => Some(Self(base_units)),
+                    None => None,
+                }
+            }
+
+            /// Split this amount into whole tokens and the remaining base
+            /// units.
+            pub const fn whole_and_fraction(self) -> (u64, u64) {
+                (self.0 / Self::ONE.0, self.0 % Self::ONE.0)
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(amount: $name) -> Self {
+                amount.0
+            }
+        }
+
+        /// Formats the amount as a decimal number of whole tokens followed by
+        /// the token symbol (e.g. "1.500000000 SOL").
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let (whole, fraction) = self.whole_and_fraction();
+                write!(
+                    f,
+                    "{whole}.{fraction:0width$} {}",
+                    Self::SYMBOL,
+                    width = usize::from(Self::DECIMALS)
+                )
+            }
+        }
+
+        /// Parses a decimal number of whole tokens (e.g. "1.5"). The token
+        /// symbol is not expected.
+        impl FromStr for $name {
+            type Err = ParseAmountError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_base_units(s, Self::DECIMALS).map(Self)
+            }
+        }
+    };
+}
+
+define_amount!(
+    /// Amount of SOL in lamports.
+    Lamports,
+    9,
+    "SOL"
+);
+
+define_amount!(
+    /// Amount of 2Z tokens in base units of the 2Z mint.
+    Token2z,
+    8,
+    "2Z"
+);
+
+fn parse_base_units(s: &str, decimals: u8) -> Result<u64, ParseAmountError> {
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+
+    let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(ParseAmountError::InvalidFormat);
+    }
+
+    let decimals = usize::from(decimals);
+
+    if fraction.len() > decimals {
+        return Err(ParseAmountError::TooManyDecimals);
+    }
+
+    // Both parts are only digits, so parsing can only fail on overflow.
+    let parse_part = |part: &str| {
+        if part.is_empty() {
+            Ok(0)
+        } else {
+            part.parse::<u64>().map_err(|_| ParseAmountError::Overflow)
+        }
+    };
+
+    // The fraction has at most as many digits as the decimals, so scaling it
+    // cannot overflow.
+    let fraction_base_units = parse_part(fraction)? * 10u64.pow((decimals - fraction.len()) as u32);
+
+    parse_part(whole)?
+        .checked_mul(10u64.pow(decimals as u32))
+        .and_then(|base_units| base_units.checked_add(fraction_base_units))
+        .ok_or(ParseAmountError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let amount = Lamports::new(1_000);
+
+        assert_eq!(
+            amount.checked_add(Lamports::new(1)),
+            Some(Lamports::new(1_001))
+        );
+        assert_eq!(Lamports::MAX.checked_add(Lamports::new(1)), None);
+        assert_eq!(Lamports::MAX.saturating_add(amount), Lamports::MAX);
+
+        assert_eq!(
+            amount.checked_sub(Lamports::new(1)),
+            Some(Lamports::new(999))
+        );
+        assert_eq!(amount.checked_sub(Lamports::new(1_001)), None);
+        assert_eq!(amount.saturating_sub(Lamports::new(1_001)), Lamports::ZERO);
+
+        assert_eq!(amount.checked_mul(3), Some(Lamports::new(3_000)));
+        assert_eq!(Lamports::MAX.checked_mul(2), None);
+        assert_eq!(amount.checked_div(3), Some(Lamports::new(333)));
+        assert_eq!(amount.checked_div(0), None);
+    }
+
+    #[test]
+    fn test_whole_tokens() {
+        assert_eq!(Lamports::ONE.get(), 1_000_000_000);
+        assert_eq!(Token2z::ONE.get(), 100_000_000);
+
+        assert_eq!(
+            Token2z::checked_from_whole(5),
+            Some(Token2z::new(500_000_000))
+        );
+        assert_eq!(Lamports::checked_from_whole(u64::MAX), None);
+
+        assert_eq!(
+            Lamports::new(1_500_000_001).whole_and_fraction(),
+            (1, 500_000_001)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Lamports::new(1_500_000_000).to_string(), "1.500000000 SOL");
+        assert_eq!(Lamports::new(1).to_string(), "0.000000001 SOL");
+        assert_eq!(Token2z::new(69_420).to_string(), "0.00069420 2Z");
+        assert_eq!(Token2z::ZERO.to_string(), "0.00000000 2Z");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("1.5".parse(), Ok(Lamports::new(1_500_000_000)));
+        assert_eq!("1".parse(), Ok(Lamports::ONE));
+        assert_eq!(".5".parse(), Ok(Token2z::new(50_000_000)));
+        assert_eq!("2.".parse(), Ok(Token2z::new(200_000_000)));
+        assert_eq!("0.00000001".parse(), Ok(Token2z::new(1)));
+
+        assert_eq!(
+            "0.000000001".parse::<Token2z>(),
+            Err(ParseAmountError::TooManyDecimals)
+        );
+        assert_eq!(
+            "18446744074".parse::<Lamports>(),
+            Err(ParseAmountError::Overflow)
+        );
+        for invalid in ["", ".", "-1", "1.2.3", "1e9", " 1", "SOL"] {
+            assert_eq!(
+                invalid.parse::<Lamports>(),
+                Err(ParseAmountError::InvalidFormat),
+                "{invalid}"
+            );
+        }
+
+        for amount in [Lamports::ZERO, Lamports::new(1), Lamports::MAX] {
+            let decimal = amount.to_string().replace(" SOL", "");
+            assert_eq!(decimal.parse(), Ok(amount));
+        }
+    }
+}
@@ -0,0 +1,468 @@
+use std::fmt::Display;
+
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+/// Maximum size of a serialized transaction.
+pub const PACKET_DATA_SIZE: usize = 1_232;
+
+/// Maximum compute unit limit of a transaction.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+const SIGNATURE_LEN: usize = 64;
+const KEY_LEN: usize = 32;
+
+/// An instruction to batch along with the compute units it is expected to
+/// consume.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchInstruction {
+    pub instruction: Instruction,
+    pub compute_units: u32,
+}
+
+/// Addresses stored in an address lookup table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LookupTable {
+    pub key: Pubkey,
+    pub addresses: Vec<Pubkey>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchOptions<'a> {
+    /// Lookup tables used to load non-signer accounts by index instead of by
+    /// key, which allows more instructions to fit in each transaction.
+    pub lookup_tables: &'a [LookupTable],
+
+    /// Compute units available to each transaction's instructions. Callers
+    /// adding compute budget instructions should account for their compute
+    /// units here.
+    pub max_compute_units: u32,
+
+    /// Bytes reserved in each transaction for instructions added after
+    /// batching (e.g. compute budget instructions).
+    pub reserved_size: usize,
+}
+
+impl Default for BatchOptions<'_> {
+    fn default() -> Self {
+        Self {
+            lookup_tables: &[],
+            max_compute_units: MAX_COMPUTE_UNIT_LIMIT,
+            reserved_size: 0,
+        }
+    }
+}
+
+/// Instructions that fit in one transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstructionBatch {
+    pub instructions: Vec<Instruction>,
+    pub compute_units: u32,
+
+    /// Keys of the lookup tables that this batch's accounts are loaded from.
+    /// Only these tables need to be passed when compiling its message.
+    pub lookup_table_keys: Vec<Pubkey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    /// The instruction at this index does not fit in a transaction by itself.
+    InstructionTooLarge(usize),
+    /// The instruction at this index consumes more compute units than a
+    /// transaction allows.
+    ComputeUnitsExceeded(usize),
+}
+
+impl Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InstructionTooLarge(index) => {
+                write!(f, "Instruction {index} does not fit in a transaction")
+            }
+            Self::ComputeUnitsExceeded(index) => {
+                write!(f, "Instruction {index} exceeds the compute unit limit")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Pack instructions into the fewest transactions paid by the payer, keeping
+/// the instructions in order. Each transaction's serialized size is within
+/// [PACKET_DATA_SIZE] and its compute units are within the max compute units
+/// in the options.
+///
+/// Sizes are computed for a v0 message, which is used whether or not there
+/// are any lookup tables.
+pub fn try_batch_instructions(
+    payer_key: &Pubkey,
+    instructions: impl IntoIterator<Item = BatchInstruction>,
+    options: BatchOptions,
+) -> Result<Vec<InstructionBatch>, BatchError> {
+    let max_size = PACKET_DATA_SIZE.saturating_sub(options.reserved_size);
+
+    let mut batches = Vec::new();
+    let mut batch = InstructionBatch::default();
+
+    for (index, batch_instruction) in instructions.into_iter().enumerate() {
+        let BatchInstruction {
+            instruction,
+            compute_units,
+        } = batch_instruction;
+
+        if compute_units > options.max_compute_units {
+            return Err(BatchError::ComputeUnitsExceeded(index));
+        }
+
+        batch.instructions.push(instruction);
+
+        let fits = batch.compute_units.saturating_add(compute_units) <= options.max_compute_units
+            && transaction_size(payer_key, &batch.instructions, options.lookup_tables).0
+                <= max_size;
+
+        if fits {
+            batch.compute_units += compute_units;
+            continue;
+        }
+
+        // Start a new batch with this instruction, which must fit in a
+        // transaction by itself.
+        let instruction = batch.instructions.pop().unwrap();
+
+        if !batch.instructions.is_empty() {
+            batches.push(finish_batch(payer_key, batch, options.lookup_tables));
+        }
+
+        batch = InstructionBatch {
+            instructions: vec![instruction],
+            compute_units,
+            lookup_table_keys: Vec::new(),
+        };
+
+        if transaction_size(payer_key, &batch.instructions, options.lookup_tables).0 > max_size {
+            return Err(BatchError::InstructionTooLarge(index));
+        }
+    }
+
+    if !batch.instructions.is_empty() {
+        batches.push(finish_batch(payer_key, batch, options.lookup_tables));
+    }
+
+    Ok(batches)
+}
+
+/// Serialized size of a v0 transaction paid by the payer with these
+/// instructions, and the keys of the lookup tables used by it.
+pub fn transaction_size(
+    payer_key: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[LookupTable],
+) -> (usize, Vec<Pubkey>) {
+    // Unique accounts with merged privileges, starting with the payer.
+    let mut accounts = vec![TransactionAccount {
+        key: *payer_key,
+        is_signer: true,
+        is_writable: true,
+        is_invoked: false,
+    }];
+
+    for instruction in instructions {
+        upsert_account(&mut accounts, &instruction.program_id, false, false, true);
+
+        for meta in &instruction.accounts {
+            upsert_account(
+                &mut accounts,
+                &meta.pubkey,
+                meta.is_signer,
+                meta.is_writable,
+                false,
+            );
+        }
+    }
+
+    // Signers and invoked programs must be static keys. Any other account
+    // found in a lookup table is loaded by its index in the first table that
+    // has it.
+    let mut static_keys_len = 0;
+    let mut signers_len = 0;
+    let mut table_indexes_len = vec![(0, 0); lookup_tables.len()];
+
+    for account in &accounts {
+        if account.is_signer {
+            signers_len += 1;
+        }
+
+        let table_position = (!account.is_signer && !account.is_invoked)
+            .then(|| {
+                lookup_tables
+                    .iter()
+                    .position(|table| table.addresses.contains(&account.key))
+            })
+            .flatten();
+
+        match table_position {
+            Some(position) if account.is_writable => table_indexes_len[position].0 += 1,
+            Some(position) => table_indexes_len[position].1 += 1,
+            None => static_keys_len += 1,
+        }
+    }
+
+    let instructions_size = instructions
+        .iter()
+        .map(|instruction| {
+            1 + compact_len_size(instruction.accounts.len())
+                + instruction.accounts.len()
+                + compact_len_size(instruction.data.len())
+                + instruction.data.len()
+        })
+        .sum::<usize>();
+
+    let mut lookup_table_keys = Vec::new();
+    let mut lookups_size = 0;
+
+    for (table, (writable_len, readonly_len)) in lookup_tables.iter().zip(table_indexes_len) {
+        if writable_len + readonly_len == 0 {
+            continue;
+        }
+
+        lookup_table_keys.push(table.key);
+        lookups_size += KEY_LEN
+            + compact_len_size(writable_len)
+            + writable_len
+            + compact_len_size(readonly_len)
+            + readonly_len;
+    }
+
+    let size = compact_len_size(signers_len)
+        + signers_len * SIGNATURE_LEN
+        // Version prefix and message header.
+        + 4
+        + compact_len_size(static_keys_len)
+        + static_keys_len * KEY_LEN
+        // Recent blockhash.
+        + KEY_LEN
+        + compact_len_size(instructions.len())
+        + instructions_size
+        + compact_len_size(lookup_table_keys.len())
+        + lookups_size;
+
+    (size, lookup_table_keys)
+}
+
+//
+
+struct TransactionAccount {
+    key: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+    is_invoked: bool,
+}
+
+fn upsert_account(
+    accounts: &mut Vec<TransactionAccount>,
+    key: &Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+    is_invoked: bool,
+) {
+    match accounts.iter_mut().find(|account| &account.key == key) {
+        Some(account) => {
+            account.is_signer |= is_signer;
+            account.is_writable |= is_writable;
+            account.is_invoked |= is_invoked;
+        }
+        None => accounts.push(TransactionAccount {
+            key: *key,
+            is_signer,
+            is_writable,
+            is_invoked,
+        }),
+    }
+}
+
+fn finish_batch(
+    payer_key: &Pubkey,
+    mut batch: InstructionBatch,
+    lookup_tables: &[LookupTable],
+) -> InstructionBatch {
+    batch.lookup_table_keys = transaction_size(payer_key, &batch.instructions, lookup_tables).1;
+    batch
+}
+
+/// Size of a length encoded as a compact-u16.
+fn compact_len_size(len: usize) -> usize {
+    if len < 0x80 {
+        1
+    } else if len < 0x4000 {
+        2
+    } else {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_instruction::AccountMeta;
+
+    use super::*;
+
+    fn new_batch_instruction(
+        program_id: &Pubkey,
+        account_keys: &[Pubkey],
+        data_len: usize,
+        compute_units: u32,
+    ) -> BatchInstruction {
+        BatchInstruction {
+            instruction: Instruction {
+                program_id: *program_id,
+                accounts: account_keys
+                    .iter()
+                    .map(|key| AccountMeta::new(*key, false))
+                    .collect(),
+                data: vec![0; data_len],
+            },
+            compute_units,
+        }
+    }
+
+    #[test]
+    fn test_transaction_size() {
+        let payer_key = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+
+        let instruction = new_batch_instruction(&program_id, &[account_key], 10, 0).instruction;
+
+        // 1 signature, 3 static keys, 1 instruction with 1 account and 10
+        // bytes of data and no lookup tables.
+        let expected_size = 1 + 64 + 4 + 1 + 3 * 32 + 32 + 1 + (1 + 1 + 1 + 1 + 10) + 1;
+        assert_eq!(
+            transaction_size(&payer_key, std::slice::from_ref(&instruction), &[]),
+            (expected_size, vec![])
+        );
+
+        // The account is loaded from the lookup table instead.
+        let lookup_table = LookupTable {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique(), account_key],
+        };
+        assert_eq!(
+            transaction_size(
+                &payer_key,
+                &[instruction],
+                std::slice::from_ref(&lookup_table)
+            ),
+            (expected_size - 32 + 32 + 1 + 1 + 1, vec![lookup_table.key])
+        );
+    }
+
+    #[test]
+    fn test_try_batch_instructions() {
+        let payer_key = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let account_keys = (0..8).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+
+        let instructions = account_keys
+            .chunks(2)
+            .map(|keys| new_batch_instruction(&program_id, keys, 150, 100_000))
+            .collect::<Vec<_>>();
+
+        // All instructions fit in one transaction.
+        let batches =
+            try_batch_instructions(&payer_key, instructions.clone(), Default::default()).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].instructions.len(), 4);
+        assert_eq!(batches[0].compute_units, 400_000);
+
+        // Compute units split the instructions in order.
+        let batches = try_batch_instructions(
+            &payer_key,
+            instructions.clone(),
+            BatchOptions {
+                max_compute_units: 250_000,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(
+            batches[0].instructions,
+            [
+                instructions[0].instruction.clone(),
+                instructions[1].instruction.clone()
+            ]
+        );
+
+        // Reserved bytes split the instructions by size.
+        let batches = try_batch_instructions(
+            &payer_key,
+            instructions.clone(),
+            BatchOptions {
+                reserved_size: 600,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(batches.len(), 2);
+        assert!(batches
+            .iter()
+            .all(|batch| batch.lookup_table_keys.is_empty()));
+
+        // Instructions that cannot fit on their own fail.
+        assert_eq!(
+            try_batch_instructions(
+                &payer_key,
+                [new_batch_instruction(&program_id, &[], PACKET_DATA_SIZE, 0)],
+                Default::default(),
+            ),
+            Err(BatchError::InstructionTooLarge(0))
+        );
+        assert_eq!(
+            try_batch_instructions(
+                &payer_key,
+                [new_batch_instruction(
+                    &program_id,
+                    &[],
+                    0,
+                    MAX_COMPUTE_UNIT_LIMIT + 1
+                )],
+                Default::default(),
+            ),
+            Err(BatchError::ComputeUnitsExceeded(0))
+        );
+    }
+
+    #[test]
+    fn test_try_batch_instructions_with_lookup_table() {
+        let payer_key = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let account_keys = (0..64).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+
+        let instructions = account_keys
+            .chunks(4)
+            .map(|keys| new_batch_instruction(&program_id, keys, 8, 0))
+            .collect::<Vec<_>>();
+
+        let batches =
+            try_batch_instructions(&payer_key, instructions.clone(), Default::default()).unwrap();
+        assert!(batches.len() > 1);
+
+        let lookup_table = LookupTable {
+            key: Pubkey::new_unique(),
+            addresses: account_keys,
+        };
+
+        let batches = try_batch_instructions(
+            &payer_key,
+            instructions,
+            BatchOptions {
+                lookup_tables: std::slice::from_ref(&lookup_table),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].lookup_table_keys, [lookup_table.key]);
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use solana_pubkey::Pubkey;
+
+/// Allowed increase over a recorded baseline before a measurement is
+/// considered a regression.
+pub const DEFAULT_REGRESSION_THRESHOLD_PCT: u64 = 5;
+
+/// When this environment variable is set, measurements are recorded as
+/// baselines instead of being checked against them.
+pub const UPDATE_BASELINES_ENV_VAR: &str = "UPDATE_COMPUTE_UNIT_BASELINES";
+
+/// Parse the compute units consumed by each top-level invocation of the given
+/// program from transaction logs. Compute units consumed by CPIs are included
+/// in the invoking program's total, so nested invocations are skipped.
+pub fn parse_consumed_compute_units(program_id: &Pubkey, logs: &[String]) -> Vec<u64> {
+    let program_prefix = format!("Program {program_id} ");
+    let mut invocation_stack = Vec::new();
+    let mut consumed_units = Vec::new();
+
+    for log in logs {
+        let Some(message) = log.strip_prefix("Program ") else {
+            continue;
+        };
+        let mut parts = message.split_whitespace();
+        let (Some(key), Some(action)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+
+        match action {
+            "invoke" => {
+                invocation_stack.push(key);
+            }
+            "success" | "failed:" => {
+                invocation_stack.pop();
+            }
+            "consumed" if invocation_stack.len() == 1 && log.starts_with(&program_prefix) => {
+                if let Some(units) = parts.next().and_then(|units| units.parse().ok()) {
+                    consumed_units.push(units);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    consumed_units
+}
+
+#[derive(Debug)]
+pub enum ComputeUnitCheckError {
+    Io(io::Error),
+    InvalidBaseline {
+        name: String,
+    },
+    MissingBaseline {
+        name: String,
+    },
+    Regression {
+        name: String,
+        baseline: u64,
+        consumed: u64,
+        threshold_pct: u64,
+    },
+}
+
+impl Display for ComputeUnitCheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Baseline I/O error: {err}"),
+            Self::InvalidBaseline { name } => write!(f, "Invalid compute unit baseline for {name}"),
+            Self::MissingBaseline { name } => write!(
+                f,
+                "Missing compute unit baseline for {name}. Set {UPDATE_BASELINES_ENV_VAR} to \
+                 record it"
+            ),
+            Self::Regression {
+                name,
+                baseline,
+                consumed,
+                threshold_pct,
+            } => write!(
+                f,
+                "{name} consumed {consumed} compute units, exceeding baseline {baseline} by more \
+                 than {threshold_pct}%. Set {UPDATE_BASELINES_ENV_VAR} to record the new baseline"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ComputeUnitCheckError {}
+
+impl From<io::Error> for ComputeUnitCheckError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Compute unit baselines recorded as one file per measurement in a
+/// directory, so tests running in parallel never write to the same file.
+///
+/// Baselines are checked in with the tests, so a measurement without a
+/// recorded baseline fails the check unless baselines are being updated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComputeUnitBaselines {
+    dir: PathBuf,
+    threshold_pct: u64,
+    update_baselines: bool,
+}
+
+impl ComputeUnitBaselines {
+    /// Baselines are updated if [UPDATE_BASELINES_ENV_VAR] is set.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            threshold_pct: DEFAULT_REGRESSION_THRESHOLD_PCT,
+            update_baselines: std::env::var_os(UPDATE_BASELINES_ENV_VAR).is_some(),
+        }
+    }
+
+    pub fn with_threshold_pct(mut self, threshold_pct: u64) -> Self {
+        self.threshold_pct = threshold_pct;
+        self
+    }
+
+    pub fn with_update_baselines(mut self, update_baselines: bool) -> Self {
+        self.update_baselines = update_baselines;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Check the consumed compute units against the recorded baseline for the
+    /// given measurement name. If baselines are being updated, the consumed
+    /// compute units are recorded as the new baseline instead.
+    pub fn check(&self, name: &str, consumed: u64) -> Result<(), ComputeUnitCheckError> {
+        let baseline_path = self.dir.join(format!("{name}.txt"));
+
+        if self.update_baselines {
+            fs::create_dir_all(&self.dir)?;
+            fs::write(&baseline_path, format!("{consumed}\n"))?;
+
+            return Ok(());
+        }
+
+        let baseline = match fs::read_to_string(&baseline_path) {
+            Ok(contents) => contents.trim().parse::<u64>().map_err(|_| {
+                ComputeUnitCheckError::InvalidBaseline {
+                    name: name.to_string(),
+                }
+            })?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(ComputeUnitCheckError::MissingBaseline {
+                    name: name.to_string(),
+                });
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let max_consumed = baseline.saturating_mul(100 + self.threshold_pct) / 100;
+
+        if consumed > max_consumed {
+            return Err(ComputeUnitCheckError::Regression {
+                name: name.to_string(),
+                baseline,
+                consumed,
+                threshold_pct: self.threshold_pct,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Panics if [check](Self::check) fails.
+    pub fn assert_within_threshold(&self, name: &str, consumed: u64) {
+        if let Err(err) = self.check(name, consumed) {
+            panic!("{err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_consumed_compute_units() {
+        let program_id = Pubkey::new_unique();
+        let other_program_id = Pubkey::new_unique();
+
+        let logs = [
+            format!("Program {program_id} invoke [1]"),
+            "Program log: Distribute rewards".to_string(),
+            format!("Program {other_program_id} invoke [2]"),
+            format!("Program {other_program_id} consumed 4645 of 180000 compute units"),
+            format!("Program {other_program_id} success"),
+            format!("Program {program_id} consumed 42069 of 200000 compute units"),
+            format!("Program {program_id} success"),
+            format!("Program {other_program_id} invoke [1]"),
+            format!("Program {other_program_id} consumed 150 of 157931 compute units"),
+            format!("Program {other_program_id} success"),
+            format!("Program {program_id} invoke [1]"),
+            format!("Program {program_id} consumed 6969 of 157781 compute units"),
+            format!("Program {program_id} failed: invalid account data for instruction"),
+        ];
+
+        assert_eq!(
+            parse_consumed_compute_units(&program_id, &logs),
+            [42_069, 6_969]
+        );
+        assert_eq!(
+            parse_consumed_compute_units(&other_program_id, &logs),
+            [150]
+        );
+    }
+
+    #[test]
+    fn test_compute_unit_baselines() {
+        let dir =
+            std::env::temp_dir().join(format!("compute_unit_baselines_{}", Pubkey::new_unique()));
+        let baselines = ComputeUnitBaselines::new(&dir)
+            .with_threshold_pct(10)
+            .with_update_baselines(false);
+
+        // Missing baseline fails the check without recording it.
+        assert!(matches!(
+            baselines.check("test_ix", 1_000),
+            Err(ComputeUnitCheckError::MissingBaseline { .. })
+        ));
+        assert!(!dir.join("test_ix.txt").exists());
+
+        // Baseline is recorded when updating.
+        baselines
+            .clone()
+            .with_update_baselines(true)
+            .check("test_ix", 1_000)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("test_ix.txt")).unwrap(),
+            "1000\n"
+        );
+
+        // Within the threshold.
+        baselines.check("test_ix", 900).unwrap();
+        baselines.check("test_ix", 1_100).unwrap();
+
+        // Regression.
+        assert!(matches!(
+            baselines.check("test_ix", 1_101),
+            Err(ComputeUnitCheckError::Regression {
+                baseline: 1_000,
+                consumed: 1_101,
+                threshold_pct: 10,
+                ..
+            })
+        ));
+
+        // Baselines are not overwritten by checks.
+        assert_eq!(
+            fs::read_to_string(dir.join("test_ix.txt")).unwrap(),
+            "1000\n"
+        );
+
+        fs::write(dir.join("bad_ix.txt"), "not a number").unwrap();
+        assert!(matches!(
+            baselines.check("bad_ix", 1_000),
+            Err(ComputeUnitCheckError::InvalidBaseline { .. })
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
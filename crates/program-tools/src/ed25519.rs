@@ -0,0 +1,218 @@
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "entrypoint")]
+use solana_account_info::AccountInfo;
+use solana_instruction::Instruction;
+use solana_msg::msg;
+use solana_program_error::ProgramError;
+use solana_pubkey::Pubkey;
+
+pub use solana_sdk_ids::ed25519_program::ID as ED25519_PROGRAM_ID;
+
+/// Instruction index referring to the Ed25519 program instruction itself.
+pub const CURRENT_INSTRUCTION_INDEX: u16 = u16::MAX;
+
+/// Ed25519 program instruction data starts with the number of signatures and a
+/// padding byte, followed by the signature offsets.
+pub const SIGNATURE_OFFSETS_START: usize = 2;
+
+/// Where the public key, signature and message start in instruction data built
+/// by [new_ed25519_instruction].
+pub const DATA_START: usize = SIGNATURE_OFFSETS_START + size_of::<Ed25519SignatureOffsets>();
+
+const PUBLIC_KEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+/// Locations of the signature, public key and message verified by the Ed25519
+/// program. Each location is an offset into the data of the instruction at the
+/// corresponding instruction index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct Ed25519SignatureOffsets {
+    pub signature_offset: u16,
+    pub signature_instruction_index: u16,
+    pub public_key_offset: u16,
+    pub public_key_instruction_index: u16,
+    pub message_data_offset: u16,
+    pub message_data_size: u16,
+    pub message_instruction_index: u16,
+}
+
+/// Message signed by a key, which was verified by an Ed25519 program
+/// instruction in the same transaction.
+///
+/// The Ed25519 program fails the transaction if a signature is invalid, so a
+/// program introspecting this instruction only needs to check that the signer
+/// and message are the ones it expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ed25519SignedMessage {
+    pub signer_key: Pubkey,
+    pub message: Vec<u8>,
+}
+
+impl Ed25519SignedMessage {
+    /// Parse Ed25519 program instruction data verifying exactly one signature,
+    /// whose public key and message are stored in this instruction data.
+    pub fn try_from_instruction_data(data: &[u8]) -> Result<Self, ProgramError> {
+        let offsets = data
+            .get(SIGNATURE_OFFSETS_START..DATA_START)
+            .filter(|_| data[0] == 1)
+            .map(bytemuck::pod_read_unaligned::<Ed25519SignatureOffsets>)
+            .ok_or_else(|| {
+                msg!("Ed25519 instruction must verify exactly one signature");
+                ProgramError::InvalidInstructionData
+            })?;
+
+        // Referencing other instructions' data would allow the signed data to
+        // differ from what is parsed here.
+        if offsets.signature_instruction_index != CURRENT_INSTRUCTION_INDEX
+            || offsets.public_key_instruction_index != CURRENT_INSTRUCTION_INDEX
+            || offsets.message_instruction_index != CURRENT_INSTRUCTION_INDEX
+        {
+            msg!("Ed25519 instruction must only reference its own data");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let public_key_offset = usize::from(offsets.public_key_offset);
+        let message_data_offset = usize::from(offsets.message_data_offset);
+
+        let signer_key = data
+            .get(public_key_offset..public_key_offset + PUBLIC_KEY_LEN)
+            .map(|key| Pubkey::try_from(key).unwrap());
+        let message = data
+            .get(message_data_offset..message_data_offset + usize::from(offsets.message_data_size));
+
+        match (signer_key, message) {
+            (Some(signer_key), Some(message)) => Ok(Self {
+                signer_key,
+                message: message.to_vec(),
+            }),
+            _ => {
+                msg!("Ed25519 instruction offsets out of bounds");
+                Err(ProgramError::InvalidInstructionData)
+            }
+        }
+    }
+
+    /// Load the signed message from the Ed25519 program instruction at the
+    /// given index relative to the currently executing instruction (e.g. -1
+    /// for the preceding instruction).
+    ///
+    /// The currently executing instruction should not be invoked via CPI.
+    /// Otherwise the relative index refers to the instruction that invoked it.
+    #[cfg(feature = "entrypoint")]
+    pub fn try_from_relative_instruction(
+        index_relative_to_current: i64,
+        account_index: usize,
+        instructions_sysvar_info: &AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        if !solana_sdk_ids::sysvar::instructions::check_id(instructions_sysvar_info.key) {
+            msg!(
+                "Invalid address for Instructions sysvar (account {})",
+                account_index
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let ix = solana_instructions_sysvar::get_instruction_relative(
+            index_relative_to_current,
+            instructions_sysvar_info,
+        )
+        .ok()
+        .filter(|ix| ix.program_id == ED25519_PROGRAM_ID)
+        .ok_or_else(|| {
+            msg!(
+                "Expected Ed25519 program instruction at relative index {}",
+                index_relative_to_current
+            );
+            ProgramError::InvalidInstructionData
+        })?;
+
+        Self::try_from_instruction_data(&ix.data)
+    }
+}
+
+/// Build an Ed25519 program instruction verifying one signature. The public
+/// key, signature and message are stored in this instruction's data, which is
+/// what [Ed25519SignedMessage::try_from_instruction_data] expects.
+pub fn new_ed25519_instruction(
+    signer_key: &Pubkey,
+    signature: &[u8; SIGNATURE_LEN],
+    message: &[u8],
+) -> Instruction {
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + PUBLIC_KEY_LEN;
+    let message_data_offset = signature_offset + SIGNATURE_LEN;
+
+    // Instruction data is limited by the transaction size, so these offsets
+    // cannot overflow.
+    let offsets = Ed25519SignatureOffsets {
+        signature_offset: signature_offset as u16,
+        signature_instruction_index: CURRENT_INSTRUCTION_INDEX,
+        public_key_offset: public_key_offset as u16,
+        public_key_instruction_index: CURRENT_INSTRUCTION_INDEX,
+        message_data_offset: message_data_offset as u16,
+        message_data_size: message.len() as u16,
+        message_instruction_index: CURRENT_INSTRUCTION_INDEX,
+    };
+
+    let mut data = Vec::with_capacity(message_data_offset + message.len());
+    data.extend_from_slice(&[1, 0]);
+    data.extend_from_slice(bytemuck::bytes_of(&offsets));
+    data.extend_from_slice(signer_key.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: ED25519_PROGRAM_ID,
+        accounts: Default::default(),
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_message_from_instruction_data() {
+        let signer_key = Pubkey::new_unique();
+        let message = b"dz::test::signed_message";
+
+        let ix = new_ed25519_instruction(&signer_key, &[1; SIGNATURE_LEN], message);
+        assert_eq!(ix.program_id, ED25519_PROGRAM_ID);
+        assert_eq!(
+            Ed25519SignedMessage::try_from_instruction_data(&ix.data),
+            Ok(Ed25519SignedMessage {
+                signer_key,
+                message: message.to_vec(),
+            })
+        );
+
+        // Exactly one signature must be verified.
+        let mut data = ix.data.clone();
+        data[0] = 2;
+        assert_eq!(
+            Ed25519SignedMessage::try_from_instruction_data(&data),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            Ed25519SignedMessage::try_from_instruction_data(&ix.data[..DATA_START - 1]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+
+        // The message cannot be in another instruction.
+        let mut data = ix.data.clone();
+        data[SIGNATURE_OFFSETS_START + 12..DATA_START].copy_from_slice(&0_u16.to_le_bytes());
+        assert_eq!(
+            Ed25519SignedMessage::try_from_instruction_data(&data),
+            Err(ProgramError::InvalidInstructionData)
+        );
+
+        // The message cannot extend past the instruction data.
+        let data = &ix.data[..ix.data.len() - 1];
+        assert_eq!(
+            Ed25519SignedMessage::try_from_instruction_data(data),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
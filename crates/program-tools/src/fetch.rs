@@ -0,0 +1,194 @@
+use std::{fmt::Display, future::Future};
+
+use bytemuck::Pod;
+use solana_pubkey::Pubkey;
+
+use crate::{zero_copy, PrecomputedDiscriminator};
+
+/// The `getMultipleAccounts` RPC method accepts at most this many keys per
+/// request.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Fetches raw account data. Implemented for the nonblocking RPC client so
+/// off-chain processes can share the same fetch helpers, and can be
+/// implemented by any other client (e.g. in tests).
+pub trait AccountDataFetcher {
+    type Error;
+
+    /// Returns the data of each account in the same order as the given keys.
+    /// Accounts that do not exist are returned as `None`.
+    fn get_multiple_accounts_data(
+        &self,
+        keys: &[Pubkey],
+    ) -> impl Future<Output = Result<Vec<Option<Vec<u8>>>, Self::Error>> + Send;
+}
+
+#[derive(Debug)]
+pub enum FetchError<E> {
+    Client(E),
+    AccountNotFound(Pubkey),
+    InvalidAccountData(Pubkey),
+    UnexpectedAccountsCount { expected: usize, actual: usize },
+}
+
+impl<E: Display> Display for FetchError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Client(err) => write!(f, "Client error: {err}"),
+            Self::AccountNotFound(key) => write!(f, "Account {key} not found"),
+            Self::InvalidAccountData(key) => {
+                write!(
+                    f,
+                    "Account {key} has unexpected discriminator or data length"
+                )
+            }
+            Self::UnexpectedAccountsCount { expected, actual } => {
+                write!(f, "Expected {expected} accounts, but fetched {actual}")
+            }
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + Display> std::error::Error for FetchError<E> {}
+
+/// Fetch and decode a zero-copy account, returning a copy of the account and
+/// any data following its layout.
+pub async fn fetch_zero_copy<T, F>(
+    fetcher: &F,
+    key: &Pubkey,
+) -> Result<(T, Vec<u8>), FetchError<F::Error>>
+where
+    T: Pod + PrecomputedDiscriminator,
+    F: AccountDataFetcher,
+{
+    fetch_many::<T, F>(fetcher, std::slice::from_ref(key))
+        .await?
+        .pop()
+        .flatten()
+        .ok_or(FetchError::AccountNotFound(*key))
+}
+
+/// Fetch and decode zero-copy accounts of the same type, batching keys into
+/// `getMultipleAccounts` requests. Accounts that do not exist are returned as
+/// `None`.
+pub async fn fetch_many<T, F>(
+    fetcher: &F,
+    keys: &[Pubkey],
+) -> Result<Vec<Option<(T, Vec<u8>)>>, FetchError<F::Error>>
+where
+    T: Pod + PrecomputedDiscriminator,
+    F: AccountDataFetcher,
+{
+    let mut accounts = Vec::with_capacity(keys.len());
+
+    for keys_chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts_data = fetcher
+            .get_multiple_accounts_data(keys_chunk)
+            .await
+            .map_err(FetchError::Client)?;
+
+        if accounts_data.len() != keys_chunk.len() {
+            return Err(FetchError::UnexpectedAccountsCount {
+                expected: keys_chunk.len(),
+                actual: accounts_data.len(),
+            });
+        }
+
+        for (key, data) in keys_chunk.iter().zip(accounts_data) {
+            let account = data
+                .map(|data| try_decode_zero_copy::<T, F::Error>(key, &data))
+                .transpose()?;
+            accounts.push(account);
+        }
+    }
+
+    Ok(accounts)
+}
+
+/// Decode account data encoded as `T`. Fetched data is not guaranteed to be
+/// aligned, so the account is copied out of the data.
+pub fn try_decode_zero_copy<T, E>(key: &Pubkey, data: &[u8]) -> Result<(T, Vec<u8>), FetchError<E>>
+where
+    T: Pod + PrecomputedDiscriminator,
+{
+    let (account_data, remaining_data) = data
+        .split_at_checked(zero_copy::data_end::<T>())
+        .filter(|(account_data, _)| T::has_discriminator(account_data))
+        .ok_or(FetchError::InvalidAccountData(*key))?;
+
+    let account = bytemuck::pod_read_unaligned(&account_data[zero_copy::data_range::<T>()]);
+
+    Ok((account, remaining_data.to_vec()))
+}
+
+impl AccountDataFetcher for solana_rpc_client::nonblocking::rpc_client::RpcClient {
+    type Error = solana_rpc_client_api::client_error::Error;
+
+    async fn get_multiple_accounts_data(
+        &self,
+        keys: &[Pubkey],
+    ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
+        let accounts = self.get_multiple_accounts(keys).await?;
+
+        Ok(accounts
+            .into_iter()
+            .map(|account| account.map(|account| account.data))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+    use crate::Discriminator;
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+    #[repr(C)]
+    struct TestAccount {
+        value: u64,
+    }
+
+    impl PrecomputedDiscriminator for TestAccount {
+        const DISCRIMINATOR: Discriminator<8> = Discriminator::new_sha2(b"dz::account::test");
+    }
+
+    #[test]
+    fn test_try_decode_zero_copy() {
+        let key = Pubkey::new_unique();
+        let account = TestAccount { value: 69 };
+
+        let mut data = TestAccount::discriminator_slice().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&account));
+
+        let (decoded, remaining_data) =
+            try_decode_zero_copy::<TestAccount, ()>(&key, &data).unwrap();
+        assert_eq!(decoded, account);
+        assert!(remaining_data.is_empty());
+
+        // Remaining data is preserved, and unaligned data can be decoded.
+        let mut unaligned_data = vec![0];
+        unaligned_data.extend_from_slice(&data);
+        unaligned_data.extend_from_slice(&[1, 2, 3]);
+
+        let (decoded, remaining_data) =
+            try_decode_zero_copy::<TestAccount, ()>(&key, &unaligned_data[1..]).unwrap();
+        assert_eq!(decoded, account);
+        assert_eq!(remaining_data, [1, 2, 3]);
+
+        // Invalid discriminator.
+        data[0] ^= 1;
+        assert!(matches!(
+            try_decode_zero_copy::<TestAccount, ()>(&key, &data),
+            Err(FetchError::InvalidAccountData(err_key)) if err_key == key
+        ));
+
+        // Data too short.
+        let data = TestAccount::discriminator_slice().to_vec();
+        assert!(matches!(
+            try_decode_zero_copy::<TestAccount, ()>(&key, &data),
+            Err(FetchError::InvalidAccountData(err_key)) if err_key == key
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt::Display;
+
+use crate::types::Flags;
+
+/// Storage for named flag bits defined with [define_flags](crate::define_flags).
+pub trait FlagStorage {
+    /// Number of bits available for flags.
+    const BIT_COUNT: usize;
+
+    fn flag_bit(&self, index: usize) -> bool;
+
+    fn set_flag_bit(&mut self, index: usize, value: bool);
+}
+
+impl FlagStorage for Flags {
+    const BIT_COUNT: usize = Flags::BITS;
+
+    fn flag_bit(&self, index: usize) -> bool {
+        self.bit(index)
+    }
+
+    fn set_flag_bit(&mut self, index: usize, value: bool) {
+        self.set_bit(index, value);
+    }
+}
+
+/// Returned when parsing a flag name that is not defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFlagError(pub String);
+
+impl Display for ParseFlagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown flag: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseFlagError {}
+
+/// Define an enum of named flag bits backed by a [FlagStorage] type. Each
+/// variant's discriminant is its bit index, so two flags assigned the same bit
+/// fail to compile. Bit indices exceeding the storage's bit count also fail to
+/// compile.
+///
+/// ```
+/// use doublezero_program_tools::{define_flags, types::Flags};
+///
+/// define_flags! {
+///     pub enum ExampleFlag: Flags {
+///         IsPaused = 0,
+///         IsMigrated = 1,
+///     }
+/// }
+///
+/// let mut flags = Flags::default();
+/// ExampleFlag::IsMigrated.set(&mut flags, true);
+///
+/// assert!(!ExampleFlag::IsPaused.is_set(&flags));
+/// assert!(ExampleFlag::IsMigrated.is_set(&flags));
+/// assert_eq!(ExampleFlag::IsMigrated.to_string(), "IsMigrated");
+/// ```
+///
+/// With the `serde` feature enabled, flags are serialized by name.
+#[macro_export]
+macro_rules! define_flags {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident: $storage:ty {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $bit:literal
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[repr(u8)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant = $bit,
+            )*
+        }
+
+        const _: () = {
+            $(
+                assert!(
+                    ($name::$variant as usize)
+                        < <$storage as $crate::flags::FlagStorage>::BIT_COUNT,
+                    concat!("Flag bit exceeds storage: ", stringify!($variant))
+                );
+            )*
+        };
+
+        impl $name {
+            pub const ALL: &'static [Self] = &[$(Self::$variant),*];
+
+            pub const fn bit_index(self) -> usize {
+                self as usize
+            }
+
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => stringify!($variant),)*
+                }
+            }
+
+            pub fn is_set(self, flags: &$storage) -> bool {
+                $crate::flags::FlagStorage::flag_bit(flags, self.bit_index())
+            }
+
+            pub fn set(self, flags: &mut $storage, value: bool) {
+                $crate::flags::FlagStorage::set_flag_bit(flags, self.bit_index(), value);
+            }
+
+            /// Iterate over the flags set in the given storage.
+            pub fn iter_set(flags: &$storage) -> impl Iterator<Item = Self> + '_ {
+                Self::ALL.iter().copied().filter(move |flag| flag.is_set(flags))
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::flags::ParseFlagError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($variant) => Ok(Self::$variant),)*
+                    _ => Err($crate::flags::ParseFlagError(s.to_string())),
+                }
+            }
+        }
+
+        $crate::__define_flags_serde!($name);
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_flags_serde {
+    ($name:ident) => {
+        impl $crate::flags::__private::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: $crate::flags::__private::serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> $crate::flags::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: $crate::flags::__private::serde::Deserializer<'de>,
+            {
+                let name = <String as $crate::flags::__private::serde::Deserialize>::deserialize(
+                    deserializer,
+                )?;
+
+                name.parse()
+                    .map_err(<D::Error as $crate::flags::__private::serde::de::Error>::custom)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_flags_serde {
+    ($name:ident) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    define_flags! {
+        enum TestFlag: Flags {
+            First = 0,
+            Second = 1,
+            Last = 63,
+        }
+    }
+
+    #[test]
+    fn test_define_flags() {
+        let mut flags = Flags::default();
+        assert_eq!(TestFlag::iter_set(&flags).count(), 0);
+
+        TestFlag::First.set(&mut flags, true);
+        TestFlag::Last.set(&mut flags, true);
+        assert!(TestFlag::First.is_set(&flags));
+        assert!(!TestFlag::Second.is_set(&flags));
+        assert!(TestFlag::Last.is_set(&flags));
+        assert!(flags.bit(63));
+        assert_eq!(
+            TestFlag::iter_set(&flags).collect::<Vec<_>>(),
+            [TestFlag::First, TestFlag::Last]
+        );
+
+        TestFlag::First.set(&mut flags, false);
+        assert!(!TestFlag::First.is_set(&flags));
+        assert!(TestFlag::Last.is_set(&flags));
+    }
+
+    #[test]
+    fn test_flag_names() {
+        for flag in TestFlag::ALL {
+            assert_eq!(flag.to_string().parse::<TestFlag>().unwrap(), *flag);
+        }
+
+        assert_eq!(TestFlag::Second.to_string(), "Second");
+        assert_eq!(
+            "Third".parse::<TestFlag>(),
+            Err(ParseFlagError("Third".to_string()))
+        );
+    }
+}
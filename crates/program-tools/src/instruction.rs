@@ -2,14 +2,109 @@ use borsh::BorshSerialize;
 use solana_instruction::{AccountMeta, Instruction};
 use solana_pubkey::Pubkey;
 
+/// Build an instruction from the given accounts and data. Privileges of
+/// duplicate accounts are merged (see [merge_duplicate_account_metas]).
 pub fn try_build_instruction(
     program_id: &Pubkey,
     accounts: impl Into<Vec<AccountMeta>>,
     data: &impl BorshSerialize,
 ) -> std::io::Result<Instruction> {
+    let mut accounts = accounts.into();
+    merge_duplicate_account_metas(&mut accounts);
+
     Ok(Instruction {
         program_id: *program_id,
-        accounts: accounts.into(),
+        accounts,
         data: borsh::to_vec(data)?,
     })
 }
+
+/// Account meta for an optional account. An omitted optional account is
+/// represented by the program ID as a read-only placeholder so the positions
+/// of the accounts following it do not change.
+pub fn optional_account_meta(
+    program_id: &Pubkey,
+    key: Option<&Pubkey>,
+    is_writable: bool,
+    is_signer: bool,
+) -> AccountMeta {
+    match key {
+        Some(key) => AccountMeta {
+            pubkey: *key,
+            is_signer,
+            is_writable,
+        },
+        None => AccountMeta::new_readonly(*program_id, false),
+    }
+}
+
+/// Merge privileges of duplicate accounts so every occurrence of an account is
+/// writable (or a signer) if any of its occurrences is. Account positions are
+/// preserved because programs read accounts by index.
+pub fn merge_duplicate_account_metas(accounts: &mut [AccountMeta]) {
+    for i in 0..accounts.len() {
+        let key = accounts[i].pubkey;
+
+        let duplicates = accounts[i..].iter().filter(|meta| meta.pubkey == key);
+        let is_signer = duplicates.clone().any(|meta| meta.is_signer);
+        let is_writable = duplicates.clone().any(|meta| meta.is_writable);
+
+        for meta in accounts[i..].iter_mut().filter(|meta| meta.pubkey == key) {
+            meta.is_signer = is_signer;
+            meta.is_writable = is_writable;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optional_account_meta() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+
+        assert_eq!(
+            optional_account_meta(&program_id, Some(&key), true, true),
+            AccountMeta::new(key, true)
+        );
+        assert_eq!(
+            optional_account_meta(&program_id, Some(&key), false, false),
+            AccountMeta::new_readonly(key, false)
+        );
+        assert_eq!(
+            optional_account_meta(&program_id, None, true, true),
+            AccountMeta::new_readonly(program_id, false)
+        );
+    }
+
+    #[test]
+    fn test_merge_duplicate_account_metas() {
+        let key_1 = Pubkey::new_unique();
+        let key_2 = Pubkey::new_unique();
+        let key_3 = Pubkey::new_unique();
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(key_1, false),
+            AccountMeta::new_readonly(key_2, true),
+            AccountMeta::new(key_1, false),
+            AccountMeta::new_readonly(key_3, false),
+            AccountMeta::new_readonly(key_2, false),
+            AccountMeta::new_readonly(key_1, true),
+        ];
+        merge_duplicate_account_metas(&mut accounts);
+
+        assert_eq!(
+            accounts,
+            [
+                AccountMeta::new(key_1, true),
+                AccountMeta::new_readonly(key_2, true),
+                AccountMeta::new(key_1, true),
+                AccountMeta::new_readonly(key_3, false),
+                AccountMeta::new_readonly(key_2, true),
+                AccountMeta::new(key_1, true),
+            ]
+        );
+    }
+}
@@ -1,5 +1,13 @@
 #[cfg(feature = "entrypoint")]
 pub mod account_info;
+pub mod amount;
+pub mod batch;
+#[cfg(feature = "test-utils")]
+pub mod compute_units;
+pub mod ed25519;
+#[cfg(feature = "offchain")]
+pub mod fetch;
+pub mod flags;
 pub mod instruction;
 #[cfg(feature = "entrypoint")]
 pub mod recipe;
@@ -0,0 +1,42 @@
+use solana_account_info::AccountInfo;
+use solana_msg::msg;
+use solana_program_error::ProgramError;
+
+/// Close an account owned by the executing program. Its data is zeroed and
+/// truncated, ownership is reassigned to the System program and all of its
+/// lamports are transferred to the destination account. Returns the number of
+/// lamports transferred.
+///
+/// The closed account's data must not be borrowed when calling this function,
+/// so any zero-copy account wrapping it must be dropped beforehand.
+pub fn try_close_account(
+    account_info: &AccountInfo,
+    destination_info: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    // Transferring lamports to the closed account itself would leave the
+    // account with its lamports intact.
+    if account_info.key == destination_info.key {
+        msg!("Cannot close account to itself");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Zero out the data before truncating it so stale state cannot be read
+    // if the account is revived within the same transaction.
+    account_info.try_borrow_mut_data()?.fill(0);
+    account_info.resize(0)?;
+
+    // Only the owning program can reassign the account, which is allowed
+    // because its data is now empty.
+    account_info.assign(&solana_system_interface::program::ID);
+
+    let mut account_lamports = account_info.try_borrow_mut_lamports()?;
+    let mut destination_lamports = destination_info.try_borrow_mut_lamports()?;
+
+    let closed_lamports = **account_lamports;
+    **destination_lamports = destination_lamports
+        .checked_add(closed_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **account_lamports = 0;
+
+    Ok(closed_lamports)
+}
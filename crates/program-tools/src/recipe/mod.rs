@@ -1,3 +1,4 @@
+pub mod close_account;
 pub mod create_account;
 pub mod create_token_account;
 
@@ -3,9 +3,42 @@ use ruint::aliases::U64;
 
 pub type Flags = U64;
 
+/// Length of each word in a [StorageGap].
+pub const STORAGE_GAP_WORD_LEN: usize = 32;
+
+/// Reserved space in a Pod struct, which allows fields to be added to existing
+/// accounts without reallocating them.
+///
+/// When adding fields, carve them out of the front of the gap, pad them to the
+/// next word and shrink the gap by the words used. For example, adding a `u64`
+/// to a struct ending with `StorageGap<8>`:
+///
+/// ```ignore
+/// pub new_field: u64,
+/// _padding_1: [u8; storage_gap_padding_len(size_of::<u64>())],
+///
+/// _storage_gap: StorageGap<{ remaining_storage_gap!(8; u64) }>,
+/// ```
+///
+/// Use [assert_field_offset] to pin the new fields to where the gap used to
+/// start.
+///
+/// [assert_field_offset]: crate::assert_field_offset
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C)]
-pub struct StorageGap<const N: usize>([[u8; 32]; N]);
+pub struct StorageGap<const N: usize>([[u8; STORAGE_GAP_WORD_LEN]; N]);
+
+impl<const N: usize> StorageGap<N> {
+    /// Size of this gap in bytes.
+    pub const LEN: usize = N * STORAGE_GAP_WORD_LEN;
+
+    /// Whether this gap is all zeros. Fields carved out of a gap rely on
+    /// existing accounts having zeros there, so new fields read as their
+    /// default values.
+    pub fn is_zeroed(&self) -> bool {
+        self.0.iter().flatten().all(|byte| *byte == 0)
+    }
+}
 
 impl<const N: usize> Default for StorageGap<N> {
     fn default() -> Self {
@@ -22,4 +55,134 @@ macro_rules! impl_storage_gap_pod_zeroable {
     };
 }
 
-impl_storage_gap_pod_zeroable!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
+// An empty gap is allowed so a struct can keep its gap field after every word
+// has been carved out.
+impl_storage_gap_pod_zeroable!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
+
+/// Number of padding bytes needed after fields carved out of a storage gap so
+/// the remaining gap starts on a word boundary.
+pub const fn storage_gap_padding_len(carved_len: usize) -> usize {
+    carved_len.next_multiple_of(STORAGE_GAP_WORD_LEN) - carved_len
+}
+
+/// Number of words left in a storage gap of `gap_len` words after carving out
+/// fields totaling `carved_len` bytes (plus padding to the next word).
+///
+/// Panics if the fields do not fit, which fails compilation when evaluated in a
+/// const context.
+pub const fn remaining_storage_gap_len(gap_len: usize, carved_len: usize) -> usize {
+    let words_used = carved_len.div_ceil(STORAGE_GAP_WORD_LEN);
+    assert!(words_used <= gap_len, "Carved fields exceed storage gap");
+
+    gap_len - words_used
+}
+
+/// Compute the number of words left in a storage gap after carving out fields
+/// of the given types. Meant to be used as the length of the new gap, e.g.
+/// `StorageGap<{ remaining_storage_gap!(8; u64, Pubkey) }>`.
+#[macro_export]
+macro_rules! remaining_storage_gap {
+    ($gap_len:expr; $($ty:ty),+ $(,)?) => {
+        $crate::types::remaining_storage_gap_len(
+            $gap_len,
+            0 $(+ ::core::mem::size_of::<$ty>())+,
+        )
+    };
+}
+
+/// Assert at compile time that a field starts at the expected offset within
+/// its struct. The offset does not include the account discriminator.
+#[macro_export]
+macro_rules! assert_field_offset {
+    ($struct:ty, $field:ident, $offset:expr $(,)?) => {
+        const _: () = assert!(
+            ::core::mem::offset_of!($struct, $field) == $offset,
+            concat!(
+                "Unexpected offset for ",
+                stringify!($struct),
+                "::",
+                stringify!($field)
+            )
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Default, Pod, Zeroable)]
+    #[repr(C)]
+    struct Before {
+        a: u64,
+        _storage_gap: StorageGap<4>,
+    }
+
+    #[derive(Clone, Copy, Default, Pod, Zeroable)]
+    #[repr(C)]
+    struct After {
+        a: u64,
+        b: u32,
+        _padding: [u8; 4],
+        c: [u8; 32],
+        _padding_1: [u8; storage_gap_padding_len(40)],
+        _storage_gap: StorageGap<{ remaining_storage_gap!(4; u32, [u8; 4], [u8; 32]) }>,
+    }
+
+    #[derive(Clone, Copy, Default, Pod, Zeroable)]
+    #[repr(C)]
+    struct Full {
+        a: u64,
+        b: [u8; 32],
+        c: [[u8; 32]; 3],
+        _storage_gap: StorageGap<{ remaining_storage_gap!(4; [u8; 32], [[u8; 32]; 3]) }>,
+    }
+
+    crate::assert_field_offset!(After, b, 8);
+    crate::assert_field_offset!(After, c, 16);
+    crate::assert_field_offset!(After, _storage_gap, 72);
+
+    #[test]
+    fn test_storage_gap_carving() {
+        assert_eq!(StorageGap::<4>::LEN, 128);
+        assert_eq!(size_of::<After>(), size_of::<Before>());
+        assert_eq!(size_of::<Full>(), size_of::<Before>());
+        assert_eq!(StorageGap::<0>::LEN, 0);
+
+        assert_eq!(storage_gap_padding_len(0), 0);
+        assert_eq!(storage_gap_padding_len(1), 31);
+        assert_eq!(storage_gap_padding_len(32), 0);
+        assert_eq!(storage_gap_padding_len(44), 20);
+
+        assert_eq!(remaining_storage_gap_len(4, 0), 4);
+        assert_eq!(remaining_storage_gap_len(4, 32), 3);
+        assert_eq!(remaining_storage_gap_len(4, 33), 2);
+        assert_eq!(remaining_storage_gap_len(4, 128), 0);
+        assert_eq!(remaining_storage_gap!(8; u64, [u8; 32]), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "Carved fields exceed storage gap")]
+    fn test_cannot_carve_past_storage_gap() {
+        remaining_storage_gap_len(1, 33);
+    }
+
+    #[test]
+    fn test_storage_gap_is_zeroed() {
+        // Carved fields are read from the zeroed gap of an existing account.
+        let before = Before {
+            a: 69,
+            ..Default::default()
+        };
+        assert!(before._storage_gap.is_zeroed());
+
+        let after = bytemuck::cast::<_, After>(before);
+        assert_eq!(after.a, 69);
+        assert_eq!(after.b, 0);
+        assert_eq!(after.c, [0; 32]);
+
+        let mut gap = StorageGap::<2>::default();
+        gap.0[1][31] = 1;
+        assert!(!gap.is_zeroed());
+    }
+}
@@ -18,7 +18,7 @@ use crate::{
     Discriminator, PrecomputedDiscriminator, DISCRIMINATOR_LEN,
 };
 
-use super::data_range;
+use super::{data_range, try_upgrade_data, VersionedAccount};
 
 #[derive(Debug)]
 pub struct ZeroCopyAccount<'a, 'b, T: Pod + PrecomputedDiscriminator> {
@@ -205,6 +205,58 @@ pub fn try_initialize<'a, T: Default + Pod + PrecomputedDiscriminator>(
     Ok((mucked_data, remaining_data))
 }
 
+/// Initialize a versioned account by writing both its discriminator and its
+/// version.
+pub fn try_initialize_versioned<'a, T: Default + VersionedAccount>(
+    account_info: &'a AccountInfo<'_>,
+) -> Result<(RefMut<'a, T>, RefMut<'a, [u8]>), ProgramError> {
+    let (mut mucked_data, remaining_data) = try_initialize::<T>(account_info)?;
+    bytemuck::bytes_of_mut(&mut *mucked_data)[T::VERSION_OFFSET] = T::VERSION;
+
+    Ok((mucked_data, remaining_data))
+}
+
+/// Upgrade an account's data from `TOld` to `TNew` in place. If the data
+/// length changes, the account is resized. The caller is responsible for
+/// keeping the account rent-exempt after it is resized.
+pub fn try_upgrade_in_place<'a, TOld, TNew>(
+    account_info: &'a AccountInfo<'_>,
+    upgrade: impl FnOnce(&TOld) -> TNew,
+) -> Result<(RefMut<'a, TNew>, RefMut<'a, [u8]>), ProgramError>
+where
+    TOld: VersionedAccount,
+    TNew: VersionedAccount,
+{
+    let upgraded_data = {
+        let data = try_borrow_data(account_info)?;
+
+        try_upgrade_data(&data, upgrade).ok_or_else(|| {
+            msg!(
+                "Cannot upgrade account {} from version {} to {}",
+                account_info.key,
+                TOld::VERSION,
+                TNew::VERSION
+            );
+            ProgramError::InvalidAccountData
+        })?
+    };
+
+    if upgraded_data.len() != account_info.data_len() {
+        account_info.resize(upgraded_data.len())?;
+    }
+
+    let mut data = try_borrow_mut_data(account_info)?;
+    data.copy_from_slice(&upgraded_data);
+
+    let RefMutSplit {
+        mucked_data,
+        remaining_data,
+        ..
+    } = RefMutSplit::try_new(data)?;
+
+    Ok((mucked_data, remaining_data))
+}
+
 //
 // Helpers.
 //
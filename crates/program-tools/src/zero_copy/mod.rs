@@ -10,6 +10,18 @@ use bytemuck::Pod;
 
 use crate::{PrecomputedDiscriminator, DISCRIMINATOR_LEN};
 
+/// Account layouts that encode their version in a `u8` field. This field
+/// should take over bytes that were padding or storage gap in the unversioned
+/// layout so that existing accounts read as version 0 without changing their
+/// discriminator or field offsets.
+pub trait VersionedAccount: Pod + PrecomputedDiscriminator {
+    const VERSION: u8;
+
+    /// Byte offset of the version field within the account layout (excluding
+    /// the discriminator).
+    const VERSION_OFFSET: usize;
+}
+
 pub const fn data_end<T: Pod + PrecomputedDiscriminator>() -> usize {
     DISCRIMINATOR_LEN + size_of::<T>()
 }
@@ -31,3 +43,133 @@ where
         None
     }
 }
+
+/// Returns the version encoded in the account data if the data has the
+/// expected discriminator.
+pub fn checked_version<T: VersionedAccount>(data: &[u8]) -> Option<u8> {
+    if T::has_discriminator(data) {
+        data.get(DISCRIMINATOR_LEN + T::VERSION_OFFSET).copied()
+    } else {
+        None
+    }
+}
+
+/// Re-encode account data from `TOld` to `TNew`. Any data following the old
+/// account layout is preserved after the new layout. Returns `None` if the data
+/// is not encoded as `TOld` or if `TNew` is not a newer version.
+pub fn try_upgrade_data<TOld, TNew>(
+    data: &[u8],
+    upgrade: impl FnOnce(&TOld) -> TNew,
+) -> Option<Vec<u8>>
+where
+    TOld: VersionedAccount,
+    TNew: VersionedAccount,
+{
+    if TNew::VERSION <= TOld::VERSION || checked_version::<TOld>(data)? != TOld::VERSION {
+        return None;
+    }
+
+    let (old_data, remaining_data) = data.split_at_checked(data_end::<TOld>())?;
+    let old = bytemuck::pod_read_unaligned::<TOld>(&old_data[DISCRIMINATOR_LEN..]);
+
+    let mut new = upgrade(&old);
+    bytemuck::bytes_of_mut(&mut new)[TNew::VERSION_OFFSET] = TNew::VERSION;
+
+    let mut upgraded_data = Vec::with_capacity(data_end::<TNew>() + remaining_data.len());
+    upgraded_data.extend_from_slice(TNew::discriminator_slice());
+    upgraded_data.extend_from_slice(bytemuck::bytes_of(&new));
+    upgraded_data.extend_from_slice(remaining_data);
+
+    Some(upgraded_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+    use crate::Discriminator;
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+    #[repr(C)]
+    struct TestAccountV0 {
+        value: u64,
+        version: u8,
+        _padding: [u8; 7],
+    }
+
+    impl PrecomputedDiscriminator for TestAccountV0 {
+        const DISCRIMINATOR: Discriminator<8> = Discriminator::new_sha2(b"dz::account::test");
+    }
+
+    impl VersionedAccount for TestAccountV0 {
+        const VERSION: u8 = 0;
+        const VERSION_OFFSET: usize = core::mem::offset_of!(TestAccountV0, version);
+    }
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+    #[repr(C)]
+    struct TestAccountV1 {
+        value: u64,
+        version: u8,
+        _padding: [u8; 7],
+        new_value: u64,
+    }
+
+    impl PrecomputedDiscriminator for TestAccountV1 {
+        const DISCRIMINATOR: Discriminator<8> = Discriminator::new_sha2(b"dz::account::test");
+    }
+
+    impl VersionedAccount for TestAccountV1 {
+        const VERSION: u8 = 1;
+        const VERSION_OFFSET: usize = core::mem::offset_of!(TestAccountV1, version);
+    }
+
+    #[test]
+    fn test_try_upgrade_data() {
+        let old = TestAccountV0 {
+            value: 69,
+            ..Default::default()
+        };
+
+        let mut data = TestAccountV0::discriminator_slice().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&old));
+        data.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(checked_version::<TestAccountV0>(&data), Some(0));
+
+        let upgraded_data = try_upgrade_data(&data, |old: &TestAccountV0| TestAccountV1 {
+            value: old.value,
+            new_value: 420,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(upgraded_data.len(), data_end::<TestAccountV1>() + 3);
+        assert_eq!(checked_version::<TestAccountV1>(&upgraded_data), Some(1));
+
+        let new = bytemuck::pod_read_unaligned::<TestAccountV1>(
+            &upgraded_data[data_range::<TestAccountV1>()],
+        );
+        assert_eq!(
+            new,
+            TestAccountV1 {
+                version: 1,
+                value: 69,
+                new_value: 420,
+                ..Default::default()
+            }
+        );
+        assert_eq!(&upgraded_data[data_end::<TestAccountV1>()..], &[1, 2, 3]);
+
+        // Cannot upgrade data that has already been upgraded.
+        assert!(try_upgrade_data(&upgraded_data, |_: &TestAccountV0| {
+            TestAccountV1::default()
+        })
+        .is_none());
+
+        // Cannot downgrade.
+        assert!(try_upgrade_data(&upgraded_data, |_: &TestAccountV1| {
+            TestAccountV0::default()
+        })
+        .is_none());
+    }
+}
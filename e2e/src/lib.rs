@@ -0,0 +1,3 @@
+//! End-to-end scenarios exercising the Passport and Revenue Distribution
+//! programs together. See the integration tests in `tests/`, which require
+//! the programs to be built with `cargo build-sbf` first.
@@ -0,0 +1,309 @@
+#![allow(dead_code)]
+
+#[ctor::ctor]
+fn init_logger() {
+    static ONCE: std::sync::Once = std::sync::Once::new();
+    ONCE.call_once(|| {
+        let mut builder = env_logger::builder();
+
+        // If DEBUG is set, show the Solana program logs.
+        if std::env::var_os("DEBUG").is_some() {
+            builder.filter_level(log::LevelFilter::Error);
+            builder.filter(
+                Some("solana_runtime::message_processor::stable_log"),
+                log::LevelFilter::Debug,
+            );
+        }
+
+        let _ = builder.try_init();
+    });
+}
+
+use bytemuck::Pod;
+use doublezero_program_tools::{
+    zero_copy::checked_from_bytes_with_discriminator, PrecomputedDiscriminator,
+};
+use doublezero_revenue_distribution::DOUBLEZERO_MINT_KEY;
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_program_pack::Pack;
+use solana_program_test::{
+    BanksClient, BanksClientError, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext,
+};
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0::Message, VersionedMessage},
+    signature::{Keypair, Signer},
+    transaction::VersionedTransaction,
+};
+use spl_token_interface::{
+    instruction as token_instruction,
+    state::{Account as TokenAccount, AccountState as SplTokenAccountState, Mint},
+};
+
+pub const TOTAL_2Z_SUPPLY: u64 = 10_000_000_000 * u64::pow(10, 8);
+
+/// Both the Passport and Revenue Distribution programs are loaded with the
+/// same upgrade authority (the owner).
+pub struct ProgramTestWithOwner {
+    pub context: ProgramTestContext,
+    pub owner_signer: Keypair,
+    pub treasury_2z_key: Pubkey,
+    pub sol_2z_swap_fills_registry_key: Pubkey,
+}
+
+pub async fn start_test() -> ProgramTestWithOwner {
+    let mut program_test = ProgramTest::new(
+        "doublezero_revenue_distribution",
+        doublezero_revenue_distribution::ID,
+        None,
+    );
+    program_test.prefer_bpf(true);
+
+    program_test.add_program("doublezero_passport", doublezero_passport::ID, None);
+    program_test.add_program("mock_swap_sol_2z", mock_swap_sol_2z::ID, None);
+
+    let owner_signer = Keypair::new();
+
+    // Fake the BPF Upgradeable Program's program data accounts for both
+    // programs.
+    for program_id in [doublezero_passport::ID, doublezero_revenue_distribution::ID] {
+        let program_data_acct = Account {
+            lamports: 69,
+            data: bincode::serialize(&UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(owner_signer.pubkey()),
+            })
+            .unwrap(),
+            ..Default::default()
+        };
+        program_test.add_account(get_program_data_address(&program_id), program_data_acct);
+    }
+
+    let mint_data = Mint {
+        mint_authority: owner_signer.pubkey().into(),
+        supply: TOTAL_2Z_SUPPLY,
+        decimals: 8,
+        is_initialized: true,
+        freeze_authority: owner_signer.pubkey().into(),
+    };
+
+    let mut mint_account_data = vec![0; Mint::LEN];
+    mint_data.pack_into_slice(&mut mint_account_data);
+
+    // Add the 2Z mint.
+    let mint_acct = Account {
+        lamports: 69,
+        owner: spl_token_interface::ID,
+        data: mint_account_data,
+        ..Default::default()
+    };
+    program_test.add_account(DOUBLEZERO_MINT_KEY, mint_acct);
+
+    let treasury_token_account_data = TokenAccount {
+        mint: DOUBLEZERO_MINT_KEY,
+        owner: owner_signer.pubkey(),
+        amount: TOTAL_2Z_SUPPLY,
+        state: SplTokenAccountState::Initialized,
+        ..Default::default()
+    };
+
+    let mut treasury_account_data = vec![0; TokenAccount::LEN];
+    treasury_token_account_data.pack_into_slice(&mut treasury_account_data);
+
+    let treasury_2z_key = Pubkey::new_unique();
+
+    // Add 2Z test treasury.
+    let treasury_token_acct = Account {
+        lamports: 69,
+        owner: spl_token_interface::ID,
+        data: treasury_account_data,
+        ..Default::default()
+    };
+    program_test.add_account(treasury_2z_key, treasury_token_acct);
+
+    let mut context = program_test.start_with_context().await;
+
+    let sol_2z_swap_fills_registry_signer = Keypair::new();
+    let sol_2z_swap_fills_registry_key = sol_2z_swap_fills_registry_signer.pubkey();
+
+    // Initialize the mock swap sol 2z program's fills tracker.
+    let (create_account_ix, initialize_fills_tracker_ix) =
+        mock_swap_sol_2z::instruction::create_and_initialize_fills_tracker(
+            &context.payer.pubkey(),
+            &sol_2z_swap_fills_registry_key,
+        );
+
+    context.last_blockhash = process_instructions_for_test(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        &[create_account_ix, initialize_fills_tracker_ix],
+        &[&context.payer, &sol_2z_swap_fills_registry_signer],
+    )
+    .await
+    .unwrap();
+
+    ProgramTestWithOwner {
+        context,
+        owner_signer,
+        treasury_2z_key,
+        sol_2z_swap_fills_registry_key,
+    }
+}
+
+impl ProgramTestWithOwner {
+    pub fn payer_key(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    pub async fn get_clock(&self) -> Clock {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+    }
+
+    pub async fn warp_timestamp_by(&mut self, seconds: u32) -> Result<&mut Self, BanksClientError> {
+        let mut clock = self.get_clock().await;
+        clock.unix_timestamp += i64::from(seconds);
+        self.context.set_sysvar::<Clock>(&clock);
+
+        Ok(self)
+    }
+
+    /// Process instructions built by the caller. The payer always signs, so
+    /// only additional signers need to be passed in.
+    pub async fn process_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let mut tx_signers = vec![payer_signer];
+        tx_signers.extend_from_slice(signers);
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            instructions,
+            &tx_signers,
+        )
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn transfer_lamports(
+        &mut self,
+        dst_key: &Pubkey,
+        amount: u64,
+    ) -> Result<&mut Self, BanksClientError> {
+        let transfer_ix =
+            solana_system_interface::instruction::transfer(&self.payer_key(), dst_key, amount);
+
+        self.process_instructions(&[transfer_ix], &[]).await
+    }
+
+    pub async fn create_2z_ata(
+        &mut self,
+        owner_key: &Pubkey,
+    ) -> Result<&mut Self, BanksClientError> {
+        // No consequence if the ATA already exists.
+        let create_ix = spl_associated_token_account_interface::instruction::create_associated_token_account_idempotent(
+            &self.payer_key(),
+            owner_key,
+            &DOUBLEZERO_MINT_KEY,
+            &spl_token_interface::ID,
+        );
+
+        self.process_instructions(&[create_ix], &[]).await
+    }
+
+    pub async fn transfer_2z(
+        &mut self,
+        dst_token_account_key: &Pubkey,
+        amount: u64,
+    ) -> Result<&mut Self, BanksClientError> {
+        let owner_signer = self.owner_signer.insecure_clone();
+
+        let token_transfer_ix = token_instruction::transfer(
+            &spl_token_interface::ID,
+            &self.treasury_2z_key,
+            dst_token_account_key,
+            &owner_signer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+
+        self.process_instructions(&[token_transfer_ix], &[&owner_signer])
+            .await
+    }
+
+    //
+    // Account fetchers.
+    //
+
+    /// Fetch a zero-copy account owned by either program. Returns `None` if
+    /// the account does not exist.
+    pub async fn fetch_zero_copy_account<T: Pod + PrecomputedDiscriminator>(
+        &self,
+        account_key: &Pubkey,
+    ) -> Option<T> {
+        let account_info = self
+            .context
+            .banks_client
+            .get_account(*account_key)
+            .await
+            .unwrap()?;
+
+        checked_from_bytes_with_discriminator(&account_info.data).map(|(account, _)| *account)
+    }
+
+    pub async fn fetch_token_account(&self, token_account_key: &Pubkey) -> TokenAccount {
+        let token_account_data = self
+            .context
+            .banks_client
+            .get_account(*token_account_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        TokenAccount::unpack(&token_account_data).unwrap()
+    }
+}
+
+pub async fn process_instructions_for_test(
+    banks_client: &mut BanksClient,
+    cached_blockhash: &Hash,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<Hash, BanksClientError> {
+    let recent_blockhash = banks_client
+        .get_new_latest_blockhash(cached_blockhash)
+        .await
+        .map_err(|_| BanksClientError::ClientError("failed to get new blockhash"))?;
+
+    let transaction = new_transaction(instructions, signers, recent_blockhash);
+
+    banks_client.process_transaction(transaction).await?;
+
+    Ok(recent_blockhash)
+}
+
+fn new_transaction(
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+    recent_blockhash: Hash,
+) -> VersionedTransaction {
+    let message =
+        Message::try_compile(&signers[0].pubkey(), instructions, &[], recent_blockhash).unwrap();
+
+    VersionedTransaction::try_new(VersionedMessage::V0(message), signers).unwrap()
+}
@@ -0,0 +1,647 @@
+mod common;
+
+//
+
+use doublezero_passport::{
+    instruction::{
+        account::{
+            ConfigureProgramAccounts as PassportConfigureProgramAccounts, GrantAccessAccounts,
+            InitializeProgramAccounts as PassportInitializeProgramAccounts, RequestAccessAccounts,
+            SetAdminAccounts as PassportSetAdminAccounts,
+        },
+        AccessMode, PassportInstructionData, ProgramConfiguration as PassportProgramConfiguration,
+        ProgramFlagConfiguration as PassportProgramFlagConfiguration, SolanaValidatorAttestation,
+    },
+    state::{AccessRequest, ProgramConfig as PassportProgramConfig},
+};
+use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
+use doublezero_revenue_distribution::{
+    instruction::{
+        account::{
+            ConfigureContributorRewardsAccounts, ConfigureDistributionDebtAccounts,
+            ConfigureDistributionRewardsAccounts, ConfigureProgramAccounts,
+            DistributeRewardsAccounts, FinalizeDistributionDebtAccounts,
+            FinalizeDistributionRewardsAccounts, InitializeContributorRewardsAccounts,
+            InitializeDistributionAccounts, InitializeJournalAccounts, InitializeProgramAccounts,
+            InitializeSolanaValidatorDepositAccounts, InitializeSwapDestinationAccounts,
+            PaySolanaValidatorDebtAccounts, SetAdminAccounts, SetRewardsManagerAccounts,
+            SweepDistributionTokensAccounts,
+        },
+        ContributorRewardsConfiguration, ProgramConfiguration, ProgramFlagConfiguration,
+        RevenueDistributionInstructionData,
+    },
+    state::{
+        Distribution, Journal, PaymentReceipt, ProgramConfig, SolanaValidatorDeposit, SwapSession,
+    },
+    types::{DoubleZeroEpoch, RewardShare, SolanaValidatorDebt},
+    DOUBLEZERO_MINT_KEY,
+};
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    rent::Rent,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account_interface::address::get_associated_token_address;
+use svm_hash::merkle::{merkle_root_from_indexed_pod_leaves, MerkleProof};
+
+//
+// Constants (round numbers to avoid rounding issues).
+//
+
+const ACCESS_REQUEST_DEPOSIT_LAMPORTS: u64 = 10_000_000;
+const ACCESS_REQUEST_FEE_LAMPORTS: u64 = 10_000;
+const INITIAL_CBR: u32 = 100_000_000; // 10%.
+const DISTRIBUTE_REWARDS_RELAY_LAMPORTS: u32 = 128 * 6_960;
+const SOLANA_VALIDATOR_DEBT_AMOUNT: u64 = 10_000_000_000; // 10 SOL.
+const SWEPT_2Z_AMOUNT: u64 = 9_000 * 100_000_000; // 9,000 2Z.
+
+//
+// Full lifecycle — a Solana validator is granted access via the Passport
+// program, pays its debt to the Revenue Distribution program and the swapped
+// 2Z is distributed to a contributor.
+//
+
+#[tokio::test]
+async fn test_solana_validator_lifecycle() {
+    let mut test_setup = common::start_test().await;
+
+    let owner_signer = test_setup.owner_signer.insecure_clone();
+    let payer_key = test_setup.payer_key();
+
+    //
+    // Passport: configure the program, request access and grant it.
+    //
+
+    let passport_admin_signer = Keypair::new();
+    let sentinel_signer = Keypair::new();
+
+    let passport_program_config_key = PassportProgramConfig::find_address().0;
+
+    let passport_configure_program_ixs = [
+        PassportProgramConfiguration::Flag(PassportProgramFlagConfiguration::IsPaused(false)),
+        PassportProgramConfiguration::DoubleZeroLedgerSentinel(sentinel_signer.pubkey()),
+        PassportProgramConfiguration::AccessRequestDeposit {
+            request_deposit_lamports: ACCESS_REQUEST_DEPOSIT_LAMPORTS,
+            request_fee_lamports: ACCESS_REQUEST_FEE_LAMPORTS,
+        },
+    ]
+    .into_iter()
+    .map(|setting| {
+        try_build_instruction(
+            &doublezero_passport::ID,
+            PassportConfigureProgramAccounts::new(&passport_admin_signer.pubkey()),
+            &PassportInstructionData::ConfigureProgram(setting),
+        )
+        .unwrap()
+    })
+    .collect::<Vec<_>>();
+
+    test_setup
+        .transfer_lamports(&sentinel_signer.pubkey(), 128 * 6_960)
+        .await
+        .unwrap()
+        .process_instructions(
+            &[
+                solana_system_interface::instruction::transfer(
+                    &payer_key,
+                    &passport_program_config_key,
+                    1,
+                ),
+                try_build_instruction(
+                    &doublezero_passport::ID,
+                    PassportInitializeProgramAccounts::new(&payer_key),
+                    &PassportInstructionData::InitializeProgram,
+                )
+                .unwrap(),
+                try_build_instruction(
+                    &doublezero_passport::ID,
+                    PassportSetAdminAccounts::new(&doublezero_passport::ID, &owner_signer.pubkey()),
+                    &PassportInstructionData::SetAdmin(passport_admin_signer.pubkey()),
+                )
+                .unwrap(),
+            ],
+            &[&owner_signer],
+        )
+        .await
+        .unwrap()
+        .process_instructions(&passport_configure_program_ixs, &[&passport_admin_signer])
+        .await
+        .unwrap();
+
+    let node_id = Pubkey::new_unique();
+    let service_key = Pubkey::new_unique();
+
+    let access_mode = AccessMode::SolanaValidator(SolanaValidatorAttestation {
+        validator_id: node_id,
+        service_key,
+        ed25519_signature: [1; 64],
+    });
+
+    test_setup
+        .process_instructions(
+            &[try_build_instruction(
+                &doublezero_passport::ID,
+                RequestAccessAccounts::new(&payer_key, &service_key, None, false),
+                &PassportInstructionData::RequestAccess(access_mode.clone()),
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap();
+
+    // The sentinel reads the attested validator identity from the request
+    // before granting access.
+    let access_request_key = AccessRequest::find_address(&service_key).0;
+    let access_request = test_setup
+        .fetch_zero_copy_account::<AccessRequest>(&access_request_key)
+        .await
+        .unwrap();
+    assert_eq!(access_request.service_key, service_key);
+    assert_eq!(access_request.checked_access_mode(), Some(access_mode));
+
+    test_setup
+        .process_instructions(
+            &[try_build_instruction(
+                &doublezero_passport::ID,
+                GrantAccessAccounts::new(
+                    &sentinel_signer.pubkey(),
+                    &access_request_key,
+                    &payer_key,
+                ),
+                &PassportInstructionData::GrantAccess,
+            )
+            .unwrap()],
+            &[&sentinel_signer],
+        )
+        .await
+        .unwrap();
+
+    assert!(test_setup
+        .fetch_zero_copy_account::<AccessRequest>(&access_request_key)
+        .await
+        .is_none());
+
+    //
+    // Revenue Distribution: configure the program.
+    //
+
+    let admin_signer = Keypair::new();
+    let contributor_manager_signer = Keypair::new();
+    let debt_accountant_signer = Keypair::new();
+    let rewards_accountant_signer = Keypair::new();
+    let rewards_manager_signer = Keypair::new();
+    let transfer_authority_signer = Keypair::new();
+
+    let program_config_key = ProgramConfig::find_address().0;
+    let journal_key = Journal::find_address().0;
+    let src_token_account_key =
+        get_associated_token_address(&transfer_authority_signer.pubkey(), &DOUBLEZERO_MINT_KEY);
+
+    let configure_program_ixs = [
+        ProgramConfiguration::Sol2zSwapProgram(mock_swap_sol_2z::ID),
+        ProgramConfiguration::ContributorManager(contributor_manager_signer.pubkey()),
+        ProgramConfiguration::DebtAccountant(debt_accountant_signer.pubkey()),
+        ProgramConfiguration::RewardsAccountant(rewards_accountant_signer.pubkey()),
+        ProgramConfiguration::SolanaValidatorFeeParameters {
+            base_block_rewards_pct: 500,
+            priority_block_rewards_pct: 0,
+            inflation_rewards_pct: 0,
+            jito_tips_pct: 0,
+            fixed_sol_amount: 0,
+            _unused: Default::default(),
+        },
+        ProgramConfiguration::CommunityBurnRateParameters {
+            limit: 500_000_000,
+            dz_epochs_to_increasing: 10,
+            dz_epochs_to_limit: 20,
+            initial_rate: Some(INITIAL_CBR),
+        },
+        ProgramConfiguration::DistributeRewardsRelayLamports(DISTRIBUTE_REWARDS_RELAY_LAMPORTS),
+        ProgramConfiguration::MinimumEpochDurationToFinalizeRewards(1),
+        ProgramConfiguration::CalculationGracePeriodMinutes(1),
+        ProgramConfiguration::DistributionInitializationGracePeriodMinutes(1),
+        ProgramConfiguration::Flag(ProgramFlagConfiguration::IsPaused(false)),
+    ]
+    .into_iter()
+    .map(|setting| {
+        let with_fee_parameter_history = matches!(
+            setting,
+            ProgramConfiguration::SolanaValidatorFeeParameters { .. }
+        );
+
+        try_build_instruction(
+            &doublezero_revenue_distribution::ID,
+            ConfigureProgramAccounts::new(&admin_signer.pubkey(), with_fee_parameter_history),
+            &RevenueDistributionInstructionData::ConfigureProgram(setting),
+        )
+        .unwrap()
+    })
+    .collect::<Vec<_>>();
+
+    test_setup
+        .create_2z_ata(&transfer_authority_signer.pubkey())
+        .await
+        .unwrap()
+        .transfer_2z(&src_token_account_key, SWEPT_2Z_AMOUNT)
+        .await
+        .unwrap()
+        .process_instructions(
+            &[
+                solana_system_interface::instruction::transfer(&payer_key, &program_config_key, 1),
+                try_build_instruction(
+                    &doublezero_revenue_distribution::ID,
+                    InitializeProgramAccounts::new(&payer_key, &DOUBLEZERO_MINT_KEY),
+                    &RevenueDistributionInstructionData::InitializeProgram,
+                )
+                .unwrap(),
+                solana_system_interface::instruction::transfer(&payer_key, &journal_key, 1),
+                try_build_instruction(
+                    &doublezero_revenue_distribution::ID,
+                    InitializeJournalAccounts::new(&payer_key, &DOUBLEZERO_MINT_KEY),
+                    &RevenueDistributionInstructionData::InitializeJournal,
+                )
+                .unwrap(),
+            ],
+            &[],
+        )
+        .await
+        .unwrap()
+        .create_2z_ata(&journal_key)
+        .await
+        .unwrap()
+        .process_instructions(
+            &[
+                try_build_instruction(
+                    &doublezero_revenue_distribution::ID,
+                    InitializeSwapDestinationAccounts::new(&payer_key, &DOUBLEZERO_MINT_KEY),
+                    &RevenueDistributionInstructionData::InitializeSwapDestination,
+                )
+                .unwrap(),
+                try_build_instruction(
+                    &doublezero_revenue_distribution::ID,
+                    SetAdminAccounts::new(
+                        &doublezero_revenue_distribution::ID,
+                        &owner_signer.pubkey(),
+                    ),
+                    &RevenueDistributionInstructionData::SetAdmin(admin_signer.pubkey()),
+                )
+                .unwrap(),
+            ],
+            &[&owner_signer],
+        )
+        .await
+        .unwrap()
+        .process_instructions(&configure_program_ixs, &[&admin_signer])
+        .await
+        .unwrap();
+
+    //
+    // Revenue Distribution: initialize distributions and finalize debt. The
+    // granted validator is the only one with debt for the DZ epoch.
+    //
+
+    let genesis_dz_epoch = DoubleZeroEpoch::new(0);
+    let dz_epoch = DoubleZeroEpoch::new(1);
+    let next_dz_epoch = DoubleZeroEpoch::new(2);
+
+    let debt_data = [SolanaValidatorDebt {
+        node_id,
+        amount: SOLANA_VALIDATOR_DEBT_AMOUNT,
+    }];
+    let debt_merkle_root =
+        merkle_root_from_indexed_pod_leaves(&debt_data, Some(SolanaValidatorDebt::LEAF_PREFIX))
+            .unwrap();
+    let debt_proof =
+        MerkleProof::from_indexed_pod_leaves(&debt_data, 0, Some(SolanaValidatorDebt::LEAF_PREFIX))
+            .unwrap();
+
+    let initialize_distribution_ix = |dz_epoch| {
+        try_build_instruction(
+            &doublezero_revenue_distribution::ID,
+            InitializeDistributionAccounts::new(
+                &debt_accountant_signer.pubkey(),
+                &payer_key,
+                dz_epoch,
+                &DOUBLEZERO_MINT_KEY,
+            ),
+            &RevenueDistributionInstructionData::InitializeDistribution,
+        )
+        .unwrap()
+    };
+
+    let finalize_distribution_debt_ix = |dz_epoch| {
+        try_build_instruction(
+            &doublezero_revenue_distribution::ID,
+            FinalizeDistributionDebtAccounts::new(
+                &debt_accountant_signer.pubkey(),
+                dz_epoch,
+                &payer_key,
+            ),
+            &RevenueDistributionInstructionData::FinalizeDistributionDebt,
+        )
+        .unwrap()
+    };
+
+    let finalize_distribution_rewards_ix = |dz_epoch| {
+        try_build_instruction(
+            &doublezero_revenue_distribution::ID,
+            FinalizeDistributionRewardsAccounts::new(&payer_key, dz_epoch),
+            &RevenueDistributionInstructionData::FinalizeDistributionRewards,
+        )
+        .unwrap()
+    };
+
+    let sol_2z_swap_fills_registry_key = test_setup.sol_2z_swap_fills_registry_key;
+
+    let sweep_distribution_tokens_ix = |dz_epoch| {
+        try_build_instruction(
+            &doublezero_revenue_distribution::ID,
+            SweepDistributionTokensAccounts::new(
+                dz_epoch,
+                &mock_swap_sol_2z::ID,
+                &sol_2z_swap_fills_registry_key,
+            ),
+            &RevenueDistributionInstructionData::SweepDistributionTokens,
+        )
+        .unwrap()
+    };
+
+    test_setup
+        .process_instructions(
+            &[initialize_distribution_ix(genesis_dz_epoch)],
+            &[&debt_accountant_signer],
+        )
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap()
+        .process_instructions(
+            &[initialize_distribution_ix(dz_epoch)],
+            &[&debt_accountant_signer],
+        )
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap()
+        .process_instructions(
+            &[
+                finalize_distribution_debt_ix(genesis_dz_epoch),
+                try_build_instruction(
+                    &doublezero_revenue_distribution::ID,
+                    ConfigureDistributionDebtAccounts::new(
+                        &debt_accountant_signer.pubkey(),
+                        dz_epoch,
+                    ),
+                    &RevenueDistributionInstructionData::ConfigureDistributionDebt {
+                        total_validators: 1,
+                        total_debt: SOLANA_VALIDATOR_DEBT_AMOUNT,
+                        merkle_root: debt_merkle_root,
+                    },
+                )
+                .unwrap(),
+                finalize_distribution_debt_ix(dz_epoch),
+            ],
+            &[&debt_accountant_signer],
+        )
+        .await
+        .unwrap()
+        .process_instructions(
+            &[initialize_distribution_ix(next_dz_epoch)],
+            &[&debt_accountant_signer],
+        )
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap()
+        .process_instructions(
+            &[
+                finalize_distribution_rewards_ix(genesis_dz_epoch),
+                sweep_distribution_tokens_ix(genesis_dz_epoch),
+            ],
+            &[],
+        )
+        .await
+        .unwrap();
+
+    //
+    // Revenue Distribution: the validator funds its deposit and pays its debt.
+    //
+
+    let deposit_key = SolanaValidatorDeposit::find_address(&node_id).0;
+
+    test_setup
+        .process_instructions(
+            &[try_build_instruction(
+                &doublezero_revenue_distribution::ID,
+                InitializeSolanaValidatorDepositAccounts::new(&payer_key, &node_id),
+                &RevenueDistributionInstructionData::InitializeSolanaValidatorDeposit(node_id),
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap()
+        .transfer_lamports(&deposit_key, SOLANA_VALIDATOR_DEBT_AMOUNT)
+        .await
+        .unwrap()
+        .process_instructions(
+            &[try_build_instruction(
+                &doublezero_revenue_distribution::ID,
+                PaySolanaValidatorDebtAccounts::new(dz_epoch, &node_id, Some(&payer_key)),
+                &RevenueDistributionInstructionData::PaySolanaValidatorDebt {
+                    amount: SOLANA_VALIDATOR_DEBT_AMOUNT,
+                    proof: debt_proof,
+                },
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let payment_receipt_key = PaymentReceipt::find_address(dz_epoch, &node_id).0;
+    let payment_receipt = test_setup
+        .fetch_zero_copy_account::<PaymentReceipt>(&payment_receipt_key)
+        .await
+        .unwrap();
+    assert_eq!(payment_receipt.amount, SOLANA_VALIDATOR_DEBT_AMOUNT);
+
+    //
+    // Revenue Distribution: contributor rewards are configured and the rewards
+    // merkle root is posted.
+    //
+
+    let contributor_key = Pubkey::new_unique();
+    let recipient_key = Pubkey::new_unique();
+
+    let rewards_data = [RewardShare::new(contributor_key, 1_000_000_000, false, 0).unwrap()];
+    let rewards_merkle_root =
+        merkle_root_from_indexed_pod_leaves(&rewards_data, Some(RewardShare::LEAF_PREFIX)).unwrap();
+    let rewards_proof =
+        MerkleProof::from_indexed_pod_leaves(&rewards_data, 0, Some(RewardShare::LEAF_PREFIX))
+            .unwrap();
+
+    test_setup
+        .process_instructions(
+            &[
+                try_build_instruction(
+                    &doublezero_revenue_distribution::ID,
+                    InitializeContributorRewardsAccounts::new(&payer_key, &contributor_key),
+                    &RevenueDistributionInstructionData::InitializeContributorRewards(
+                        contributor_key,
+                    ),
+                )
+                .unwrap(),
+                try_build_instruction(
+                    &doublezero_revenue_distribution::ID,
+                    SetRewardsManagerAccounts::new(
+                        &contributor_manager_signer.pubkey(),
+                        &contributor_key,
+                    ),
+                    &RevenueDistributionInstructionData::SetRewardsManager(
+                        rewards_manager_signer.pubkey(),
+                    ),
+                )
+                .unwrap(),
+            ],
+            &[&contributor_manager_signer],
+        )
+        .await
+        .unwrap()
+        .process_instructions(
+            &[try_build_instruction(
+                &doublezero_revenue_distribution::ID,
+                ConfigureContributorRewardsAccounts::new(
+                    &rewards_manager_signer.pubkey(),
+                    &contributor_key,
+                    None,
+                ),
+                &RevenueDistributionInstructionData::ConfigureContributorRewards(
+                    ContributorRewardsConfiguration::Recipients(vec![(recipient_key, 10_000)]),
+                ),
+            )
+            .unwrap()],
+            &[&rewards_manager_signer],
+        )
+        .await
+        .unwrap()
+        .process_instructions(
+            &[try_build_instruction(
+                &doublezero_revenue_distribution::ID,
+                ConfigureDistributionRewardsAccounts::new(
+                    &rewards_accountant_signer.pubkey(),
+                    dz_epoch,
+                ),
+                &RevenueDistributionInstructionData::ConfigureDistributionRewards {
+                    total_contributors: 1,
+                    merkle_root: rewards_merkle_root,
+                },
+            )
+            .unwrap()],
+            &[&rewards_accountant_signer],
+        )
+        .await
+        .unwrap();
+
+    //
+    // Swap the collected SOL for 2Z, sweep and distribute rewards. The
+    // recipient's ATA is created when rewards are distributed.
+    //
+
+    let sol_destination_key = Pubkey::new_unique();
+    let ata_payer_key = test_setup.context.payer.pubkey();
+    let relayer_key = Pubkey::new_unique();
+
+    let buy_sol_ix = mock_swap_sol_2z::instruction::buy_sol(
+        &sol_2z_swap_fills_registry_key,
+        &src_token_account_key,
+        &transfer_authority_signer.pubkey(),
+        &sol_destination_key,
+        SWEPT_2Z_AMOUNT,
+        SOLANA_VALIDATOR_DEBT_AMOUNT,
+    );
+
+    // The transfer authority pays for the swap session, which is refunded when
+    // SOL is withdrawn.
+    let swap_session_rent = Rent::default().minimum_balance(zero_copy::data_end::<SwapSession>());
+
+    test_setup
+        .transfer_lamports(&transfer_authority_signer.pubkey(), swap_session_rent)
+        .await
+        .unwrap()
+        .process_instructions(&[buy_sol_ix], &[&transfer_authority_signer])
+        .await
+        .unwrap()
+        .process_instructions(
+            &[
+                finalize_distribution_rewards_ix(dz_epoch),
+                sweep_distribution_tokens_ix(dz_epoch),
+            ],
+            &[],
+        )
+        .await
+        .unwrap()
+        .process_instructions(
+            &[try_build_instruction(
+                &doublezero_revenue_distribution::ID,
+                DistributeRewardsAccounts::new(
+                    dz_epoch,
+                    &contributor_key,
+                    &DOUBLEZERO_MINT_KEY,
+                    &relayer_key,
+                    &[&recipient_key],
+                    Some(&ata_payer_key),
+                    None, // bitmap_payer_key
+                ),
+                &RevenueDistributionInstructionData::DistributeRewards {
+                    unit_share: rewards_data[0].unit_share,
+                    economic_burn_rate: rewards_data[0].economic_burn_rate(),
+                    proof: rewards_proof,
+                },
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap();
+
+    //
+    // Check the end state across both programs.
+    //
+
+    let recipient_ata_key = get_associated_token_address(&recipient_key, &DOUBLEZERO_MINT_KEY);
+    let recipient_ata = test_setup.fetch_token_account(&recipient_ata_key).await;
+
+    // 10% of the swept 2Z is burned per the community burn rate.
+    assert_eq!(recipient_ata.amount, SWEPT_2Z_AMOUNT / 10 * 9);
+
+    let relayer_balance = test_setup
+        .context
+        .banks_client
+        .get_balance(relayer_key)
+        .await
+        .unwrap();
+    assert_eq!(
+        relayer_balance,
+        u64::from(DISTRIBUTE_REWARDS_RELAY_LAMPORTS)
+    );
+
+    let journal = test_setup
+        .fetch_zero_copy_account::<Journal>(&journal_key)
+        .await
+        .unwrap();
+    let distribution = test_setup
+        .fetch_zero_copy_account::<Distribution>(&Distribution::find_address(dz_epoch).0)
+        .await
+        .unwrap();
+    assert_eq!(
+        distribution.collected_solana_validator_payments,
+        SOLANA_VALIDATOR_DEBT_AMOUNT
+    );
+    assert!(distribution.status(&journal).is_settled());
+}
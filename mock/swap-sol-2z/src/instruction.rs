@@ -19,6 +19,13 @@ pub enum MockSwapSol2zInstructionData {
         amount_sol_out: u64,
     },
     DequeueFills(u64),
+
+    /// Same as [Self::BuySol] without opening a swap session, which the
+    /// withdraw SOL instruction should reject.
+    BuySolWithoutSwapSession {
+        amount_2z_in: u64,
+        amount_sol_out: u64,
+    },
 }
 
 impl MockSwapSol2zInstructionData {
@@ -28,6 +35,8 @@ impl MockSwapSol2zInstructionData {
         Discriminator::new([2, 0, 0, 0, 0, 0, 0, 0]);
     pub const DEQUEUE_FILLS: Discriminator<DISCRIMINATOR_LEN> =
         Discriminator::new([146, 69, 6, 12, 174, 95, 136, 61]);
+    pub const BUY_SOL_WITHOUT_SWAP_SESSION: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new([3, 0, 0, 0, 0, 0, 0, 0]);
 }
 
 impl BorshDeserialize for MockSwapSol2zInstructionData {
@@ -45,6 +54,14 @@ impl BorshDeserialize for MockSwapSol2zInstructionData {
             Self::DEQUEUE_FILLS => {
                 BorshDeserialize::deserialize_reader(reader).map(Self::DequeueFills)
             }
+            Self::BUY_SOL_WITHOUT_SWAP_SESSION => {
+                let amount_2z_in = BorshDeserialize::deserialize_reader(reader)?;
+                let amount_sol_out = BorshDeserialize::deserialize_reader(reader)?;
+                Ok(Self::BuySolWithoutSwapSession {
+                    amount_2z_in,
+                    amount_sol_out,
+                })
+            }
             _ => Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "Invalid discriminator",
@@ -69,6 +86,14 @@ impl BorshSerialize for MockSwapSol2zInstructionData {
                 Self::DEQUEUE_FILLS.serialize(writer)?;
                 max_sol_amount.serialize(writer)
             }
+            Self::BuySolWithoutSwapSession {
+                amount_2z_in,
+                amount_sol_out,
+            } => {
+                Self::BUY_SOL_WITHOUT_SWAP_SESSION.serialize(writer)?;
+                amount_2z_in.serialize(writer)?;
+                amount_sol_out.serialize(writer)
+            }
         }
     }
 }
@@ -105,18 +130,56 @@ pub fn buy_sol(
     sol_destination_key: &Pubkey,
     amount_2z_in: u64,
     amount_sol_out: u64,
+) -> Instruction {
+    build_buy_sol_instruction(
+        fills_tracker_key,
+        src_token_key,
+        transfer_authority_key,
+        sol_destination_key,
+        &MockSwapSol2zInstructionData::BuySol {
+            amount_2z_in,
+            amount_sol_out,
+        },
+    )
+}
+
+pub fn buy_sol_without_swap_session(
+    fills_tracker_key: &Pubkey,
+    src_token_key: &Pubkey,
+    transfer_authority_key: &Pubkey,
+    sol_destination_key: &Pubkey,
+    amount_2z_in: u64,
+    amount_sol_out: u64,
+) -> Instruction {
+    build_buy_sol_instruction(
+        fills_tracker_key,
+        src_token_key,
+        transfer_authority_key,
+        sol_destination_key,
+        &MockSwapSol2zInstructionData::BuySolWithoutSwapSession {
+            amount_2z_in,
+            amount_sol_out,
+        },
+    )
+}
+
+/// The transfer authority also pays for the swap session.
+fn build_buy_sol_instruction(
+    fills_tracker_key: &Pubkey,
+    src_token_key: &Pubkey,
+    transfer_authority_key: &Pubkey,
+    sol_destination_key: &Pubkey,
+    ix_data: &MockSwapSol2zInstructionData,
 ) -> Instruction {
     let WithdrawSolAccounts {
         program_config_key: rd_program_config_key,
         withdraw_sol_authority_key,
         journal_key: rd_journal_key,
         sol_destination_key,
-    } = WithdrawSolAccounts::new(&ID, sol_destination_key);
-
-    let rd_swap_authority_key =
-        doublezero_revenue_distribution::state::find_swap_authority_address().0;
-    let dst_token_key =
-        doublezero_revenue_distribution::state::find_2z_token_pda_address(&rd_swap_authority_key).0;
+        swap_destination_2z_key: dst_token_key,
+        swap_session_key,
+        ..
+    } = WithdrawSolAccounts::new(&ID, sol_destination_key, transfer_authority_key);
 
     try_build_instruction(
         &ID,
@@ -130,13 +193,12 @@ pub fn buy_sol(
             AccountMeta::new(withdraw_sol_authority_key, false),
             AccountMeta::new(rd_journal_key, false),
             AccountMeta::new(sol_destination_key, false),
+            AccountMeta::new(swap_session_key, false),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
             AccountMeta::new_readonly(spl_token_interface::ID, false),
             AccountMeta::new_readonly(doublezero_revenue_distribution::ID, false),
         ],
-        &MockSwapSol2zInstructionData::BuySol {
-            amount_2z_in,
-            amount_sol_out,
-        },
+        ix_data,
     )
     .unwrap()
 }
@@ -5,7 +5,8 @@ use doublezero_program_tools::{
     zero_copy::{self, ZeroCopyMutAccount},
 };
 use doublezero_revenue_distribution::instruction::{
-    account::WithdrawSolAccounts, RevenueDistributionInstructionData,
+    account::{OpenSwapSessionAccounts, WithdrawSolAccounts},
+    RevenueDistributionInstructionData,
 };
 use solana_account_info::AccountInfo;
 use solana_cpi::invoke_signed_unchecked;
@@ -41,10 +42,14 @@ fn try_process_instruction(
         MockSwapSol2zInstructionData::BuySol {
             amount_2z_in,
             amount_sol_out,
-        } => try_buy_sol(accounts, amount_2z_in, amount_sol_out),
+        } => try_buy_sol(accounts, amount_2z_in, amount_sol_out, true),
         MockSwapSol2zInstructionData::DequeueFills(max_sol_amount) => {
             try_dequeue_fills(accounts, max_sol_amount)
         }
+        MockSwapSol2zInstructionData::BuySolWithoutSwapSession {
+            amount_2z_in,
+            amount_sol_out,
+        } => try_buy_sol(accounts, amount_2z_in, amount_sol_out, false),
     }
 }
 
@@ -61,7 +66,12 @@ fn try_initialize_fills_registry(accounts: &[AccountInfo]) -> ProgramResult {
     Ok(())
 }
 
-fn try_buy_sol(accounts: &[AccountInfo], amount_2z_in: u64, amount_sol_out: u64) -> ProgramResult {
+fn try_buy_sol(
+    accounts: &[AccountInfo],
+    amount_2z_in: u64,
+    amount_sol_out: u64,
+    should_open_swap_session: bool,
+) -> ProgramResult {
     msg!("Buy SOL");
 
     let mut accounts_iter = accounts.iter().enumerate();
@@ -86,6 +96,45 @@ fn try_buy_sol(accounts: &[AccountInfo], amount_2z_in: u64, amount_sol_out: u64)
     let (_, dst_token_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())?;
     let (_, transfer_authority_info) =
         try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+    let (_, rd_program_config_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+    let (_, withdraw_authority_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+    let (_, rd_journal_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+    let (_, sol_destination_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+    let (_, swap_session_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    let (_, withdraw_authority_bump) =
+        doublezero_revenue_distribution::state::find_withdraw_sol_authority_address(&ID);
+    let withdraw_authority_signer_seeds: &[&[u8]] = &[
+        doublezero_revenue_distribution::state::WITHDRAW_SOL_AUTHORITY_SEED_PREFIX,
+        &[withdraw_authority_bump],
+    ];
+
+    // Open the swap session before transferring 2Z to the swap destination.
+    // The transfer authority pays for the session.
+    if should_open_swap_session {
+        let open_swap_session_ix = try_build_instruction(
+            &doublezero_revenue_distribution::ID,
+            OpenSwapSessionAccounts {
+                program_config_key: *rd_program_config_info.key,
+                withdraw_sol_authority_key: *withdraw_authority_info.key,
+                swap_destination_2z_key: *dst_token_info.key,
+                payer_key: *transfer_authority_info.key,
+                swap_session_key: *swap_session_info.key,
+            },
+            &RevenueDistributionInstructionData::OpenSwapSession,
+        )
+        .unwrap();
+
+        invoke_signed_unchecked(
+            &open_swap_session_ix,
+            accounts,
+            &[withdraw_authority_signer_seeds],
+        )?;
+    }
 
     // Transfer 2Z to the swap destination.
     let token_transfer_ix = token_instruction::transfer_checked(
@@ -102,14 +151,6 @@ fn try_buy_sol(accounts: &[AccountInfo], amount_2z_in: u64, amount_sol_out: u64)
 
     invoke_signed_unchecked(&token_transfer_ix, accounts, &[])?;
 
-    let (_, rd_program_config_info) =
-        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
-    let (_, withdraw_authority_info) =
-        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
-    let (_, rd_journal_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())?;
-    let (_, sol_destination_info) =
-        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
-
     let withdraw_sol_ix = try_build_instruction(
         &doublezero_revenue_distribution::ID,
         WithdrawSolAccounts {
@@ -117,21 +158,18 @@ fn try_buy_sol(accounts: &[AccountInfo], amount_2z_in: u64, amount_sol_out: u64)
             withdraw_sol_authority_key: *withdraw_authority_info.key,
             journal_key: *rd_journal_info.key,
             sol_destination_key: *sol_destination_info.key,
+            swap_destination_2z_key: *dst_token_info.key,
+            swap_session_key: *swap_session_info.key,
+            swap_session_rent_beneficiary_key: *transfer_authority_info.key,
         },
         &RevenueDistributionInstructionData::WithdrawSol(amount_sol_out),
     )
     .unwrap();
 
-    let (_, withdraw_authority_bump) =
-        doublezero_revenue_distribution::state::find_withdraw_sol_authority_address(&ID);
-
     invoke_signed_unchecked(
         &withdraw_sol_ix,
         accounts,
-        &[&[
-            doublezero_revenue_distribution::state::WITHDRAW_SOL_AUTHORITY_SEED_PREFIX,
-            &[withdraw_authority_bump],
-        ]],
+        &[withdraw_authority_signer_seeds],
     )?;
 
     Ok(())
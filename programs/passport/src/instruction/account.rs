@@ -103,14 +103,29 @@ pub struct RequestAccessAccounts {
     pub program_config_key: Pubkey,
     pub payer_key: Pubkey,
     pub new_access_request_key: Pubkey,
+    pub vote_account_key: Option<Pubkey>,
+    pub instructions_sysvar_key: Option<Pubkey>,
 }
 
 impl RequestAccessAccounts {
-    pub fn new(payer_key: &Pubkey, service_key: &Pubkey) -> Self {
+    /// The vote account must be included when requesting access with a vote
+    /// account attestation. The Instructions sysvar should be included when
+    /// the instruction preceding the request is the service key's ownership
+    /// proof (see
+    /// [service_key_proof_message](crate::instruction::service_key_proof_message)).
+    pub fn new(
+        payer_key: &Pubkey,
+        service_key: &Pubkey,
+        vote_account_key: Option<&Pubkey>,
+        with_service_key_proof: bool,
+    ) -> Self {
         Self {
             program_config_key: ProgramConfig::find_address().0,
             payer_key: *payer_key,
             new_access_request_key: AccessRequest::find_address(service_key).0,
+            vote_account_key: vote_account_key.copied(),
+            instructions_sysvar_key: with_service_key_proof
+                .then_some(solana_sdk_ids::sysvar::instructions::ID),
         }
     }
 }
@@ -121,14 +136,26 @@ impl From<RequestAccessAccounts> for Vec<AccountMeta> {
             program_config_key,
             payer_key,
             new_access_request_key,
+            vote_account_key,
+            instructions_sysvar_key,
         } = accounts;
 
-        vec![
+        let mut account_metas = vec![
             AccountMeta::new_readonly(program_config_key, false),
             AccountMeta::new(payer_key, true),
             AccountMeta::new(new_access_request_key, false),
             AccountMeta::new_readonly(solana_system_interface::program::ID, false),
-        ]
+        ];
+
+        if let Some(vote_account_key) = vote_account_key {
+            account_metas.push(AccountMeta::new_readonly(vote_account_key, false));
+        }
+
+        if let Some(instructions_sysvar_key) = instructions_sysvar_key {
+            account_metas.push(AccountMeta::new_readonly(instructions_sysvar_key, false));
+        }
+
+        account_metas
     }
 }
 
@@ -138,6 +165,8 @@ pub struct GrantAccessAccounts {
     pub dz_ledger_sentinel_key: Pubkey,
     pub access_request_key: Pubkey,
     pub rent_beneficiary_key: Pubkey,
+    pub onboarding_funder_key: Option<Pubkey>,
+    pub service_key: Option<Pubkey>,
 }
 
 impl GrantAccessAccounts {
@@ -151,6 +180,29 @@ impl GrantAccessAccounts {
             dz_ledger_sentinel_key: *dz_ledger_sentinel_key,
             access_request_key: *access_request_key,
             rent_beneficiary_key: *rent_beneficiary_key,
+            onboarding_funder_key: None,
+            service_key: None,
+        }
+    }
+
+    /// The onboarding funder and the user's service key must be included when
+    /// the program config has a top-up threshold, so the user can be funded
+    /// with the onboarding amount.
+    pub fn new_with_onboarding_funding(
+        dz_ledger_sentinel_key: &Pubkey,
+        access_request_key: &Pubkey,
+        rent_beneficiary_key: &Pubkey,
+        onboarding_funder_key: &Pubkey,
+        service_key: &Pubkey,
+    ) -> Self {
+        Self {
+            onboarding_funder_key: Some(*onboarding_funder_key),
+            service_key: Some(*service_key),
+            ..Self::new(
+                dz_ledger_sentinel_key,
+                access_request_key,
+                rent_beneficiary_key,
+            )
         }
     }
 }
@@ -162,14 +214,28 @@ impl From<GrantAccessAccounts> for Vec<AccountMeta> {
             dz_ledger_sentinel_key,
             access_request_key,
             rent_beneficiary_key,
+            onboarding_funder_key,
+            service_key,
         } = accounts;
 
-        vec![
+        let mut account_metas = vec![
             AccountMeta::new_readonly(program_config_key, false),
             AccountMeta::new(dz_ledger_sentinel_key, true),
             AccountMeta::new(access_request_key, false),
             AccountMeta::new(rent_beneficiary_key, false),
-        ]
+        ];
+
+        if let (Some(onboarding_funder_key), Some(service_key)) =
+            (onboarding_funder_key, service_key)
+        {
+            account_metas.extend([
+                AccountMeta::new(onboarding_funder_key, true),
+                AccountMeta::new(service_key, false),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ]);
+        }
+
+        account_metas
     }
 }
 
@@ -178,6 +244,7 @@ pub struct DenyAccessAccounts {
     pub program_config_key: Pubkey,
     pub dz_ledger_sentinel_key: Pubkey,
     pub access_request_key: Pubkey,
+    pub rent_beneficiary_key: Option<Pubkey>,
 }
 
 impl DenyAccessAccounts {
@@ -186,6 +253,20 @@ impl DenyAccessAccounts {
             program_config_key: ProgramConfig::find_address().0,
             dz_ledger_sentinel_key: *dz_ledger_sentinel_key,
             access_request_key: *access_request_key,
+            rent_beneficiary_key: None,
+        }
+    }
+
+    /// The rent beneficiary must be included when the access request refunds
+    /// part of its deposit on deny.
+    pub fn new_with_refund(
+        dz_ledger_sentinel_key: &Pubkey,
+        access_request_key: &Pubkey,
+        rent_beneficiary_key: &Pubkey,
+    ) -> Self {
+        Self {
+            rent_beneficiary_key: Some(*rent_beneficiary_key),
+            ..Self::new(dz_ledger_sentinel_key, access_request_key)
         }
     }
 }
@@ -196,12 +277,131 @@ impl From<DenyAccessAccounts> for Vec<AccountMeta> {
             program_config_key,
             dz_ledger_sentinel_key,
             access_request_key,
+            rent_beneficiary_key,
+        } = accounts;
+
+        let mut account_metas = vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new(dz_ledger_sentinel_key, true),
+            AccountMeta::new(access_request_key, false),
+        ];
+
+        if let Some(rent_beneficiary_key) = rent_beneficiary_key {
+            account_metas.push(AccountMeta::new(rent_beneficiary_key, false));
+        }
+
+        account_metas
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpireAccessRequestAccounts {
+    pub program_config_key: Pubkey,
+    pub dz_ledger_sentinel_key: Pubkey,
+    pub access_request_key: Pubkey,
+    pub rent_beneficiary_key: Pubkey,
+}
+
+impl ExpireAccessRequestAccounts {
+    pub fn new(
+        dz_ledger_sentinel_key: &Pubkey,
+        access_request_key: &Pubkey,
+        rent_beneficiary_key: &Pubkey,
+    ) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            dz_ledger_sentinel_key: *dz_ledger_sentinel_key,
+            access_request_key: *access_request_key,
+            rent_beneficiary_key: *rent_beneficiary_key,
+        }
+    }
+}
+
+impl From<ExpireAccessRequestAccounts> for Vec<AccountMeta> {
+    fn from(accounts: ExpireAccessRequestAccounts) -> Self {
+        let ExpireAccessRequestAccounts {
+            program_config_key,
+            dz_ledger_sentinel_key,
+            access_request_key,
+            rent_beneficiary_key,
         } = accounts;
 
         vec![
             AccountMeta::new_readonly(program_config_key, false),
             AccountMeta::new(dz_ledger_sentinel_key, true),
             AccountMeta::new(access_request_key, false),
+            AccountMeta::new(rent_beneficiary_key, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateValidatorIdAccounts {
+    pub program_config_key: Pubkey,
+    pub payer_key: Pubkey,
+    pub sentinel_key: Pubkey,
+}
+
+impl UpdateValidatorIdAccounts {
+    /// The instruction preceding the update must be the old validator
+    /// identity's proof (see
+    /// [validator_id_update_proof_message](crate::instruction::validator_id_update_proof_message)).
+    pub fn new(payer_key: &Pubkey, sentinel_key: &Pubkey) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            payer_key: *payer_key,
+            sentinel_key: *sentinel_key,
+        }
+    }
+}
+
+impl From<UpdateValidatorIdAccounts> for Vec<AccountMeta> {
+    fn from(accounts: UpdateValidatorIdAccounts) -> Self {
+        let UpdateValidatorIdAccounts {
+            program_config_key,
+            payer_key,
+            sentinel_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new(payer_key, true),
+            AccountMeta::new(sentinel_key, false),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            AccountMeta::new_readonly(solana_sdk_ids::sysvar::instructions::ID, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApproveAccessAccounts {
+    pub program_config_key: Pubkey,
+    pub quorum_sentinel_key: Pubkey,
+    pub access_request_key: Pubkey,
+}
+
+impl ApproveAccessAccounts {
+    pub fn new(quorum_sentinel_key: &Pubkey, access_request_key: &Pubkey) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            quorum_sentinel_key: *quorum_sentinel_key,
+            access_request_key: *access_request_key,
+        }
+    }
+}
+
+impl From<ApproveAccessAccounts> for Vec<AccountMeta> {
+    fn from(accounts: ApproveAccessAccounts) -> Self {
+        let ApproveAccessAccounts {
+            program_config_key,
+            quorum_sentinel_key,
+            access_request_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new_readonly(quorum_sentinel_key, true),
+            AccountMeta::new(access_request_key, false),
         ]
     }
 }
@@ -17,6 +17,22 @@ pub enum ProgramConfiguration {
         request_fee_lamports: u64,
     },
     SolanaValidatorBackupIdsLimit(u16),
+    AccessRequestEscrow {
+        deny_refund_basis_points: u16,
+        expiry_seconds: u32,
+    },
+    OnboardingFunding {
+        onboarding_lamports: u64,
+        top_up_threshold_lamports: u64,
+    },
+
+    /// Replaces the sentinels that approve access requests and the number of
+    /// their approvals required before access can be granted. An empty list
+    /// with a zero quorum removes the approval requirement.
+    QuorumSentinels {
+        sentinel_keys: Vec<Pubkey>,
+        sentinel_quorum: u8,
+    },
 }
 
 #[derive(Debug, BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq)]
@@ -32,6 +48,20 @@ pub struct SolanaValidatorAttestation {
     pub ed25519_signature: [u8; 64],
 }
 
+/// Attestation signed by the authorized withdrawer of the validator's vote
+/// account instead of the validator identity, for operators whose identity key
+/// cannot sign arbitrary messages (e.g. it lives on an HSM). The request access
+/// instruction verifies that the vote account's node identity is the validator
+/// ID, and the sentinel verifies that the signer is its authorized withdrawer.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SolanaVoteAccountAttestation {
+    pub validator_id: Pubkey,
+    pub vote_account_key: Pubkey,
+    pub authorized_withdrawer_key: Pubkey,
+    pub service_key: Pubkey,
+    pub ed25519_signature: [u8; 64],
+}
+
 #[derive(Debug, BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq)]
 pub enum AccessMode {
     SolanaValidator(SolanaValidatorAttestation),
@@ -39,14 +69,89 @@ pub enum AccessMode {
         attestation: SolanaValidatorAttestation,
         backup_ids: Vec<Pubkey>,
     },
+    SolanaValidatorWithVoteAccount(SolanaVoteAccountAttestation),
+}
+
+/// Prefix of the message signed by the service key to prove that its owner
+/// requested access for a validator identity. The validator ID follows this
+/// prefix.
+pub const SERVICE_KEY_PROOF_MESSAGE_PREFIX: &[u8] = b"dz::passport::service_key_proof::";
+
+/// Message the service key signs with the Ed25519 program instruction
+/// preceding a request for access, which prevents anyone else from binding
+/// this service key to their validator identity.
+pub fn service_key_proof_message(validator_id: &Pubkey) -> Vec<u8> {
+    [SERVICE_KEY_PROOF_MESSAGE_PREFIX, validator_id.as_ref()].concat()
+}
+
+/// Prefix of the message signed by both the current and the new validator
+/// identities to prove that their owners requested the rotation. The service
+/// key and the new validator ID follow this prefix.
+pub const VALIDATOR_ID_UPDATE_PROOF_MESSAGE_PREFIX: &[u8] =
+    b"dz::passport::validator_id_update_proof::";
+
+/// Message the current and the new validator identities each sign with one of
+/// the two Ed25519 program instructions preceding a validator ID update, the
+/// current identity's first. This prevents anyone else from rotating a service
+/// key away from the current validator identity or to the new one.
+pub fn validator_id_update_proof_message(
+    service_key: &Pubkey,
+    new_validator_id: &Pubkey,
+) -> Vec<u8> {
+    [
+        VALIDATOR_ID_UPDATE_PROOF_MESSAGE_PREFIX,
+        service_key.as_ref(),
+        new_validator_id.as_ref(),
+    ]
+    .concat()
+}
+
+/// Rotates the validator identity of an already granted service key. The old
+/// and new validator identities' signatures are verified on-chain (see
+/// [validator_id_update_proof_message]). The new validator identity also signs
+/// [ValidatorIdUpdate::message], which the sentinel verifies before updating
+/// the DoubleZero Ledger.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ValidatorIdUpdate {
+    pub service_key: Pubkey,
+    pub old_validator_id: Pubkey,
+    pub new_validator_id: Pubkey,
+    pub new_ed25519_signature: [u8; 64],
+}
+
+impl ValidatorIdUpdate {
+    #[cfg(feature = "offchain")]
+    pub fn message(&self) -> String {
+        format!(
+            "service_key={},old_validator_id={},new_validator_id={}",
+            self.service_key, self.old_validator_id, self.new_validator_id
+        )
+    }
 }
 
 impl AccessMode {
+    pub fn validator_id(&self) -> Pubkey {
+        match self {
+            Self::SolanaValidator(attestation) => attestation.validator_id,
+            Self::SolanaValidatorWithBackupIds { attestation, .. } => attestation.validator_id,
+            Self::SolanaValidatorWithVoteAccount(attestation) => attestation.validator_id,
+        }
+    }
+
     #[cfg(feature = "offchain")]
     pub fn service_key(&self) -> Pubkey {
         match self {
             Self::SolanaValidator(attestation) => attestation.service_key,
             Self::SolanaValidatorWithBackupIds { attestation, .. } => attestation.service_key,
+            Self::SolanaValidatorWithVoteAccount(attestation) => attestation.service_key,
+        }
+    }
+    /// Vote account that must accompany a vote account attestation when
+    /// requesting access.
+    pub fn vote_account_key(&self) -> Option<Pubkey> {
+        match self {
+            Self::SolanaValidatorWithVoteAccount(attestation) => Some(attestation.vote_account_key),
+            _ => None,
         }
     }
 }
@@ -59,6 +164,12 @@ pub enum PassportInstructionData {
     RequestAccess(AccessMode),
     GrantAccess,
     DenyAccess,
+    ExpireAccessRequest,
+    UpdateValidatorId(ValidatorIdUpdate),
+
+    /// Records a quorum sentinel's approval in an access request. Access can
+    /// only be granted once the sentinel quorum is reached.
+    ApproveAccess,
 }
 
 impl PassportInstructionData {
@@ -74,6 +185,12 @@ impl PassportInstructionData {
         Discriminator::new_sha2(b"dz::ix::grant_access");
     pub const DENY_ACCESS: Discriminator<DISCRIMINATOR_LEN> =
         Discriminator::new_sha2(b"dz::ix::deny_access");
+    pub const EXPIRE_ACCESS_REQUEST: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::expire_access_request");
+    pub const UPDATE_VALIDATOR_ID: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::update_validator_id");
+    pub const APPROVE_ACCESS: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::approve_access");
 }
 
 impl BorshDeserialize for PassportInstructionData {
@@ -89,6 +206,11 @@ impl BorshDeserialize for PassportInstructionData {
             }
             Self::GRANT_ACCESS => Ok(Self::GrantAccess),
             Self::DENY_ACCESS => Ok(Self::DenyAccess),
+            Self::EXPIRE_ACCESS_REQUEST => Ok(Self::ExpireAccessRequest),
+            Self::UPDATE_VALIDATOR_ID => {
+                BorshDeserialize::deserialize_reader(reader).map(Self::UpdateValidatorId)
+            }
+            Self::APPROVE_ACCESS => Ok(Self::ApproveAccess),
             _ => Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "Invalid discriminator",
@@ -115,6 +237,12 @@ impl BorshSerialize for PassportInstructionData {
             }
             Self::GrantAccess => Self::GRANT_ACCESS.serialize(writer),
             Self::DenyAccess => Self::DENY_ACCESS.serialize(writer),
+            Self::ExpireAccessRequest => Self::EXPIRE_ACCESS_REQUEST.serialize(writer),
+            Self::UpdateValidatorId(update) => {
+                Self::UPDATE_VALIDATOR_ID.serialize(writer)?;
+                update.serialize(writer)
+            }
+            Self::ApproveAccess => Self::APPROVE_ACCESS.serialize(writer),
         }
     }
 }
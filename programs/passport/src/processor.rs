@@ -4,6 +4,7 @@ use doublezero_program_tools::{
         try_next_enumerated_account, EnumeratedAccountInfoIter, NextAccountOptions,
         TryNextAccounts, UpgradeAuthority,
     },
+    ed25519::Ed25519SignedMessage,
     recipe::{
         create_account::{try_create_account, CreateAccountOptions},
         Invoker,
@@ -11,16 +12,20 @@ use doublezero_program_tools::{
     zero_copy::{self, ZeroCopyAccount, ZeroCopyMutAccount},
 };
 use solana_account_info::AccountInfo;
+use solana_cpi::invoke_signed_unchecked;
 use solana_instruction::{syscalls::get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
 use solana_msg::msg;
 use solana_program_error::{ProgramError, ProgramResult};
 use solana_pubkey::Pubkey;
+use solana_system_interface::instruction as system_instruction;
+use solana_sysvar::{clock::Clock, Sysvar};
 
 use crate::{
     instruction::{
-        AccessMode, PassportInstructionData, ProgramConfiguration, ProgramFlagConfiguration,
+        service_key_proof_message, validator_id_update_proof_message, AccessMode,
+        PassportInstructionData, ProgramConfiguration, ProgramFlagConfiguration, ValidatorIdUpdate,
     },
-    state::{AccessRequest, ProgramConfig},
+    state::{AccessRequest, AccessRequestEscrow, ProgramConfig, MAX_QUORUM_SENTINELS},
     ID,
 };
 
@@ -51,6 +56,11 @@ fn try_process_instruction(
         }
         PassportInstructionData::GrantAccess => try_grant_access(accounts),
         PassportInstructionData::DenyAccess => try_deny_access(accounts),
+        PassportInstructionData::ExpireAccessRequest => try_expire_access_request(accounts),
+        PassportInstructionData::UpdateValidatorId(update) => {
+            try_update_validator_id(accounts, update)
+        }
+        PassportInstructionData::ApproveAccess => try_approve_access(accounts),
     }
 }
 
@@ -180,6 +190,25 @@ fn try_configure_program(accounts: &[AccountInfo], setting: ProgramConfiguration
             msg!("  request_fee_lamports: {}", fee_lamports);
             program_config.request_fee_lamports = fee_lamports;
         }
+        ProgramConfiguration::AccessRequestEscrow {
+            deny_refund_basis_points,
+            expiry_seconds,
+        } => {
+            if deny_refund_basis_points > ProgramConfig::MAX_BASIS_POINTS {
+                msg!(
+                    "Deny refund basis points cannot exceed {}",
+                    ProgramConfig::MAX_BASIS_POINTS
+                );
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            msg!("Set access_request_escrow_parameters");
+            msg!("  deny_refund_basis_points: {}", deny_refund_basis_points);
+            program_config.deny_refund_basis_points = deny_refund_basis_points;
+
+            msg!("  access_request_expiry_seconds: {}", expiry_seconds);
+            program_config.access_request_expiry_seconds = expiry_seconds;
+        }
         ProgramConfiguration::SolanaValidatorBackupIdsLimit(limit) => {
             if limit == 0 {
                 msg!("Solana validator backup IDs limit must not be zero");
@@ -189,6 +218,88 @@ fn try_configure_program(accounts: &[AccountInfo], setting: ProgramConfiguration
             msg!("Set solana_validator_backup_ids_limit: {}", limit);
             program_config.solana_validator_backup_ids_limit = limit;
         }
+        ProgramConfiguration::OnboardingFunding {
+            onboarding_lamports,
+            top_up_threshold_lamports,
+        } => {
+            // Topping up is only meaningful if the balance can fall below the
+            // amount the sentinel tops up to.
+            if top_up_threshold_lamports != 0 && top_up_threshold_lamports >= onboarding_lamports {
+                msg!("Top-up threshold must be less than the onboarding amount");
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            msg!("Set onboarding_funding_parameters");
+            msg!("  onboarding_lamports: {}", onboarding_lamports);
+            program_config.onboarding_lamports = onboarding_lamports;
+
+            msg!("  top_up_threshold_lamports: {}", top_up_threshold_lamports);
+            program_config.top_up_threshold_lamports = top_up_threshold_lamports;
+        }
+        ProgramConfiguration::QuorumSentinels {
+            sentinel_keys,
+            sentinel_quorum,
+        } => {
+            if sentinel_keys.len() > MAX_QUORUM_SENTINELS {
+                msg!("Cannot exceed {} quorum sentinels", MAX_QUORUM_SENTINELS);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            // The default pubkey marks an unused entry.
+            if sentinel_keys.contains(&Pubkey::default()) {
+                msg!("Quorum sentinel cannot be the default pubkey");
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            // Each sentinel can only approve once, so duplicates would make
+            // the quorum unreachable.
+            if let Some(duplicate_key) = sentinel_keys
+                .iter()
+                .enumerate()
+                .find_map(|(i, key)| sentinel_keys[..i].contains(key).then_some(key))
+            {
+                msg!("Duplicate quorum sentinel {}", duplicate_key);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            if usize::from(sentinel_quorum) > sentinel_keys.len() {
+                msg!("Sentinel quorum cannot exceed the number of quorum sentinels");
+                return Err(ProgramError::InvalidInstructionData);
+            } else if sentinel_quorum == 0 && !sentinel_keys.is_empty() {
+                msg!("Sentinel quorum must not be zero");
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            msg!("Set quorum_sentinel_parameters");
+            msg!("  sentinel_quorum: {}", sentinel_quorum);
+            program_config.sentinel_quorum = sentinel_quorum;
+
+            // Approvals recorded by the previous quorum sentinels no longer
+            // count toward the quorum.
+            program_config.quorum_sentinels_version = program_config
+                .quorum_sentinels_version
+                .checked_add(1)
+                .ok_or_else(|| {
+                    msg!("Quorum sentinels version overflow");
+                    ProgramError::ArithmeticOverflow
+                })?;
+            msg!(
+                "  quorum_sentinels_version: {}",
+                program_config.quorum_sentinels_version
+            );
+
+            msg!("  quorum_sentinel_keys:");
+            program_config.quorum_sentinel_keys = Default::default();
+
+            for (quorum_sentinel_key, sentinel_key) in program_config
+                .quorum_sentinel_keys
+                .iter_mut()
+                .zip(sentinel_keys)
+            {
+                msg!("    {}", sentinel_key);
+                *quorum_sentinel_key = sentinel_key;
+            }
+        }
     }
 
     Ok(())
@@ -207,6 +318,8 @@ fn try_request_access(accounts: &[AccountInfo], access_mode: AccessMode) -> Prog
     // - 1: Payer (funder and rent beneficiary)
     // - 2: New access request account
     // - 3: System program
+    // - 4: Vote account (only with a vote account attestation)
+    // - 4 or 5: Optional Instructions sysvar
 
     let mut accounts_iter = accounts.iter().enumerate();
 
@@ -248,6 +361,23 @@ fn try_request_access(accounts: &[AccountInfo], access_mode: AccessMode) -> Prog
                 return Err(ProgramError::InvalidInstructionData);
             }
 
+            attestation.service_key
+        }
+        AccessMode::SolanaValidatorWithVoteAccount(attestation) => {
+            msg!("Solana validator with vote account");
+
+            // The vote account is checked against the validator ID below, and
+            // the sentinel verifies its authorized withdrawer, so these keys
+            // must be specified.
+            if attestation.vote_account_key == Pubkey::default()
+                || attestation.authorized_withdrawer_key == Pubkey::default()
+            {
+                msg!("Vote account and authorized withdrawer cannot be zero address");
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            msg!("vote_account: {}", attestation.vote_account_key);
+
             attestation.service_key
         }
     };
@@ -285,6 +415,52 @@ fn try_request_access(accounts: &[AccountInfo], access_mode: AccessMode) -> Prog
         return Err(ProgramError::InvalidSeeds);
     }
 
+    // Account 3 must be the System program, which is checked when creating the
+    // access request.
+    try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // With a vote account attestation, account 4 must be the vote account,
+    // whose node identity must be the validator ID.
+    if let AccessMode::SolanaValidatorWithVoteAccount(attestation) = &access_mode {
+        let (account_index, vote_account_info) = try_next_enumerated_account(
+            &mut accounts_iter,
+            NextAccountOptions {
+                owned_by: Some(&solana_sdk_ids::vote::ID),
+                ..Default::default()
+            },
+        )?;
+
+        // Enforce this account location.
+        if vote_account_info.key != &attestation.vote_account_key {
+            msg!(
+                "Invalid address for vote account (account {})",
+                account_index
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        try_require_vote_account_node_identity(vote_account_info, &attestation.validator_id)?;
+    }
+
+    // The next account may be the Instructions sysvar. If this account is
+    // provided, the preceding instruction must prove that the owner of the
+    // service key requested access for this validator identity.
+    let service_key_verified =
+        match try_next_enumerated_account(&mut accounts_iter, Default::default()) {
+            Ok((account_index, instructions_sysvar_info)) => {
+                try_verify_service_key_proof(
+                    account_index,
+                    instructions_sysvar_info,
+                    &service_key,
+                    &access_mode.validator_id(),
+                )?;
+                msg!("Verified service key proof");
+
+                true
+            }
+            Err(_) => false,
+        };
+
     try_create_account(
         Invoker::Signer(payer_info.key),
         Invoker::Pda {
@@ -296,7 +472,7 @@ fn try_request_access(accounts: &[AccountInfo], access_mode: AccessMode) -> Prog
             ],
         },
         new_access_request_info.lamports(),
-        zero_copy::data_end::<AccessRequest>(),
+        zero_copy::data_end::<AccessRequest>() + AccessRequest::ESCROW_LEN,
         &ID,
         accounts,
         CreateAccountOptions {
@@ -306,12 +482,25 @@ fn try_request_access(accounts: &[AccountInfo], access_mode: AccessMode) -> Prog
     )?;
 
     // Finalize the access request with the user service and beneficiary keys.
-    let (mut access_request, _) =
+    let (mut access_request, mut remaining_data) =
         zero_copy::try_initialize::<AccessRequest>(new_access_request_info)?;
     access_request.service_key = service_key;
     access_request.rent_beneficiary_key = *payer_info.key;
     access_request.request_fee_lamports = program_config.request_fee_lamports;
 
+    // The escrow terms are stored after the access request.
+    let mut escrow = AccessRequestEscrow::default();
+    escrow.request_deposit_lamports = additional_lamports;
+    escrow.deny_refund_basis_points = program_config.deny_refund_basis_points;
+    escrow.service_key_verified = service_key_verified.into();
+
+    let expiry_seconds = program_config.access_request_expiry_seconds;
+    if expiry_seconds != 0 {
+        escrow.expiry_timestamp = Clock::get()?.unix_timestamp + i64::from(expiry_seconds);
+    }
+
+    remaining_data[..AccessRequest::ESCROW_LEN].copy_from_slice(bytemuck::bytes_of(&escrow));
+
     // Copy the access mode into the access request.
     borsh::to_writer(access_request.encoded_access_mode.as_mut(), &access_mode).map_err(|_| {
         msg!("Failed to serialize access mode");
@@ -334,6 +523,9 @@ fn try_grant_access(accounts: &[AccountInfo]) -> ProgramResult {
     // - 1: DZ Ledger Sentinel
     // - 2: New access request account
     // - 3: Rent beneficiary (original payer)
+    // - 4: Onboarding funder, required if a top-up threshold is configured
+    // - 5: User service key, required if a top-up threshold is configured
+    // - 6: System program, required if a top-up threshold is configured
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
@@ -351,37 +543,108 @@ fn try_grant_access(accounts: &[AccountInfo]) -> ProgramResult {
     let access_request =
         ZeroCopyAccount::<AccessRequest>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
 
-    let (_, sentinel_info) = authorized_use.authority;
+    // Access requests created before deposits were escrowed have no escrow
+    // terms, so they never expire and cannot record approvals. Because they
+    // predate the sentinel quorum, the sentinel can grant them on its own.
+    match AccessRequest::escrow(&access_request.remaining_data) {
+        Some(escrow) => {
+            escrow.try_require_unexpired()?;
 
-    let request_fee = access_request.request_fee_lamports;
-    let mut access_request_lamports = access_request.info.try_borrow_mut_lamports()?;
-    let request_refund = access_request_lamports.saturating_sub(request_fee);
+            // If a sentinel quorum is configured, enough quorum sentinels must
+            // have approved this request.
+            if !escrow.is_sentinel_quorum_reached(&authorized_use.program_config) {
+                msg!(
+                    "Sentinel quorum not reached: {} of {} approvals",
+                    escrow.sentinel_approvals_count(&authorized_use.program_config),
+                    authorized_use.program_config.sentinel_quorum
+                );
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+        None => msg!("Access request predates sentinel quorum"),
+    }
 
-    **sentinel_info.lamports.borrow_mut() += request_fee;
+    // Only the request fee is retained. The rest of the deposit is refunded.
+    let (_, sentinel_info) = authorized_use.authority;
+    let request_fee = access_request.request_fee_lamports;
 
     // Account 3 must be the rent beneficiary.
-    let (_, rent_beneficiary_info) =
+    let (rent_beneficiary_info, request_refund) = try_close_access_request(
+        &mut accounts_iter,
+        &access_request,
+        sentinel_info,
+        request_fee,
+    )?;
+
+    msg!("Grant {} access", access_request.service_key);
+    msg!(
+        "Return {} lamports to {}",
+        request_refund,
+        rent_beneficiary_info.key,
+    );
+
+    let ProgramConfig {
+        onboarding_lamports,
+        top_up_threshold_lamports,
+        ..
+    } = *authorized_use.program_config;
+
+    // Granted users are only funded if a top-up threshold is configured.
+    if top_up_threshold_lamports == 0 {
+        return Ok(());
+    }
+
+    // Account 4 must be the onboarding funder. The System program will
+    // automatically ensure this account is a signer and writable in order to
+    // transfer the onboarding lamports.
+    let (_, onboarding_funder_info) =
         try_next_enumerated_account(&mut accounts_iter, Default::default())?;
 
-    // Cannot use another account as rent beneficiary.
-    if rent_beneficiary_info.key != &access_request.rent_beneficiary_key {
+    // Account 5 must be the user's service key.
+    let (account_index, service_key_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Enforce this account location.
+    if service_key_info.key != &access_request.service_key {
         msg!(
-            "Expected rent beneficiary key: {}",
-            access_request.rent_beneficiary_key
+            "Invalid address for service key (account {})",
+            account_index
         );
         return Err(ProgramError::InvalidAccountData);
     }
 
-    **rent_beneficiary_info.lamports.borrow_mut() += request_refund;
+    // Account 6 must be the System program, which is checked when transferring
+    // the onboarding lamports.
+    try_next_enumerated_account(&mut accounts_iter, Default::default())?;
 
-    // Zero out the access request lamports to close the account.
-    **access_request_lamports = 0;
+    // Only fund the user up to the onboarding amount if its balance is below
+    // the top-up threshold. The configured threshold is always less than the
+    // onboarding amount.
+    let service_key_balance = service_key_info.lamports();
+
+    if service_key_balance >= top_up_threshold_lamports {
+        msg!(
+            "Service key balance {} is not below top-up threshold {}",
+            service_key_balance,
+            top_up_threshold_lamports
+        );
+        return Ok(());
+    }
+
+    let funding_lamports = onboarding_lamports - service_key_balance;
+
+    let transfer_ix = system_instruction::transfer(
+        onboarding_funder_info.key,
+        service_key_info.key,
+        funding_lamports,
+    );
+
+    invoke_signed_unchecked(&transfer_ix, accounts, &[])?;
 
-    msg!("Grant {} access", access_request.service_key);
     msg!(
-        "Return {} lamports to {}",
-        request_refund,
-        rent_beneficiary_info.key,
+        "Fund {} with {} lamports",
+        service_key_info.key,
+        funding_lamports
     );
 
     Ok(())
@@ -394,6 +657,78 @@ fn try_deny_access(accounts: &[AccountInfo]) -> ProgramResult {
     // - 0: Program Config
     // - 1: DZ Ledger Sentinel
     // - 2: New access request account
+    // - 3: Rent beneficiary (original payer), required if part of the deposit
+    //      is refunded
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    let authorized_use =
+        VerifiedProgramAuthority::try_next_accounts(&mut accounts_iter, Authority::Sentinel)?;
+
+    // Make sure program is not paused globally.
+    authorized_use.program_config.try_require_unpaused()?;
+
+    let access_request =
+        ZeroCopyAccount::<AccessRequest>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    let escrow = AccessRequest::escrow(&access_request.remaining_data).unwrap_or_default();
+    escrow.try_require_unexpired()?;
+
+    let (_, sentinel_info) = authorized_use.authority;
+
+    msg!("Deny {} access", access_request.service_key);
+
+    // Unless a refund is configured, the requestor forfeits all of the access
+    // request's lamports.
+    if escrow.deny_refund_basis_points == 0 {
+        let mut access_request_lamports = access_request.info.try_borrow_mut_lamports()?;
+        let forfeit_deposit = **access_request_lamports;
+
+        **sentinel_info.lamports.borrow_mut() += forfeit_deposit;
+
+        // Zero out the access request lamports to close the account.
+        **access_request_lamports = 0;
+
+        msg!("Requestor forfeit {} lamports", forfeit_deposit);
+
+        return Ok(());
+    }
+
+    // The request fee and the unrefunded portion of the remaining deposit are
+    // retained.
+    let forfeit_deposit = escrow
+        .checked_deny_refund_lamports(access_request.request_fee_lamports)
+        .and_then(|refund_lamports| escrow.request_deposit_lamports.checked_sub(refund_lamports))
+        .ok_or_else(|| {
+            msg!("Failed to compute retained deposit");
+            ProgramError::InvalidAccountData
+        })?;
+
+    // Account 3 must be the rent beneficiary.
+    let (rent_beneficiary_info, request_refund) = try_close_access_request(
+        &mut accounts_iter,
+        &access_request,
+        sentinel_info,
+        forfeit_deposit,
+    )?;
+
+    msg!("Requestor forfeit {} lamports", forfeit_deposit);
+    msg!(
+        "Return {} lamports to {}",
+        request_refund,
+        rent_beneficiary_info.key,
+    );
+
+    Ok(())
+}
+
+fn try_expire_access_request(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Expire access request");
+
+    // Instruction accounts are expected in the following order:
+    // - 0: Program Config
+    // - 1: DZ Ledger Sentinel
+    // - 2: Expired access request account
+    // - 3: Rent beneficiary (original payer)
     let mut accounts_iter = accounts.iter().enumerate();
 
     let authorized_use =
@@ -405,16 +740,352 @@ fn try_deny_access(accounts: &[AccountInfo]) -> ProgramResult {
     let access_request =
         ZeroCopyAccount::<AccessRequest>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
 
+    let escrow = AccessRequest::escrow(&access_request.remaining_data).unwrap_or_default();
+
+    if !escrow.is_expired(Clock::get()?.unix_timestamp) {
+        msg!("Access request has not expired");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // The entire deposit is retained. Only rent is returned.
     let (_, sentinel_info) = authorized_use.authority;
+    let forfeit_deposit = escrow.request_deposit_lamports;
+
+    let (rent_beneficiary_info, request_refund) = try_close_access_request(
+        &mut accounts_iter,
+        &access_request,
+        sentinel_info,
+        forfeit_deposit,
+    )?;
+
+    msg!("Expire {} access request", access_request.service_key);
+    msg!("Requestor forfeit {} lamports", forfeit_deposit);
+    msg!(
+        "Return {} lamports to {}",
+        request_refund,
+        rent_beneficiary_info.key,
+    );
+
+    Ok(())
+}
+
+fn try_update_validator_id(accounts: &[AccountInfo], update: ValidatorIdUpdate) -> ProgramResult {
+    msg!("Update validator ID");
+
+    if get_stack_height() != TRANSACTION_LEVEL_STACK_HEIGHT {
+        msg!("Cannot CPI update validator ID");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Instruction accounts are expected in the following order:
+    // - 0: Program config
+    // - 1: Payer (funder of the request fee)
+    // - 2: Sentinel
+    // - 3: System program
+    // - 4: Instructions sysvar
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    let program_config =
+        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    // Make sure program is not paused globally.
+    program_config.try_require_unpaused()?;
+
+    // Rotating the validator identity is subject to the same pause as
+    // requesting access.
+    if program_config.is_request_access_paused() {
+        msg!("Request access is paused");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let ValidatorIdUpdate {
+        service_key,
+        old_validator_id,
+        new_validator_id,
+        ..
+    } = update;
+
+    if service_key == Pubkey::default() {
+        msg!("User service key cannot be zero address");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if old_validator_id == Pubkey::default() || new_validator_id == Pubkey::default() {
+        msg!("Validator ID cannot be zero address");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if old_validator_id == new_validator_id {
+        msg!("New validator ID must differ from the old validator ID");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    msg!("old_validator_id: {}", old_validator_id);
+    msg!("new_validator_id: {}", new_validator_id);
+
+    // Account 1 must be the payer. The system program will automatically
+    // ensure this account is a signer and writable in order to transfer the
+    // request fee.
+    let (_, payer_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Account 2 must be the sentinel, which is paid the request fee for
+    // processing the update.
+    let (account_index, sentinel_info) = try_next_enumerated_account(
+        &mut accounts_iter,
+        NextAccountOptions {
+            must_be_writable: true,
+            ..Default::default()
+        },
+    )?;
+
+    // Enforce this account location.
+    if sentinel_info.key != &program_config.sentinel_key {
+        msg!("Invalid address for sentinel (account {})", account_index);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Account 3 must be the System program, which is checked when transferring
+    // the request fee.
+    try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Account 4 must be the Instructions sysvar. The two preceding
+    // instructions must prove that the owners of both the old and the new
+    // validator identities requested this update.
+    let (account_index, instructions_sysvar_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    try_verify_validator_id_update_proof(
+        -2,
+        account_index,
+        instructions_sysvar_info,
+        &old_validator_id,
+        "old",
+        &service_key,
+        &new_validator_id,
+    )?;
+    try_verify_validator_id_update_proof(
+        -1,
+        account_index,
+        instructions_sysvar_info,
+        &new_validator_id,
+        "new",
+        &service_key,
+        &new_validator_id,
+    )?;
+    msg!("Verified validator ID update proofs");
+
+    let request_fee_lamports = program_config.request_fee_lamports;
+
+    if request_fee_lamports != 0 {
+        let transfer_ix =
+            system_instruction::transfer(payer_info.key, sentinel_info.key, request_fee_lamports);
+
+        invoke_signed_unchecked(&transfer_ix, accounts, &[])?;
+        msg!("Paid {} lamports to sentinel", request_fee_lamports);
+    }
+
+    // The sentinel uses this log statement to filter transaction logs to
+    // successfully submitted validator ID updates when subscribing to program
+    // logs.
+    msg!("Requested validator ID update for {}", service_key);
+
+    Ok(())
+}
+
+fn try_approve_access(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Approve access request");
+
+    // Instruction accounts are expected in the following order:
+    // - 0: Program Config
+    // - 1: Quorum sentinel
+    // - 2: Access request account
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    // Account 1 must be one of the quorum sentinels.
+    let authorized_use =
+        VerifiedProgramAuthority::try_next_accounts(&mut accounts_iter, Authority::QuorumSentinel)?;
+
+    let program_config = &authorized_use.program_config;
+
+    // Make sure program is not paused globally.
+    program_config.try_require_unpaused()?;
+
+    if program_config.sentinel_quorum == 0 {
+        msg!("Sentinel quorum is not configured");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Account 2 must be the access request account.
+    let mut access_request =
+        ZeroCopyMutAccount::<AccessRequest>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    // Approvals are recorded in the escrow terms, which access requests
+    // created before deposits were escrowed do not have. The sentinel grants
+    // these requests without approvals.
+    let mut escrow = AccessRequest::escrow(&access_request.remaining_data).ok_or_else(|| {
+        msg!("Access request cannot record approvals");
+        ProgramError::InvalidAccountData
+    })?;
+
+    escrow.try_require_unexpired()?;
+
+    let (_, sentinel_info) = authorized_use.authority;
+
+    // The quorum sentinel was found when verifying the authority.
+    let sentinel_index = program_config
+        .quorum_sentinel_index(sentinel_info.key)
+        .unwrap();
+
+    if !escrow.try_record_sentinel_approval(program_config, sentinel_index) {
+        msg!("Access request already approved by {}", sentinel_info.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    access_request.remaining_data[..AccessRequest::ESCROW_LEN]
+        .copy_from_slice(bytemuck::bytes_of(&escrow));
+
+    msg!("Approve {} access", access_request.service_key);
+    msg!(
+        "Sentinel approvals: {} of {}",
+        escrow.sentinel_approvals_count(program_config),
+        program_config.sentinel_quorum
+    );
+
+    Ok(())
+}
+
+/// Close the access request by sending the retained lamports to the sentinel
+/// and the remaining lamports to the rent beneficiary, which is the next
+/// account. Returns the rent beneficiary and the refunded lamports.
+fn try_close_access_request<'a, 'b>(
+    accounts_iter: &mut EnumeratedAccountInfoIter<'a, 'b>,
+    access_request: &ZeroCopyAccount<AccessRequest>,
+    sentinel_info: &AccountInfo,
+    retained_lamports: u64,
+) -> Result<(&'a AccountInfo<'b>, u64), ProgramError> {
+    let (_, rent_beneficiary_info) =
+        try_next_enumerated_account(accounts_iter, Default::default())?;
+
+    // Cannot use another account as rent beneficiary.
+    if rent_beneficiary_info.key != &access_request.rent_beneficiary_key {
+        msg!(
+            "Expected rent beneficiary key: {}",
+            access_request.rent_beneficiary_key
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
 
     let mut access_request_lamports = access_request.info.try_borrow_mut_lamports()?;
-    let forfeit_deposit = **access_request_lamports;
+    let retained_lamports = retained_lamports.min(**access_request_lamports);
+    let request_refund = **access_request_lamports - retained_lamports;
 
-    **sentinel_info.lamports.borrow_mut() += forfeit_deposit;
+    **sentinel_info.lamports.borrow_mut() += retained_lamports;
+    **rent_beneficiary_info.lamports.borrow_mut() += request_refund;
+
+    // Zero out the access request lamports to close the account.
     **access_request_lamports = 0;
 
-    msg!("Deny {} access", access_request.service_key);
-    msg!("Requestor forfeit {} lamports", forfeit_deposit);
+    Ok((rent_beneficiary_info, request_refund))
+}
+
+/// Verify that the vote account's node identity is the validator ID. Every
+/// vote state version is serialized with its version as a `u32` followed by the
+/// node identity.
+fn try_require_vote_account_node_identity(
+    vote_account_info: &AccountInfo,
+    validator_id: &Pubkey,
+) -> ProgramResult {
+    const NODE_PUBKEY_OFFSET: usize = size_of::<u32>();
+
+    let node_pubkey = vote_account_info
+        .try_borrow_data()?
+        .get(NODE_PUBKEY_OFFSET..NODE_PUBKEY_OFFSET + size_of::<Pubkey>())
+        .map(|node_pubkey| Pubkey::try_from(node_pubkey).unwrap())
+        .ok_or_else(|| {
+            msg!("Vote account data is too short");
+            ProgramError::InvalidAccountData
+        })?;
+
+    if &node_pubkey != validator_id {
+        msg!(
+            "Vote account node identity {} is not validator ID {}",
+            node_pubkey,
+            validator_id
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Verify that the instruction preceding this one is an Ed25519 program
+/// instruction with a single signature by the service key over the service key
+/// proof message.
+fn try_verify_service_key_proof(
+    account_index: usize,
+    instructions_sysvar_info: &AccountInfo,
+    service_key: &Pubkey,
+    validator_id: &Pubkey,
+) -> ProgramResult {
+    let signed_message = Ed25519SignedMessage::try_from_relative_instruction(
+        -1,
+        account_index,
+        instructions_sysvar_info,
+    )?;
+
+    if &signed_message.signer_key != service_key {
+        msg!("Service key proof must be signed by the service key");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if signed_message.message != service_key_proof_message(validator_id) {
+        msg!(
+            "Service key proof must be for validator ID {}",
+            validator_id
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    Ok(())
+}
+
+/// Verify that the instruction at the given index relative to this one is an
+/// Ed25519 program instruction with a single signature by the given validator
+/// identity (labeled as either "old" or "new" in logs) over the validator ID
+/// update proof message.
+fn try_verify_validator_id_update_proof(
+    index_relative_to_current: i64,
+    account_index: usize,
+    instructions_sysvar_info: &AccountInfo,
+    signer_key: &Pubkey,
+    signer_label: &str,
+    service_key: &Pubkey,
+    new_validator_id: &Pubkey,
+) -> ProgramResult {
+    let signed_message = Ed25519SignedMessage::try_from_relative_instruction(
+        index_relative_to_current,
+        account_index,
+        instructions_sysvar_info,
+    )?;
+
+    if &signed_message.signer_key != signer_key {
+        msg!(
+            "Validator ID update proof must be signed by the {} validator ID",
+            signer_label
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if signed_message.message != validator_id_update_proof_message(service_key, new_validator_id) {
+        msg!(
+            "Validator ID update proof must be for service key {} and validator ID {}",
+            service_key,
+            new_validator_id
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
 
     Ok(())
 }
@@ -426,6 +1097,7 @@ fn try_deny_access(accounts: &[AccountInfo]) -> ProgramResult {
 enum Authority {
     Admin,
     Sentinel,
+    QuorumSentinel,
 }
 
 impl Authority {
@@ -455,6 +1127,15 @@ impl Authority {
                     return Err(ProgramError::InvalidAccountData);
                 }
             }
+            Authority::QuorumSentinel => {
+                if program_config
+                    .quorum_sentinel_index(authority_info.key)
+                    .is_none()
+                {
+                    msg!("Unauthorized quorum sentinel (account {})", index);
+                    return Err(ProgramError::InvalidAccountData);
+                }
+            }
         }
 
         Ok((index, authority_info))
@@ -511,6 +1192,18 @@ impl<'a, 'b> TryNextAccounts<'a, 'b, Authority> for VerifiedProgramAuthorityMut<
     }
 }
 
+impl AccessRequestEscrow {
+    #[inline(always)]
+    fn try_require_unexpired(&self) -> ProgramResult {
+        if self.is_expired(Clock::get()?.unix_timestamp) {
+            msg!("Access request has expired");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(())
+    }
+}
+
 impl ProgramConfig {
     #[inline(always)]
     fn try_require_unpaused(&self) -> ProgramResult {
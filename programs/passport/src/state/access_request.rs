@@ -1,11 +1,12 @@
 use bytemuck::{Pod, Zeroable};
-use doublezero_program_tools::{Discriminator, PrecomputedDiscriminator};
+use doublezero_program_tools::{types::StorageGap, Discriminator, PrecomputedDiscriminator};
 #[cfg(feature = "offchain")]
 use itertools::Itertools;
 use solana_pubkey::Pubkey;
 
 #[cfg(feature = "offchain")]
 use crate::instruction::AccessMode;
+use crate::state::ProgramConfig;
 
 pub const REQUEST_ACCESS_MAX_DATA_SIZE: usize = 4_096;
 
@@ -39,10 +40,22 @@ impl PrecomputedDiscriminator for AccessRequest {
 impl AccessRequest {
     pub const SEED_PREFIX: &'static [u8] = b"access_request";
 
+    /// Length of the remaining data storing the escrow terms.
+    pub const ESCROW_LEN: usize = size_of::<AccessRequestEscrow>();
+
     pub fn find_address(service_key: &Pubkey) -> (Pubkey, u8) {
         Pubkey::find_program_address(&[Self::SEED_PREFIX, service_key.as_ref()], &crate::ID)
     }
 
+    /// Returns the escrow terms stored in the account's remaining data, which
+    /// may not be aligned. Access requests created before deposits were
+    /// escrowed do not have them.
+    pub fn escrow(remaining_data: &[u8]) -> Option<AccessRequestEscrow> {
+        remaining_data
+            .get(..Self::ESCROW_LEN)
+            .map(bytemuck::pod_read_unaligned)
+    }
+
     #[cfg(feature = "offchain")]
     pub fn access_request_message(access_mode: &AccessMode) -> String {
         match access_mode {
@@ -59,6 +72,12 @@ impl AccessRequest {
                     backup_ids.iter().join(",")
                 )
             }
+            AccessMode::SolanaValidatorWithVoteAccount(attestation) => {
+                format!(
+                    "service_key={},validator_id={},vote_account={}",
+                    attestation.service_key, attestation.validator_id, attestation.vote_account_key
+                )
+            }
         }
     }
 
@@ -73,16 +92,206 @@ const _: () = assert!(
     "`AccessRequest` size changed"
 );
 
+/// Terms of the deposit escrowed by an access request, which are stored after
+/// the [AccessRequest] in the account's data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct AccessRequestEscrow {
+    /// Lamports escrowed in addition to rent, which include the request fee.
+    pub request_deposit_lamports: u64,
+
+    /// Unix timestamp at which this request expires. Zero if it never expires.
+    pub expiry_timestamp: i64,
+
+    /// Portion of the refundable deposit (the deposit less the request fee)
+    /// returned when this request is denied. Zero means the requestor forfeits
+    /// all of the access request's lamports, including rent.
+    pub deny_refund_basis_points: u16,
+
+    /// Nonzero if the service key signed a proof of ownership for the
+    /// requesting validator identity, which was verified by this program.
+    pub service_key_verified: u8,
+
+    /// Bitmap of quorum sentinels (indexed by their position in
+    /// [ProgramConfig::quorum_sentinel_keys]) that approved this request.
+    pub sentinel_approvals: u8,
+
+    /// Version of the quorum sentinels that recorded
+    /// [Self::sentinel_approvals].
+    pub quorum_sentinels_version: u32,
+
+    /// 1 * 32 bytes of a storage gap in case more fields need to be added.
+    _storage_gap: StorageGap<1>,
+}
+
+impl AccessRequestEscrow {
+    pub fn is_expired(&self, current_timestamp: i64) -> bool {
+        self.expiry_timestamp != 0 && current_timestamp >= self.expiry_timestamp
+    }
+
+    pub fn is_service_key_verified(&self) -> bool {
+        self.service_key_verified != 0
+    }
+
+    /// Number of approvals by the program config's current quorum sentinels.
+    pub fn sentinel_approvals_count(&self, program_config: &ProgramConfig) -> u32 {
+        if self.quorum_sentinels_version != program_config.quorum_sentinels_version {
+            return 0;
+        }
+
+        self.sentinel_approvals.count_ones()
+    }
+
+    pub fn is_sentinel_quorum_reached(&self, program_config: &ProgramConfig) -> bool {
+        self.sentinel_approvals_count(program_config) >= program_config.sentinel_quorum.into()
+    }
+
+    /// Record the approval of the quorum sentinel at the given index. Approvals
+    /// recorded by previous quorum sentinels are discarded. Returns false if
+    /// this sentinel has already approved.
+    pub fn try_record_sentinel_approval(
+        &mut self,
+        program_config: &ProgramConfig,
+        sentinel_index: usize,
+    ) -> bool {
+        if self.quorum_sentinels_version != program_config.quorum_sentinels_version {
+            self.quorum_sentinels_version = program_config.quorum_sentinels_version;
+            self.sentinel_approvals = 0;
+        }
+
+        let approval_bit = 1 << sentinel_index;
+
+        if self.sentinel_approvals & approval_bit != 0 {
+            return false;
+        }
+
+        self.sentinel_approvals |= approval_bit;
+        true
+    }
+
+    /// Lamports refunded from the deposit when this request is denied: the
+    /// refunded portion of the deposit less the request fee.
+    pub fn checked_deny_refund_lamports(&self, request_fee_lamports: u64) -> Option<u64> {
+        let refundable_lamports = self
+            .request_deposit_lamports
+            .checked_sub(request_fee_lamports)?;
+
+        u128::from(refundable_lamports)
+            .checked_mul(self.deny_refund_basis_points.into())?
+            .checked_div(ProgramConfig::MAX_BASIS_POINTS.into())?
+            .try_into()
+            .ok()
+    }
+}
+
+const _: () = assert!(
+    size_of::<AccessRequestEscrow>() == 56,
+    "`AccessRequestEscrow` size changed"
+);
+
 #[allow(unused_imports)]
 #[cfg(test)]
 mod tests {
     use borsh::BorshSerialize;
     use solana_sdk::signature::Signature;
 
-    use crate::instruction::SolanaValidatorAttestation;
+    use crate::instruction::{SolanaValidatorAttestation, SolanaVoteAccountAttestation};
 
     use super::*;
 
+    #[test]
+    fn test_escrow() {
+        let escrow = AccessRequestEscrow {
+            request_deposit_lamports: 1_010_000,
+            expiry_timestamp: 69,
+            deny_refund_basis_points: 2_500,
+            ..Default::default()
+        };
+
+        // Access requests created before deposits were escrowed have no
+        // remaining data.
+        assert_eq!(AccessRequest::escrow(&[]), None);
+
+        let mut remaining_data = [0; AccessRequest::ESCROW_LEN + 1];
+        remaining_data[1..].copy_from_slice(bytemuck::bytes_of(&escrow));
+        assert_eq!(AccessRequest::escrow(&remaining_data[1..]), Some(escrow));
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let mut escrow = AccessRequestEscrow::default();
+        assert!(!escrow.is_expired(i64::MAX));
+
+        escrow.expiry_timestamp = 69;
+        assert!(!escrow.is_expired(68));
+        assert!(escrow.is_expired(69));
+        assert!(escrow.is_expired(70));
+    }
+
+    #[test]
+    fn test_sentinel_approvals() {
+        let mut program_config = ProgramConfig::default();
+        program_config.sentinel_quorum = 2;
+
+        let mut escrow = AccessRequestEscrow::default();
+        assert!(!escrow.is_sentinel_quorum_reached(&program_config));
+
+        assert!(escrow.try_record_sentinel_approval(&program_config, 0));
+        assert!(!escrow.try_record_sentinel_approval(&program_config, 0));
+        assert_eq!(escrow.sentinel_approvals_count(&program_config), 1);
+        assert!(!escrow.is_sentinel_quorum_reached(&program_config));
+
+        assert!(escrow.try_record_sentinel_approval(&program_config, 4));
+        assert_eq!(escrow.sentinel_approvals, 0b10001);
+        assert!(escrow.is_sentinel_quorum_reached(&program_config));
+
+        // Changing the quorum sentinels invalidates previous approvals.
+        program_config.quorum_sentinels_version = 1;
+        assert_eq!(escrow.sentinel_approvals_count(&program_config), 0);
+        assert!(!escrow.is_sentinel_quorum_reached(&program_config));
+
+        assert!(escrow.try_record_sentinel_approval(&program_config, 4));
+        assert_eq!(escrow.sentinel_approvals, 0b10000);
+        assert_eq!(escrow.quorum_sentinels_version, 1);
+
+        // Approvals do not become valid again after many reconfigurations.
+        program_config.quorum_sentinels_version = 257;
+        assert_eq!(escrow.sentinel_approvals_count(&program_config), 0);
+    }
+
+    #[test]
+    fn test_checked_deny_refund_lamports() {
+        let request_fee_lamports = 10_000;
+
+        let mut escrow = AccessRequestEscrow {
+            request_deposit_lamports: 1_010_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            escrow.checked_deny_refund_lamports(request_fee_lamports),
+            Some(0)
+        );
+
+        escrow.deny_refund_basis_points = 2_500;
+        assert_eq!(
+            escrow.checked_deny_refund_lamports(request_fee_lamports),
+            Some(250_000)
+        );
+
+        escrow.deny_refund_basis_points = ProgramConfig::MAX_BASIS_POINTS;
+        assert_eq!(
+            escrow.checked_deny_refund_lamports(request_fee_lamports),
+            Some(1_000_000)
+        );
+
+        // Fee cannot exceed the deposit.
+        escrow.request_deposit_lamports = 9_999;
+        assert_eq!(
+            escrow.checked_deny_refund_lamports(request_fee_lamports),
+            None
+        );
+    }
+
     #[cfg(feature = "offchain")]
     #[test]
     fn test_checked_access_mode() {
@@ -131,5 +340,25 @@ mod tests {
             ..Default::default()
         };
         assert_eq!(access_request.checked_access_mode().unwrap(), access_mode);
+
+        let access_mode =
+            AccessMode::SolanaValidatorWithVoteAccount(SolanaVoteAccountAttestation {
+                validator_id: Pubkey::new_unique(),
+                vote_account_key: Pubkey::new_unique(),
+                authorized_withdrawer_key: Pubkey::new_unique(),
+                service_key: Pubkey::new_unique(),
+                ed25519_signature: Signature::new_unique().into(),
+            });
+
+        let mut encoded_access_mode = [0; REQUEST_ACCESS_MAX_DATA_SIZE];
+        access_mode
+            .serialize(&mut encoded_access_mode.as_mut())
+            .unwrap();
+
+        let access_request = AccessRequest {
+            encoded_access_mode,
+            ..Default::default()
+        };
+        assert_eq!(access_request.checked_access_mode().unwrap(), access_mode);
     }
 }
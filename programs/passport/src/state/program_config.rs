@@ -1,10 +1,15 @@
 use bytemuck::{Pod, Zeroable};
 use doublezero_program_tools::{
+    define_flags,
     types::{Flags, StorageGap},
     Discriminator, PrecomputedDiscriminator,
 };
 use solana_pubkey::Pubkey;
 
+/// Maximum number of sentinels whose approvals count toward the sentinel
+/// quorum.
+pub const MAX_QUORUM_SENTINELS: usize = 5;
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
 #[repr(C, align(8))]
 pub struct ProgramConfig {
@@ -19,10 +24,49 @@ pub struct ProgramConfig {
     pub request_fee_lamports: u64,
 
     pub solana_validator_backup_ids_limit: u16,
-    _padding: [u8; 30],
 
-    /// 7 * 32 bytes of a storage gap in case more fields need to be added.
-    _storage_gap: StorageGap<7>,
+    /// Portion of the refundable deposit (the deposit less the request fee)
+    /// returned when an access request is denied. Zero means the requestor
+    /// forfeits all of the access request's lamports.
+    pub deny_refund_basis_points: u16,
+
+    /// Duration an access request can wait for the sentinel before it expires
+    /// and its entire deposit is retained. Zero means requests never expire.
+    pub access_request_expiry_seconds: u32,
+
+    /// Lamports a granted user's service key is funded up to when access is
+    /// granted.
+    pub onboarding_lamports: u64,
+
+    /// When a granted user's service key balance is below this amount, granting
+    /// access funds it up to the onboarding amount. Zero means granted users
+    /// are not funded.
+    pub top_up_threshold_lamports: u64,
+
+    /// Number of approvals from [Self::quorum_sentinel_keys] an access request
+    /// needs before the sentinel can grant access. Zero means approvals are
+    /// not required.
+    pub sentinel_quorum: u8,
+    _padding: [u8; 3],
+
+    /// Incremented whenever the quorum sentinels change, which invalidates
+    /// approvals recorded by the previous quorum sentinels.
+    pub quorum_sentinels_version: u32,
+
+    /// Sentinels that approve access requests. Unused entries are the default
+    /// pubkey.
+    pub quorum_sentinel_keys: [Pubkey; MAX_QUORUM_SENTINELS],
+
+    /// 2 * 32 bytes of a storage gap in case more fields need to be added.
+    _storage_gap: StorageGap<2>,
+}
+
+define_flags! {
+    /// Flag bits stored in [ProgramConfig::flags].
+    pub enum ProgramConfigFlag: Flags {
+        IsPaused = 0,
+        IsRequestAccessPaused = 1,
+    }
 }
 
 impl PrecomputedDiscriminator for ProgramConfig {
@@ -32,28 +76,38 @@ impl PrecomputedDiscriminator for ProgramConfig {
 impl ProgramConfig {
     pub const SEED_PREFIX: &'static [u8] = b"program_config";
 
-    pub const FLAG_IS_PAUSED_BIT: usize = 0;
-    pub const FLAG_IS_REQUEST_ACCESS_PAUSED_BIT: usize = 1;
+    pub const MAX_BASIS_POINTS: u16 = 10_000;
 
     pub fn find_address() -> (Pubkey, u8) {
         Pubkey::find_program_address(&[Self::SEED_PREFIX], &crate::ID)
     }
 
     pub fn is_paused(&self) -> bool {
-        self.flags.bit(Self::FLAG_IS_PAUSED_BIT)
+        ProgramConfigFlag::IsPaused.is_set(&self.flags)
     }
 
     pub fn set_is_paused(&mut self, should_pause: bool) {
-        self.flags.set_bit(Self::FLAG_IS_PAUSED_BIT, should_pause);
+        ProgramConfigFlag::IsPaused.set(&mut self.flags, should_pause);
     }
 
     pub fn is_request_access_paused(&self) -> bool {
-        self.flags.bit(Self::FLAG_IS_REQUEST_ACCESS_PAUSED_BIT)
+        ProgramConfigFlag::IsRequestAccessPaused.is_set(&self.flags)
     }
 
     pub fn set_is_request_access_paused(&mut self, should_pause: bool) {
-        self.flags
-            .set_bit(Self::FLAG_IS_REQUEST_ACCESS_PAUSED_BIT, should_pause);
+        ProgramConfigFlag::IsRequestAccessPaused.set(&mut self.flags, should_pause);
+    }
+
+    /// Index of the key in [Self::quorum_sentinel_keys], which is the bit of
+    /// this sentinel's approval in an access request.
+    pub fn quorum_sentinel_index(&self, key: &Pubkey) -> Option<usize> {
+        if key == &Pubkey::default() {
+            return None;
+        }
+
+        self.quorum_sentinel_keys
+            .iter()
+            .position(|sentinel_key| sentinel_key == key)
     }
 
     pub fn checked_request_deposit_lamports(&self) -> Option<u64> {
@@ -0,0 +1,390 @@
+mod common;
+
+//
+
+use doublezero_passport::{
+    instruction::{
+        account::{ApproveAccessAccounts, GrantAccessAccounts},
+        AccessMode, PassportInstructionData, ProgramConfiguration, SolanaValidatorAttestation,
+    },
+    state::AccessRequest,
+    ID,
+};
+use doublezero_program_tools::{instruction::try_build_instruction, PrecomputedDiscriminator};
+use solana_program_test::{tokio, BanksClientError};
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    account::Account,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+//
+// Setup.
+//
+
+struct ApproveAccessSetup {
+    test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
+    sentinel_signer: Keypair,
+    quorum_sentinel_signers: Vec<Keypair>,
+    service_key: Pubkey,
+    access_request_key: Pubkey,
+}
+
+/// Set up a configured program requiring two of three quorum sentinels to
+/// approve an access request, which has been requested.
+async fn setup_for_approve_access() -> ApproveAccessSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    let quorum_sentinel_signers = (0..3).map(|_| Keypair::new()).collect::<Vec<_>>();
+
+    let service_key = Pubkey::new_unique();
+
+    let attestation = SolanaValidatorAttestation {
+        validator_id: Pubkey::new_unique(),
+        service_key,
+        ed25519_signature: [1; 64],
+    };
+
+    test_setup
+        .configure_program(
+            [ProgramConfiguration::QuorumSentinels {
+                sentinel_keys: quorum_sentinel_signers
+                    .iter()
+                    .map(|signer| signer.pubkey())
+                    .collect(),
+                sentinel_quorum: 2,
+            }],
+            &configured.admin_signer,
+        )
+        .await
+        .unwrap()
+        .request_access(&service_key, AccessMode::SolanaValidator(attestation))
+        .await
+        .unwrap();
+
+    let (access_request_key, _) = test_setup.fetch_access_request(&service_key).await;
+
+    ApproveAccessSetup {
+        test_setup,
+        admin_signer: configured.admin_signer,
+        sentinel_signer: configured.sentinel_signer,
+        quorum_sentinel_signers,
+        service_key,
+        access_request_key,
+    }
+}
+
+//
+// Approve access — happy path.
+//
+
+#[tokio::test]
+async fn test_approve_access() {
+    let ApproveAccessSetup {
+        mut test_setup,
+        sentinel_signer,
+        quorum_sentinel_signers,
+        service_key,
+        access_request_key,
+        ..
+    } = setup_for_approve_access().await;
+
+    // Cannot grant access without any approvals.
+    let (tx_err, program_logs) =
+        simulate_grant_access_revert(&mut test_setup, &sentinel_signer, &access_request_key)
+            .await
+            .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Sentinel quorum not reached: 0 of 2 approvals"
+    );
+
+    test_setup
+        .approve_access(&quorum_sentinel_signers[2], &access_request_key)
+        .await
+        .unwrap();
+
+    let (_, program_config) = test_setup.fetch_program_config().await;
+    let escrow = test_setup.fetch_access_request_escrow(&service_key).await;
+    assert_eq!(escrow.sentinel_approvals, 0b100);
+    assert_eq!(
+        escrow.quorum_sentinels_version,
+        program_config.quorum_sentinels_version
+    );
+
+    // The same quorum sentinel cannot approve twice.
+    let (tx_err, program_logs) = simulate_approve_access_revert(
+        &mut test_setup,
+        &quorum_sentinel_signers[2],
+        &access_request_key,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        &format!(
+            "Program log: Access request already approved by {}",
+            quorum_sentinel_signers[2].pubkey()
+        )
+    );
+
+    // One approval is not enough.
+    let (tx_err, program_logs) =
+        simulate_grant_access_revert(&mut test_setup, &sentinel_signer, &access_request_key)
+            .await
+            .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Sentinel quorum not reached: 1 of 2 approvals"
+    );
+
+    let payer_key = test_setup.payer_signer.pubkey();
+
+    test_setup
+        .approve_access(&quorum_sentinel_signers[0], &access_request_key)
+        .await
+        .unwrap()
+        .grant_access(&sentinel_signer, &access_request_key, &payer_key)
+        .await
+        .unwrap();
+
+    let access_request_info = test_setup
+        .banks_client
+        .get_account(access_request_key)
+        .await
+        .unwrap();
+    assert!(access_request_info.is_none());
+}
+
+//
+// Approve access — quorum sentinels replaced.
+//
+
+#[tokio::test]
+async fn test_approvals_invalidated_by_replacing_quorum_sentinels() {
+    let ApproveAccessSetup {
+        mut test_setup,
+        admin_signer,
+        sentinel_signer,
+        quorum_sentinel_signers,
+        access_request_key,
+        ..
+    } = setup_for_approve_access().await;
+
+    test_setup
+        .approve_access(&quorum_sentinel_signers[0], &access_request_key)
+        .await
+        .unwrap()
+        .approve_access(&quorum_sentinel_signers[1], &access_request_key)
+        .await
+        .unwrap()
+        .configure_program(
+            [ProgramConfiguration::QuorumSentinels {
+                sentinel_keys: quorum_sentinel_signers
+                    .iter()
+                    .map(|signer| signer.pubkey())
+                    .collect(),
+                sentinel_quorum: 2,
+            }],
+            &admin_signer,
+        )
+        .await
+        .unwrap();
+
+    // Approvals recorded before the quorum sentinels were replaced no longer
+    // count.
+    let (tx_err, program_logs) =
+        simulate_grant_access_revert(&mut test_setup, &sentinel_signer, &access_request_key)
+            .await
+            .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Sentinel quorum not reached: 0 of 2 approvals"
+    );
+
+    // Removing the quorum sentinels removes the approval requirement.
+    let payer_key = test_setup.payer_signer.pubkey();
+
+    test_setup
+        .configure_program(
+            [ProgramConfiguration::QuorumSentinels {
+                sentinel_keys: Default::default(),
+                sentinel_quorum: 0,
+            }],
+            &admin_signer,
+        )
+        .await
+        .unwrap()
+        .grant_access(&sentinel_signer, &access_request_key, &payer_key)
+        .await
+        .unwrap();
+}
+
+//
+// Approve access — access request created before the sentinel quorum.
+//
+
+#[tokio::test]
+async fn test_grant_legacy_access_request_without_approvals() {
+    let service_key = Pubkey::new_unique();
+    let access_request_key = AccessRequest::find_address(&service_key).0;
+    let rent_beneficiary_key = Pubkey::new_unique();
+
+    // Legacy access requests do not have escrow terms after the access
+    // request, so they have nowhere to record approvals.
+    let access_request = AccessRequest {
+        service_key,
+        rent_beneficiary_key,
+        request_fee_lamports: 10_000,
+        ..Default::default()
+    };
+
+    let mut data = AccessRequest::discriminator_slice().to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&access_request));
+
+    let mut test_setup = common::start_test_with_accounts(vec![common::TestAccount {
+        key: access_request_key,
+        info: Account {
+            lamports: 69_000_000,
+            data,
+            owner: ID,
+            ..Default::default()
+        },
+    }])
+    .await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+    let sentinel_signer = configured.sentinel_signer;
+    let quorum_sentinel_signer = Keypair::new();
+
+    test_setup
+        .configure_program(
+            [ProgramConfiguration::QuorumSentinels {
+                sentinel_keys: vec![quorum_sentinel_signer.pubkey()],
+                sentinel_quorum: 1,
+            }],
+            &configured.admin_signer,
+        )
+        .await
+        .unwrap();
+
+    let (tx_err, program_logs) = simulate_approve_access_revert(
+        &mut test_setup,
+        &quorum_sentinel_signer,
+        &access_request_key,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Access request cannot record approvals"
+    );
+
+    // The sentinel grants the legacy access request on its own.
+    test_setup
+        .grant_access(&sentinel_signer, &access_request_key, &rent_beneficiary_key)
+        .await
+        .unwrap();
+
+    let access_request_info = test_setup
+        .banks_client
+        .get_account(access_request_key)
+        .await
+        .unwrap();
+    assert!(access_request_info.is_none());
+}
+
+//
+// Approve access — unauthorized quorum sentinel.
+//
+
+#[tokio::test]
+async fn test_cannot_approve_access_unauthorized_quorum_sentinel() {
+    let ApproveAccessSetup {
+        mut test_setup,
+        sentinel_signer,
+        access_request_key,
+        ..
+    } = setup_for_approve_access().await;
+
+    // The sentinel granting access is not a quorum sentinel.
+    let (tx_err, program_logs) =
+        simulate_approve_access_revert(&mut test_setup, &sentinel_signer, &access_request_key)
+            .await
+            .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Unauthorized quorum sentinel (account 1)"
+    );
+}
+
+//
+// Helpers.
+//
+
+async fn simulate_approve_access_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    quorum_sentinel_signer: &Keypair,
+    access_request_key: &Pubkey,
+) -> Result<(TransactionError, Vec<String>), BanksClientError> {
+    let approve_access_ix = try_build_instruction(
+        &ID,
+        ApproveAccessAccounts::new(&quorum_sentinel_signer.pubkey(), access_request_key),
+        &PassportInstructionData::ApproveAccess,
+    )
+    .unwrap();
+
+    test_setup
+        .unwrap_simulation_error(&[approve_access_ix], &[quorum_sentinel_signer])
+        .await
+}
+
+async fn simulate_grant_access_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    sentinel_signer: &Keypair,
+    access_request_key: &Pubkey,
+) -> Result<(TransactionError, Vec<String>), BanksClientError> {
+    let grant_access_ix = try_build_instruction(
+        &ID,
+        GrantAccessAccounts::new(
+            &sentinel_signer.pubkey(),
+            access_request_key,
+            &test_setup.payer_signer.pubkey(),
+        ),
+        &PassportInstructionData::GrantAccess,
+    )
+    .unwrap();
+
+    test_setup
+        .unwrap_simulation_error(&[grant_access_ix], &[sentinel_signer])
+        .await
+}
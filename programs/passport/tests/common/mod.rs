@@ -22,35 +22,46 @@ fn init_logger() {
 use doublezero_passport::{
     instruction::{
         account::{
-            ConfigureProgramAccounts, DenyAccessAccounts, GrantAccessAccounts,
-            InitializeProgramAccounts, RequestAccessAccounts, SetAdminAccounts,
+            ApproveAccessAccounts, ConfigureProgramAccounts, DenyAccessAccounts,
+            ExpireAccessRequestAccounts, GrantAccessAccounts, InitializeProgramAccounts,
+            RequestAccessAccounts, SetAdminAccounts, UpdateValidatorIdAccounts,
         },
-        AccessMode, PassportInstructionData, ProgramConfiguration, ProgramFlagConfiguration,
+        service_key_proof_message, validator_id_update_proof_message, AccessMode,
+        PassportInstructionData, ProgramConfiguration, ProgramFlagConfiguration, ValidatorIdUpdate,
     },
-    state::{AccessRequest, ProgramConfig},
+    state::{AccessRequest, AccessRequestEscrow, ProgramConfig},
     ID,
 };
 use doublezero_program_tools::{
-    instruction::try_build_instruction, zero_copy::checked_from_bytes_with_discriminator,
+    ed25519::new_ed25519_instruction, instruction::try_build_instruction,
+    zero_copy::checked_from_bytes_with_discriminator,
 };
 use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
-use solana_program_test::{BanksClient, BanksClientError, ProgramTest, ProgramTestBanksClientExt};
+use solana_program_test::{
+    BanksClient, BanksClientError, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext,
+};
 use solana_pubkey::Pubkey;
 use solana_sdk::{
     account::Account,
+    clock::Clock,
     hash::Hash,
-    instruction::Instruction,
+    instruction::{Instruction, InstructionError},
     message::{v0::Message, VersionedMessage},
     signature::{Keypair, Signer},
     transaction::{TransactionError, VersionedTransaction},
 };
 
+/// Error the runtime reports when an instruction is not given enough accounts.
+#[allow(deprecated)]
+pub const NOT_ENOUGH_ACCOUNT_KEYS: InstructionError = InstructionError::NotEnoughAccountKeys;
+
 pub struct TestAccount {
     pub key: Pubkey,
     pub info: Account,
 }
 
 pub struct ProgramTestWithOwner {
+    pub context: ProgramTestContext,
     pub banks_client: BanksClient,
     pub payer_signer: Keypair,
     pub cached_blockhash: Hash,
@@ -85,13 +96,14 @@ pub async fn start_test_with_accounts(accounts: Vec<TestAccount>) -> ProgramTest
         program_test.add_account(key, info);
     }
 
-    let (banks_client, payer_signer, cached_blockhash) = program_test.start().await;
+    let context = program_test.start_with_context().await;
 
     ProgramTestWithOwner {
-        banks_client,
-        payer_signer,
-        cached_blockhash,
+        banks_client: context.banks_client.clone(),
+        payer_signer: context.payer.insecure_clone(),
+        cached_blockhash: context.last_blockhash,
         owner_signer,
+        context,
     }
 }
 
@@ -107,6 +119,14 @@ impl ProgramTestWithOwner {
             .map_err(Into::into)
     }
 
+    pub async fn warp_timestamp_by(&mut self, seconds: u32) -> Result<&mut Self, BanksClientError> {
+        let mut clock = self.banks_client.get_sysvar::<Clock>().await?;
+        clock.unix_timestamp += i64::from(seconds);
+        self.context.set_sysvar::<Clock>(&clock);
+
+        Ok(self)
+    }
+
     pub async fn transfer_lamports(
         &mut self,
         dst_key: &Pubkey,
@@ -276,7 +296,12 @@ impl ProgramTestWithOwner {
 
         let request_access_ix = try_build_instruction(
             &ID,
-            RequestAccessAccounts::new(&payer_signer.pubkey(), service_key),
+            RequestAccessAccounts::new(
+                &payer_signer.pubkey(),
+                service_key,
+                access_mode.vote_account_key().as_ref(),
+                false,
+            ),
             &PassportInstructionData::RequestAccess(access_mode),
         )
         .unwrap();
@@ -292,6 +317,41 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    pub async fn request_access_with_service_key_proof(
+        &mut self,
+        service_key_signer: &Keypair,
+        access_mode: AccessMode,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.payer_signer;
+
+        let service_key_proof_ix = new_signed_ed25519_instruction(
+            service_key_signer,
+            &service_key_proof_message(&access_mode.validator_id()),
+        );
+
+        let request_access_ix = try_build_instruction(
+            &ID,
+            RequestAccessAccounts::new(
+                &payer_signer.pubkey(),
+                &service_key_signer.pubkey(),
+                access_mode.vote_account_key().as_ref(),
+                true,
+            ),
+            &PassportInstructionData::RequestAccess(access_mode),
+        )
+        .unwrap();
+
+        self.cached_blockhash = process_instructions_for_test(
+            &mut self.banks_client,
+            &self.cached_blockhash,
+            &[service_key_proof_ix, request_access_ix],
+            &[payer_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
     pub async fn grant_access(
         &mut self,
         dz_ledger_sentinel: &Keypair,
@@ -322,16 +382,28 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    /// The rent beneficiary is only required if the access request refunds
+    /// part of its deposit on deny.
     pub async fn deny_access(
         &mut self,
         dz_ledger_sentinel: &Keypair,
         access_request_key: &Pubkey,
+        rent_beneficiary_key: Option<&Pubkey>,
     ) -> Result<&mut Self, BanksClientError> {
         let payer_signer = &self.payer_signer;
 
+        let deny_access_accounts = match rent_beneficiary_key {
+            Some(rent_beneficiary_key) => DenyAccessAccounts::new_with_refund(
+                &dz_ledger_sentinel.pubkey(),
+                access_request_key,
+                rent_beneficiary_key,
+            ),
+            None => DenyAccessAccounts::new(&dz_ledger_sentinel.pubkey(), access_request_key),
+        };
+
         let deny_access_ix = try_build_instruction(
             &ID,
-            DenyAccessAccounts::new(&dz_ledger_sentinel.pubkey(), access_request_key),
+            deny_access_accounts,
             &PassportInstructionData::DenyAccess,
         )
         .unwrap();
@@ -347,6 +419,103 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    pub async fn expire_access_request(
+        &mut self,
+        dz_ledger_sentinel: &Keypair,
+        access_request_key: &Pubkey,
+        rent_beneficiary_key: &Pubkey,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.payer_signer;
+
+        let expire_access_request_ix = try_build_instruction(
+            &ID,
+            ExpireAccessRequestAccounts::new(
+                &dz_ledger_sentinel.pubkey(),
+                access_request_key,
+                rent_beneficiary_key,
+            ),
+            &PassportInstructionData::ExpireAccessRequest,
+        )
+        .unwrap();
+
+        self.cached_blockhash = process_instructions_for_test(
+            &mut self.banks_client,
+            &self.cached_blockhash,
+            &[expire_access_request_ix],
+            &[payer_signer, dz_ledger_sentinel],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn update_validator_id(
+        &mut self,
+        old_validator_signer: &Keypair,
+        new_validator_signer: &Keypair,
+        sentinel_key: &Pubkey,
+        update: ValidatorIdUpdate,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.payer_signer;
+
+        let validator_id_update_proof_message =
+            validator_id_update_proof_message(&update.service_key, &update.new_validator_id);
+        let old_validator_id_update_proof_ix = new_signed_ed25519_instruction(
+            old_validator_signer,
+            &validator_id_update_proof_message,
+        );
+        let new_validator_id_update_proof_ix = new_signed_ed25519_instruction(
+            new_validator_signer,
+            &validator_id_update_proof_message,
+        );
+
+        let update_validator_id_ix = try_build_instruction(
+            &ID,
+            UpdateValidatorIdAccounts::new(&payer_signer.pubkey(), sentinel_key),
+            &PassportInstructionData::UpdateValidatorId(update),
+        )
+        .unwrap();
+
+        self.cached_blockhash = process_instructions_for_test(
+            &mut self.banks_client,
+            &self.cached_blockhash,
+            &[
+                old_validator_id_update_proof_ix,
+                new_validator_id_update_proof_ix,
+                update_validator_id_ix,
+            ],
+            &[payer_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn approve_access(
+        &mut self,
+        quorum_sentinel: &Keypair,
+        access_request_key: &Pubkey,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.payer_signer;
+
+        let approve_access_ix = try_build_instruction(
+            &ID,
+            ApproveAccessAccounts::new(&quorum_sentinel.pubkey(), access_request_key),
+            &PassportInstructionData::ApproveAccess,
+        )
+        .unwrap();
+
+        self.cached_blockhash = process_instructions_for_test(
+            &mut self.banks_client,
+            &self.cached_blockhash,
+            &[approve_access_ix],
+            &[payer_signer, quorum_sentinel],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
     //
     // Account fetchers.
     //
@@ -388,6 +557,24 @@ impl ProgramTestWithOwner {
                 .0,
         )
     }
+
+    pub async fn fetch_access_request_escrow(&self, service_key: &Pubkey) -> AccessRequestEscrow {
+        let access_request_key = AccessRequest::find_address(service_key).0;
+
+        let access_request_account_data = self
+            .banks_client
+            .get_account(access_request_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        let (_, remaining_data) =
+            checked_from_bytes_with_discriminator::<AccessRequest>(&access_request_account_data)
+                .unwrap();
+
+        AccessRequest::escrow(remaining_data).unwrap()
+    }
 }
 
 pub async fn process_instructions_for_test(
@@ -408,6 +595,11 @@ pub async fn process_instructions_for_test(
     Ok(recent_blockhash)
 }
 
+pub fn new_signed_ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    let signature = signer.sign_message(message);
+    new_ed25519_instruction(&signer.pubkey(), signature.as_array(), message)
+}
+
 fn new_transaction(
     instructions: &[Instruction],
     signers: &[&Keypair],
@@ -3,12 +3,21 @@ mod common;
 //
 
 use doublezero_passport::{
-    instruction::{ProgramConfiguration, ProgramFlagConfiguration},
+    instruction::{
+        account::ConfigureProgramAccounts, PassportInstructionData, ProgramConfiguration,
+        ProgramFlagConfiguration,
+    },
     state::ProgramConfig,
+    ID,
 };
+use doublezero_program_tools::instruction::try_build_instruction;
 use solana_program_test::tokio;
 use solana_pubkey::Pubkey;
-use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
 
 //
 // Setup.
@@ -59,6 +68,10 @@ async fn test_configure_program() {
     let required_deposit_lamports = 1_000_000;
     let fee_lamports = 1_000;
     let solana_validator_backup_ids_limit = 10;
+    let deny_refund_basis_points = 2_500;
+    let access_request_expiry_seconds = 86_400;
+    let onboarding_lamports = 50_000_000;
+    let top_up_threshold_lamports = 10_000_000;
 
     test_setup
         .configure_program(
@@ -75,6 +88,14 @@ async fn test_configure_program() {
                 ProgramConfiguration::SolanaValidatorBackupIdsLimit(
                     solana_validator_backup_ids_limit,
                 ),
+                ProgramConfiguration::AccessRequestEscrow {
+                    deny_refund_basis_points,
+                    expiry_seconds: access_request_expiry_seconds,
+                },
+                ProgramConfiguration::OnboardingFunding {
+                    onboarding_lamports,
+                    top_up_threshold_lamports,
+                },
             ],
             &admin_signer,
         )
@@ -91,5 +112,114 @@ async fn test_configure_program() {
     expected_program_config.request_deposit_lamports = required_deposit_lamports;
     expected_program_config.request_fee_lamports = fee_lamports;
     expected_program_config.solana_validator_backup_ids_limit = solana_validator_backup_ids_limit;
+    expected_program_config.deny_refund_basis_points = deny_refund_basis_points;
+    expected_program_config.access_request_expiry_seconds = access_request_expiry_seconds;
+    expected_program_config.onboarding_lamports = onboarding_lamports;
+    expected_program_config.top_up_threshold_lamports = top_up_threshold_lamports;
     assert_eq!(program_config, expected_program_config);
 }
+
+//
+// Configure program — deny refund basis points too large.
+//
+
+#[tokio::test]
+async fn test_cannot_configure_deny_refund_basis_points_too_large() {
+    let ConfigureProgramSetup {
+        mut test_setup,
+        admin_signer,
+    } = setup_for_configure_program().await;
+
+    let configure_program_ix = try_build_instruction(
+        &ID,
+        ConfigureProgramAccounts::new(&admin_signer.pubkey()),
+        &PassportInstructionData::ConfigureProgram(ProgramConfiguration::AccessRequestEscrow {
+            deny_refund_basis_points: ProgramConfig::MAX_BASIS_POINTS + 1,
+            expiry_seconds: 0,
+        }),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[configure_program_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Deny refund basis points cannot exceed 10000"
+    );
+}
+
+//
+// Configure program — top-up threshold not less than onboarding amount.
+//
+
+#[tokio::test]
+async fn test_cannot_configure_top_up_threshold_too_large() {
+    let ConfigureProgramSetup {
+        mut test_setup,
+        admin_signer,
+    } = setup_for_configure_program().await;
+
+    let configure_program_ix = try_build_instruction(
+        &ID,
+        ConfigureProgramAccounts::new(&admin_signer.pubkey()),
+        &PassportInstructionData::ConfigureProgram(ProgramConfiguration::OnboardingFunding {
+            onboarding_lamports: 10_000_000,
+            top_up_threshold_lamports: 10_000_000,
+        }),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[configure_program_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Top-up threshold must be less than the onboarding amount"
+    );
+}
+
+//
+// Configure program — sentinel quorum too large.
+//
+
+#[tokio::test]
+async fn test_cannot_configure_sentinel_quorum_too_large() {
+    let ConfigureProgramSetup {
+        mut test_setup,
+        admin_signer,
+    } = setup_for_configure_program().await;
+
+    let configure_program_ix = try_build_instruction(
+        &ID,
+        ConfigureProgramAccounts::new(&admin_signer.pubkey()),
+        &PassportInstructionData::ConfigureProgram(ProgramConfiguration::QuorumSentinels {
+            sentinel_keys: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            sentinel_quorum: 3,
+        }),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[configure_program_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Sentinel quorum cannot exceed the number of quorum sentinels"
+    );
+}
@@ -4,16 +4,19 @@ mod common;
 
 use doublezero_passport::{
     instruction::{
-        account::DenyAccessAccounts, AccessMode, PassportInstructionData,
+        account::DenyAccessAccounts, AccessMode, PassportInstructionData, ProgramConfiguration,
         SolanaValidatorAttestation,
     },
-    state::AccessRequest,
+    state::{AccessRequest, ProgramConfig},
     ID,
 };
-use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
+use doublezero_program_tools::{
+    instruction::try_build_instruction, zero_copy, PrecomputedDiscriminator,
+};
 use solana_program_test::{tokio, BanksClientError};
 use solana_pubkey::Pubkey;
 use solana_sdk::{
+    account::Account,
     instruction::InstructionError,
     signature::{Keypair, Signer},
     transaction::TransactionError,
@@ -28,13 +31,25 @@ struct DenyAccessSetup {
     sentinel_signer: Keypair,
     service_key: Pubkey,
     access_deposit: u64,
+    access_fee: u64,
 }
 
-async fn setup_for_deny_access() -> DenyAccessSetup {
+async fn setup_for_deny_access(deny_refund_basis_points: u16) -> DenyAccessSetup {
     let mut test_setup = common::start_test().await;
 
     let configured = test_setup.setup_configured_program().await.unwrap();
 
+    test_setup
+        .configure_program(
+            [ProgramConfiguration::AccessRequestEscrow {
+                deny_refund_basis_points,
+                expiry_seconds: 0,
+            }],
+            &configured.admin_signer,
+        )
+        .await
+        .unwrap();
+
     let service_key = Pubkey::new_unique();
     let validator_id = Pubkey::new_unique();
 
@@ -54,6 +69,7 @@ async fn setup_for_deny_access() -> DenyAccessSetup {
         sentinel_signer: configured.sentinel_signer,
         service_key,
         access_deposit: 10_000_000,
+        access_fee: 10_000,
     }
 }
 
@@ -68,34 +84,117 @@ async fn test_deny_access() {
         sentinel_signer,
         service_key,
         access_deposit,
-    } = setup_for_deny_access().await;
+        ..
+    } = setup_for_deny_access(0).await;
 
-    let sentinel_before_balance = test_setup
-        .banks_client
-        .get_balance(sentinel_signer.pubkey())
-        .await
-        .unwrap();
+    // Without a refund, the requestor forfeits the entire deposit and rent.
+    assert_deny_access_balances(
+        &mut test_setup,
+        &sentinel_signer,
+        &service_key,
+        access_deposit,
+        None,
+    )
+    .await;
+}
 
-    let (access_request_key, access_request) = test_setup.fetch_access_request(&service_key).await;
+//
+// Deny access — partial refund.
+//
 
-    let access_request_balance = test_setup
-        .banks_client
-        .get_balance(access_request_key)
-        .await
-        .unwrap();
+#[tokio::test]
+async fn test_deny_access_partial_refund() {
+    let DenyAccessSetup {
+        mut test_setup,
+        sentinel_signer,
+        service_key,
+        access_deposit,
+        access_fee,
+    } = setup_for_deny_access(2_500).await;
+
+    // Rent and 25% of the deposit less the request fee are returned.
+    let expected_refund = (access_deposit - access_fee) / 4;
+    assert_deny_access_balances(
+        &mut test_setup,
+        &sentinel_signer,
+        &service_key,
+        access_deposit,
+        Some(expected_refund),
+    )
+    .await;
+}
 
-    let request_rent = test_setup
+//
+// Deny access — full refund.
+//
+
+#[tokio::test]
+async fn test_deny_access_full_refund() {
+    let DenyAccessSetup {
+        mut test_setup,
+        sentinel_signer,
+        service_key,
+        access_deposit,
+        access_fee,
+    } = setup_for_deny_access(ProgramConfig::MAX_BASIS_POINTS).await;
+
+    // Only the request fee is retained.
+    assert_deny_access_balances(
+        &mut test_setup,
+        &sentinel_signer,
+        &service_key,
+        access_deposit,
+        Some(access_deposit - access_fee),
+    )
+    .await;
+}
+
+//
+// Deny access — access request created before deposits were escrowed.
+//
+
+#[tokio::test]
+async fn test_deny_legacy_access_request() {
+    let service_key = Pubkey::new_unique();
+    let access_request_key = AccessRequest::find_address(&service_key).0;
+
+    // Legacy access requests do not have escrow terms after the access
+    // request.
+    let access_request = AccessRequest {
+        service_key,
+        rent_beneficiary_key: Pubkey::new_unique(),
+        request_fee_lamports: 10_000,
+        ..Default::default()
+    };
+
+    let mut data = AccessRequest::discriminator_slice().to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&access_request));
+
+    let access_request_lamports = 69_000_000;
+
+    let mut test_setup = common::start_test_with_accounts(vec![common::TestAccount {
+        key: access_request_key,
+        info: Account {
+            lamports: access_request_lamports,
+            data,
+            owner: ID,
+            ..Default::default()
+        },
+    }])
+    .await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+    let sentinel_signer = configured.sentinel_signer;
+
+    let sentinel_before_balance = test_setup
         .banks_client
-        .get_rent()
+        .get_balance(sentinel_signer.pubkey())
         .await
-        .unwrap()
-        .minimum_balance(zero_copy::data_end::<AccessRequest>());
-
-    assert_eq!(access_request_balance - request_rent, access_deposit);
-    assert_eq!(access_request.service_key, service_key);
+        .unwrap();
 
+    // The requestor forfeits all lamports, so no rent beneficiary is needed.
     test_setup
-        .deny_access(&sentinel_signer, &access_request_key)
+        .deny_access(&sentinel_signer, &access_request_key, None)
         .await
         .unwrap();
 
@@ -104,10 +203,9 @@ async fn test_deny_access() {
         .get_balance(sentinel_signer.pubkey())
         .await
         .unwrap();
-
     assert_eq!(
-        sentinel_before_balance + access_deposit + request_rent,
-        sentinel_after_balance,
+        sentinel_before_balance + access_request_lamports,
+        sentinel_after_balance
     );
 
     let access_request_info = test_setup
@@ -129,7 +227,7 @@ async fn test_cannot_deny_access_unauthorized_sentinel() {
         sentinel_signer,
         service_key,
         ..
-    } = setup_for_deny_access().await;
+    } = setup_for_deny_access(0).await;
 
     let sentinel_before_balance = test_setup
         .banks_client
@@ -140,10 +238,16 @@ async fn test_cannot_deny_access_unauthorized_sentinel() {
     let (access_request_key, _) = test_setup.fetch_access_request(&service_key).await;
     let unauthorized_signer = Keypair::new();
 
-    let (tx_err, _) =
-        simulate_deny_access_revert(&mut test_setup, &unauthorized_signer, &access_request_key)
-            .await
-            .unwrap();
+    let payer_key = test_setup.payer_signer.pubkey();
+
+    let (tx_err, _) = simulate_deny_access_revert(
+        &mut test_setup,
+        &unauthorized_signer,
+        &access_request_key,
+        Some(&payer_key),
+    )
+    .await
+    .unwrap();
 
     assert_eq!(
         tx_err,
@@ -167,19 +271,172 @@ async fn test_cannot_deny_access_unauthorized_sentinel() {
     assert!(access_request_info.is_some());
 }
 
+//
+// Deny access — wrong rent beneficiary.
+//
+
+#[tokio::test]
+async fn test_cannot_deny_access_wrong_rent_beneficiary() {
+    let DenyAccessSetup {
+        mut test_setup,
+        sentinel_signer,
+        service_key,
+        ..
+    } = setup_for_deny_access(2_500).await;
+
+    let (access_request_key, access_request) = test_setup.fetch_access_request(&service_key).await;
+
+    let (tx_err, program_logs) = simulate_deny_access_revert(
+        &mut test_setup,
+        &sentinel_signer,
+        &access_request_key,
+        Some(&Pubkey::new_unique()),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        &format!(
+            "Program log: Expected rent beneficiary key: {}",
+            access_request.rent_beneficiary_key
+        )
+    );
+}
+
+//
+// Deny access — missing rent beneficiary for refund.
+//
+
+#[tokio::test]
+async fn test_cannot_deny_access_refund_without_rent_beneficiary() {
+    let DenyAccessSetup {
+        mut test_setup,
+        sentinel_signer,
+        service_key,
+        ..
+    } = setup_for_deny_access(2_500).await;
+
+    let (access_request_key, _) = test_setup.fetch_access_request(&service_key).await;
+
+    let (tx_err, _) =
+        simulate_deny_access_revert(&mut test_setup, &sentinel_signer, &access_request_key, None)
+            .await
+            .unwrap();
+
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, common::NOT_ENOUGH_ACCOUNT_KEYS)
+    );
+}
+
 //
 // Helpers.
 //
 
+/// If no refund is expected, the access request is denied without a rent
+/// beneficiary and the sentinel receives all of its lamports.
+async fn assert_deny_access_balances(
+    test_setup: &mut common::ProgramTestWithOwner,
+    sentinel_signer: &Keypair,
+    service_key: &Pubkey,
+    access_deposit: u64,
+    expected_refund: Option<u64>,
+) {
+    let payer_key = test_setup.payer_signer.pubkey();
+
+    let sentinel_before_balance = test_setup
+        .banks_client
+        .get_balance(sentinel_signer.pubkey())
+        .await
+        .unwrap();
+    let payer_before_balance = test_setup
+        .banks_client
+        .get_balance(payer_key)
+        .await
+        .unwrap();
+
+    let (access_request_key, access_request) = test_setup.fetch_access_request(service_key).await;
+    let escrow = test_setup.fetch_access_request_escrow(service_key).await;
+
+    let access_request_balance = test_setup
+        .banks_client
+        .get_balance(access_request_key)
+        .await
+        .unwrap();
+
+    let request_rent = test_setup
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(zero_copy::data_end::<AccessRequest>() + AccessRequest::ESCROW_LEN);
+
+    assert_eq!(access_request_balance - request_rent, access_deposit);
+    assert_eq!(escrow.request_deposit_lamports, access_deposit);
+    assert_eq!(access_request.service_key, *service_key);
+
+    let rent_beneficiary_key = expected_refund.map(|_| &payer_key);
+
+    test_setup
+        .deny_access(sentinel_signer, &access_request_key, rent_beneficiary_key)
+        .await
+        .unwrap();
+
+    let expected_returned = expected_refund.map_or(0, |refund| refund + request_rent);
+
+    let sentinel_after_balance = test_setup
+        .banks_client
+        .get_balance(sentinel_signer.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        sentinel_before_balance + access_request_balance - expected_returned,
+        sentinel_after_balance,
+    );
+
+    let payer_after_balance = test_setup
+        .banks_client
+        .get_balance(payer_key)
+        .await
+        .unwrap();
+    let txn_signer_cost_adjustment = 10_000;
+    assert_eq!(
+        payer_before_balance + expected_returned - txn_signer_cost_adjustment,
+        payer_after_balance,
+    );
+
+    let access_request_info = test_setup
+        .banks_client
+        .get_account(access_request_key)
+        .await
+        .unwrap();
+    assert!(access_request_info.is_none());
+}
+
 async fn simulate_deny_access_revert(
     test_setup: &mut common::ProgramTestWithOwner,
     sentinel_signer: &Keypair,
     access_request_key: &Pubkey,
+    rent_beneficiary_key: Option<&Pubkey>,
 ) -> Result<(TransactionError, Vec<String>), BanksClientError> {
+    let deny_access_accounts = match rent_beneficiary_key {
+        Some(rent_beneficiary_key) => DenyAccessAccounts::new_with_refund(
+            &sentinel_signer.pubkey(),
+            access_request_key,
+            rent_beneficiary_key,
+        ),
+        None => DenyAccessAccounts::new(&sentinel_signer.pubkey(), access_request_key),
+    };
+
     let deny_access_ix = try_build_instruction(
         &ID,
-        DenyAccessAccounts::new(&sentinel_signer.pubkey(), access_request_key),
-        &PassportInstructionData::GrantAccess,
+        deny_access_accounts,
+        &PassportInstructionData::DenyAccess,
     )
     .unwrap();
 
@@ -0,0 +1,235 @@
+mod common;
+
+//
+
+use doublezero_passport::{
+    instruction::{
+        account::{ExpireAccessRequestAccounts, GrantAccessAccounts},
+        AccessMode, PassportInstructionData, ProgramConfiguration, SolanaValidatorAttestation,
+    },
+    state::AccessRequest,
+    ID,
+};
+use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    clock::Clock,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+//
+// Setup.
+//
+
+struct ExpireAccessRequestSetup {
+    test_setup: common::ProgramTestWithOwner,
+    sentinel_signer: Keypair,
+    service_key: Pubkey,
+    access_request_key: Pubkey,
+    access_deposit: u64,
+}
+
+async fn setup_for_expire_access_request() -> ExpireAccessRequestSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    let service_key = Pubkey::new_unique();
+
+    let attestation = SolanaValidatorAttestation {
+        validator_id: Pubkey::new_unique(),
+        service_key,
+        ed25519_signature: [1; 64],
+    };
+
+    test_setup
+        .configure_program(
+            [ProgramConfiguration::AccessRequestEscrow {
+                deny_refund_basis_points: 0,
+                expiry_seconds: 60,
+            }],
+            &configured.admin_signer,
+        )
+        .await
+        .unwrap()
+        .request_access(&service_key, AccessMode::SolanaValidator(attestation))
+        .await
+        .unwrap();
+
+    ExpireAccessRequestSetup {
+        test_setup,
+        sentinel_signer: configured.sentinel_signer,
+        service_key,
+        access_request_key: AccessRequest::find_address(&service_key).0,
+        access_deposit: 10_000_000,
+    }
+}
+
+//
+// Expire access request — happy path.
+//
+
+#[tokio::test]
+async fn test_expire_access_request() {
+    let ExpireAccessRequestSetup {
+        mut test_setup,
+        sentinel_signer,
+        service_key,
+        access_request_key,
+        access_deposit,
+    } = setup_for_expire_access_request().await;
+
+    let payer_key = test_setup.payer_signer.pubkey();
+
+    warp_to_expiry(&mut test_setup, &service_key).await;
+
+    let sentinel_before_balance = test_setup
+        .banks_client
+        .get_balance(sentinel_signer.pubkey())
+        .await
+        .unwrap();
+    let payer_before_balance = test_setup
+        .banks_client
+        .get_balance(payer_key)
+        .await
+        .unwrap();
+
+    let request_rent = test_setup
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(zero_copy::data_end::<AccessRequest>() + AccessRequest::ESCROW_LEN);
+
+    test_setup
+        .expire_access_request(&sentinel_signer, &access_request_key, &payer_key)
+        .await
+        .unwrap();
+
+    // The entire deposit is retained. Only rent is returned.
+    let sentinel_after_balance = test_setup
+        .banks_client
+        .get_balance(sentinel_signer.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        sentinel_before_balance + access_deposit,
+        sentinel_after_balance
+    );
+
+    let payer_after_balance = test_setup
+        .banks_client
+        .get_balance(payer_key)
+        .await
+        .unwrap();
+    let txn_signer_cost_adjustment = 10_000;
+    assert_eq!(
+        payer_before_balance + request_rent - txn_signer_cost_adjustment,
+        payer_after_balance
+    );
+
+    let access_request_info = test_setup
+        .banks_client
+        .get_account(access_request_key)
+        .await
+        .unwrap();
+    assert!(access_request_info.is_none());
+}
+
+//
+// Expire access request — not expired yet.
+//
+
+#[tokio::test]
+async fn test_cannot_expire_access_request_before_expiry() {
+    let ExpireAccessRequestSetup {
+        mut test_setup,
+        sentinel_signer,
+        access_request_key,
+        ..
+    } = setup_for_expire_access_request().await;
+
+    let expire_access_request_ix = try_build_instruction(
+        &ID,
+        ExpireAccessRequestAccounts::new(
+            &sentinel_signer.pubkey(),
+            &access_request_key,
+            &test_setup.payer_signer.pubkey(),
+        ),
+        &PassportInstructionData::ExpireAccessRequest,
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[expire_access_request_ix], &[&sentinel_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Access request has not expired"
+    );
+}
+
+//
+// Grant access — expired access request.
+//
+
+#[tokio::test]
+async fn test_cannot_grant_expired_access_request() {
+    let ExpireAccessRequestSetup {
+        mut test_setup,
+        sentinel_signer,
+        service_key,
+        access_request_key,
+        ..
+    } = setup_for_expire_access_request().await;
+
+    warp_to_expiry(&mut test_setup, &service_key).await;
+
+    let grant_access_ix = try_build_instruction(
+        &ID,
+        GrantAccessAccounts::new(
+            &sentinel_signer.pubkey(),
+            &access_request_key,
+            &test_setup.payer_signer.pubkey(),
+        ),
+        &PassportInstructionData::GrantAccess,
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[grant_access_ix], &[&sentinel_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Access request has expired"
+    );
+}
+
+//
+// Helpers.
+//
+
+async fn warp_to_expiry(test_setup: &mut common::ProgramTestWithOwner, service_key: &Pubkey) {
+    let escrow = test_setup.fetch_access_request_escrow(service_key).await;
+
+    let clock = test_setup.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert!(escrow.expiry_timestamp > clock.unix_timestamp);
+
+    test_setup
+        .warp_timestamp_by((escrow.expiry_timestamp - clock.unix_timestamp) as u32)
+        .await
+        .unwrap();
+}
@@ -2,9 +2,10 @@ mod common;
 
 //
 
+use common::process_instructions_for_test;
 use doublezero_passport::{
     instruction::{
-        account::GrantAccessAccounts, AccessMode, PassportInstructionData,
+        account::GrantAccessAccounts, AccessMode, PassportInstructionData, ProgramConfiguration,
         SolanaValidatorAttestation,
     },
     state::AccessRequest,
@@ -25,6 +26,7 @@ use solana_sdk::{
 
 struct GrantAccessSetup {
     test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
     sentinel_signer: Keypair,
     service_key: Pubkey,
     access_deposit: u64,
@@ -52,6 +54,7 @@ async fn setup_for_grant_access() -> GrantAccessSetup {
 
     GrantAccessSetup {
         test_setup,
+        admin_signer: configured.admin_signer,
         sentinel_signer: configured.sentinel_signer,
         service_key,
         access_deposit: 10_000_000,
@@ -71,6 +74,7 @@ async fn test_grant_access() {
         service_key,
         access_deposit,
         access_fee,
+        ..
     } = setup_for_grant_access().await;
 
     let sentinel_before_balance = test_setup
@@ -91,7 +95,7 @@ async fn test_grant_access() {
         .get_rent()
         .await
         .unwrap()
-        .minimum_balance(zero_copy::data_end::<AccessRequest>());
+        .minimum_balance(zero_copy::data_end::<AccessRequest>() + AccessRequest::ESCROW_LEN);
 
     let access_request_balance = test_setup
         .banks_client
@@ -137,6 +141,231 @@ async fn test_grant_access() {
     assert!(access_request_info.is_none());
 }
 
+//
+// Grant access — onboarding funding.
+//
+
+#[tokio::test]
+async fn test_grant_access_with_onboarding_funding() {
+    let GrantAccessSetup {
+        mut test_setup,
+        admin_signer,
+        sentinel_signer,
+        service_key,
+        ..
+    } = setup_for_grant_access().await;
+
+    let onboarding_lamports = 50_000_000;
+    let top_up_threshold_lamports = 10_000_000;
+    let service_key_before_balance = 1_000_000;
+
+    let onboarding_funder_signer = Keypair::new();
+    let onboarding_funder_before_balance = 100_000_000;
+
+    test_setup
+        .configure_program(
+            [ProgramConfiguration::OnboardingFunding {
+                onboarding_lamports,
+                top_up_threshold_lamports,
+            }],
+            &admin_signer,
+        )
+        .await
+        .unwrap()
+        .transfer_lamports(
+            &onboarding_funder_signer.pubkey(),
+            onboarding_funder_before_balance,
+        )
+        .await
+        .unwrap()
+        .transfer_lamports(&service_key, service_key_before_balance)
+        .await
+        .unwrap();
+
+    let (access_request_key, _) = test_setup.fetch_access_request(&service_key).await;
+    let payer_key = test_setup.payer_signer.pubkey();
+
+    // Cannot grant access without the onboarding funding accounts.
+
+    let (tx_err, _) = simulate_grant_access_revert(
+        &mut test_setup,
+        &sentinel_signer,
+        &access_request_key,
+        &payer_key,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, common::NOT_ENOUGH_ACCOUNT_KEYS)
+    );
+
+    // Cannot fund another account.
+
+    let grant_access_ix = try_build_instruction(
+        &ID,
+        GrantAccessAccounts::new_with_onboarding_funding(
+            &sentinel_signer.pubkey(),
+            &access_request_key,
+            &payer_key,
+            &onboarding_funder_signer.pubkey(),
+            &Pubkey::new_unique(),
+        ),
+        &PassportInstructionData::GrantAccess,
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(
+            &[grant_access_ix],
+            &[&sentinel_signer, &onboarding_funder_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(4).unwrap(),
+        "Program log: Invalid address for service key (account 5)"
+    );
+
+    // Grant access, funding the service key up to the onboarding amount.
+
+    let grant_access_ix = try_build_instruction(
+        &ID,
+        GrantAccessAccounts::new_with_onboarding_funding(
+            &sentinel_signer.pubkey(),
+            &access_request_key,
+            &payer_key,
+            &onboarding_funder_signer.pubkey(),
+            &service_key,
+        ),
+        &PassportInstructionData::GrantAccess,
+    )
+    .unwrap();
+
+    let recent_blockhash = test_setup.get_latest_blockhash().await.unwrap();
+    process_instructions_for_test(
+        &mut test_setup.banks_client,
+        &recent_blockhash,
+        &[grant_access_ix],
+        &[
+            &test_setup.payer_signer,
+            &sentinel_signer,
+            &onboarding_funder_signer,
+        ],
+    )
+    .await
+    .unwrap();
+
+    let funding_lamports = onboarding_lamports - service_key_before_balance;
+
+    let service_key_after_balance = test_setup
+        .banks_client
+        .get_balance(service_key)
+        .await
+        .unwrap();
+    assert_eq!(service_key_after_balance, onboarding_lamports);
+
+    let onboarding_funder_after_balance = test_setup
+        .banks_client
+        .get_balance(onboarding_funder_signer.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        onboarding_funder_after_balance,
+        onboarding_funder_before_balance - funding_lamports
+    );
+}
+
+#[tokio::test]
+async fn test_grant_access_without_onboarding_funding_above_top_up_threshold() {
+    let GrantAccessSetup {
+        mut test_setup,
+        admin_signer,
+        sentinel_signer,
+        service_key,
+        ..
+    } = setup_for_grant_access().await;
+
+    let top_up_threshold_lamports = 10_000_000;
+
+    let onboarding_funder_signer = Keypair::new();
+    let onboarding_funder_before_balance = 100_000_000;
+
+    test_setup
+        .configure_program(
+            [ProgramConfiguration::OnboardingFunding {
+                onboarding_lamports: 50_000_000,
+                top_up_threshold_lamports,
+            }],
+            &admin_signer,
+        )
+        .await
+        .unwrap()
+        .transfer_lamports(
+            &onboarding_funder_signer.pubkey(),
+            onboarding_funder_before_balance,
+        )
+        .await
+        .unwrap()
+        .transfer_lamports(&service_key, top_up_threshold_lamports)
+        .await
+        .unwrap();
+
+    let (access_request_key, _) = test_setup.fetch_access_request(&service_key).await;
+    let payer_key = test_setup.payer_signer.pubkey();
+
+    let grant_access_ix = try_build_instruction(
+        &ID,
+        GrantAccessAccounts::new_with_onboarding_funding(
+            &sentinel_signer.pubkey(),
+            &access_request_key,
+            &payer_key,
+            &onboarding_funder_signer.pubkey(),
+            &service_key,
+        ),
+        &PassportInstructionData::GrantAccess,
+    )
+    .unwrap();
+
+    let recent_blockhash = test_setup.get_latest_blockhash().await.unwrap();
+    process_instructions_for_test(
+        &mut test_setup.banks_client,
+        &recent_blockhash,
+        &[grant_access_ix],
+        &[
+            &test_setup.payer_signer,
+            &sentinel_signer,
+            &onboarding_funder_signer,
+        ],
+    )
+    .await
+    .unwrap();
+
+    // Neither balance changes because the service key is not below the top-up
+    // threshold.
+
+    let service_key_after_balance = test_setup
+        .banks_client
+        .get_balance(service_key)
+        .await
+        .unwrap();
+    assert_eq!(service_key_after_balance, top_up_threshold_lamports);
+
+    let onboarding_funder_after_balance = test_setup
+        .banks_client
+        .get_balance(onboarding_funder_signer.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        onboarding_funder_after_balance,
+        onboarding_funder_before_balance
+    );
+}
+
 //
 // Grant access — unauthorized sentinel.
 //
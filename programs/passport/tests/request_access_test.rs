@@ -5,8 +5,9 @@ mod common;
 use common::process_instructions_for_test;
 use doublezero_passport::{
     instruction::{
-        account::RequestAccessAccounts, AccessMode, PassportInstructionData, ProgramConfiguration,
-        ProgramFlagConfiguration, SolanaValidatorAttestation,
+        account::RequestAccessAccounts, service_key_proof_message, AccessMode,
+        PassportInstructionData, ProgramConfiguration, ProgramFlagConfiguration,
+        SolanaValidatorAttestation, SolanaVoteAccountAttestation,
     },
     state::{AccessRequest, REQUEST_ACCESS_MAX_DATA_SIZE},
     ID,
@@ -15,7 +16,8 @@ use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
 use solana_program_test::{tokio, BanksClientError};
 use solana_pubkey::Pubkey;
 use solana_sdk::{
-    instruction::InstructionError,
+    account::{Account, AccountSharedData},
+    instruction::{Instruction, InstructionError},
     signature::{Keypair, Signer},
     transaction::TransactionError,
 };
@@ -176,12 +178,15 @@ async fn test_request_access() {
     };
     assert_eq!(access_request, expected_access_request);
 
+    let escrow = test_setup.fetch_access_request_escrow(&service_key_1).await;
+    assert_eq!(escrow.request_deposit_lamports, request_deposit_lamports);
+
     let request_rent = test_setup
         .banks_client
         .get_rent()
         .await
         .unwrap()
-        .minimum_balance(zero_copy::data_end::<AccessRequest>());
+        .minimum_balance(zero_copy::data_end::<AccessRequest>() + AccessRequest::ESCROW_LEN);
 
     let access_request_balance_after = test_setup
         .banks_client
@@ -208,6 +213,9 @@ async fn test_request_access() {
     };
     assert_eq!(access_request, expected_access_request);
 
+    let escrow = test_setup.fetch_access_request_escrow(&service_key_2).await;
+    assert_eq!(escrow.request_deposit_lamports, request_deposit_lamports);
+
     let access_request_balance_after = test_setup
         .banks_client
         .get_balance(access_request_key)
@@ -221,7 +229,12 @@ async fn test_request_access() {
     // Fail on duplicate access request.
     let duplicate_ix = try_build_instruction(
         &ID,
-        RequestAccessAccounts::new(&test_setup.payer_signer.pubkey(), &service_key_1),
+        RequestAccessAccounts::new(
+            &test_setup.payer_signer.pubkey(),
+            &service_key_1,
+            None,
+            false,
+        ),
         &PassportInstructionData::RequestAccess(AccessMode::SolanaValidator(attestation_1)),
     )
     .unwrap();
@@ -252,6 +265,266 @@ async fn test_request_access() {
     );
 }
 
+//
+// Request access — vote account attestation.
+//
+
+#[tokio::test]
+async fn test_request_access_with_vote_account() {
+    let RequestAccessSetup { mut test_setup, .. } = setup_for_request_access().await;
+
+    let service_key = Pubkey::new_unique();
+    let validator_id = Pubkey::new_unique();
+    let vote_account_key = Pubkey::new_unique();
+
+    set_vote_account(&mut test_setup, &vote_account_key, &validator_id);
+
+    let access_mode = AccessMode::SolanaValidatorWithVoteAccount(SolanaVoteAccountAttestation {
+        validator_id,
+        vote_account_key,
+        authorized_withdrawer_key: Pubkey::new_unique(),
+        service_key,
+        ed25519_signature: [1; 64],
+    });
+
+    test_setup
+        .request_access(&service_key, access_mode.clone())
+        .await
+        .unwrap();
+
+    let (_, access_request) = test_setup.fetch_access_request(&service_key).await;
+    assert_eq!(access_request.service_key, service_key);
+
+    let mut encoded_access_mode = [0; REQUEST_ACCESS_MAX_DATA_SIZE];
+    borsh::to_writer(encoded_access_mode.as_mut(), &access_mode).unwrap();
+    assert_eq!(access_request.encoded_access_mode, encoded_access_mode);
+}
+
+#[tokio::test]
+async fn test_cannot_request_access_with_zero_vote_account() {
+    let RequestAccessSetup { mut test_setup, .. } = setup_for_request_access().await;
+
+    let service_key = Pubkey::new_unique();
+
+    let (tx_err, program_logs) = simulate_request_access_revert(
+        &mut test_setup,
+        &service_key,
+        AccessMode::SolanaValidatorWithVoteAccount(SolanaVoteAccountAttestation {
+            validator_id: Pubkey::new_unique(),
+            vote_account_key: Pubkey::default(),
+            authorized_withdrawer_key: Pubkey::new_unique(),
+            service_key,
+            ed25519_signature: [1; 64],
+        }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Vote account and authorized withdrawer cannot be zero address"
+    );
+}
+
+#[tokio::test]
+async fn test_cannot_request_access_with_other_node_vote_account() {
+    let RequestAccessSetup { mut test_setup, .. } = setup_for_request_access().await;
+
+    let service_key = Pubkey::new_unique();
+    let validator_id = Pubkey::new_unique();
+    let vote_account_key = Pubkey::new_unique();
+    let other_node_id = Pubkey::new_unique();
+
+    set_vote_account(&mut test_setup, &vote_account_key, &other_node_id);
+
+    let (tx_err, program_logs) = simulate_request_access_revert(
+        &mut test_setup,
+        &service_key,
+        AccessMode::SolanaValidatorWithVoteAccount(SolanaVoteAccountAttestation {
+            validator_id,
+            vote_account_key,
+            authorized_withdrawer_key: Pubkey::new_unique(),
+            service_key,
+            ed25519_signature: [1; 64],
+        }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(4).unwrap().as_str(),
+        format!(
+            "Program log: Vote account node identity {other_node_id} is not validator ID {validator_id}"
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_cannot_request_access_without_vote_account() {
+    let RequestAccessSetup { mut test_setup, .. } = setup_for_request_access().await;
+
+    let service_key = Pubkey::new_unique();
+    let payer_signer = Keypair::new();
+
+    let access_mode = AccessMode::SolanaValidatorWithVoteAccount(SolanaVoteAccountAttestation {
+        validator_id: Pubkey::new_unique(),
+        vote_account_key: Pubkey::new_unique(),
+        authorized_withdrawer_key: Pubkey::new_unique(),
+        service_key,
+        ed25519_signature: [1; 64],
+    });
+
+    let ix = try_build_instruction(
+        &ID,
+        RequestAccessAccounts::new(&payer_signer.pubkey(), &service_key, None, false),
+        &PassportInstructionData::RequestAccess(access_mode),
+    )
+    .unwrap();
+
+    let (tx_err, _) = test_setup
+        .unwrap_simulation_error(&[ix], &[&payer_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, common::NOT_ENOUGH_ACCOUNT_KEYS)
+    );
+}
+
+//
+// Request access — service key proof.
+//
+
+#[tokio::test]
+async fn test_request_access_with_service_key_proof() {
+    let RequestAccessSetup { mut test_setup, .. } = setup_for_request_access().await;
+
+    let service_key_signer = Keypair::new();
+    let service_key = service_key_signer.pubkey();
+
+    let access_mode = AccessMode::SolanaValidator(SolanaValidatorAttestation {
+        validator_id: Pubkey::new_unique(),
+        service_key,
+        ed25519_signature: [1; 64],
+    });
+
+    test_setup
+        .request_access_with_service_key_proof(&service_key_signer, access_mode.clone())
+        .await
+        .unwrap();
+
+    let (_, access_request) = test_setup.fetch_access_request(&service_key).await;
+    assert_eq!(access_request.service_key, service_key);
+
+    let escrow = test_setup.fetch_access_request_escrow(&service_key).await;
+    assert!(escrow.is_service_key_verified());
+
+    let mut encoded_access_mode = [0; REQUEST_ACCESS_MAX_DATA_SIZE];
+    borsh::to_writer(encoded_access_mode.as_mut(), &access_mode).unwrap();
+    assert_eq!(access_request.encoded_access_mode, encoded_access_mode);
+
+    // Requests without the proof are not verified.
+    let service_key = Pubkey::new_unique();
+
+    test_setup
+        .request_access(
+            &service_key,
+            AccessMode::SolanaValidator(SolanaValidatorAttestation {
+                validator_id: Pubkey::new_unique(),
+                service_key,
+                ed25519_signature: [1; 64],
+            }),
+        )
+        .await
+        .unwrap();
+
+    let escrow = test_setup.fetch_access_request_escrow(&service_key).await;
+    assert!(!escrow.is_service_key_verified());
+}
+
+#[tokio::test]
+async fn test_cannot_request_access_with_invalid_service_key_proof() {
+    let RequestAccessSetup { mut test_setup, .. } = setup_for_request_access().await;
+
+    let service_key_signer = Keypair::new();
+    let service_key = service_key_signer.pubkey();
+    let validator_id = Pubkey::new_unique();
+
+    let access_mode = AccessMode::SolanaValidator(SolanaValidatorAttestation {
+        validator_id,
+        service_key,
+        ed25519_signature: [1; 64],
+    });
+
+    // Cannot request access without the proof preceding the instruction.
+    let (tx_err, program_logs) = simulate_request_access_with_proof_revert(
+        &mut test_setup,
+        None,
+        &service_key,
+        access_mode.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Expected Ed25519 program instruction at relative index -1"
+    );
+
+    // Cannot bind a service key using another key's signature.
+    let (tx_err, program_logs) = simulate_request_access_with_proof_revert(
+        &mut test_setup,
+        Some(common::new_signed_ed25519_instruction(
+            &Keypair::new(),
+            &service_key_proof_message(&validator_id),
+        )),
+        &service_key,
+        access_mode.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(1, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        request_access_log(&program_logs, 3),
+        "Program log: Service key proof must be signed by the service key"
+    );
+
+    // Cannot reuse a proof signed for another validator identity.
+    let (tx_err, program_logs) = simulate_request_access_with_proof_revert(
+        &mut test_setup,
+        Some(common::new_signed_ed25519_instruction(
+            &service_key_signer,
+            &service_key_proof_message(&Pubkey::new_unique()),
+        )),
+        &service_key,
+        access_mode,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(1, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        request_access_log(&program_logs, 3),
+        format!("Program log: Service key proof must be for validator ID {validator_id}")
+    );
+}
+
 //
 // Request access — program paused.
 //
@@ -306,6 +579,30 @@ async fn simulate_request_access_revert(
         .await
 }
 
+/// Store a vote account whose vote state names the node identity. Every vote
+/// state version starts with its `u32` version followed by the node identity.
+fn set_vote_account(
+    test_setup: &mut common::ProgramTestWithOwner,
+    vote_account_key: &Pubkey,
+    node_pubkey: &Pubkey,
+) {
+    const VOTE_STATE_SIZE: usize = 3_762;
+
+    let mut data = vec![0; VOTE_STATE_SIZE];
+    data[..4].copy_from_slice(&2_u32.to_le_bytes());
+    data[4..36].copy_from_slice(node_pubkey.as_ref());
+
+    test_setup.context.set_account(
+        vote_account_key,
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: solana_sdk_ids::vote::ID,
+            ..Default::default()
+        }),
+    );
+}
+
 async fn simulate_request_access_revert_with_payer(
     test_setup: &mut common::ProgramTestWithOwner,
     payer_signer: &Keypair,
@@ -314,7 +611,12 @@ async fn simulate_request_access_revert_with_payer(
 ) -> Result<(TransactionError, Vec<String>), BanksClientError> {
     let ix = try_build_instruction(
         &ID,
-        RequestAccessAccounts::new(&payer_signer.pubkey(), service_key),
+        RequestAccessAccounts::new(
+            &payer_signer.pubkey(),
+            service_key,
+            access_mode.vote_account_key().as_ref(),
+            false,
+        ),
         &PassportInstructionData::RequestAccess(access_mode),
     )
     .unwrap();
@@ -323,3 +625,45 @@ async fn simulate_request_access_revert_with_payer(
         .unwrap_simulation_error(&[ix], &[payer_signer])
         .await
 }
+
+async fn simulate_request_access_with_proof_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    service_key_proof_ix: Option<Instruction>,
+    service_key: &Pubkey,
+    access_mode: AccessMode,
+) -> Result<(TransactionError, Vec<String>), BanksClientError> {
+    let payer_signer = Keypair::new();
+
+    let request_access_ix = try_build_instruction(
+        &ID,
+        RequestAccessAccounts::new(
+            &payer_signer.pubkey(),
+            service_key,
+            access_mode.vote_account_key().as_ref(),
+            true,
+        ),
+        &PassportInstructionData::RequestAccess(access_mode),
+    )
+    .unwrap();
+
+    let instructions = service_key_proof_ix
+        .into_iter()
+        .chain([request_access_ix])
+        .collect::<Vec<_>>();
+
+    test_setup
+        .unwrap_simulation_error(&instructions, &[&payer_signer])
+        .await
+}
+
+/// Logs of the Ed25519 program instruction precede the request access logs,
+/// so index the request access logs relative to its invocation.
+fn request_access_log(program_logs: &[String], index: usize) -> &str {
+    let invoke_index = program_logs
+        .iter()
+        .position(|log| log == "Program log: Request access")
+        .unwrap()
+        - 1;
+
+    &program_logs[invoke_index + index]
+}
@@ -0,0 +1,408 @@
+mod common;
+
+//
+
+use doublezero_passport::{
+    instruction::{
+        account::UpdateValidatorIdAccounts, validator_id_update_proof_message,
+        PassportInstructionData, ProgramConfiguration, ProgramFlagConfiguration, ValidatorIdUpdate,
+    },
+    ID,
+};
+use doublezero_program_tools::instruction::try_build_instruction;
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+//
+// Setup.
+//
+
+struct UpdateValidatorIdSetup {
+    test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
+    sentinel_key: Pubkey,
+    old_validator_signer: Keypair,
+    new_validator_signer: Keypair,
+    update: ValidatorIdUpdate,
+}
+
+async fn setup_for_update_validator_id() -> UpdateValidatorIdSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    let old_validator_signer = Keypair::new();
+    let new_validator_signer = Keypair::new();
+
+    let update = ValidatorIdUpdate {
+        service_key: Pubkey::new_unique(),
+        old_validator_id: old_validator_signer.pubkey(),
+        new_validator_id: new_validator_signer.pubkey(),
+        new_ed25519_signature: [2; 64],
+    };
+
+    UpdateValidatorIdSetup {
+        test_setup,
+        admin_signer: configured.admin_signer,
+        sentinel_key: configured.sentinel_signer.pubkey(),
+        old_validator_signer,
+        new_validator_signer,
+        update,
+    }
+}
+
+//
+// Update validator ID — happy path.
+//
+
+#[tokio::test]
+async fn test_update_validator_id() {
+    let UpdateValidatorIdSetup {
+        mut test_setup,
+        sentinel_key,
+        old_validator_signer,
+        new_validator_signer,
+        update,
+        ..
+    } = setup_for_update_validator_id().await;
+
+    let (_, program_config) = test_setup.fetch_program_config().await;
+    let sentinel_balance_before = test_setup
+        .banks_client
+        .get_balance(sentinel_key)
+        .await
+        .unwrap();
+
+    test_setup
+        .update_validator_id(
+            &old_validator_signer,
+            &new_validator_signer,
+            &sentinel_key,
+            update,
+        )
+        .await
+        .unwrap();
+
+    // The sentinel is paid the request fee for processing the update.
+    let sentinel_balance_after = test_setup
+        .banks_client
+        .get_balance(sentinel_key)
+        .await
+        .unwrap();
+    assert_eq!(
+        sentinel_balance_after,
+        sentinel_balance_before + program_config.request_fee_lamports
+    );
+}
+
+//
+// Update validator ID — same validator ID.
+//
+
+#[tokio::test]
+async fn test_cannot_update_validator_id_same_validator_id() {
+    let UpdateValidatorIdSetup {
+        mut test_setup,
+        sentinel_key,
+        old_validator_signer,
+        new_validator_signer,
+        mut update,
+        ..
+    } = setup_for_update_validator_id().await;
+
+    update.new_validator_id = update.old_validator_id;
+
+    let (tx_err, program_logs) = simulate_update_validator_id_revert(
+        &mut test_setup,
+        &sentinel_key,
+        validator_id_update_proof_ixs(&old_validator_signer, &new_validator_signer, &update),
+        update,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(2, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        update_validator_id_log(&program_logs, 2),
+        "Program log: New validator ID must differ from the old validator ID"
+    );
+}
+
+//
+// Update validator ID — zero address validator ID.
+//
+
+#[tokio::test]
+async fn test_cannot_update_validator_id_zero_address() {
+    let UpdateValidatorIdSetup {
+        mut test_setup,
+        sentinel_key,
+        old_validator_signer,
+        new_validator_signer,
+        mut update,
+        ..
+    } = setup_for_update_validator_id().await;
+
+    update.new_validator_id = Pubkey::default();
+
+    let (tx_err, program_logs) = simulate_update_validator_id_revert(
+        &mut test_setup,
+        &sentinel_key,
+        validator_id_update_proof_ixs(&old_validator_signer, &new_validator_signer, &update),
+        update,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(2, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        update_validator_id_log(&program_logs, 2),
+        "Program log: Validator ID cannot be zero address"
+    );
+}
+
+//
+// Update validator ID — request access paused.
+//
+
+#[tokio::test]
+async fn test_cannot_update_validator_id_request_access_paused() {
+    let UpdateValidatorIdSetup {
+        mut test_setup,
+        admin_signer,
+        sentinel_key,
+        old_validator_signer,
+        new_validator_signer,
+        update,
+    } = setup_for_update_validator_id().await;
+
+    test_setup
+        .configure_program(
+            [ProgramConfiguration::Flag(
+                ProgramFlagConfiguration::IsRequestAccessPaused(true),
+            )],
+            &admin_signer,
+        )
+        .await
+        .unwrap();
+
+    let (tx_err, program_logs) = simulate_update_validator_id_revert(
+        &mut test_setup,
+        &sentinel_key,
+        validator_id_update_proof_ixs(&old_validator_signer, &new_validator_signer, &update),
+        update,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(2, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        update_validator_id_log(&program_logs, 2),
+        "Program log: Request access is paused"
+    );
+}
+
+//
+// Update validator ID — invalid sentinel.
+//
+
+#[tokio::test]
+async fn test_cannot_update_validator_id_invalid_sentinel() {
+    let UpdateValidatorIdSetup {
+        mut test_setup,
+        old_validator_signer,
+        new_validator_signer,
+        update,
+        ..
+    } = setup_for_update_validator_id().await;
+
+    let (tx_err, program_logs) = simulate_update_validator_id_revert(
+        &mut test_setup,
+        &Pubkey::new_unique(),
+        validator_id_update_proof_ixs(&old_validator_signer, &new_validator_signer, &update),
+        update,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(2, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        update_validator_id_log(&program_logs, 4),
+        "Program log: Invalid address for sentinel (account 2)"
+    );
+}
+
+//
+// Update validator ID — invalid validator ID proofs.
+//
+
+#[tokio::test]
+async fn test_cannot_update_validator_id_invalid_proof() {
+    let UpdateValidatorIdSetup {
+        mut test_setup,
+        sentinel_key,
+        old_validator_signer,
+        new_validator_signer,
+        update,
+        ..
+    } = setup_for_update_validator_id().await;
+
+    // Cannot update without the proofs.
+    let (tx_err, program_logs) =
+        simulate_update_validator_id_revert(&mut test_setup, &sentinel_key, vec![], update).await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(4).unwrap(),
+        "Program log: Expected Ed25519 program instruction at relative index -2"
+    );
+
+    // Cannot update with only the old validator ID proof.
+    let (tx_err, program_logs) = simulate_update_validator_id_revert(
+        &mut test_setup,
+        &sentinel_key,
+        vec![new_validator_id_update_proof_ix(
+            &old_validator_signer,
+            &update,
+        )],
+        update,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(1, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        update_validator_id_log(&program_logs, 4),
+        "Program log: Expected Ed25519 program instruction at relative index -2"
+    );
+
+    // Cannot rotate someone else's validator identity.
+    let (tx_err, program_logs) = simulate_update_validator_id_revert(
+        &mut test_setup,
+        &sentinel_key,
+        validator_id_update_proof_ixs(&Keypair::new(), &new_validator_signer, &update),
+        update,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(2, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        update_validator_id_log(&program_logs, 4),
+        "Program log: Validator ID update proof must be signed by the old validator ID"
+    );
+
+    // Cannot rotate to someone else's validator identity.
+    let (tx_err, program_logs) = simulate_update_validator_id_revert(
+        &mut test_setup,
+        &sentinel_key,
+        validator_id_update_proof_ixs(&old_validator_signer, &Keypair::new(), &update),
+        update,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(2, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        update_validator_id_log(&program_logs, 4),
+        "Program log: Validator ID update proof must be signed by the new validator ID"
+    );
+
+    // Cannot reuse proofs for another new validator ID.
+    let mut other_update = update;
+    other_update.new_validator_id = Pubkey::new_unique();
+
+    let (tx_err, program_logs) = simulate_update_validator_id_revert(
+        &mut test_setup,
+        &sentinel_key,
+        validator_id_update_proof_ixs(&old_validator_signer, &new_validator_signer, &other_update),
+        update,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(2, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        update_validator_id_log(&program_logs, 4),
+        format!(
+            "Program log: Validator ID update proof must be for service key {} and validator ID {}",
+            update.service_key, update.new_validator_id
+        )
+    );
+}
+
+//
+// Helpers.
+//
+
+fn new_validator_id_update_proof_ix(
+    validator_signer: &Keypair,
+    update: &ValidatorIdUpdate,
+) -> Instruction {
+    common::new_signed_ed25519_instruction(
+        validator_signer,
+        &validator_id_update_proof_message(&update.service_key, &update.new_validator_id),
+    )
+}
+
+fn validator_id_update_proof_ixs(
+    old_validator_signer: &Keypair,
+    new_validator_signer: &Keypair,
+    update: &ValidatorIdUpdate,
+) -> Vec<Instruction> {
+    vec![
+        new_validator_id_update_proof_ix(old_validator_signer, update),
+        new_validator_id_update_proof_ix(new_validator_signer, update),
+    ]
+}
+
+async fn simulate_update_validator_id_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    sentinel_key: &Pubkey,
+    validator_id_update_proof_ixs: Vec<Instruction>,
+    update: ValidatorIdUpdate,
+) -> (TransactionError, Vec<String>) {
+    let update_validator_id_ix = try_build_instruction(
+        &ID,
+        UpdateValidatorIdAccounts::new(&test_setup.payer_signer.pubkey(), sentinel_key),
+        &PassportInstructionData::UpdateValidatorId(update),
+    )
+    .unwrap();
+
+    let instructions = validator_id_update_proof_ixs
+        .into_iter()
+        .chain([update_validator_id_ix])
+        .collect::<Vec<_>>();
+
+    test_setup
+        .unwrap_simulation_error(&instructions, &[])
+        .await
+        .unwrap()
+}
+
+/// Logs of the Ed25519 program instruction precede the update validator ID
+/// logs, so index the update validator ID logs relative to its invocation.
+fn update_validator_id_log(program_logs: &[String], index: usize) -> &str {
+    let invoke_index = program_logs
+        .iter()
+        .position(|log| log == "Program log: Update validator ID")
+        .unwrap()
+        - 1;
+
+    &program_logs[invoke_index + index]
+}
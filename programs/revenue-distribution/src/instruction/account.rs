@@ -5,12 +5,15 @@ use solana_system_interface::program as system_program;
 use spl_associated_token_account_interface::address::get_associated_token_address;
 
 use crate::{
+    instruction::EmergencyWithdrawSource,
     state::{
         find_2z_token_pda_address, find_swap_authority_address,
-        find_withdraw_sol_authority_address, ContributorRewards, Distribution, Journal,
-        ProgramConfig, RewardsIntegration, SolanaValidatorDeposit,
+        find_withdraw_sol_authority_address, ContributorRewards, Distribution, FeeParameterHistory,
+        Journal, PaymentReceipt, ProgramConfig, RewardsIntegration, SolanaValidatorDeposit,
+        SwapSession,
     },
     types::DoubleZeroEpoch,
+    DOUBLEZERO_MINT_KEY,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -54,6 +57,56 @@ impl From<InitializeProgramAccounts> for Vec<AccountMeta> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrateProgramAccountsAccounts {
+    pub program_data_key: Pubkey,
+    pub upgrade_authority_key: Pubkey,
+    pub program_config_key: Pubkey,
+
+    /// Journal and distribution accounts to migrate.
+    pub account_keys: Vec<Pubkey>,
+}
+
+impl MigrateProgramAccountsAccounts {
+    pub fn new(
+        program_id: &Pubkey,
+        upgrade_authority_key: &Pubkey,
+        account_keys: &[Pubkey],
+    ) -> Self {
+        Self {
+            program_data_key: get_program_data_address(program_id).0,
+            upgrade_authority_key: *upgrade_authority_key,
+            program_config_key: ProgramConfig::find_address().0,
+            account_keys: account_keys.to_vec(),
+        }
+    }
+}
+
+impl From<MigrateProgramAccountsAccounts> for Vec<AccountMeta> {
+    fn from(accounts: MigrateProgramAccountsAccounts) -> Self {
+        let MigrateProgramAccountsAccounts {
+            program_data_key,
+            upgrade_authority_key,
+            program_config_key,
+            account_keys,
+        } = accounts;
+
+        let mut account_metas = vec![
+            AccountMeta::new_readonly(program_data_key, false),
+            AccountMeta::new_readonly(upgrade_authority_key, true),
+            AccountMeta::new(program_config_key, false),
+        ];
+
+        account_metas.extend(
+            account_keys
+                .into_iter()
+                .map(|account_key| AccountMeta::new(account_key, false)),
+        );
+
+        account_metas
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SetAdminAccounts {
     pub program_data_key: Pubkey,
@@ -91,13 +144,19 @@ impl From<SetAdminAccounts> for Vec<AccountMeta> {
 pub struct ConfigureProgramAccounts {
     pub program_config_key: Pubkey,
     pub admin_key: Pubkey,
+    pub fee_parameter_history_key: Option<Pubkey>,
 }
 
 impl ConfigureProgramAccounts {
-    pub fn new(admin_key: &Pubkey) -> Self {
+    /// The fee parameter history may be included as the last account when
+    /// configuring Solana validator fee parameters so the change is recorded.
+    /// It must be initialized if it is included.
+    pub fn new(admin_key: &Pubkey, with_fee_parameter_history: bool) -> Self {
         Self {
             program_config_key: ProgramConfig::find_address().0,
             admin_key: *admin_key,
+            fee_parameter_history_key: with_fee_parameter_history
+                .then(|| FeeParameterHistory::find_address().0),
         }
     }
 }
@@ -107,12 +166,19 @@ impl From<ConfigureProgramAccounts> for Vec<AccountMeta> {
         let ConfigureProgramAccounts {
             program_config_key,
             admin_key,
+            fee_parameter_history_key,
         } = accounts;
 
-        vec![
+        let mut account_metas = vec![
             AccountMeta::new(program_config_key, false),
             AccountMeta::new_readonly(admin_key, true),
-        ]
+        ];
+
+        if let Some(fee_parameter_history_key) = fee_parameter_history_key {
+            account_metas.push(AccountMeta::new(fee_parameter_history_key, false));
+        }
+
+        account_metas
     }
 }
 
@@ -408,6 +474,19 @@ pub struct DistributeRewardsAccounts {
     pub dz_mint_key: Pubkey,
     pub relayer_key: Pubkey,
     pub recipient_ata_keys: Vec<Pubkey>,
+
+    /// Payer for the rent of new ATAs, only specified if missing recipient
+    /// ATAs should be created.
+    pub ata_payer_key: Option<Pubkey>,
+
+    /// Recipients (ATA owners), only passed to the instruction if missing
+    /// recipient ATAs should be created.
+    pub recipient_keys: Vec<Pubkey>,
+
+    /// Payer for the rent of the distribution's partially distributed rewards
+    /// bitmap, only required when distributing the first page of recipients
+    /// before this bitmap is allocated.
+    pub bitmap_payer_key: Option<Pubkey>,
 }
 
 impl DistributeRewardsAccounts {
@@ -417,6 +496,8 @@ impl DistributeRewardsAccounts {
         dz_mint_key: &Pubkey,
         relayer_key: &Pubkey,
         recipient_keys: &[&Pubkey],
+        ata_payer_key: Option<&Pubkey>,
+        bitmap_payer_key: Option<&Pubkey>,
     ) -> Self {
         let distribution_key = Distribution::find_address(dz_epoch).0;
         let recipient_ata_keys = recipient_keys
@@ -432,8 +513,33 @@ impl DistributeRewardsAccounts {
             dz_mint_key: *dz_mint_key,
             relayer_key: *relayer_key,
             recipient_ata_keys,
+            ata_payer_key: ata_payer_key.copied(),
+            recipient_keys: recipient_keys.iter().map(|&&key| key).collect(),
+            bitmap_payer_key: bitmap_payer_key.copied(),
         }
     }
+
+    /// Accounts referenced by every distribute rewards instruction for the
+    /// distribution of this DZ epoch. Loading these from an address lookup
+    /// table allows more of these instructions to fit in each transaction.
+    pub fn lookup_table_addresses(
+        dz_epoch: DoubleZeroEpoch,
+        dz_mint_key: &Pubkey,
+        relayer_key: &Pubkey,
+    ) -> Vec<Pubkey> {
+        let distribution_key = Distribution::find_address(dz_epoch).0;
+
+        vec![
+            ProgramConfig::find_address().0,
+            distribution_key,
+            find_2z_token_pda_address(&distribution_key).0,
+            *dz_mint_key,
+            *relayer_key,
+            spl_token_interface::ID,
+            spl_associated_token_account_interface::program::ID,
+            system_program::ID,
+        ]
+    }
 }
 
 impl From<DistributeRewardsAccounts> for Vec<AccountMeta> {
@@ -446,12 +552,15 @@ impl From<DistributeRewardsAccounts> for Vec<AccountMeta> {
             dz_mint_key,
             relayer_key,
             recipient_ata_keys,
+            ata_payer_key,
+            recipient_keys,
+            bitmap_payer_key,
         } = accounts;
 
         let mut accounts = vec![
             AccountMeta::new_readonly(program_config_key, false),
             AccountMeta::new(distribution_key, false),
-            AccountMeta::new_readonly(contributor_rewards_key, false),
+            AccountMeta::new(contributor_rewards_key, false),
             AccountMeta::new(distribution_2z_token_pda_key, false),
             AccountMeta::new(dz_mint_key, false),
             AccountMeta::new(relayer_key, false),
@@ -464,6 +573,26 @@ impl From<DistributeRewardsAccounts> for Vec<AccountMeta> {
 
         accounts.extend(recipient_ata_accounts);
 
+        if let Some(ata_payer_key) = ata_payer_key {
+            accounts.push(AccountMeta::new_readonly(
+                spl_associated_token_account_interface::program::ID,
+                false,
+            ));
+            accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+            accounts.push(AccountMeta::new(ata_payer_key, true));
+
+            let recipient_accounts = recipient_keys
+                .into_iter()
+                .map(|key| AccountMeta::new_readonly(key, false));
+
+            accounts.extend(recipient_accounts);
+        }
+
+        if let Some(bitmap_payer_key) = bitmap_payer_key {
+            accounts.push(AccountMeta::new(bitmap_payer_key, true));
+            accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+        }
+
         accounts
     }
 }
@@ -536,14 +665,25 @@ pub struct ConfigureContributorRewardsAccounts {
     pub program_config_key: Pubkey,
     pub contributor_rewards_key: Pubkey,
     pub rewards_manager_key: Pubkey,
+    pub payer_key: Option<Pubkey>,
 }
 
 impl ConfigureContributorRewardsAccounts {
-    pub fn new(rewards_manager_key: &Pubkey, service_key: &Pubkey) -> Self {
+    /// The payer should be specified when configuring more than
+    /// [MAX_RECIPIENTS_PER_PAGE] recipients for the first time so the
+    /// contributor rewards account can be resized.
+    ///
+    /// [MAX_RECIPIENTS_PER_PAGE]: crate::state::MAX_RECIPIENTS_PER_PAGE
+    pub fn new(
+        rewards_manager_key: &Pubkey,
+        service_key: &Pubkey,
+        payer_key: Option<&Pubkey>,
+    ) -> Self {
         Self {
             program_config_key: ProgramConfig::find_address().0,
             contributor_rewards_key: ContributorRewards::find_address(service_key).0,
             rewards_manager_key: *rewards_manager_key,
+            payer_key: payer_key.copied(),
         }
     }
 }
@@ -554,13 +694,21 @@ impl From<ConfigureContributorRewardsAccounts> for Vec<AccountMeta> {
             program_config_key,
             contributor_rewards_key,
             rewards_manager_key,
+            payer_key,
         } = accounts;
 
-        vec![
+        let mut account_metas = vec![
             AccountMeta::new_readonly(program_config_key, false),
             AccountMeta::new(contributor_rewards_key, false),
             AccountMeta::new_readonly(rewards_manager_key, true),
-        ]
+        ];
+
+        if let Some(payer_key) = payer_key {
+            account_metas.push(AccountMeta::new(payer_key, true));
+            account_metas.push(AccountMeta::new_readonly(system_program::ID, false));
+        }
+
+        account_metas
     }
 }
 
@@ -621,15 +769,29 @@ pub struct PaySolanaValidatorDebtAccounts {
     pub distribution_key: Pubkey,
     pub solana_validator_deposit_key: Pubkey,
     pub journal_key: Pubkey,
+    pub payment_receipt: Option<PaymentReceiptAccounts>,
+}
+
+/// Accounts needed to create a payment receipt when paying Solana validator
+/// debt. The payer also funds the payment history for deposits that do not
+/// have one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentReceiptAccounts {
+    pub payer_key: Pubkey,
+    pub new_payment_receipt_key: Pubkey,
 }
 
 impl PaySolanaValidatorDebtAccounts {
-    pub fn new(dz_epoch: DoubleZeroEpoch, node_id: &Pubkey) -> Self {
+    pub fn new(dz_epoch: DoubleZeroEpoch, node_id: &Pubkey, payer_key: Option<&Pubkey>) -> Self {
         Self {
             program_config_key: ProgramConfig::find_address().0,
             distribution_key: Distribution::find_address(dz_epoch).0,
             solana_validator_deposit_key: SolanaValidatorDeposit::find_address(node_id).0,
             journal_key: Journal::find_address().0,
+            payment_receipt: payer_key.map(|payer_key| PaymentReceiptAccounts {
+                payer_key: *payer_key,
+                new_payment_receipt_key: PaymentReceipt::find_address(dz_epoch, node_id).0,
+            }),
         }
     }
 }
@@ -641,14 +803,27 @@ impl From<PaySolanaValidatorDebtAccounts> for Vec<AccountMeta> {
             distribution_key,
             solana_validator_deposit_key,
             journal_key,
+            payment_receipt,
         } = accounts;
 
-        vec![
+        let mut account_metas = vec![
             AccountMeta::new_readonly(program_config_key, false),
             AccountMeta::new(distribution_key, false),
             AccountMeta::new(solana_validator_deposit_key, false),
             AccountMeta::new(journal_key, false),
-        ]
+        ];
+
+        if let Some(PaymentReceiptAccounts {
+            payer_key,
+            new_payment_receipt_key,
+        }) = payment_receipt
+        {
+            account_metas.push(AccountMeta::new(payer_key, true));
+            account_metas.push(AccountMeta::new(new_payment_receipt_key, false));
+            account_metas.push(AccountMeta::new_readonly(system_program::ID, false));
+        }
+
+        account_metas
     }
 }
 
@@ -830,6 +1005,7 @@ pub struct SweepDistributionTokensAccounts {
     pub distribution_2z_token_pda_key: Pubkey,
     pub swap_authority_key: Pubkey,
     pub swap_2z_token_pda_key: Pubkey,
+    pub reserve_2z_key: Pubkey,
 }
 
 impl SweepDistributionTokensAccounts {
@@ -838,6 +1014,7 @@ impl SweepDistributionTokensAccounts {
         sol_2z_swap_program_id: &Pubkey,
         sol_2z_swap_fills_registry_key: &Pubkey,
     ) -> Self {
+        let program_config_key = ProgramConfig::find_address().0;
         let distribution_key = Distribution::find_address(dz_epoch).0;
         let swap_authority_key = find_swap_authority_address().0;
 
@@ -845,13 +1022,14 @@ impl SweepDistributionTokensAccounts {
             DequeueFillsCpiAccounts::new(sol_2z_swap_program_id, sol_2z_swap_fills_registry_key);
 
         Self {
-            program_config_key: ProgramConfig::find_address().0,
+            program_config_key,
             distribution_key,
             journal_key: Journal::find_address().0,
             dequeue_fills_cpi_keys,
             distribution_2z_token_pda_key: find_2z_token_pda_address(&distribution_key).0,
             swap_authority_key,
             swap_2z_token_pda_key: find_2z_token_pda_address(&swap_authority_key).0,
+            reserve_2z_key: find_2z_token_pda_address(&program_config_key).0,
         }
     }
 }
@@ -866,6 +1044,7 @@ impl From<SweepDistributionTokensAccounts> for Vec<AccountMeta> {
             distribution_2z_token_pda_key,
             swap_authority_key,
             swap_2z_token_pda_key,
+            reserve_2z_key,
         } = accounts;
 
         // This method assumes that the dequeue fills CPI accounts were created
@@ -897,6 +1076,7 @@ impl From<SweepDistributionTokensAccounts> for Vec<AccountMeta> {
             AccountMeta::new_readonly(swap_authority_key, false),
             AccountMeta::new(swap_2z_token_pda_key, false),
             AccountMeta::new_readonly(spl_token_interface::ID, false),
+            AccountMeta::new(reserve_2z_key, false),
         ]
     }
 }
@@ -907,6 +1087,11 @@ pub struct WithdrawSolAccounts {
     pub withdraw_sol_authority_key: Pubkey,
     pub journal_key: Pubkey,
     pub sol_destination_key: Pubkey,
+    pub swap_destination_2z_key: Pubkey,
+    pub swap_session_key: Pubkey,
+
+    /// Must be the rent beneficiary recorded in the swap session.
+    pub swap_session_rent_beneficiary_key: Pubkey,
 }
 
 impl WithdrawSolAccounts {
@@ -915,13 +1100,20 @@ impl WithdrawSolAccounts {
     /// 1,500 CU per bump iteration. It is recommended to instantiate the
     /// struct by defining its members directly. Please only use this method
     /// for testing purposes.
-    pub fn new(sol_2z_swap_program_id: &Pubkey, sol_destination_key: &Pubkey) -> Self {
+    pub fn new(
+        sol_2z_swap_program_id: &Pubkey,
+        sol_destination_key: &Pubkey,
+        swap_session_rent_beneficiary_key: &Pubkey,
+    ) -> Self {
         Self {
             program_config_key: ProgramConfig::find_address().0,
             withdraw_sol_authority_key: find_withdraw_sol_authority_address(sol_2z_swap_program_id)
                 .0,
             journal_key: Journal::find_address().0,
             sol_destination_key: *sol_destination_key,
+            swap_destination_2z_key: find_2z_token_pda_address(&find_swap_authority_address().0).0,
+            swap_session_key: SwapSession::find_address().0,
+            swap_session_rent_beneficiary_key: *swap_session_rent_beneficiary_key,
         }
     }
 }
@@ -933,6 +1125,9 @@ impl From<WithdrawSolAccounts> for Vec<AccountMeta> {
             withdraw_sol_authority_key,
             journal_key,
             sol_destination_key,
+            swap_destination_2z_key,
+            swap_session_key,
+            swap_session_rent_beneficiary_key,
         } = accounts;
 
         vec![
@@ -940,6 +1135,54 @@ impl From<WithdrawSolAccounts> for Vec<AccountMeta> {
             AccountMeta::new_readonly(withdraw_sol_authority_key, true),
             AccountMeta::new(journal_key, false),
             AccountMeta::new(sol_destination_key, false),
+            AccountMeta::new_readonly(swap_destination_2z_key, false),
+            AccountMeta::new(swap_session_key, false),
+            AccountMeta::new(swap_session_rent_beneficiary_key, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenSwapSessionAccounts {
+    pub program_config_key: Pubkey,
+    pub withdraw_sol_authority_key: Pubkey,
+    pub swap_destination_2z_key: Pubkey,
+    pub payer_key: Pubkey,
+    pub swap_session_key: Pubkey,
+}
+
+impl OpenSwapSessionAccounts {
+    /// NOTE: Like [WithdrawSolAccounts::new], this method should only be used
+    /// for testing purposes.
+    pub fn new(sol_2z_swap_program_id: &Pubkey, payer_key: &Pubkey) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            withdraw_sol_authority_key: find_withdraw_sol_authority_address(sol_2z_swap_program_id)
+                .0,
+            swap_destination_2z_key: find_2z_token_pda_address(&find_swap_authority_address().0).0,
+            payer_key: *payer_key,
+            swap_session_key: SwapSession::find_address().0,
+        }
+    }
+}
+
+impl From<OpenSwapSessionAccounts> for Vec<AccountMeta> {
+    fn from(accounts: OpenSwapSessionAccounts) -> Self {
+        let OpenSwapSessionAccounts {
+            program_config_key,
+            withdraw_sol_authority_key,
+            swap_destination_2z_key,
+            payer_key,
+            swap_session_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new_readonly(withdraw_sol_authority_key, true),
+            AccountMeta::new_readonly(swap_destination_2z_key, false),
+            AccountMeta::new(payer_key, true),
+            AccountMeta::new(swap_session_key, false),
+            AccountMeta::new_readonly(system_program::ID, false),
         ]
     }
 }
@@ -1127,6 +1370,436 @@ impl From<CollectIntegrationRewardsAccounts> for Vec<AccountMeta> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitializeFeeParameterHistoryAccounts {
+    pub payer_key: Pubkey,
+    pub new_fee_parameter_history_key: Pubkey,
+}
+
+impl InitializeFeeParameterHistoryAccounts {
+    pub fn new(payer_key: &Pubkey) -> Self {
+        Self {
+            payer_key: *payer_key,
+            new_fee_parameter_history_key: FeeParameterHistory::find_address().0,
+        }
+    }
+}
+
+impl From<InitializeFeeParameterHistoryAccounts> for Vec<AccountMeta> {
+    fn from(accounts: InitializeFeeParameterHistoryAccounts) -> Self {
+        let InitializeFeeParameterHistoryAccounts {
+            payer_key,
+            new_fee_parameter_history_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new(payer_key, true),
+            AccountMeta::new(new_fee_parameter_history_key, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconcileDistributionAccounts {
+    pub program_config_key: Pubkey,
+    pub rewards_accountant_key: Pubkey,
+    pub distribution_key: Pubkey,
+    pub distribution_2z_token_pda_key: Pubkey,
+}
+
+impl ReconcileDistributionAccounts {
+    pub fn new(rewards_accountant_key: &Pubkey, dz_epoch: DoubleZeroEpoch) -> Self {
+        let distribution_key = Distribution::find_address(dz_epoch).0;
+
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            rewards_accountant_key: *rewards_accountant_key,
+            distribution_key,
+            distribution_2z_token_pda_key: find_2z_token_pda_address(&distribution_key).0,
+        }
+    }
+}
+
+impl From<ReconcileDistributionAccounts> for Vec<AccountMeta> {
+    fn from(accounts: ReconcileDistributionAccounts) -> Self {
+        let ReconcileDistributionAccounts {
+            program_config_key,
+            rewards_accountant_key,
+            distribution_key,
+            distribution_2z_token_pda_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new_readonly(rewards_accountant_key, true),
+            AccountMeta::new(distribution_key, false),
+            AccountMeta::new_readonly(distribution_2z_token_pda_key, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplaceDistributionRewardsRootAccounts {
+    pub program_config_key: Pubkey,
+    pub rewards_accountant_key: Pubkey,
+    pub distribution_key: Pubkey,
+}
+
+impl ReplaceDistributionRewardsRootAccounts {
+    pub fn new(rewards_accountant_key: &Pubkey, dz_epoch: DoubleZeroEpoch) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            rewards_accountant_key: *rewards_accountant_key,
+            distribution_key: Distribution::find_address(dz_epoch).0,
+        }
+    }
+}
+
+impl From<ReplaceDistributionRewardsRootAccounts> for Vec<AccountMeta> {
+    fn from(accounts: ReplaceDistributionRewardsRootAccounts) -> Self {
+        let ReplaceDistributionRewardsRootAccounts {
+            program_config_key,
+            rewards_accountant_key,
+            distribution_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new_readonly(rewards_accountant_key, true),
+            AccountMeta::new(distribution_key, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetRecipientSharesLockedAccounts {
+    pub program_config_key: Pubkey,
+    pub rewards_accountant_key: Pubkey,
+    pub contributor_rewards_keys: Vec<Pubkey>,
+}
+
+impl SetRecipientSharesLockedAccounts {
+    pub fn new(rewards_accountant_key: &Pubkey, service_keys: &[&Pubkey]) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            rewards_accountant_key: *rewards_accountant_key,
+            contributor_rewards_keys: service_keys
+                .iter()
+                .map(|service_key| ContributorRewards::find_address(service_key).0)
+                .collect(),
+        }
+    }
+}
+
+impl From<SetRecipientSharesLockedAccounts> for Vec<AccountMeta> {
+    fn from(accounts: SetRecipientSharesLockedAccounts) -> Self {
+        let SetRecipientSharesLockedAccounts {
+            program_config_key,
+            rewards_accountant_key,
+            contributor_rewards_keys,
+        } = accounts;
+
+        let mut account_metas = vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new_readonly(rewards_accountant_key, true),
+        ];
+
+        account_metas.extend(
+            contributor_rewards_keys
+                .into_iter()
+                .map(|key| AccountMeta::new(key, false)),
+        );
+
+        account_metas
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertJournalInvariantsAccounts {
+    pub program_config_key: Pubkey,
+    pub journal_key: Pubkey,
+    pub journal_2z_token_pda_key: Pubkey,
+    pub swap_destination_2z_key: Pubkey,
+}
+
+impl Default for AssertJournalInvariantsAccounts {
+    fn default() -> Self {
+        let journal_key = Journal::find_address().0;
+
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            journal_key,
+            journal_2z_token_pda_key: find_2z_token_pda_address(&journal_key).0,
+            swap_destination_2z_key: find_2z_token_pda_address(&find_swap_authority_address().0).0,
+        }
+    }
+}
+
+impl From<AssertJournalInvariantsAccounts> for Vec<AccountMeta> {
+    fn from(accounts: AssertJournalInvariantsAccounts) -> Self {
+        let AssertJournalInvariantsAccounts {
+            program_config_key,
+            journal_key,
+            journal_2z_token_pda_key,
+            swap_destination_2z_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new_readonly(journal_key, false),
+            AccountMeta::new_readonly(journal_2z_token_pda_key, false),
+            AccountMeta::new_readonly(swap_destination_2z_key, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositSolForValidatorAccounts {
+    pub program_config_key: Pubkey,
+    pub solana_validator_deposit_key: Pubkey,
+    pub depositor_key: Pubkey,
+}
+
+impl DepositSolForValidatorAccounts {
+    pub fn new(depositor_key: &Pubkey, node_id: &Pubkey) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            solana_validator_deposit_key: SolanaValidatorDeposit::find_address(node_id).0,
+            depositor_key: *depositor_key,
+        }
+    }
+}
+
+impl From<DepositSolForValidatorAccounts> for Vec<AccountMeta> {
+    fn from(accounts: DepositSolForValidatorAccounts) -> Self {
+        let DepositSolForValidatorAccounts {
+            program_config_key,
+            solana_validator_deposit_key,
+            depositor_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new(solana_validator_deposit_key, false),
+            AccountMeta::new(depositor_key, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCommunityBurnRateAccounts {
+    pub program_config_key: Pubkey,
+}
+
+impl Default for QueryCommunityBurnRateAccounts {
+    fn default() -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+        }
+    }
+}
+
+impl From<QueryCommunityBurnRateAccounts> for Vec<AccountMeta> {
+    fn from(accounts: QueryCommunityBurnRateAccounts) -> Self {
+        let QueryCommunityBurnRateAccounts { program_config_key } = accounts;
+
+        vec![AccountMeta::new_readonly(program_config_key, false)]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryToken2zBalanceAccounts {
+    pub token_owner_key: Pubkey,
+    pub token_2z_pda_key: Pubkey,
+}
+
+impl QueryToken2zBalanceAccounts {
+    pub fn new(token_owner_key: &Pubkey) -> Self {
+        Self {
+            token_owner_key: *token_owner_key,
+            token_2z_pda_key: find_2z_token_pda_address(token_owner_key).0,
+        }
+    }
+}
+
+impl From<QueryToken2zBalanceAccounts> for Vec<AccountMeta> {
+    fn from(accounts: QueryToken2zBalanceAccounts) -> Self {
+        let QueryToken2zBalanceAccounts {
+            token_owner_key,
+            token_2z_pda_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(token_owner_key, false),
+            AccountMeta::new_readonly(token_2z_pda_key, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkipDistributionSweepAccounts {
+    pub program_config_key: Pubkey,
+    pub admin_key: Pubkey,
+    pub distribution_key: Pubkey,
+    pub journal_key: Pubkey,
+}
+
+impl SkipDistributionSweepAccounts {
+    pub fn new(admin_key: &Pubkey, dz_epoch: DoubleZeroEpoch) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            admin_key: *admin_key,
+            distribution_key: Distribution::find_address(dz_epoch).0,
+            journal_key: Journal::find_address().0,
+        }
+    }
+}
+
+impl From<SkipDistributionSweepAccounts> for Vec<AccountMeta> {
+    fn from(accounts: SkipDistributionSweepAccounts) -> Self {
+        let SkipDistributionSweepAccounts {
+            program_config_key,
+            admin_key,
+            distribution_key,
+            journal_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new_readonly(admin_key, true),
+            AccountMeta::new(distribution_key, false),
+            AccountMeta::new(journal_key, false),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmergencyWithdrawAccounts {
+    pub program_data_key: Pubkey,
+    pub upgrade_authority_key: Pubkey,
+    pub program_config_key: Pubkey,
+    pub admin_key: Pubkey,
+
+    /// The journal if withdrawing SOL. Otherwise the owner of the 2Z token PDA.
+    pub source_key: Pubkey,
+
+    /// Only specified if withdrawing 2Z tokens.
+    pub source_2z_token_pda_key: Option<Pubkey>,
+
+    /// The emergency recovery account if withdrawing SOL. Otherwise its 2Z
+    /// ATA.
+    pub recovery_key: Pubkey,
+}
+
+impl EmergencyWithdrawAccounts {
+    pub fn new(
+        program_id: &Pubkey,
+        upgrade_authority_key: &Pubkey,
+        admin_key: &Pubkey,
+        source: EmergencyWithdrawSource,
+        emergency_recovery_key: &Pubkey,
+    ) -> Self {
+        let source_key = match source {
+            EmergencyWithdrawSource::JournalSol | EmergencyWithdrawSource::Journal2z => {
+                Journal::find_address().0
+            }
+            EmergencyWithdrawSource::Distribution2z(dz_epoch) => {
+                Distribution::find_address(dz_epoch).0
+            }
+            EmergencyWithdrawSource::SwapDestination2z => find_swap_authority_address().0,
+        };
+
+        let (source_2z_token_pda_key, recovery_key) = match source {
+            EmergencyWithdrawSource::JournalSol => (None, *emergency_recovery_key),
+            _ => (
+                Some(find_2z_token_pda_address(&source_key).0),
+                get_associated_token_address(emergency_recovery_key, &DOUBLEZERO_MINT_KEY),
+            ),
+        };
+
+        Self {
+            program_data_key: get_program_data_address(program_id).0,
+            upgrade_authority_key: *upgrade_authority_key,
+            program_config_key: ProgramConfig::find_address().0,
+            admin_key: *admin_key,
+            source_key,
+            source_2z_token_pda_key,
+            recovery_key,
+        }
+    }
+}
+
+impl From<EmergencyWithdrawAccounts> for Vec<AccountMeta> {
+    fn from(accounts: EmergencyWithdrawAccounts) -> Self {
+        let EmergencyWithdrawAccounts {
+            program_data_key,
+            upgrade_authority_key,
+            program_config_key,
+            admin_key,
+            source_key,
+            source_2z_token_pda_key,
+            recovery_key,
+        } = accounts;
+
+        let mut account_metas = vec![
+            AccountMeta::new_readonly(program_data_key, false),
+            AccountMeta::new_readonly(upgrade_authority_key, true),
+            AccountMeta::new(program_config_key, false),
+            AccountMeta::new_readonly(admin_key, true),
+        ];
+
+        match source_2z_token_pda_key {
+            Some(source_2z_token_pda_key) => account_metas.extend([
+                AccountMeta::new_readonly(source_key, false),
+                AccountMeta::new(source_2z_token_pda_key, false),
+                AccountMeta::new(recovery_key, false),
+                AccountMeta::new_readonly(spl_token_interface::ID, false),
+            ]),
+            None => account_metas.extend([
+                AccountMeta::new(source_key, false),
+                AccountMeta::new(recovery_key, false),
+            ]),
+        }
+
+        account_metas
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconcileDepositSurplusAccounts {
+    pub program_config_key: Pubkey,
+    pub debt_accountant_key: Pubkey,
+    pub solana_validator_deposit_key: Pubkey,
+}
+
+impl ReconcileDepositSurplusAccounts {
+    pub fn new(debt_accountant_key: &Pubkey, node_id: &Pubkey) -> Self {
+        Self {
+            program_config_key: ProgramConfig::find_address().0,
+            debt_accountant_key: *debt_accountant_key,
+            solana_validator_deposit_key: SolanaValidatorDeposit::find_address(node_id).0,
+        }
+    }
+}
+
+impl From<ReconcileDepositSurplusAccounts> for Vec<AccountMeta> {
+    fn from(accounts: ReconcileDepositSurplusAccounts) -> Self {
+        let ReconcileDepositSurplusAccounts {
+            program_config_key,
+            debt_accountant_key,
+            solana_validator_deposit_key,
+        } = accounts;
+
+        vec![
+            AccountMeta::new_readonly(program_config_key, false),
+            AccountMeta::new_readonly(debt_accountant_key, true),
+            AccountMeta::new(solana_validator_deposit_key, false),
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1141,6 +1814,34 @@ mod tests {
 
         // Debug assert should not panic.
         let accounts = Vec::from(accounts);
-        assert_eq!(accounts.len(), 11);
+        assert_eq!(accounts.len(), 12);
+    }
+
+    #[test]
+    fn test_distribute_rewards_lookup_table_addresses() {
+        let dz_epoch = DoubleZeroEpoch::new(69);
+        let dz_mint_key = Pubkey::new_unique();
+        let relayer_key = Pubkey::new_unique();
+
+        let lookup_table_addresses =
+            DistributeRewardsAccounts::lookup_table_addresses(dz_epoch, &dz_mint_key, &relayer_key);
+
+        let accounts = Vec::from(DistributeRewardsAccounts::new(
+            dz_epoch,
+            &Pubkey::new_unique(),
+            &dz_mint_key,
+            &relayer_key,
+            &[&Pubkey::new_unique()],
+            Some(&Pubkey::new_unique()),
+            None, // bitmap_payer_key
+        ));
+
+        // Only the contributor rewards, ATA payer and recipient accounts are
+        // not in the lookup table.
+        let (in_lookup_table, not_in_lookup_table) = accounts
+            .iter()
+            .partition::<Vec<_>, _>(|meta| lookup_table_addresses.contains(&meta.pubkey));
+        assert_eq!(in_lookup_table.len(), lookup_table_addresses.len());
+        assert_eq!(not_in_lookup_table.len(), 4);
     }
 }
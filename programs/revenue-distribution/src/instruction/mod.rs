@@ -42,6 +42,33 @@ pub enum ProgramConfiguration {
         feature: ProgramFeatureConfiguration,
         activation_epoch: DoubleZeroEpoch,
     },
+
+    /// Replaces the relayers allowed to collect relay lamports. An empty list
+    /// allows any relayer.
+    AllowedRelayers(Vec<Pubkey>),
+
+    /// Sets a key accepted alongside the primary accountant key of the given
+    /// role until the next completed DZ epoch reaches the expiry epoch. The
+    /// default pubkey removes the secondary accountant.
+    SecondaryAccountant {
+        role: AccountantRole,
+        key: Pubkey,
+        expiry_dz_epoch: DoubleZeroEpoch,
+    },
+
+    /// Sets the share of 2Z tokens converted from SOL debt that is transferred
+    /// to the protocol treasury when distribution tokens are swept. The share
+    /// is out of 10,000 (e.g. 420 is 4.20%). The treasury's tokens are held in
+    /// the reserve 2Z token account and tracked separately by the journal.
+    Treasury2zShare(u16),
+
+    /// Sets the Solana validator debt amount (in lamports) below which debt
+    /// is considered dust. Zero means no debt is dust.
+    MinimumSolanaValidatorDebt(u64),
+
+    /// Sets the key receiving funds moved out of the program by an emergency
+    /// withdrawal. The default pubkey disallows emergency withdrawals.
+    EmergencyRecovery(Pubkey),
 }
 
 #[derive(Debug, BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq)]
@@ -54,6 +81,32 @@ pub enum ProgramFeatureConfiguration {
     SolanaValidatorDebtWriteOff,
 }
 
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccountantRole {
+    Debt,
+    Rewards,
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Eq)]
+pub enum Query {
+    /// Sets `CommunityBurnRateView` as return data.
+    CommunityBurnRate,
+
+    /// Sets `Token2zBalanceView` as return data for the 2Z token PDA of the
+    /// token owner passed in.
+    Token2zBalance,
+}
+
+/// Program-owned balance moved by `EmergencyWithdraw`.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Eq)]
+pub enum EmergencyWithdrawSource {
+    /// Journal lamports in excess of its rent-exempt minimum.
+    JournalSol,
+    Journal2z,
+    Distribution2z(DoubleZeroEpoch),
+    SwapDestination2z,
+}
+
 #[derive(Debug, BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq)]
 pub enum ContributorRewardsConfiguration {
     Recipients(Vec<(Pubkey, u16)>),
@@ -69,7 +122,12 @@ pub enum DistributionMerkleRootKind {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RevenueDistributionInstructionData {
     InitializeProgram,
-    MigrateProgramAccounts,
+
+    /// Only the upgrade authority can migrate program accounts to the target
+    /// account version. Accounts written before their layouts were versioned
+    /// are at version 0.
+    MigrateProgramAccounts(u8),
+
     SetAdmin(Pubkey),
     ConfigureProgram(ProgramConfiguration),
     InitializeJournal,
@@ -109,6 +167,12 @@ pub enum RevenueDistributionInstructionData {
     },
     InitializeSwapDestination,
     SweepDistributionTokens,
+    /// Only the SOL/2Z swap program can withdraw SOL, right after its 2Z
+    /// transfer to the swap destination. Besides the SOL destination, it must
+    /// pass the swap destination 2Z token account, the swap session it opened
+    /// in the same transaction and the session's rent beneficiary. Swap
+    /// program versions that only pass the first four accounts are not
+    /// supported, so the swap program must be upgraded with this program.
     WithdrawSol(u64),
     SetDistributionEconomicBurnRate(u32),
     WithdrawSolanaValidatorDeposit,
@@ -126,6 +190,78 @@ pub enum RevenueDistributionInstructionData {
     /// `RewardsIntegration` PDA; rev-distr signs the `Distribution` PDA so
     /// the integration can verify the caller.
     CollectIntegrationRewards,
+
+    /// Creates the `FeeParameterHistory` PDA, which records each change to
+    /// the Solana validator fee parameters made via `ConfigureProgram`.
+    InitializeFeeParameterHistory,
+
+    /// Checks that every 2Z token collected by a fully distributed
+    /// distribution was either distributed, burned or remains in its 2Z token
+    /// account. The outcome is recorded in the distribution, where a mismatch
+    /// is flagged instead of reverting.
+    ///
+    /// Only the rewards accountant can reconcile, and it can reconcile again
+    /// to overwrite a previously recorded outcome.
+    ReconcileDistribution,
+
+    /// Overwrites an already configured rewards merkle root before the rewards
+    /// calculation is finalized. The correction memo is a hash of the reason
+    /// for the correction, which is recorded in the distribution along with
+    /// the incremented rewards root revision.
+    ReplaceDistributionRewardsRoot {
+        total_contributors: u32,
+        merkle_root: Hash,
+        correction_memo: Hash,
+    },
+
+    /// Only the rewards accountant can lock or unlock the recipient shares of
+    /// the contributor rewards accounts passed in. While locked, rewards
+    /// managers cannot change recipients, so the recipients known when rewards
+    /// are finalized are the ones paid when rewards are distributed.
+    SetRecipientSharesLocked(bool),
+
+    /// Compares the journal's recorded balances with the actual balances of
+    /// the journal, its 2Z token account and the swap destination 2Z token
+    /// account. Drift amounts are logged and set as return data (see
+    /// `JournalDrift`) instead of reverting so monitoring can catch
+    /// accounting bugs early.
+    AssertJournalInvariants,
+
+    /// Transfers lamports from the depositor to a Solana validator deposit
+    /// and records the amount in the deposit's cumulative deposited amount.
+    /// Anyone can deposit on behalf of a validator.
+    DepositSolForValidator(u64),
+
+    /// Computes derived values without modifying any accounts and sets them as
+    /// return data, so wallets and UIs can obtain them by simulating this
+    /// instruction instead of reimplementing the program's math.
+    Query(Query),
+
+    /// Only the admin can mark the next distribution to sweep tokens as swept
+    /// if it has zero SOL debt and no contributor rewards, so an epoch without
+    /// any activity cannot block sweeping for the epochs after it.
+    SkipDistributionSweep,
+
+    /// Requires both the upgrade authority and the admin to sign. Moves the
+    /// entire balance of the specified source to a recovery account when a
+    /// critical vulnerability is discovered. The program is paused and an
+    /// irreversible flag is set in the program config, so the program cannot
+    /// be unpaused afterwards.
+    EmergencyWithdraw(EmergencyWithdrawSource),
+
+    /// Attributes lamports held by a Solana validator deposit beyond its rent
+    /// exemption and recorded SOL balance (e.g. lamports transferred directly
+    /// to the deposit) to the validator by adding them to its recorded SOL
+    /// balance. These lamports stay in the deposit, so they can pay debt or be
+    /// withdrawn like any other deposit. Only the debt accountant can reconcile
+    /// a deposit.
+    ReconcileDepositSurplus,
+
+    /// Only the SOL/2Z swap program can open a swap session, which it must do
+    /// before transferring 2Z to the swap destination. The withdraw SOL
+    /// instruction consumes the session in the same transaction. A session
+    /// left open from an earlier slot is reopened.
+    OpenSwapSession,
 }
 
 impl RevenueDistributionInstructionData {
@@ -179,20 +315,57 @@ impl RevenueDistributionInstructionData {
         Discriminator::new_sha2(b"dz::ix::initialize_rewards_integration");
     pub const COLLECT_INTEGRATION_REWARDS: Discriminator<DISCRIMINATOR_LEN> =
         Discriminator::new_sha2(b"dz::ix::collect_integration_rewards");
+    pub const INITIALIZE_FEE_PARAMETER_HISTORY: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::initialize_fee_parameter_history");
+    pub const RECONCILE_DISTRIBUTION: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::reconcile_distribution");
+    pub const REPLACE_DISTRIBUTION_REWARDS_ROOT: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::replace_distribution_rewards_root");
+    pub const SET_RECIPIENT_SHARES_LOCKED: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::set_recipient_shares_locked");
+    pub const ASSERT_JOURNAL_INVARIANTS: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::assert_journal_invariants");
+    pub const DEPOSIT_SOL_FOR_VALIDATOR: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::deposit_sol_for_validator");
+    pub const QUERY: Discriminator<DISCRIMINATOR_LEN> = Discriminator::new_sha2(b"dz::ix::query");
+    pub const SKIP_DISTRIBUTION_SWEEP: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::skip_distribution_sweep");
+    pub const EMERGENCY_WITHDRAW: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::emergency_withdraw");
+    pub const RECONCILE_DEPOSIT_SURPLUS: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::reconcile_deposit_surplus");
+    pub const OPEN_SWAP_SESSION: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::open_swap_session");
 
     //
     // Versioned instruction selectors.
     //
+    // Instructions expected to evolve are serialized with a selector suffixed
+    // by their version. When an instruction gains a new version, the selector
+    // it replaces is still accepted during a migration window so clients built
+    // against the previous version keep working until they upgrade.
+    //
 
     pub const SWEEP_DISTRIBUTION_TOKENS_V1: Discriminator<DISCRIMINATOR_LEN> =
         Discriminator::new_sha2(b"dz::ix::sweep_distribution_tokens::v1");
+    pub const MIGRATE_PROGRAM_ACCOUNTS_V1: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::migrate_program_accounts::v1");
+    pub const DISTRIBUTE_REWARDS_V1: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::distribute_rewards::v1");
+    pub const PAY_SOLANA_VALIDATOR_DEBT_V1: Discriminator<DISCRIMINATOR_LEN> =
+        Discriminator::new_sha2(b"dz::ix::pay_solana_validator_debt::v1");
 }
 
 impl BorshDeserialize for RevenueDistributionInstructionData {
     fn deserialize_reader<R: io::Read>(reader: &mut R) -> std::io::Result<Self> {
         match Discriminator::deserialize_reader(reader)? {
             Self::INITIALIZE_PROGRAM => Ok(Self::InitializeProgram),
-            Self::MIGRATE_PROGRAM_ACCOUNTS => Ok(Self::MigrateProgramAccounts),
+            // The previous selector has no target version. It performed what is
+            // now the first migration step.
+            Self::MIGRATE_PROGRAM_ACCOUNTS => Ok(Self::MigrateProgramAccounts(1)),
+            Self::MIGRATE_PROGRAM_ACCOUNTS_V1 => {
+                BorshDeserialize::deserialize_reader(reader).map(Self::MigrateProgramAccounts)
+            }
             Self::SET_ADMIN => BorshDeserialize::deserialize_reader(reader).map(Self::SetAdmin),
             Self::CONFIGURE_PROGRAM => {
                 BorshDeserialize::deserialize_reader(reader).map(Self::ConfigureProgram)
@@ -221,7 +394,9 @@ impl BorshDeserialize for RevenueDistributionInstructionData {
                 })
             }
             Self::FINALIZE_DISTRIBUTION_REWARDS => Ok(Self::FinalizeDistributionRewards),
-            Self::DISTRIBUTE_REWARDS => {
+            // TODO: Stop accepting the unversioned selector once clients have
+            // migrated to v1.
+            Self::DISTRIBUTE_REWARDS | Self::DISTRIBUTE_REWARDS_V1 => {
                 let unit_share = BorshDeserialize::deserialize_reader(reader)?;
                 let economic_burn_rate = BorshDeserialize::deserialize_reader(reader)?;
                 let proof = BorshDeserialize::deserialize_reader(reader)?;
@@ -252,7 +427,9 @@ impl BorshDeserialize for RevenueDistributionInstructionData {
                 BorshDeserialize::deserialize_reader(reader)
                     .map(Self::InitializeSolanaValidatorDeposit)
             }
-            Self::PAY_SOLANA_VALIDATOR_DEBT => {
+            // TODO: Stop accepting the unversioned selector once clients have
+            // migrated to v1.
+            Self::PAY_SOLANA_VALIDATOR_DEBT | Self::PAY_SOLANA_VALIDATOR_DEBT_V1 => {
                 let amount = BorshDeserialize::deserialize_reader(reader)?;
                 let proof = BorshDeserialize::deserialize_reader(reader)?;
 
@@ -281,6 +458,33 @@ impl BorshDeserialize for RevenueDistributionInstructionData {
                 BorshDeserialize::deserialize_reader(reader).map(Self::InitializeRewardsIntegration)
             }
             Self::COLLECT_INTEGRATION_REWARDS => Ok(Self::CollectIntegrationRewards),
+            Self::INITIALIZE_FEE_PARAMETER_HISTORY => Ok(Self::InitializeFeeParameterHistory),
+            Self::RECONCILE_DISTRIBUTION => Ok(Self::ReconcileDistribution),
+            Self::REPLACE_DISTRIBUTION_REWARDS_ROOT => {
+                let total_contributors = BorshDeserialize::deserialize_reader(reader)?;
+                let merkle_root = BorshDeserialize::deserialize_reader(reader)?;
+                let correction_memo = BorshDeserialize::deserialize_reader(reader)?;
+
+                Ok(Self::ReplaceDistributionRewardsRoot {
+                    total_contributors,
+                    merkle_root,
+                    correction_memo,
+                })
+            }
+            Self::SET_RECIPIENT_SHARES_LOCKED => {
+                BorshDeserialize::deserialize_reader(reader).map(Self::SetRecipientSharesLocked)
+            }
+            Self::ASSERT_JOURNAL_INVARIANTS => Ok(Self::AssertJournalInvariants),
+            Self::DEPOSIT_SOL_FOR_VALIDATOR => {
+                BorshDeserialize::deserialize_reader(reader).map(Self::DepositSolForValidator)
+            }
+            Self::QUERY => BorshDeserialize::deserialize_reader(reader).map(Self::Query),
+            Self::SKIP_DISTRIBUTION_SWEEP => Ok(Self::SkipDistributionSweep),
+            Self::EMERGENCY_WITHDRAW => {
+                BorshDeserialize::deserialize_reader(reader).map(Self::EmergencyWithdraw)
+            }
+            Self::RECONCILE_DEPOSIT_SURPLUS => Ok(Self::ReconcileDepositSurplus),
+            Self::OPEN_SWAP_SESSION => Ok(Self::OpenSwapSession),
             _ => Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "Invalid discriminator",
@@ -293,7 +497,10 @@ impl BorshSerialize for RevenueDistributionInstructionData {
     fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         match self {
             Self::InitializeProgram => Self::INITIALIZE_PROGRAM.serialize(writer),
-            Self::MigrateProgramAccounts => Self::MIGRATE_PROGRAM_ACCOUNTS.serialize(writer),
+            Self::MigrateProgramAccounts(target_version) => {
+                Self::MIGRATE_PROGRAM_ACCOUNTS_V1.serialize(writer)?;
+                target_version.serialize(writer)
+            }
             Self::SetAdmin(admin_key) => {
                 Self::SET_ADMIN.serialize(writer)?;
                 admin_key.serialize(writer)
@@ -331,7 +538,7 @@ impl BorshSerialize for RevenueDistributionInstructionData {
                 economic_burn_rate,
                 proof,
             } => {
-                Self::DISTRIBUTE_REWARDS.serialize(writer)?;
+                Self::DISTRIBUTE_REWARDS_V1.serialize(writer)?;
                 unit_share.serialize(writer)?;
                 economic_burn_rate.serialize(writer)?;
                 proof.serialize(writer)
@@ -358,7 +565,7 @@ impl BorshSerialize for RevenueDistributionInstructionData {
                 solana_validator_deposit_key.serialize(writer)
             }
             Self::PaySolanaValidatorDebt { amount, proof } => {
-                Self::PAY_SOLANA_VALIDATOR_DEBT.serialize(writer)?;
+                Self::PAY_SOLANA_VALIDATOR_DEBT_V1.serialize(writer)?;
                 amount.serialize(writer)?;
                 proof.serialize(writer)
             }
@@ -388,6 +595,133 @@ impl BorshSerialize for RevenueDistributionInstructionData {
                 integration_program_id.serialize(writer)
             }
             Self::CollectIntegrationRewards => Self::COLLECT_INTEGRATION_REWARDS.serialize(writer),
+            Self::InitializeFeeParameterHistory => {
+                Self::INITIALIZE_FEE_PARAMETER_HISTORY.serialize(writer)
+            }
+            Self::ReconcileDistribution => Self::RECONCILE_DISTRIBUTION.serialize(writer),
+            Self::ReplaceDistributionRewardsRoot {
+                total_contributors,
+                merkle_root,
+                correction_memo,
+            } => {
+                Self::REPLACE_DISTRIBUTION_REWARDS_ROOT.serialize(writer)?;
+                total_contributors.serialize(writer)?;
+                merkle_root.serialize(writer)?;
+                correction_memo.serialize(writer)
+            }
+            Self::SetRecipientSharesLocked(should_lock) => {
+                Self::SET_RECIPIENT_SHARES_LOCKED.serialize(writer)?;
+                should_lock.serialize(writer)
+            }
+            Self::AssertJournalInvariants => Self::ASSERT_JOURNAL_INVARIANTS.serialize(writer),
+            Self::DepositSolForValidator(amount) => {
+                Self::DEPOSIT_SOL_FOR_VALIDATOR.serialize(writer)?;
+                amount.serialize(writer)
+            }
+            Self::Query(query) => {
+                Self::QUERY.serialize(writer)?;
+                query.serialize(writer)
+            }
+            Self::SkipDistributionSweep => Self::SKIP_DISTRIBUTION_SWEEP.serialize(writer),
+            Self::EmergencyWithdraw(source) => {
+                Self::EMERGENCY_WITHDRAW.serialize(writer)?;
+                source.serialize(writer)
+            }
+            Self::ReconcileDepositSurplus => Self::RECONCILE_DEPOSIT_SURPLUS.serialize(writer),
+            Self::OpenSwapSession => Self::OPEN_SWAP_SESSION.serialize(writer),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_versioned_selector(
+        ix_data: RevenueDistributionInstructionData,
+        previous_selector: Discriminator<DISCRIMINATOR_LEN>,
+        selector: Discriminator<DISCRIMINATOR_LEN>,
+    ) {
+        let serialized = borsh::to_vec(&ix_data).unwrap();
+        assert_eq!(
+            serialized[..DISCRIMINATOR_LEN],
+            borsh::to_vec(&selector).unwrap()
+        );
+        assert_eq!(
+            RevenueDistributionInstructionData::try_from_slice(&serialized).unwrap(),
+            ix_data
+        );
+
+        // The previous selector is still accepted.
+        let mut previous_serialized = borsh::to_vec(&previous_selector).unwrap();
+        previous_serialized.extend_from_slice(&serialized[DISCRIMINATOR_LEN..]);
+        assert_eq!(
+            RevenueDistributionInstructionData::try_from_slice(&previous_serialized).unwrap(),
+            ix_data
+        );
+    }
+
+    #[test]
+    fn test_migrate_program_accounts_selector() {
+        let ix_data = RevenueDistributionInstructionData::MigrateProgramAccounts(1);
+
+        let serialized = borsh::to_vec(&ix_data).unwrap();
+        assert_eq!(
+            serialized[..DISCRIMINATOR_LEN],
+            borsh::to_vec(&RevenueDistributionInstructionData::MIGRATE_PROGRAM_ACCOUNTS_V1)
+                .unwrap()
+        );
+        assert_eq!(
+            RevenueDistributionInstructionData::try_from_slice(&serialized).unwrap(),
+            ix_data
+        );
+
+        // The previous selector has no target version and migrates to the
+        // first account version.
+        let previous_serialized =
+            borsh::to_vec(&RevenueDistributionInstructionData::MIGRATE_PROGRAM_ACCOUNTS).unwrap();
+        assert_eq!(
+            RevenueDistributionInstructionData::try_from_slice(&previous_serialized).unwrap(),
+            ix_data
+        );
+    }
+
+    #[test]
+    fn test_distribute_rewards_selector() {
+        let debt = [SolanaValidatorDebt {
+            node_id: Pubkey::new_unique(),
+            amount: 69_420,
+        }];
+        let proof = MerkleProof::from_indexed_pod_leaves(&debt, 0, None).unwrap();
+
+        assert_versioned_selector(
+            RevenueDistributionInstructionData::DistributeRewards {
+                unit_share: 420,
+                economic_burn_rate: 69,
+                proof,
+            },
+            RevenueDistributionInstructionData::DISTRIBUTE_REWARDS,
+            RevenueDistributionInstructionData::DISTRIBUTE_REWARDS_V1,
+        );
+    }
+
+    #[test]
+    fn test_pay_solana_validator_debt_selector() {
+        let debt = [SolanaValidatorDebt {
+            node_id: Pubkey::new_unique(),
+            amount: 69_420,
+        }];
+        let proof =
+            MerkleProof::from_indexed_pod_leaves(&debt, 0, Some(SolanaValidatorDebt::LEAF_PREFIX))
+                .unwrap();
+
+        assert_versioned_selector(
+            RevenueDistributionInstructionData::PaySolanaValidatorDebt {
+                amount: 69_420,
+                proof,
+            },
+            RevenueDistributionInstructionData::PAY_SOLANA_VALIDATOR_DEBT,
+            RevenueDistributionInstructionData::PAY_SOLANA_VALIDATOR_DEBT_V1,
+        );
+    }
+}
@@ -16,3 +16,8 @@ pub use env::development::DOUBLEZERO_MINT_KEY;
 pub use env::mainnet::DOUBLEZERO_MINT_KEY;
 
 pub const DOUBLEZERO_MINT_DECIMALS: u8 = 8;
+
+const _: () = assert!(
+    doublezero_program_tools::amount::Token2z::DECIMALS == DOUBLEZERO_MINT_DECIMALS,
+    "2Z amount decimals must match the 2Z mint"
+);
@@ -4,13 +4,16 @@ use doublezero_program_tools::{
         try_next_enumerated_account, EnumeratedAccountInfoIter, NextAccountOptions,
         TryNextAccounts, UpgradeAuthority,
     },
+    amount::{Lamports, Token2z},
     instruction::try_build_instruction,
     recipe::{
+        close_account::try_close_account,
         create_account::{try_create_account, CreateAccountOptions},
         create_token_account::try_create_token_account,
         Invoker,
     },
-    zero_copy::{self, ZeroCopyAccount, ZeroCopyMutAccount},
+    zero_copy::{self, VersionedAccount, ZeroCopyAccount, ZeroCopyMutAccount},
+    PrecomputedDiscriminator,
 };
 use ruint::Uint;
 use solana_account_info::{AccountInfo, MAX_PERMITTED_DATA_INCREASE};
@@ -27,17 +30,21 @@ use svm_hash::{merkle::MerkleProof, sha2::Hash};
 
 use crate::{
     instruction::{
-        account::DequeueFillsCpiAccounts, ContributorRewardsConfiguration,
-        DistributionMerkleRootKind, ProgramConfiguration, ProgramFeatureConfiguration,
-        ProgramFlagConfiguration, RevenueDistributionInstructionData,
+        account::DequeueFillsCpiAccounts, AccountantRole, ContributorRewardsConfiguration,
+        DistributionMerkleRootKind, EmergencyWithdrawSource, ProgramConfiguration,
+        ProgramFeatureConfiguration, ProgramFlagConfiguration, Query,
+        RevenueDistributionInstructionData,
     },
     integration::{IntegrationInstructionData, WithdrawIntegrationRewardsAccounts},
     state::{
-        self, CommunityBurnRateParameters, ContributorRewards, Distribution, Journal,
+        self, CommunityBurnRateParameters, CommunityBurnRateView, ContributorRewards, Distribution,
+        FeeParameterHistory, FeeParameterHistoryEntry, Journal, JournalDrift, PaymentReceipt,
         ProgramConfig, RecipientShare, RecipientShares, RelayParameters, RewardsIntegration,
-        SolanaValidatorDeposit, SolanaValidatorFeeParameters,
+        SecondaryAuthority, SolanaValidatorDeposit, SolanaValidatorFeeParameters,
+        SolanaValidatorPayment, SolanaValidatorPaymentHistory, SwapSession, Token2zBalanceView,
+        MAX_ALLOWED_RELAYERS, MAX_RECIPIENTS_PER_PAGE,
     },
-    types::{BurnRate, ByteFlags, RewardShare, SolanaValidatorDebt, ValidatorFee},
+    types::{BurnRate, ByteFlags, RewardShare, SolanaValidatorDebt, UnitShare16, ValidatorFee},
     DOUBLEZERO_MINT_KEY, ID,
 };
 
@@ -45,12 +52,19 @@ use crate::{
 // would be intentional (and should be associated with a program account
 // migration).
 //
-// Note: We do not need to check the program config or journal because 10kb was
-// allocated to each of those accounts.
+// Note: We do not need to check the program config or journal sizes because 10kb
+// was allocated to each of those accounts. Fields appended to them are read as
+// zeros from existing accounts as long as they still fit.
+const _: () = assert!(zero_copy::data_end::<ProgramConfig>() <= MAX_PERMITTED_DATA_INCREASE);
+const _: () = assert!(zero_copy::data_end::<Journal>() <= MAX_PERMITTED_DATA_INCREASE);
 const _: () = assert!(size_of::<ContributorRewards>() == 600);
 const _: () = assert!(size_of::<Distribution>() == 448);
+const _: () = assert!(size_of::<FeeParameterHistory>() == 1_864);
+const _: () = assert!(size_of::<PaymentReceipt>() == 96);
 const _: () = assert!(size_of::<RewardsIntegration>() == 176);
 const _: () = assert!(size_of::<SolanaValidatorDeposit>() == 96);
+const _: () = assert!(size_of::<SolanaValidatorPaymentHistory>() == 104);
+const _: () = assert!(size_of::<SwapSession>() == 88);
 
 solana_program_entrypoint::entrypoint!(try_process_instruction);
 
@@ -70,8 +84,8 @@ fn try_process_instruction(
 
     match ix_data {
         RevenueDistributionInstructionData::InitializeProgram => try_initialize_program(accounts),
-        RevenueDistributionInstructionData::MigrateProgramAccounts => {
-            try_migrate_program_accounts(accounts)
+        RevenueDistributionInstructionData::MigrateProgramAccounts(target_version) => {
+            try_migrate_program_accounts(accounts, target_version)
         }
         RevenueDistributionInstructionData::SetAdmin(admin_key) => {
             try_set_admin(accounts, admin_key)
@@ -148,6 +162,42 @@ fn try_process_instruction(
         RevenueDistributionInstructionData::CollectIntegrationRewards => {
             try_collect_integration_rewards(accounts)
         }
+        RevenueDistributionInstructionData::InitializeFeeParameterHistory => {
+            try_initialize_fee_parameter_history(accounts)
+        }
+        RevenueDistributionInstructionData::ReconcileDistribution => {
+            try_reconcile_distribution(accounts)
+        }
+        RevenueDistributionInstructionData::ReplaceDistributionRewardsRoot {
+            total_contributors,
+            merkle_root,
+            correction_memo,
+        } => try_replace_distribution_rewards_root(
+            accounts,
+            total_contributors,
+            merkle_root,
+            correction_memo,
+        ),
+        RevenueDistributionInstructionData::SetRecipientSharesLocked(should_lock) => {
+            try_set_recipient_shares_locked(accounts, should_lock)
+        }
+        RevenueDistributionInstructionData::AssertJournalInvariants => {
+            try_assert_journal_invariants(accounts)
+        }
+        RevenueDistributionInstructionData::DepositSolForValidator(amount) => {
+            try_deposit_sol_for_validator(accounts, amount)
+        }
+        RevenueDistributionInstructionData::Query(query) => try_query(accounts, query),
+        RevenueDistributionInstructionData::SkipDistributionSweep => {
+            try_skip_distribution_sweep(accounts)
+        }
+        RevenueDistributionInstructionData::EmergencyWithdraw(source) => {
+            try_emergency_withdraw(accounts, source)
+        }
+        RevenueDistributionInstructionData::ReconcileDepositSurplus => {
+            try_reconcile_deposit_surplus(accounts)
+        }
+        RevenueDistributionInstructionData::OpenSwapSession => try_open_swap_session(accounts),
     }
 }
 
@@ -243,7 +293,7 @@ fn try_initialize_program(accounts: &[AccountInfo]) -> ProgramResult {
 
     // Set the bump seeds and pause the program.
     let (mut program_config, _) =
-        zero_copy::try_initialize::<ProgramConfig>(new_program_config_info)?;
+        zero_copy::try_initialize_versioned::<ProgramConfig>(new_program_config_info)?;
     program_config.bump_seed = program_config_bump;
     program_config.reserve_2z_bump_seed = reserve_2z_bump;
 
@@ -286,6 +336,8 @@ fn try_configure_program(accounts: &[AccountInfo], setting: ProgramConfiguration
     // We expect the following accounts for this instruction:
     // - 0: Program config.
     // - 1: Admin.
+    // - 2: Fee parameter history (optional, only when configuring Solana
+    //   validator fee parameters).
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
@@ -302,6 +354,13 @@ fn try_configure_program(accounts: &[AccountInfo], setting: ProgramConfiguration
             msg!("Set flag");
             match configure_flag {
                 ProgramFlagConfiguration::IsPaused(should_pause) => {
+                    // An emergency withdrawal leaves the program's accounting
+                    // inconsistent with its balances, so it stays paused.
+                    if !should_pause && program_config.has_emergency_withdrawn() {
+                        msg!("Cannot unpause after emergency withdrawal");
+                        return Err(ProgramError::InvalidAccountData);
+                    }
+
                     msg!("is_paused: {}", should_pause);
                     program_config.set_is_paused(should_pause);
                 }
@@ -403,6 +462,50 @@ fn try_configure_program(accounts: &[AccountInfo], setting: ProgramConfiguration
 
             msg!("  fixed_sol_amount: {}", fixed_sol_amount);
             fee_params.fixed_sol_amount = fixed_sol_amount;
+
+            // Account 2 may be the fee parameter history, which must be the
+            // last account. If provided, the history must be initialized so
+            // this change is recorded. The next distribution initialized will
+            // be the first to use these fee parameters.
+            if let Ok((account_index, fee_parameter_history_info)) =
+                try_next_enumerated_account(&mut accounts_iter, Default::default())
+            {
+                if fee_parameter_history_info.key != &FeeParameterHistory::find_address().0 {
+                    msg!(
+                        "Invalid address for fee parameter history (account {})",
+                        account_index
+                    );
+                    return Err(ProgramError::InvalidSeeds);
+                }
+
+                // Only this program can create the fee parameter history, so it
+                // is not initialized yet if this program does not own it.
+                if fee_parameter_history_info.owner != &ID {
+                    msg!("Fee parameter history is not initialized");
+                    return Err(ProgramError::UninitializedAccount);
+                }
+
+                let mut fee_parameter_history =
+                    ZeroCopyMutAccount::<FeeParameterHistory>::try_from_account_info(
+                        account_index,
+                        fee_parameter_history_info,
+                        Some(&ID),
+                    )?;
+
+                fee_parameter_history.append(FeeParameterHistoryEntry {
+                    effective_dz_epoch: program_config.next_completed_dz_epoch,
+                    timestamp: Clock::get().unwrap().unix_timestamp,
+                    solana_validator_fee_parameters: program_config
+                        .distribution_parameters
+                        .solana_validator_fee_parameters,
+                });
+
+                msg!(
+                    "Recorded fee parameter history entry {} effective at DZ epoch {}",
+                    fee_parameter_history.total_entries_count,
+                    program_config.next_completed_dz_epoch
+                );
+            }
         }
         ProgramConfiguration::CalculationGracePeriodMinutes(grace_period_minutes) => {
             // If the grace period is zero, we treat this as unset.
@@ -587,6 +690,93 @@ fn try_configure_program(accounts: &[AccountInfo], setting: ProgramConfiguration
                 }
             }
         }
+        ProgramConfiguration::AllowedRelayers(allowed_relayer_keys) => {
+            if allowed_relayer_keys.len() > MAX_ALLOWED_RELAYERS {
+                msg!("Cannot exceed {} allowed relayers", MAX_ALLOWED_RELAYERS);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            // The default pubkey marks an unused entry.
+            if allowed_relayer_keys.contains(&Pubkey::default()) {
+                msg!("Allowed relayer cannot be the default pubkey");
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            msg!("Set allowed_relayer_keys");
+            program_config.allowed_relayer_keys = Default::default();
+
+            for (allowed_relayer_key, relayer_key) in program_config
+                .allowed_relayer_keys
+                .iter_mut()
+                .zip(allowed_relayer_keys)
+            {
+                msg!("  {}", relayer_key);
+                *allowed_relayer_key = relayer_key;
+            }
+        }
+        ProgramConfiguration::SecondaryAccountant {
+            role,
+            key,
+            expiry_dz_epoch,
+        } => {
+            let secondary_accountant = if key == Pubkey::default() {
+                SecondaryAuthority::default()
+            } else {
+                // A secondary accountant that has already expired would never
+                // be accepted.
+                if expiry_dz_epoch <= program_config.next_completed_dz_epoch {
+                    msg!(
+                        "Secondary accountant expiry must be after DZ epoch {}",
+                        program_config.next_completed_dz_epoch
+                    );
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                SecondaryAuthority {
+                    key,
+                    expiry_dz_epoch,
+                }
+            };
+
+            match role {
+                AccountantRole::Debt => {
+                    msg!("Set secondary_debt_accountant");
+                    program_config.secondary_debt_accountant = secondary_accountant;
+                }
+                AccountantRole::Rewards => {
+                    msg!("Set secondary_rewards_accountant");
+                    program_config.secondary_rewards_accountant = secondary_accountant;
+                }
+            }
+
+            msg!("  key: {}", secondary_accountant.key);
+            msg!(
+                "  expiry_dz_epoch: {}",
+                secondary_accountant.expiry_dz_epoch
+            );
+        }
+        ProgramConfiguration::Treasury2zShare(treasury_2z_share) => {
+            let treasury_2z_share = UnitShare16::new(treasury_2z_share).ok_or_else(|| {
+                msg!("Invalid treasury 2Z share: {}", treasury_2z_share);
+                ProgramError::InvalidInstructionData
+            })?;
+
+            msg!("Set treasury_2z_share: {}", treasury_2z_share);
+            program_config.treasury_2z_share = treasury_2z_share;
+        }
+        ProgramConfiguration::MinimumSolanaValidatorDebt(minimum_debt) => {
+            msg!(
+                "Set distribution_parameters.minimum_solana_validator_debt: {}",
+                minimum_debt
+            );
+            program_config
+                .distribution_parameters
+                .minimum_solana_validator_debt = minimum_debt;
+        }
+        ProgramConfiguration::EmergencyRecovery(emergency_recovery_key) => {
+            msg!("Set emergency_recovery_key: {}", emergency_recovery_key);
+            program_config.emergency_recovery_key = emergency_recovery_key;
+        }
     }
 
     Ok(())
@@ -680,7 +870,7 @@ fn try_initialize_journal(accounts: &[AccountInfo]) -> ProgramResult {
     )?;
 
     // Set the bump seeds.
-    let (mut journal, _) = zero_copy::try_initialize::<Journal>(new_journal_info)?;
+    let (mut journal, _) = zero_copy::try_initialize_versioned::<Journal>(new_journal_info)?;
     journal.bump_seed = journal_bump;
     journal.token_2z_pda_bump_seed = journal_2z_token_pda_bump;
 
@@ -863,7 +1053,8 @@ fn try_initialize_distribution(accounts: &[AccountInfo]) -> ProgramResult {
     )?;
 
     // Finally, initialize some distribution account fields.
-    let (mut distribution, _) = zero_copy::try_initialize::<Distribution>(new_distribution_info)?;
+    let (mut distribution, _) =
+        zero_copy::try_initialize_versioned::<Distribution>(new_distribution_info)?;
 
     // Set DZ epoch. The DZ epoch should never change with any interaction with
     // the epoch distribution account.
@@ -1126,6 +1317,13 @@ fn try_configure_distribution_rewards(
     distribution.try_require_unfinalized_rewards_calculation()?;
     distribution.try_require_calculation_allowed()?;
 
+    // Once configured, the rewards merkle root can only be changed with the
+    // replace rewards root instruction, which requires a correction memo.
+    if distribution.rewards_merkle_root != Hash::default() {
+        msg!("Rewards merkle root already configured");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
     msg!("Set total_contributors: {}", total_contributors);
     distribution.total_contributors = total_contributors;
 
@@ -1281,9 +1479,26 @@ fn try_distribute_rewards(
     // - 6: SPL Token program.
     //
     // Remaining accounts are recipient ATAs, whose owners are specified in
-    // the contributor rewards account. Because this account specifies a
-    // maximum number of 8 recipients, there will be at most 15 accounts passed
-    // to this instruction.
+    // the contributor rewards account. Because recipients are distributed at
+    // most 8 at a time (one page), there will be at most 15 accounts passed to
+    // this instruction (26 accounts if missing recipient ATAs are created).
+    //
+    // Contributors with more than one page of recipients have rewards
+    // distributed by invoking this instruction once per page. The first
+    // invocation distributes to the first page of recipients and the last
+    // invocation distributes to the last page, burns and marks the rewards as
+    // distributed.
+    //
+    // To create missing recipient ATAs, the N recipient ATAs are followed by:
+    // - 7 + N: Associated Token Account program.
+    // - 8 + N: System program.
+    // - 9 + N: Payer for the rent of new ATAs.
+    // - 10 + N + i: Recipient (owner of ATA i).
+    //
+    // If the partially distributed rewards bitmap has to be allocated, the
+    // last accounts must be:
+    // - Payer for the rent of this bitmap.
+    // - System program.
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
@@ -1325,16 +1540,18 @@ fn try_distribute_rewards(
     // Each bit represents one leaf: 1 = distributed, 0 = not yet distributed.
     let processed_bitmap_range = distribution.processed_rewards_bitmap_range();
 
-    try_process_remaining_data_leaf_index(
-        &mut distribution.remaining_data[processed_bitmap_range],
+    try_require_unprocessed_remaining_data_leaf_index(
+        &distribution.remaining_data[processed_bitmap_range.clone()],
         leaf_index,
     )
     .inspect_err(|_| {
         msg!("Rewards already distributed");
     })?;
 
-    let contributor_rewards =
-        ZeroCopyAccount::<ContributorRewards>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+    // Account 2 must be the contributor rewards. Its distributed and burned
+    // 2Z totals are updated by this instruction.
+    let mut contributor_rewards =
+        ZeroCopyMutAccount::<ContributorRewards>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
     msg!("Service key: {}", contributor_rewards.service_key);
 
     let reward_share = RewardShare::new(
@@ -1358,6 +1575,107 @@ fn try_distribute_rewards(
         return Err(ProgramError::InvalidInstructionData);
     }
 
+    let recipient_page_count =
+        contributor_rewards.recipient_page_count(&contributor_rewards.remaining_data);
+
+    // Bits indicating whether the first page of recipients has been
+    // distributed are stored in the distribution's remaining data. This bitmap
+    // is only needed for contributors with more than one page of recipients,
+    // so it is allocated when the first of these contributors has rewards
+    // distributed. Its rent is paid by the payer passed as one of the last
+    // accounts.
+    let mut partially_distributed_bitmap_rent = 0;
+
+    if recipient_page_count > 1
+        && distribution
+            .partially_distributed_rewards_bitmap_range()
+            .is_empty()
+    {
+        let additional_data_len = distribution.total_contributors.div_ceil(8);
+
+        distribution.partially_distributed_rewards_start_index =
+            distribution.remaining_data.len() as u32;
+        distribution.partially_distributed_rewards_end_index = distribution
+            .partially_distributed_rewards_start_index
+            .saturating_add(additional_data_len);
+
+        // Avoid borrowing while in mutable borrow state.
+        let distribution_index = distribution.index;
+        let distribution_info = distribution.info;
+        drop(distribution);
+
+        let data_len = distribution_info.data_len();
+        let new_data_len = data_len.saturating_add(additional_data_len as usize);
+        distribution_info.resize(new_data_len)?;
+
+        let rent = Rent::get().unwrap();
+        partially_distributed_bitmap_rent = rent
+            .minimum_balance(new_data_len)
+            .saturating_sub(rent.minimum_balance(data_len));
+
+        msg!(
+            "Increase distribution account size by {} byte{}",
+            additional_data_len,
+            if additional_data_len == 1 { "" } else { "s" }
+        );
+
+        distribution = ZeroCopyMutAccount::try_from_account_info(
+            distribution_index,
+            distribution_info,
+            Some(&ID),
+        )?;
+    }
+
+    let partially_distributed_bitmap_range =
+        distribution.partially_distributed_rewards_bitmap_range();
+
+    let page = if is_remaining_data_leaf_index_processed(
+        &distribution.remaining_data[partially_distributed_bitmap_range.clone()],
+        leaf_index,
+    ) {
+        1
+    } else {
+        0
+    };
+    let is_last_page = page + 1 >= recipient_page_count;
+
+    if recipient_page_count > 1 {
+        msg!("Recipient page {} of {}", page + 1, recipient_page_count);
+    }
+
+    // Rewards for one DZ epoch must be distributed to every page before
+    // another DZ epoch's first page can be distributed. Recipients cannot
+    // change in between, so the pages distributed for a DZ epoch are
+    // consistent.
+    match contributor_rewards.checked_partially_distributed_dz_epoch() {
+        Some(partially_distributed_dz_epoch) if page == 0 => {
+            msg!(
+                "Rewards for DZ epoch {} are partially distributed",
+                partially_distributed_dz_epoch
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Some(partially_distributed_dz_epoch)
+            if partially_distributed_dz_epoch == distribution.dz_epoch => {}
+        None if page == 0 => {}
+        _ => {
+            msg!("Rewards are not partially distributed for this DZ epoch");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    if is_last_page {
+        try_process_remaining_data_leaf_index(
+            &mut distribution.remaining_data[processed_bitmap_range],
+            leaf_index,
+        )?;
+    } else {
+        try_process_remaining_data_leaf_index(
+            &mut distribution.remaining_data[partially_distributed_bitmap_range],
+            leaf_index,
+        )?;
+    }
+
     // Account 3 must be the distribution 2Z token account.
     let (_, distribution_2z_token_pda_info, _) = try_next_2z_token_pda_info(
         &mut accounts_iter,
@@ -1375,7 +1693,7 @@ fn try_distribute_rewards(
     //
     // To avoid a potential lamport accounting issue, moving lamports to this
     // account will happen at the end of this instruction.
-    let (_, relayer_info) = try_next_enumerated_account(
+    let (account_index, relayer_info) = try_next_enumerated_account(
         &mut accounts_iter,
         NextAccountOptions {
             must_be_writable: true,
@@ -1383,6 +1701,13 @@ fn try_distribute_rewards(
         },
     )?;
 
+    // Only registered relayers can collect relay lamports if there is an
+    // allowlist.
+    if !program_config.is_relayer_allowed(relayer_info.key) {
+        msg!("Relayer is not allowed (account {})", account_index);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
     // Account 6 must be the SPL Token program.
     try_next_token_program_info(&mut accounts_iter)?;
 
@@ -1400,16 +1725,17 @@ fn try_distribute_rewards(
         &[distribution.bump_seed],
     ];
 
-    let mut total_transferred_share_amount = 0;
-    let mut transfer_count = 0;
+    // Take the ATA for each recipient in this page. Transfers happen after any
+    // missing ATAs are created.
+    let recipient_shares = contributor_rewards
+        .recipient_shares_page(&contributor_rewards.remaining_data, page)
+        .unwrap_or_default();
+    let mut recipient_atas = Vec::with_capacity(MAX_RECIPIENTS_PER_PAGE);
 
-    // Now split up the remaining share amount across the recipient ATAs. For
-    // each recipient, take the Associated Token Account (ATA) and transfer the
-    // share of 2Z tokens to it.
     for RecipientShare {
         recipient_key,
         share,
-    } in contributor_rewards.recipient_shares.active_iter()
+    } in recipient_shares.active_iter()
     {
         // Account 7 + i must be the ATA owned by the recipient. This account
         // must be writable, but we do not need to check this because the
@@ -1428,6 +1754,102 @@ fn try_distribute_rewards(
             return Err(ProgramError::InvalidAccountData);
         }
 
+        recipient_atas.push((recipient_key, share, ata_info));
+    }
+
+    // There must be at least one recipient.
+    if recipient_atas.is_empty() {
+        msg!("Contributor recipients must be configured");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // If the recipient ATAs are followed by the ATA program, create any
+    // missing recipient ATAs. Rent for these ATAs is paid by the payer so the
+    // relay lamports owed to the relayer are left untouched.
+    let is_creating_recipient_atas = accounts_iter
+        .clone()
+        .next()
+        .is_some_and(|(_, info)| info.key == &spl_associated_token_account_interface::program::ID);
+
+    if is_creating_recipient_atas {
+        // Account 7 + N is the Associated Token Account program, which was
+        // checked above.
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+        // Account 8 + N must be the System program. We do not need to check
+        // this account because the create-ATA CPI call will fail if it is not.
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+        // Account 9 + N must be the payer. This account must be a signer and
+        // writable, but we do not need to check these fields because the
+        // create-ATA CPI call will fail if it is not.
+        let (_, payer_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+        for (recipient_key, _, ata_info) in recipient_atas.iter() {
+            // Account 10 + N + i must be the recipient, which is the owner of
+            // the ATA.
+            let (account_index, recipient_info) =
+                try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+            // Enforce this account location.
+            if recipient_info.key != *recipient_key {
+                msg!(
+                    "Expected recipient {} (account {})",
+                    recipient_key,
+                    account_index
+                );
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            if !ata_info.data_is_empty() {
+                continue;
+            }
+
+            let create_ata_ix =
+                spl_associated_token_account_interface::instruction::create_associated_token_account_idempotent(
+                    payer_info.key,
+                    recipient_key,
+                    &DOUBLEZERO_MINT_KEY,
+                    &spl_token_interface::ID,
+                );
+
+            invoke_signed_unchecked(&create_ata_ix, accounts, &[])?;
+            msg!("Created ATA for recipient {}", recipient_key);
+        }
+    }
+
+    if partially_distributed_bitmap_rent != 0 {
+        // The next account must be the payer for the partially distributed
+        // rewards bitmap. In order to transfer lamports from the payer to the
+        // distribution, this account must be writable.
+        //
+        // The account after the payer must be the System program. We do not
+        // need to check this account because the transfer CPI call will fail
+        // if it is not.
+        let (_, payer_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())
+            .inspect_err(|_| {
+            msg!("Payer required to allocate partially distributed rewards bitmap");
+        })?;
+
+        let transfer_ix = system_instruction::transfer(
+            payer_info.key,
+            distribution.info.key,
+            partially_distributed_bitmap_rent,
+        );
+
+        invoke_signed_unchecked(&transfer_ix, accounts, &[])?;
+        msg!(
+            "Paid {} lamports of rent for partially distributed rewards bitmap",
+            partially_distributed_bitmap_rent
+        );
+    }
+
+    let mut total_transferred_share_amount = 0;
+
+    // Now split up the remaining share amount across the recipient ATAs. For
+    // each recipient, take the Associated Token Account (ATA) and transfer the
+    // share of 2Z tokens to it.
+    for (recipient_key, share, ata_info) in recipient_atas {
         // Calculate this recipient's portion of the remaining share amount
         // based on their proportional share percentage
         let recipient_share_amount = share.mul_scalar(remaining_share_amount);
@@ -1436,7 +1858,7 @@ fn try_distribute_rewards(
         let token_transfer_ix = token_instruction::transfer(
             &spl_token_interface::ID,
             distribution_2z_token_pda_info.key,
-            &ata_key,
+            ata_info.key,
             distribution.info.key,
             &[], // signer_pubkeys
             recipient_share_amount,
@@ -1449,48 +1871,75 @@ fn try_distribute_rewards(
             recipient_share_amount,
             recipient_key
         );
-
-        transfer_count += 1;
-    }
-
-    // There must be at least one recipient.
-    if transfer_count == 0 {
-        msg!("Contributor recipients must be configured");
-        return Err(ProgramError::InvalidAccountData);
     }
 
-    // Add any dust (rounding remainder) to the burn amount to ensure all tokens
-    // are accounted for.
-    burn_share_amount += remaining_share_amount - total_transferred_share_amount;
-
     distribution.distributed_2z_amount += total_transferred_share_amount;
-    distribution.burned_2z_amount += burn_share_amount;
-    distribution.distributed_rewards_count += 1;
+    contributor_rewards.total_distributed_2z_amount = contributor_rewards
+        .total_distributed_2z_amount
+        .saturating_add(total_transferred_share_amount);
+
+    // Only the last page burns. Add any dust (rounding remainder) across all
+    // pages to the burn amount to ensure all tokens are accounted for. Dust is
+    // computed from the amounts actually transferred to each page.
+    if is_last_page {
+        let total_recipient_share_amount = if page == 0 {
+            total_transferred_share_amount
+        } else {
+            contributor_rewards
+                .take_partially_distributed_2z_amount()
+                .saturating_add(total_transferred_share_amount)
+        };
+
+        let rounding_dust_amount =
+            remaining_share_amount.saturating_sub(total_recipient_share_amount);
+        burn_share_amount += rounding_dust_amount;
+
+        distribution.burned_2z_amount += burn_share_amount;
+        distribution.burned_rounding_dust_2z_amount += rounding_dust_amount;
+        distribution.distributed_rewards_count += 1;
+
+        contributor_rewards.total_burned_2z_amount = contributor_rewards
+            .total_burned_2z_amount
+            .saturating_add(burn_share_amount);
+        contributor_rewards.distributed_dz_epochs_count += 1;
+        contributor_rewards.last_distributed_dz_epoch = distribution.dz_epoch;
+
+        let token_burn_ix = token_instruction::burn(
+            &spl_token_interface::ID,
+            distribution_2z_token_pda_info.key,
+            &DOUBLEZERO_MINT_KEY,
+            distribution.info.key,
+            &[],
+            burn_share_amount,
+        )
+        .unwrap();
 
-    let token_burn_ix = token_instruction::burn(
-        &spl_token_interface::ID,
-        distribution_2z_token_pda_info.key,
-        &DOUBLEZERO_MINT_KEY,
-        distribution.info.key,
-        &[],
-        burn_share_amount,
-    )
-    .unwrap();
+        invoke_signed_unchecked(&token_burn_ix, accounts, &[distribution_signer_seeds])?;
+        msg!("Burned {} 2Z tokens", burn_share_amount);
+    } else {
+        contributor_rewards
+            .set_partially_distributed(distribution.dz_epoch, total_transferred_share_amount);
+    }
 
-    invoke_signed_unchecked(&token_burn_ix, accounts, &[distribution_signer_seeds])?;
-    msg!("Burned {} 2Z tokens", burn_share_amount);
+    // Finally, pay the relayer for invoking this instruction. Relay lamports
+    // are split evenly across pages, where the last page also receives any
+    // remainder.
 
-    // Finally, pay the relayer for invoking this instruction.
+    let relay_lamports_per_page =
+        distribution.distribute_rewards_relay_lamports as u64 / recipient_page_count as u64;
+    let relay_lamports_remainder =
+        distribution.distribute_rewards_relay_lamports as u64 % recipient_page_count as u64;
 
-    let distribute_rewards_relay_lamports = distribution.distribute_rewards_relay_lamports as u64;
+    let relayer_lamports = if is_last_page {
+        relay_lamports_per_page + relay_lamports_remainder
+    } else {
+        relay_lamports_per_page
+    };
 
-    **relayer_info.lamports.borrow_mut() += distribute_rewards_relay_lamports;
-    **distribution.info.lamports.borrow_mut() -= distribute_rewards_relay_lamports;
+    **relayer_info.lamports.borrow_mut() += relayer_lamports;
+    **distribution.info.lamports.borrow_mut() -= relayer_lamports;
 
-    msg!(
-        "Moved {} lamports to relayer",
-        distribute_rewards_relay_lamports
-    );
+    msg!("Moved {} lamports to relayer", relayer_lamports);
 
     Ok(())
 }
@@ -1604,6 +2053,11 @@ fn try_configure_contributor_rewards(
     // - 0: Program config.
     // - 1: Contributor rewards.
     // - 2: Rewards manager.
+    //
+    // To resize the contributor rewards account for a second page of
+    // recipients, the following accounts are also expected:
+    // - 3: Payer (funder of realloc lamports).
+    // - 4: System program.
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
@@ -1636,16 +2090,58 @@ fn try_configure_contributor_rewards(
 
     match setting {
         ContributorRewardsConfiguration::Recipients(recipients) => {
-            let recipient_shares = RecipientShares::new(&recipients).ok_or_else(|| {
-                msg!("Invalid recipients");
-                ProgramError::InvalidAccountData
-            })?;
+            if contributor_rewards.is_recipient_shares_locked() {
+                msg!("Recipient shares are locked");
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            if let Some(partially_distributed_dz_epoch) =
+                contributor_rewards.checked_partially_distributed_dz_epoch()
+            {
+                msg!(
+                    "Cannot change recipients while rewards for DZ epoch {} are partially distributed",
+                    partially_distributed_dz_epoch
+                );
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            let [recipient_shares, extension_recipient_shares] =
+                RecipientShares::try_new_pages(&recipients).ok_or_else(|| {
+                    msg!("Invalid recipients");
+                    ProgramError::InvalidAccountData
+                })?;
 
             msg!("Recipients");
-            recipient_shares.active_iter().for_each(|recipient| {
-                msg!("{}: {}", recipient.recipient_key, recipient.share);
-            });
+            recipient_shares
+                .active_iter()
+                .chain(extension_recipient_shares.active_iter())
+                .for_each(|recipient| {
+                    msg!("{}: {}", recipient.recipient_key, recipient.share);
+                });
             contributor_rewards.recipient_shares = recipient_shares;
+
+            // The second page of recipients is stored in the remaining data.
+            // If this data has already been allocated, overwrite it (which
+            // clears the second page if there are no more recipients for it).
+            let extension_data_len = ContributorRewards::EXTENSION_RECIPIENT_SHARES_LEN;
+
+            if let Some(extension_data) = contributor_rewards
+                .remaining_data
+                .get_mut(..extension_data_len)
+            {
+                extension_data.copy_from_slice(bytemuck::bytes_of(&extension_recipient_shares));
+            } else if extension_recipient_shares.active_iter().next().is_some() {
+                // Avoid borrowing while in mutable borrow state.
+                let contributor_rewards_info = contributor_rewards.info;
+                drop(contributor_rewards);
+
+                try_resize_for_extension_recipient_shares(
+                    accounts,
+                    &mut accounts_iter,
+                    contributor_rewards_info,
+                    &extension_recipient_shares,
+                )?;
+            }
         }
         ContributorRewardsConfiguration::IsSetRewardsManagerBlocked(should_block) => {
             msg!("Set flag");
@@ -1657,6 +2153,53 @@ fn try_configure_contributor_rewards(
     Ok(())
 }
 
+fn try_resize_for_extension_recipient_shares(
+    accounts: &[AccountInfo],
+    accounts_iter: &mut EnumeratedAccountInfoIter,
+    contributor_rewards_info: &AccountInfo,
+    extension_recipient_shares: &RecipientShares,
+) -> ProgramResult {
+    // Account 3 must be the payer. In order to transfer lamports from the payer
+    // to the contributor rewards, this account must be writable.
+    //
+    // Account 4 must be the System program. We do not need to check this
+    // account because the transfer CPI call will fail if it is not.
+    let (_, payer_info) = try_next_enumerated_account(accounts_iter, Default::default())
+        .inspect_err(|_| {
+            msg!(
+                "Payer required to configure more than {} recipients",
+                MAX_RECIPIENTS_PER_PAGE
+            );
+        })?;
+
+    let data_end = zero_copy::data_end::<ContributorRewards>();
+    let new_data_len = data_end.saturating_add(ContributorRewards::EXTENSION_RECIPIENT_SHARES_LEN);
+    contributor_rewards_info.resize(new_data_len)?;
+
+    let additional_lamports_for_resize = Rent::get()
+        .unwrap()
+        .minimum_balance(new_data_len)
+        .saturating_sub(contributor_rewards_info.lamports());
+
+    let transfer_ix = system_instruction::transfer(
+        payer_info.key,
+        contributor_rewards_info.key,
+        additional_lamports_for_resize,
+    );
+
+    invoke_signed_unchecked(&transfer_ix, accounts, &[])?;
+
+    contributor_rewards_info.try_borrow_mut_data()?[data_end..new_data_len]
+        .copy_from_slice(bytemuck::bytes_of(extension_recipient_shares));
+
+    msg!(
+        "Increase contributor rewards account size by {} bytes",
+        ContributorRewards::EXTENSION_RECIPIENT_SHARES_LEN
+    );
+
+    Ok(())
+}
+
 fn try_verify_distribution_merkle_root(
     accounts: &[AccountInfo],
     kind: DistributionMerkleRootKind,
@@ -1771,7 +2314,8 @@ fn try_initialize_solana_validator_deposit(
             ],
         },
         new_solana_validator_deposit_info.lamports(),
-        zero_copy::data_end::<SolanaValidatorDeposit>(),
+        zero_copy::data_end::<SolanaValidatorDeposit>()
+            + SolanaValidatorDeposit::PAYMENT_HISTORY_LEN,
         &ID,
         accounts,
         CreateAccountOptions {
@@ -1780,11 +2324,23 @@ fn try_initialize_solana_validator_deposit(
         },
     )?;
 
-    // Finally, initialize the solana validator deposit with the node id.
+    // Finally, initialize the solana validator deposit with the node id. The
+    // payment history in its remaining data starts out empty.
     let (mut solana_validator_deposit, _) =
         zero_copy::try_initialize::<SolanaValidatorDeposit>(new_solana_validator_deposit_info)?;
     solana_validator_deposit.node_id = node_id;
 
+    // Lamports captured before the account's creation are treated as
+    // deposited, so only lamports transferred directly from now on are
+    // surplus.
+    solana_validator_deposit.recorded_sol_balance =
+        new_solana_validator_deposit_info.lamports().saturating_sub(
+            Rent::get()
+                .unwrap()
+                .minimum_balance(new_solana_validator_deposit_info.data_len()),
+        );
+    solana_validator_deposit.set_is_balance_tracked(true);
+
     Ok(())
 }
 
@@ -2024,6 +2580,10 @@ fn try_pay_solana_validator_debt(
     // - 1: Distribution.
     // - 2: Solana validator deposit.
     // - 3: Journal.
+    // - 4: Payer (optional, funder for new payment receipt and missing
+    //   payment history).
+    // - 5: New payment receipt (optional).
+    // - 6: System program (optional).
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
@@ -2036,7 +2596,8 @@ fn try_pay_solana_validator_debt(
     // Account 1 must be the distribution.
     let mut distribution =
         ZeroCopyMutAccount::<Distribution>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
-    msg!("DZ epoch: {}", distribution.dz_epoch);
+    let dz_epoch = distribution.dz_epoch;
+    msg!("DZ epoch: {}", dz_epoch);
 
     // We cannot pay Solana validator debt until the debt accountant has
     // finalized the debt calculation.
@@ -2048,10 +2609,11 @@ fn try_pay_solana_validator_debt(
     distribution.solana_validator_payments_count += 1;
 
     // Account 2 must be the Solana validator deposit.
-    let solana_validator_deposit = ZeroCopyMutAccount::<SolanaValidatorDeposit>::try_next_accounts(
-        &mut accounts_iter,
-        Some(&ID),
-    )?;
+    let mut solana_validator_deposit =
+        ZeroCopyMutAccount::<SolanaValidatorDeposit>::try_next_accounts(
+            &mut accounts_iter,
+            Some(&ID),
+        )?;
     msg!("Node ID: {}", solana_validator_deposit.node_id);
 
     // Bits indicating whether debt has been paid for specific leaf indices are
@@ -2088,10 +2650,12 @@ fn try_pay_solana_validator_debt(
     // swap program in exchange for 2Z tokens.
     let mut solana_validator_deposit_lamports = solana_validator_deposit.info.lamports.borrow_mut();
 
-    // We cannot remove more lamports than the rent exemption.
-    let rent_exemption_lamports = Rent::get()
-        .unwrap()
-        .minimum_balance(zero_copy::data_end::<SolanaValidatorDeposit>());
+    // We cannot remove more lamports than the rent exemption, which includes
+    // the payment history if there is one.
+    let rent_exemption_lamports = Rent::get().unwrap().minimum_balance(
+        zero_copy::data_end::<SolanaValidatorDeposit>()
+            + solana_validator_deposit.remaining_data.len(),
+    );
 
     if solana_validator_deposit_lamports.saturating_sub(rent_exemption_lamports) < amount {
         msg!("Insufficient funds in Solana validator deposit to pay debt");
@@ -2105,35 +2669,175 @@ fn try_pay_solana_validator_debt(
     **solana_validator_deposit_lamports -= amount;
     **journal.info.lamports.borrow_mut() += amount;
 
+    solana_validator_deposit.recorded_sol_balance = solana_validator_deposit
+        .recorded_sol_balance
+        .saturating_sub(amount);
+
     journal.total_sol_balance += amount;
     msg!(
         "Updated journal's SOL balance to {}",
         journal.total_sol_balance
     );
 
-    Ok(())
-}
+    let payment = SolanaValidatorPayment {
+        dz_epoch,
+        amount,
+        timestamp: Clock::get().unwrap().unix_timestamp,
+    };
 
-fn try_enable_solana_validator_debt_write_off(accounts: &[AccountInfo]) -> ProgramResult {
-    msg!("Enable Solana validator debt write off");
+    // Record this payment in the deposit's payment history. Deposits
+    // initialized before payment history was added do not have one until a
+    // payer funds it below.
+    let payment_history = SolanaValidatorDeposit::payment_history(
+        &solana_validator_deposit.remaining_data,
+    )
+    .map(|mut payment_history| {
+        payment_history.record(payment);
+        payment_history
+    });
+
+    if let Some(payment_history) = &payment_history {
+        solana_validator_deposit.remaining_data[..SolanaValidatorDeposit::PAYMENT_HISTORY_LEN]
+            .copy_from_slice(bytemuck::bytes_of(payment_history));
+    }
 
-    // We expect the following accounts for this instruction:
-    // - 0: Program config.
-    // - 1: Distribution.
-    // - 2: Payer.
-    // - 3: System program.
-    let mut accounts_iter = accounts.iter().enumerate();
+    // Account 4 may be the payer for a new payment receipt. If this account is
+    // not provided, no receipt is created.
+    let payer_info = match try_next_enumerated_account(&mut accounts_iter, Default::default()) {
+        Ok((_, payer_info)) => payer_info,
+        Err(_) => return Ok(()),
+    };
 
-    // Account 0 must be the program config.
-    let program_config =
-        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+    let node_id = debt.node_id;
 
-    // Make sure the program is not paused.
-    program_config.try_require_unpaused()?;
+    // Avoid borrowing lamports while creating the payment receipt.
+    drop(solana_validator_deposit_lamports);
 
-    // Cannot enable write-offs before the activation epoch.
-    if !program_config.is_debt_write_off_feature_activated() {
-        let activation_epoch = program_config.debt_write_off_feature_activation_epoch;
+    // The payer also funds the payment history for deposits that do not have
+    // one yet.
+    if payment_history.is_none() {
+        // Avoid borrowing while in mutable borrow state.
+        let solana_validator_deposit_info = solana_validator_deposit.info;
+        drop(solana_validator_deposit);
+
+        try_resize_for_payment_history(
+            accounts,
+            solana_validator_deposit_info,
+            payer_info,
+            payment,
+        )?;
+    }
+
+    // Account 5 must be the new payment receipt. The create-account workflow
+    // requires that this account does not exist yet and is writable.
+    let (account_index, new_payment_receipt_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    let (expected_payment_receipt_key, payment_receipt_bump) =
+        PaymentReceipt::find_address(dz_epoch, &node_id);
+
+    // Enforce this account location.
+    if new_payment_receipt_info.key != &expected_payment_receipt_key {
+        msg!(
+            "Invalid address for payment receipt (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    try_create_account(
+        Invoker::Signer(payer_info.key),
+        Invoker::Pda {
+            key: &expected_payment_receipt_key,
+            signer_seeds: &[
+                PaymentReceipt::SEED_PREFIX,
+                &dz_epoch.as_seed(),
+                node_id.as_ref(),
+                &[payment_receipt_bump],
+            ],
+        },
+        new_payment_receipt_info.lamports(),
+        zero_copy::data_end::<PaymentReceipt>(),
+        &ID,
+        accounts,
+        Default::default(),
+    )?;
+
+    let (mut payment_receipt, _) =
+        zero_copy::try_initialize::<PaymentReceipt>(new_payment_receipt_info)?;
+    payment_receipt.node_id = node_id;
+    payment_receipt.dz_epoch = dz_epoch;
+    payment_receipt.amount = amount;
+    payment_receipt.slot = Clock::get().unwrap().slot;
+    payment_receipt.bump_seed = payment_receipt_bump;
+
+    msg!(
+        "Initialized payment receipt at slot {}",
+        payment_receipt.slot
+    );
+
+    Ok(())
+}
+
+fn try_resize_for_payment_history(
+    accounts: &[AccountInfo],
+    solana_validator_deposit_info: &AccountInfo,
+    payer_info: &AccountInfo,
+    payment: SolanaValidatorPayment,
+) -> ProgramResult {
+    let data_end = zero_copy::data_end::<SolanaValidatorDeposit>();
+    let new_data_len = data_end.saturating_add(SolanaValidatorDeposit::PAYMENT_HISTORY_LEN);
+    solana_validator_deposit_info.resize(new_data_len)?;
+
+    // The deposit's lamports above its rent exemption belong to the Solana
+    // validator, so the payer covers the additional rent for the new data.
+    let rent = Rent::get().unwrap();
+    let additional_lamports_for_resize = rent
+        .minimum_balance(new_data_len)
+        .saturating_sub(rent.minimum_balance(data_end));
+
+    let transfer_ix = system_instruction::transfer(
+        payer_info.key,
+        solana_validator_deposit_info.key,
+        additional_lamports_for_resize,
+    );
+
+    invoke_signed_unchecked(&transfer_ix, accounts, &[])?;
+
+    let mut payment_history = SolanaValidatorPaymentHistory::default();
+    payment_history.record(payment);
+
+    solana_validator_deposit_info.try_borrow_mut_data()?[data_end..new_data_len]
+        .copy_from_slice(bytemuck::bytes_of(&payment_history));
+
+    msg!(
+        "Increase Solana validator deposit account size by {} bytes",
+        SolanaValidatorDeposit::PAYMENT_HISTORY_LEN
+    );
+
+    Ok(())
+}
+
+fn try_enable_solana_validator_debt_write_off(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Enable Solana validator debt write off");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Distribution.
+    // - 2: Payer.
+    // - 3: System program.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    let program_config =
+        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    // Make sure the program is not paused.
+    program_config.try_require_unpaused()?;
+
+    // Cannot enable write-offs before the activation epoch.
+    if !program_config.is_debt_write_off_feature_activated() {
+        let activation_epoch = program_config.debt_write_off_feature_activation_epoch;
 
         if activation_epoch == 0 {
             msg!("Debt write-off feature activation epoch not configured");
@@ -2266,7 +2970,20 @@ fn try_write_off_solana_validator_debt(
         rent_sysvar.minimum_balance(solana_validator_deposit_info.data_len());
     let excess_lamports = deposit_lamports.saturating_sub(rent_exemption_lamports);
 
-    if excess_lamports >= amount {
+    // Dust debt is written off regardless of the deposit balance so it does
+    // not have to be paid.
+    if authorized_use
+        .program_config
+        .is_dust_solana_validator_debt(amount)
+    {
+        msg!(
+            "Debt is below minimum of {}",
+            authorized_use
+                .program_config
+                .distribution_parameters
+                .minimum_solana_validator_debt
+        );
+    } else if excess_lamports >= amount {
         msg!("Lamports balance in deposit account is enough to cover debt amount");
         return Err(ProgramError::InvalidAccountData);
     }
@@ -2485,6 +3202,9 @@ fn try_sweep_distribution_tokens(accounts: &[AccountInfo]) -> ProgramResult {
     // - 7: Distribution 2Z token account.
     // - 8: Swap authority.
     // - 9: Swap 2Z destination account.
+    // - 10: SPL Token program.
+    // - 11: Reserve 2Z token account (only if the treasury 2Z share is
+    //   nonzero).
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
@@ -2529,25 +3249,27 @@ fn try_sweep_distribution_tokens(accounts: &[AccountInfo]) -> ProgramResult {
     // We will attempt to account for the total SOL debt and account for this
     // amount by reducing the SOL balance of the journal. The SOL that this
     // balance tracks will have already been swapped by the swap program.
-    let total_sol_debt = distribution.checked_total_sol_debt().unwrap();
+    let total_sol_debt = Lamports::new(distribution.checked_total_sol_debt().unwrap());
 
     // If there is no debt, we can return early.
-    if total_sol_debt == 0 {
+    if total_sol_debt.is_zero() {
         msg!("Zero SOL debt. Nothing to sweep");
 
         return Ok(());
     }
 
-    if journal.swapped_sol_amount < total_sol_debt {
-        msg!("Journal does not have enough swapped SOL to cover the SOL debt");
-        return Err(ProgramError::InvalidAccountData);
-    }
+    let swapped_sol_amount = Lamports::new(journal.swapped_sol_amount)
+        .checked_sub(total_sol_debt)
+        .ok_or_else(|| {
+            msg!("Journal does not have enough swapped SOL to cover the SOL debt");
+            ProgramError::InvalidAccountData
+        })?;
 
     msg!(
         "Journal's swapped SOL balance before: {}",
         journal.swapped_sol_amount
     );
-    journal.swapped_sol_amount -= total_sol_debt;
+    journal.swapped_sol_amount = swapped_sol_amount.get();
 
     ////////////////////////////////////////////////////////////////////////////
     //
@@ -2589,7 +3311,7 @@ fn try_sweep_distribution_tokens(accounts: &[AccountInfo]) -> ProgramResult {
 
     let mut dequeue_fills_ix_data = [0; 16];
     dequeue_fills_ix_data[..8].copy_from_slice(&DEQUEUE_FILLS_SELECTOR);
-    dequeue_fills_ix_data[8..16].copy_from_slice(&total_sol_debt.to_le_bytes());
+    dequeue_fills_ix_data[8..16].copy_from_slice(&total_sol_debt.get().to_le_bytes());
 
     let dequeue_fills_ix = try_build_instruction(
         &sol_2z_swap_program_id,
@@ -2621,11 +3343,15 @@ fn try_sweep_distribution_tokens(accounts: &[AccountInfo]) -> ProgramResult {
         return Err(ProgramError::InvalidAccountData);
     }
 
+    // The SOL/2Z Swap program returns the dequeued SOL amount in lamports and
+    // the corresponding 2Z amount in base units of the 2Z mint.
     let (return_sol_amount, token_2z_amount, _) =
-        <(u64, u64, u64) as BorshDeserialize>::try_from_slice(&return_data).map_err(|_| {
-            msg!("Failed to deserialize return data from SOL/2Z Swap program");
-            ProgramError::InvalidAccountData
-        })?;
+        <(Lamports, Token2z, u64) as BorshDeserialize>::try_from_slice(&return_data).map_err(
+            |_| {
+                msg!("Failed to deserialize return data from SOL/2Z Swap program");
+                ProgramError::InvalidAccountData
+            },
+        )?;
 
     if return_sol_amount != total_sol_debt {
         msg!("SOL amount in return data does not equal total SOL debt");
@@ -2638,9 +3364,18 @@ fn try_sweep_distribution_tokens(accounts: &[AccountInfo]) -> ProgramResult {
     //
     ////////////////////////////////////////////////////////////////////////////
 
-    // Record the swept amount to the distribution. This amount will also be
-    // used to token transfer the 2Z tokens to the distribution.
-    distribution.collected_2z_converted_from_sol = token_2z_amount;
+    // The protocol treasury takes its share of the swept 2Z tokens before the
+    // rest is recorded to the distribution. These amounts will also be used to
+    // token transfer the 2Z tokens.
+    let treasury_2z_amount = Token2z::new(
+        program_config
+            .treasury_2z_share
+            .mul_scalar(token_2z_amount.get()),
+    );
+    let distribution_2z_amount = token_2z_amount.saturating_sub(treasury_2z_amount);
+
+    distribution.collected_2z_converted_from_sol = distribution_2z_amount.get();
+    distribution.treasury_2z_amount = treasury_2z_amount.get();
 
     // Account 7 must be the distribution's 2Z token account.
     let (_, distribution_2z_token_pda_info, _) = try_next_2z_token_pda_info(
@@ -2681,7 +3416,7 @@ fn try_sweep_distribution_tokens(accounts: &[AccountInfo]) -> ProgramResult {
         distribution_2z_token_pda_info.key,
         swap_authority_info.key,
         &[], // signer_pubkeys
-        token_2z_amount,
+        distribution_2z_amount.get(),
     )
     .unwrap();
 
@@ -2694,14 +3429,69 @@ fn try_sweep_distribution_tokens(accounts: &[AccountInfo]) -> ProgramResult {
         ]],
     )?;
 
-    msg!("Total SOL debt accounted for: {}", total_sol_debt);
+    if !treasury_2z_amount.is_zero() {
+        // Account 10 must be the SPL Token program.
+        try_next_token_program_info(&mut accounts_iter)?;
+
+        // Account 11 must be the reserve 2Z token account, which holds the
+        // protocol treasury's 2Z tokens alongside the reserve's. The journal
+        // tracks the treasury's portion separately.
+        let (_, reserve_2z_info, _) = try_next_2z_token_pda_info(
+            &mut accounts_iter,
+            program_config.info.key,
+            "reserve",
+            Some(program_config.reserve_2z_bump_seed),
+        )?;
+
+        let token_transfer_ix = token_instruction::transfer(
+            &spl_token_interface::ID,
+            swap_destination_2z_info.key,
+            reserve_2z_info.key,
+            swap_authority_info.key,
+            &[], // signer_pubkeys
+            treasury_2z_amount.get(),
+        )
+        .unwrap();
+
+        invoke_signed_unchecked(
+            &token_transfer_ix,
+            accounts,
+            &[&[
+                state::SWAP_AUTHORITY_SEED_PREFIX,
+                &[program_config.swap_authority_bump_seed],
+            ]],
+        )?;
+
+        journal.total_treasury_2z_amount = journal
+            .total_treasury_2z_amount
+            .saturating_add(treasury_2z_amount.get());
+    }
+
+    msg!("Total SOL debt accounted for: {}", total_sol_debt.get());
     msg!(
         "Journal's swapped SOL balance after: {}",
         journal.swapped_sol_amount
     );
-    msg!("Transferred {} 2Z tokens to distribution", token_2z_amount);
+    msg!(
+        "Transferred {} 2Z tokens to distribution",
+        distribution_2z_amount.get()
+    );
+    msg!(
+        "Transferred {} 2Z tokens to treasury",
+        treasury_2z_amount.get()
+    );
+    msg!(
+        "Journal's total treasury 2Z amount: {}",
+        journal.total_treasury_2z_amount
+    );
 
-    journal.swap_2z_destination_balance -= token_2z_amount;
+    journal.swap_2z_destination_balance = Token2z::new(journal.swap_2z_destination_balance)
+        .checked_sub(token_2z_amount)
+        .ok_or_else(|| {
+            msg!("Journal's 2Z swap destination balance does not cover swept 2Z tokens");
+            ProgramError::InvalidAccountData
+        })?
+        .get();
     msg!(
         "2Z swap destination balance now {}",
         journal.swap_2z_destination_balance
@@ -2710,171 +3500,1111 @@ fn try_sweep_distribution_tokens(accounts: &[AccountInfo]) -> ProgramResult {
     Ok(())
 }
 
-fn try_withdraw_sol(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
-    const MINT_2Z_ACCOUNT_INDEX: usize = 1;
-    const DESTINATION_ACCOUNT_INDEX: usize = 2;
-
-    msg!("Withdraw SOL");
+fn try_skip_distribution_sweep(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Skip distribution sweep");
 
     // We expect the following accounts for this instruction:
     // - 0: Program config.
-    // - 1: Withdraw SOL authority.
-    // - 2: Journal.
-    // - 3: SOL destination.
+    // - 1: Admin.
+    // - 2: Distribution.
+    // - 3: Journal.
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
-    let program_config =
-        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+    // Account 1 must be the admin.
+    //
+    // This call ensures that the admin is a signer and is the same admin
+    // encoded in the program config.
+    let authorized_use =
+        VerifiedProgramAuthority::try_next_accounts(&mut accounts_iter, Authority::Admin)?;
 
     // Make sure the program is not paused.
-    program_config.try_require_unpaused()?;
+    authorized_use.program_config.try_require_unpaused()?;
 
-    // Make sure the SOL/2Z swap program ID is set by checking if the bump seed
-    // for the withdraw SOL authority is set.
-    if program_config.withdraw_sol_authority_bump_seed == 0 {
-        msg!("SOL/2Z swap program ID is not set");
-        return Err(ProgramError::InvalidAccountData);
-    }
+    // Account 2 must be the distribution.
+    let mut distribution =
+        ZeroCopyMutAccount::<Distribution>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+    msg!("DZ epoch: {}", distribution.dz_epoch);
 
-    // Account 1 must be the withdraw SOL authority.
-    let (account_index, withdraw_sol_authority_info) = try_next_enumerated_account(
-        &mut accounts_iter,
-        NextAccountOptions {
-            must_be_signer: true,
-            ..Default::default()
-        },
-    )?;
+    // Make sure the distribution has not already swept 2Z tokens.
+    distribution.try_require_has_not_swept_2z_tokens()?;
+    distribution.set_has_swept_2z_tokens(true);
 
-    let expected_withdraw_sol_authority_key = program_config
-        .checked_withdraw_sol_authority_address()
-        .unwrap();
+    // The debt calculation must be finalized so the SOL debt cannot change
+    // after the sweep is skipped.
+    distribution.try_require_finalized_debt_calculation()?;
 
-    // Enforce this account location.
-    if withdraw_sol_authority_info.key != &expected_withdraw_sol_authority_key {
-        msg!(
-            "Invalid address for withdraw SOL authority (account {})",
-            account_index
-        );
+    if distribution.checked_total_sol_debt().unwrap() != 0 {
+        msg!("Cannot skip sweep with SOL debt");
         return Err(ProgramError::InvalidAccountData);
     }
 
-    // Check for a sibling instruction immediately before the invocation of this
-    // instruction. This ensures that a token transfer happened right before
-    // this withdraw SOL instruction, implementing atomic swap semantics.
-    let sibling_ix = solana_instruction::syscalls::get_processed_sibling_instruction(0)
-        .ok_or_else(|| {
-            msg!("No processed sibling instruction found");
-            ProgramError::InvalidAccountData
-        })?;
-
-    // We are enforcing that the sibling instruction is an SPL Token transfer
-    // to the swap destination account. This creates an atomic swap where
-    // 2Z tokens must be transferred before SOL can be withdrawn.
-    //
-    // First, check that the program is the SPL Token program.
-    if sibling_ix.program_id != spl_token_interface::ID {
-        msg!("Sibling instruction's program ID is not SPL Token");
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
-    // Next, make sure that the instruction is a transfer checked call. Transfer
-    // checked requires the mint account, which we will verify is the 2Z mint.
-    // We will need the transfer amount to update the journal's balance of the
-    // swap destination account.
-    let transfer_amount = if let Ok(token_instruction::TokenInstruction::TransferChecked {
-        amount,
-        decimals: _,
-    }) = token_instruction::TokenInstruction::unpack(&sibling_ix.data)
-    {
-        amount
-    } else {
-        msg!("Sibling instruction is not a token transfer checked call");
-        return Err(ProgramError::InvalidInstructionData);
-    };
-
-    // Generate the swap destination key so we can validate the destination
-    // token account in the sibling instruction. Presumably, the swap
-    // destination account has already been created if the token transfer was
-    // successful.
-    let expected_swap_destination_2z_key = program_config
-        .checked_swap_destination_2z_address()
-        .unwrap();
-
-    // Make sure the mint of the transfer checked call is 2Z.
-    if sibling_ix.accounts[MINT_2Z_ACCOUNT_INDEX].pubkey != DOUBLEZERO_MINT_KEY {
-        msg!("Sibling transfer checked call is not for 2Z mint");
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
-    // Finally, make sure that the transfer is to the swap destination account.
-    if sibling_ix.accounts[DESTINATION_ACCOUNT_INDEX].pubkey != expected_swap_destination_2z_key {
-        msg!("Sibling transfer not for 2Z swap destination");
-        return Err(ProgramError::InvalidInstructionData);
+    if distribution.total_contributors != 0 || distribution.rewards_merkle_root != Hash::default() {
+        msg!("Cannot skip sweep with contributor rewards");
+        return Err(ProgramError::InvalidAccountData);
     }
 
-    // Account 2 must be the journal. We need to update the SOL balance and
-    // the 2Z swap destination balance.
+    // Account 3 must be the journal.
     let mut journal =
         ZeroCopyMutAccount::<Journal>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
 
-    // Make sure the journal has enough SOL to cover the amount.
-    if journal.total_sol_balance < amount {
-        msg!("Journal does not have enough SOL to cover the amount");
+    if journal.next_dz_epoch_to_sweep_tokens != distribution.dz_epoch {
+        msg!(
+            "Can only sweep tokens for DZ epoch {}",
+            journal.next_dz_epoch_to_sweep_tokens
+        );
         return Err(ProgramError::InvalidAccountData);
     }
 
-    // Update balances.
-
-    journal.total_sol_balance -= amount;
-    msg!("Journal's SOL balance now {}", journal.total_sol_balance);
-
-    journal.swapped_sol_amount += amount;
-    msg!("Swapped SOL balance now {}", journal.swapped_sol_amount);
-
-    journal.swap_2z_destination_balance += transfer_amount;
-    msg!(
-        "2Z swap destination balance now {} after transfer of {}",
-        journal.swap_2z_destination_balance,
-        transfer_amount
-    );
+    // Uptick the next DZ epoch for the next distribution to sweep tokens.
+    journal.next_dz_epoch_to_sweep_tokens = journal
+        .next_dz_epoch_to_sweep_tokens
+        .saturating_add_duration(1);
 
-    journal.lifetime_swapped_2z_amount += Uint::from(transfer_amount);
     msg!(
-        "Lifetime swapped 2Z amount now {}",
-        journal.lifetime_swapped_2z_amount
+        "Next DZ epoch to sweep tokens: {}",
+        journal.next_dz_epoch_to_sweep_tokens
     );
 
-    // Move lamports from the journal to the SOL destination.
-    let (_, sol_destination_info) = try_next_enumerated_account(
-        &mut accounts_iter,
-        NextAccountOptions {
-            must_be_writable: true,
-            ..Default::default()
-        },
-    )?;
-
-    **journal.info.lamports.borrow_mut() -= amount;
-    **sol_destination_info.lamports.borrow_mut() += amount;
-
     Ok(())
 }
 
-fn try_set_distribution_economic_burn_rate(
+fn try_emergency_withdraw(
     accounts: &[AccountInfo],
-    burn_rate_value: u32,
+    source: EmergencyWithdrawSource,
 ) -> ProgramResult {
-    msg!("Set distribution economic burn rate");
+    msg!("Emergency withdraw");
 
-    let burn_rate = BurnRate::new(burn_rate_value).ok_or_else(|| {
-        msg!("Invalid burn rate value");
+    // We expect the following accounts for this instruction:
+    // - 0: Program data.
+    // - 1: Upgrade authority.
+    // - 2: Program config.
+    // - 3: Admin.
+    // - 4: Journal or 2Z token owner.
+    //
+    // If the source is the journal's SOL:
+    // - 5: Emergency recovery account.
+    //
+    // Otherwise:
+    // - 5: Token owner's 2Z token PDA.
+    // - 6: Emergency recovery account's 2Z ATA.
+    // - 7: SPL Token program.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program data belonging to this program.
+    // Account 1 must be the upgrade authority.
+    //
+    // This call ensures that the upgrade authority is a signer and is the
+    // same authority encoded in the program data.
+    UpgradeAuthority::try_next_accounts(&mut accounts_iter, &ID)?;
+
+    // Account 2 must be the program config. Ensure it is writable so we can
+    // pause the program and record the emergency withdrawal.
+    let mut program_config =
+        ZeroCopyMutAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    // Account 3 must be the admin.
+    //
+    // Requiring the admin in addition to the upgrade authority means neither
+    // key alone can move funds out of the program.
+    Authority::Admin.try_next_as_authorized_account(&mut accounts_iter, &program_config.data)?;
+
+    // Funds can only be moved to the emergency recovery account configured
+    // ahead of time, so the signers cannot choose where funds go.
+    let emergency_recovery_key =
+        program_config
+            .checked_emergency_recovery_key()
+            .ok_or_else(|| {
+                msg!("Emergency recovery key is not configured");
+                ProgramError::InvalidAccountData
+            })?;
+
+    // The program's accounting no longer matches its balances after this
+    // withdrawal, so the program is paused for good. This instruction does
+    // not require the program to be unpaused, which allows withdrawing from
+    // each source in separate transactions.
+    program_config.set_is_paused(true);
+    program_config.set_has_emergency_withdrawn();
+    msg!("Program paused");
+
+    match source {
+        EmergencyWithdrawSource::JournalSol => {
+            // Account 4 must be the journal. Ensure it is writable so we can
+            // move its lamports.
+            let journal =
+                ZeroCopyMutAccount::<Journal>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+            // Account 5 must be the emergency recovery account.
+            let (account_index, recovery_info) = try_next_enumerated_account(
+                &mut accounts_iter,
+                NextAccountOptions {
+                    must_be_writable: true,
+                    ..Default::default()
+                },
+            )?;
+
+            // Enforce this account location.
+            if recovery_info.key != &emergency_recovery_key {
+                msg!(
+                    "Invalid address for emergency recovery account (account {})",
+                    account_index
+                );
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            // The journal must remain rent-exempt.
+            let amount = journal.info.lamports().saturating_sub(
+                Rent::get()
+                    .unwrap()
+                    .minimum_balance(journal.info.data_len()),
+            );
+
+            **journal.info.lamports.borrow_mut() -= amount;
+            **recovery_info.lamports.borrow_mut() += amount;
+
+            msg!("Withdrew {} lamports from journal", amount);
+        }
+        EmergencyWithdrawSource::Journal2z => {
+            let (journal_key, journal_bump) = Journal::find_address();
+
+            try_emergency_withdraw_2z(
+                accounts,
+                &mut accounts_iter,
+                &emergency_recovery_key,
+                &journal_key,
+                &[Journal::SEED_PREFIX, &[journal_bump]],
+            )?;
+        }
+        EmergencyWithdrawSource::Distribution2z(dz_epoch) => {
+            let (distribution_key, distribution_bump) = Distribution::find_address(dz_epoch);
+
+            try_emergency_withdraw_2z(
+                accounts,
+                &mut accounts_iter,
+                &emergency_recovery_key,
+                &distribution_key,
+                &[
+                    Distribution::SEED_PREFIX,
+                    &dz_epoch.as_seed(),
+                    &[distribution_bump],
+                ],
+            )?;
+        }
+        EmergencyWithdrawSource::SwapDestination2z => {
+            let swap_authority_key =
+                program_config
+                    .checked_swap_authority_address()
+                    .ok_or_else(|| {
+                        msg!("Swap destination is not initialized");
+                        ProgramError::InvalidAccountData
+                    })?;
+
+            try_emergency_withdraw_2z(
+                accounts,
+                &mut accounts_iter,
+                &emergency_recovery_key,
+                &swap_authority_key,
+                &[
+                    state::SWAP_AUTHORITY_SEED_PREFIX,
+                    &[program_config.swap_authority_bump_seed],
+                ],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn try_emergency_withdraw_2z(
+    accounts: &[AccountInfo],
+    accounts_iter: &mut EnumeratedAccountInfoIter,
+    emergency_recovery_key: &Pubkey,
+    token_owner_key: &Pubkey,
+    token_owner_signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    // Account 4 must be the token owner, which signs the token transfer.
+    let (account_index, token_owner_info) =
+        try_next_enumerated_account(accounts_iter, Default::default())?;
+
+    // Enforce this account location.
+    if token_owner_info.key != token_owner_key {
+        msg!(
+            "Invalid address for 2Z token owner (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Account 5 must be the token owner's 2Z token PDA.
+    let (_, token_2z_pda_info, _) =
+        try_next_2z_token_pda_info(accounts_iter, token_owner_key, "token owner's", None)?;
+
+    // Account 6 must be the emergency recovery account's 2Z ATA.
+    let (account_index, recovery_2z_info) =
+        try_next_enumerated_account(accounts_iter, Default::default())?;
+
+    // Enforce this account location.
+    if recovery_2z_info.key
+        != &get_associated_token_address(emergency_recovery_key, &DOUBLEZERO_MINT_KEY)
+    {
+        msg!(
+            "Invalid address for emergency recovery 2Z token account (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Account 7 must be the SPL Token program.
+    try_next_token_program_info(accounts_iter)?;
+
+    let amount = try_token_account_amount(token_2z_pda_info)?;
+
+    let token_transfer_ix = token_instruction::transfer(
+        &spl_token_interface::ID,
+        token_2z_pda_info.key,
+        recovery_2z_info.key,
+        token_owner_key,
+        &[], // signer_pubkeys
+        amount,
+    )
+    .unwrap();
+
+    invoke_signed_unchecked(&token_transfer_ix, accounts, &[token_owner_signer_seeds])?;
+
+    msg!("Withdrew {} 2Z tokens from {}", amount, token_owner_key);
+
+    Ok(())
+}
+
+fn try_withdraw_sol(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    const MINT_2Z_ACCOUNT_INDEX: usize = 1;
+    const DESTINATION_ACCOUNT_INDEX: usize = 2;
+
+    msg!("Withdraw SOL");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Withdraw SOL authority.
+    // - 2: Journal.
+    // - 3: SOL destination.
+    // - 4: Swap destination 2Z token account.
+    // - 5: Swap session.
+    // - 6: Swap session rent beneficiary.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    let program_config =
+        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    // Make sure the program is not paused.
+    program_config.try_require_unpaused()?;
+
+    // Account 1 must be the withdraw SOL authority.
+    try_next_withdraw_sol_authority_info(&mut accounts_iter, &program_config)?;
+
+    // Check for a sibling instruction immediately before the invocation of this
+    // instruction. This ensures that a token transfer happened right before
+    // this withdraw SOL instruction, implementing atomic swap semantics.
+    let sibling_ix = solana_instruction::syscalls::get_processed_sibling_instruction(0)
+        .ok_or_else(|| {
+            msg!("No processed sibling instruction found");
+            ProgramError::InvalidAccountData
+        })?;
+
+    // We are enforcing that the sibling instruction is an SPL Token transfer
+    // to the swap destination account. This creates an atomic swap where
+    // 2Z tokens must be transferred before SOL can be withdrawn.
+    //
+    // First, check that the program is the SPL Token program.
+    if sibling_ix.program_id != spl_token_interface::ID {
+        msg!("Sibling instruction's program ID is not SPL Token");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Next, make sure that the instruction is a transfer checked call. Transfer
+    // checked requires the mint account, which we will verify is the 2Z mint.
+    // We will need the transfer amount to update the journal's balance of the
+    // swap destination account.
+    let transfer_amount = if let Ok(token_instruction::TokenInstruction::TransferChecked {
+        amount,
+        decimals: _,
+    }) = token_instruction::TokenInstruction::unpack(&sibling_ix.data)
+    {
+        Token2z::new(amount)
+    } else {
+        msg!("Sibling instruction is not a token transfer checked call");
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    // Generate the swap destination key so we can validate the destination
+    // token account in the sibling instruction. Presumably, the swap
+    // destination account has already been created if the token transfer was
+    // successful.
+    let expected_swap_destination_2z_key = program_config
+        .checked_swap_destination_2z_address()
+        .unwrap();
+
+    // Make sure the mint of the transfer checked call is 2Z.
+    if sibling_ix.accounts[MINT_2Z_ACCOUNT_INDEX].pubkey != DOUBLEZERO_MINT_KEY {
+        msg!("Sibling transfer checked call is not for 2Z mint");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Finally, make sure that the transfer is to the swap destination account.
+    if sibling_ix.accounts[DESTINATION_ACCOUNT_INDEX].pubkey != expected_swap_destination_2z_key {
+        msg!("Sibling transfer not for 2Z swap destination");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Account 2 must be the journal. We need to update the SOL balance and
+    // the 2Z swap destination balance.
+    let mut journal =
+        ZeroCopyMutAccount::<Journal>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    let amount = Lamports::new(amount);
+
+    // Make sure the journal has enough SOL to cover the amount.
+    let total_sol_balance = Lamports::new(journal.total_sol_balance)
+        .checked_sub(amount)
+        .ok_or_else(|| {
+            msg!("Journal does not have enough SOL to cover the amount");
+            ProgramError::InvalidAccountData
+        })?;
+
+    // Update balances.
+
+    journal.total_sol_balance = total_sol_balance.get();
+    msg!("Journal's SOL balance now {}", journal.total_sol_balance);
+
+    journal.swapped_sol_amount = Lamports::new(journal.swapped_sol_amount)
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .get();
+    msg!("Swapped SOL balance now {}", journal.swapped_sol_amount);
+
+    journal.swap_2z_destination_balance = Token2z::new(journal.swap_2z_destination_balance)
+        .checked_add(transfer_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .get();
+    msg!(
+        "2Z swap destination balance now {} after transfer of {}",
+        journal.swap_2z_destination_balance,
+        transfer_amount.get()
+    );
+
+    journal.lifetime_swapped_2z_amount += Uint::from(transfer_amount.get());
+    msg!(
+        "Lifetime swapped 2Z amount now {}",
+        journal.lifetime_swapped_2z_amount
+    );
+
+    // Move lamports from the journal to the SOL destination.
+    let (_, sol_destination_info) = try_next_enumerated_account(
+        &mut accounts_iter,
+        NextAccountOptions {
+            must_be_writable: true,
+            ..Default::default()
+        },
+    )?;
+
+    // Account 4 must be the swap destination 2Z token account.
+    let (account_index, swap_destination_2z_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Enforce this account location.
+    if swap_destination_2z_info.key != &expected_swap_destination_2z_key {
+        msg!(
+            "Invalid address for swap destination 2Z token account (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // The sibling instruction check alone does not prove that the transfer
+    // moved 2Z into the swap destination for this withdrawal. The journal's
+    // swap destination balance acts as the swap's session state: every
+    // transfer that has been credited must be backed by the token account's
+    // balance, so a transfer cannot be credited twice and a transfer that did
+    // not move any tokens cannot be credited at all.
+    let swap_destination_2z_amount = try_token_account_amount(swap_destination_2z_info)?;
+
+    if swap_destination_2z_amount < journal.swap_2z_destination_balance {
+        msg!(
+            "Swap destination 2Z balance {} does not cover journal balance {}",
+            swap_destination_2z_amount,
+            journal.swap_2z_destination_balance
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Account 5 must be the swap session opened by the swap program in this
+    // transaction. Only 2Z that arrived in the swap destination while this
+    // session was open can back the sibling transfer, so an unrelated transfer
+    // cannot be used to withdraw SOL.
+    let swap_session =
+        ZeroCopyAccount::<SwapSession>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    if swap_session.slot != Clock::get().unwrap().slot {
+        msg!("Swap session opened at slot {} expired", swap_session.slot);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let session_2z_amount =
+        swap_destination_2z_amount.saturating_sub(swap_session.swap_destination_2z_amount);
+
+    if session_2z_amount < transfer_amount.get() {
+        msg!(
+            "Swap destination received {} 2Z during swap session, less than transfer of {}",
+            session_2z_amount,
+            transfer_amount.get()
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Account 6 must be the swap session's rent beneficiary.
+    let (account_index, rent_beneficiary_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Enforce this account location.
+    if rent_beneficiary_info.key != &swap_session.rent_beneficiary_key {
+        msg!(
+            "Invalid address for swap session rent beneficiary (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Consume the swap session so it cannot back another withdrawal.
+    let swap_session_info = swap_session.info;
+    drop(swap_session);
+
+    try_close_account(swap_session_info, rent_beneficiary_info)?;
+    msg!("Consumed swap session");
+
+    **journal.info.lamports.borrow_mut() -= amount.get();
+    **sol_destination_info.lamports.borrow_mut() += amount.get();
+
+    Ok(())
+}
+
+fn try_open_swap_session(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Open swap session");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Withdraw SOL authority.
+    // - 2: Swap destination 2Z token account.
+    // - 3: Payer (rent beneficiary).
+    // - 4: Swap session.
+    // - 5: System program.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    let program_config =
+        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    // Make sure the program is not paused.
+    program_config.try_require_unpaused()?;
+
+    // Account 1 must be the withdraw SOL authority. Only the SOL/2Z swap
+    // program can sign for this account.
+    try_next_withdraw_sol_authority_info(&mut accounts_iter, &program_config)?;
+
+    // Account 2 must be the swap destination 2Z token account.
+    let (account_index, swap_destination_2z_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Enforce this account location.
+    if swap_destination_2z_info.key
+        != &program_config
+            .checked_swap_destination_2z_address()
+            .unwrap()
+    {
+        msg!(
+            "Invalid address for swap destination 2Z token account (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let swap_destination_2z_amount = try_token_account_amount(swap_destination_2z_info)?;
+
+    // Account 3 must be the payer. The system program will automatically
+    // ensure this account is a signer and writable in order to transfer the
+    // lamports to create the new account.
+    let (_, payer_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Account 4 must be the swap session.
+    let (account_index, swap_session_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    let (expected_swap_session_key, swap_session_bump) = SwapSession::find_address();
+
+    // Enforce this account location.
+    if swap_session_info.key != &expected_swap_session_key {
+        msg!(
+            "Invalid address for swap session (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let current_slot = Clock::get().unwrap().slot;
+
+    // A session is only consumed by a withdraw SOL instruction in the same
+    // transaction, so a session opened in an earlier slot can never be
+    // consumed. Reopen it in place so it cannot block later swaps. Its rent
+    // beneficiary is kept because it funded the account.
+    if swap_session_info.owner == &ID {
+        let mut swap_session = ZeroCopyMutAccount::<SwapSession>::try_from_account_info(
+            account_index,
+            swap_session_info,
+            Some(&ID),
+        )?;
+
+        if swap_session.slot >= current_slot {
+            msg!("Swap session already opened at slot {}", swap_session.slot);
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        msg!(
+            "Reopen stale swap session opened at slot {}",
+            swap_session.slot
+        );
+        swap_session.swap_destination_2z_amount = swap_destination_2z_amount;
+        swap_session.slot = current_slot;
+    } else {
+        // Account 5 must be the System program, which is checked when creating
+        // the swap session.
+        try_create_account(
+            Invoker::Signer(payer_info.key),
+            Invoker::Pda {
+                key: &expected_swap_session_key,
+                signer_seeds: &[SwapSession::SEED_PREFIX, &[swap_session_bump]],
+            },
+            swap_session_info.lamports(),
+            zero_copy::data_end::<SwapSession>(),
+            &ID,
+            accounts,
+            Default::default(),
+        )?;
+
+        let (mut swap_session, _) = zero_copy::try_initialize::<SwapSession>(swap_session_info)?;
+        swap_session.rent_beneficiary_key = *payer_info.key;
+        swap_session.swap_destination_2z_amount = swap_destination_2z_amount;
+        swap_session.slot = current_slot;
+        swap_session.bump_seed = swap_session_bump;
+    }
+
+    msg!(
+        "Opened swap session with 2Z swap destination balance {}",
+        swap_destination_2z_amount
+    );
+
+    Ok(())
+}
+
+fn try_set_distribution_economic_burn_rate(
+    accounts: &[AccountInfo],
+    burn_rate_value: u32,
+) -> ProgramResult {
+    msg!("Set distribution economic burn rate");
+
+    let burn_rate = BurnRate::new(burn_rate_value).ok_or_else(|| {
+        msg!("Invalid burn rate value");
         ProgramError::InvalidInstructionData
     })?;
 
     // We expect the following accounts for this instruction:
     // - 0: Program config.
     // - 1: Rewards accountant.
-    // - 2: Distribution.
+    // - 2: Distribution.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    // Account 1 must be the rewards accountant.
+    //
+    // This call ensures that the rewards accountant is a signer and is the same
+    // rewards accountant encoded in the program config.
+    let authorized_use = VerifiedProgramAuthority::try_next_accounts(
+        &mut accounts_iter,
+        Authority::RewardsAccountant,
+    )?;
+
+    // Make sure the program is not paused.
+    authorized_use.program_config.try_require_unpaused()?;
+
+    // Account 2 must be the distribution.
+    let mut distribution =
+        ZeroCopyMutAccount::<Distribution>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+    msg!("DZ epoch: {}", distribution.dz_epoch);
+
+    // Cannot set an economic burn rate if the rewards calculation has already
+    // been finalized.
+    distribution.try_require_unfinalized_rewards_calculation()?;
+
+    distribution.economic_burn_rate = burn_rate;
+
+    msg!("Economic burn rate is now {}", burn_rate);
+
+    Ok(())
+}
+
+fn try_withdraw_solana_validator_deposit(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Withdraw Solana validator deposit");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Solana validator deposit.
+    // - 2: Validator node.
+    // - 3: Beneficiary (optional).
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    let program_config =
+        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    // Make sure the program is not paused.
+    program_config.try_require_unpaused()?;
+
+    // Account 1 must be the Solana validator deposit.
+    let mut solana_validator_deposit =
+        ZeroCopyMutAccount::<SolanaValidatorDeposit>::try_next_accounts(
+            &mut accounts_iter,
+            Some(&ID),
+        )?;
+    let node_id = solana_validator_deposit.node_id;
+    msg!("Node ID: {}", node_id);
+
+    // Account 2 must be the validator node. This account must match the
+    // node ID encoded in the Solana validator deposit.
+    let (account_index, validator_node_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    if validator_node_info.key != &node_id {
+        msg!(
+            "Invalid address for validator node (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Account 3 may be the beneficiary of the excess lamports. If provided, the
+    // validator node must be a signer to act as an authority for the
+    // beneficiary. Otherwise, the validator node is the destination.
+    let beneficiary_info = match try_next_enumerated_account(&mut accounts_iter, Default::default())
+    {
+        Ok((_, specified_beneficiary_info)) => {
+            if !validator_node_info.is_signer {
+                msg!("Validator node must be a signer when a beneficiary is provided");
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+
+            specified_beneficiary_info
+        }
+        Err(_) => validator_node_info,
+    };
+
+    let written_off_sol_debt = solana_validator_deposit.written_off_sol_debt;
+    let solana_validator_deposit_info = solana_validator_deposit.info;
+
+    let solana_validator_deposit_lamports = solana_validator_deposit_info.lamports();
+
+    // Withdraw the excess lamports beyond rent exemption and written-off debt.
+    let rent_exemption_lamports = Rent::get()
+        .unwrap()
+        .minimum_balance(solana_validator_deposit_info.data_len());
+
+    let withdrawn_lamports = solana_validator_deposit_lamports
+        .saturating_sub(rent_exemption_lamports)
+        .saturating_sub(written_off_sol_debt);
+
+    if withdrawn_lamports == 0 {
+        msg!(
+            "No excess lamports to withdraw. Delinquent debt: {}",
+            written_off_sol_debt
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    solana_validator_deposit.recorded_sol_balance = solana_validator_deposit
+        .recorded_sol_balance
+        .saturating_sub(withdrawn_lamports);
+    drop(solana_validator_deposit);
+
+    **solana_validator_deposit_info.lamports.borrow_mut() -= withdrawn_lamports;
+    **beneficiary_info.lamports.borrow_mut() += withdrawn_lamports;
+
+    Ok(())
+}
+
+fn try_reconcile_deposit_surplus(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Reconcile deposit surplus");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Debt accountant.
+    // - 2: Solana validator deposit.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    // Account 1 must be the debt accountant.
+    //
+    // This call ensures that the debt accountant is a signer and is the same
+    // debt accountant encoded in the program config.
+    let authorized_use =
+        VerifiedProgramAuthority::try_next_accounts(&mut accounts_iter, Authority::DebtAccountant)?;
+
+    // Make sure the program is not paused.
+    authorized_use.program_config.try_require_unpaused()?;
+
+    // Account 2 must be the Solana validator deposit.
+    let mut solana_validator_deposit =
+        ZeroCopyMutAccount::<SolanaValidatorDeposit>::try_next_accounts(
+            &mut accounts_iter,
+            Some(&ID),
+        )?;
+    msg!("Node ID: {}", solana_validator_deposit.node_id);
+
+    let excess_lamports = solana_validator_deposit.info.lamports().saturating_sub(
+        Rent::get()
+            .unwrap()
+            .minimum_balance(solana_validator_deposit.info.data_len()),
+    );
+
+    // Deposits initialized before the recorded SOL balance was added cannot
+    // distinguish deposited lamports from surplus, so their current balance
+    // is taken as deposited.
+    if !solana_validator_deposit.is_balance_tracked() {
+        solana_validator_deposit.recorded_sol_balance = excess_lamports;
+        solana_validator_deposit.set_is_balance_tracked(true);
+        msg!("Started tracking recorded SOL balance: {}", excess_lamports);
+
+        return Ok(());
+    }
+
+    let surplus_lamports =
+        excess_lamports.saturating_sub(solana_validator_deposit.recorded_sol_balance);
+
+    if surplus_lamports == 0 {
+        msg!("No surplus lamports in Solana validator deposit");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Lamports transferred directly to the deposit are how validators are
+    // expected to fund it, so the surplus belongs to the validator. These
+    // lamports stay in the deposit and are counted as deposited.
+    solana_validator_deposit.deposited_sol_amount = solana_validator_deposit
+        .deposited_sol_amount
+        .saturating_add(surplus_lamports);
+    solana_validator_deposit.recorded_sol_balance = solana_validator_deposit
+        .recorded_sol_balance
+        .saturating_add(surplus_lamports);
+
+    msg!("Attributed {} surplus lamports", surplus_lamports);
+    msg!(
+        "Updated recorded SOL balance to {}",
+        solana_validator_deposit.recorded_sol_balance
+    );
+
+    Ok(())
+}
+
+fn try_deposit_sol_for_validator(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    msg!("Deposit SOL for validator");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Solana validator deposit.
+    // - 2: Depositor.
+    // - 3: System program.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    let program_config =
+        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    // Make sure the program is not paused.
+    program_config.try_require_unpaused()?;
+
+    if amount == 0 {
+        msg!("Deposit amount must be greater than zero");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Account 1 must be the Solana validator deposit.
+    let mut solana_validator_deposit =
+        ZeroCopyMutAccount::<SolanaValidatorDeposit>::try_next_accounts(
+            &mut accounts_iter,
+            Some(&ID),
+        )?;
+    msg!("Node ID: {}", solana_validator_deposit.node_id);
+
+    solana_validator_deposit.deposited_sol_amount = solana_validator_deposit
+        .deposited_sol_amount
+        .saturating_add(amount);
+    solana_validator_deposit.recorded_sol_balance = solana_validator_deposit
+        .recorded_sol_balance
+        .saturating_add(amount);
+    msg!(
+        "Updated deposited SOL amount to {}",
+        solana_validator_deposit.deposited_sol_amount
+    );
+
+    // Avoid borrowing while the transfer CPI updates lamports.
+    let solana_validator_deposit_info = solana_validator_deposit.info;
+    drop(solana_validator_deposit);
+
+    // Account 2 must be the depositor. In order to transfer lamports from the
+    // depositor to the Solana validator deposit, this account must be a signer
+    // and writable. We do not need to check these fields because the transfer
+    // CPI call will fail if they are not.
+    //
+    // Account 3 must be the System program. We do not need to check this
+    // account because the transfer CPI call will fail if it is not.
+    let (_, depositor_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    let transfer_ix = system_instruction::transfer(
+        depositor_info.key,
+        solana_validator_deposit_info.key,
+        amount,
+    );
+
+    invoke_signed_unchecked(&transfer_ix, accounts, &[])?;
+
+    Ok(())
+}
+
+fn try_initialize_fee_parameter_history(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Initialize fee parameter history");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Payer.
+    // - 1: New fee parameter history.
+    // - 2: System program.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be a signer and writable because it will send lamports to
+    // the new fee parameter history account. We do not check these fields
+    // because the create-account workflow requires that this account is
+    // writable and a signer.
+    let (_, payer_info) = try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Account 1 must be the new fee parameter history account. The
+    // create-account workflow requires that this account does not exist yet
+    // and is writable.
+    let (account_index, new_fee_parameter_history_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    let (expected_fee_parameter_history_key, fee_parameter_history_bump) =
+        FeeParameterHistory::find_address();
+
+    // Enforce this account location and seed validity.
+    if new_fee_parameter_history_info.key != &expected_fee_parameter_history_key {
+        msg!(
+            "Invalid seeds for fee parameter history (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    try_create_account(
+        Invoker::Signer(payer_info.key),
+        Invoker::Pda {
+            key: &expected_fee_parameter_history_key,
+            signer_seeds: &[
+                FeeParameterHistory::SEED_PREFIX,
+                &[fee_parameter_history_bump],
+            ],
+        },
+        new_fee_parameter_history_info.lamports(),
+        zero_copy::data_end::<FeeParameterHistory>(),
+        &ID,
+        accounts,
+        Default::default(),
+    )?;
+
+    let (mut fee_parameter_history, _) =
+        zero_copy::try_initialize::<FeeParameterHistory>(new_fee_parameter_history_info)?;
+    fee_parameter_history.bump_seed = fee_parameter_history_bump;
+
+    Ok(())
+}
+
+fn try_reconcile_distribution(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Reconcile distribution");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Rewards accountant.
+    // - 2: Distribution.
+    // - 3: Distribution 2Z token account.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    // Account 1 must be the rewards accountant.
+    //
+    // The residual 2Z amount is read from the token account, which anyone can
+    // transfer 2Z tokens to. Only the rewards accountant can record the outcome
+    // so it is not recorded before the accountant expects tokens to stop
+    // moving.
+    let authorized_use = VerifiedProgramAuthority::try_next_accounts(
+        &mut accounts_iter,
+        Authority::RewardsAccountant,
+    )?;
+
+    // Make sure the program is not paused.
+    authorized_use.program_config.try_require_unpaused()?;
+
+    // Account 2 must be the distribution.
+    let mut distribution =
+        ZeroCopyMutAccount::<Distribution>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+    msg!("DZ epoch: {}", distribution.dz_epoch);
+
+    // Reconciling again overwrites the previous outcome, so a mismatch can be
+    // cleared once it has been investigated and resolved.
+    if distribution.is_reconciled() {
+        msg!(
+            "Overwriting previous reconciliation (residual {} 2Z tokens)",
+            distribution.reconciled_residual_2z_amount
+        );
+    }
+
+    // 2Z tokens can only be reconciled once they are no longer expected to
+    // move, which is after every contributor has had rewards distributed.
+    if !distribution.has_swept_2z_tokens() {
+        msg!("Distribution has not swept 2Z tokens");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if !distribution.are_all_rewards_distributed() {
+        msg!("Not all rewards have been distributed");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Account 3 must be the distribution 2Z token account.
+    let (_, distribution_2z_token_pda_info, _) = try_next_2z_token_pda_info(
+        &mut accounts_iter,
+        distribution.info.key,
+        "distribution's",
+        Some(distribution.token_2z_pda_bump_seed),
+    )?;
+
+    let residual_2z_amount = try_token_account_amount(distribution_2z_token_pda_info)?;
+
+    msg!(
+        "Collected {} 2Z tokens",
+        distribution.total_collected_2z_tokens()
+    );
+    msg!(
+        "Distributed {} 2Z tokens",
+        distribution.distributed_2z_amount
+    );
+    msg!(
+        "Burned {} 2Z tokens ({} rounding dust)",
+        distribution.burned_2z_amount,
+        distribution.burned_rounding_dust_2z_amount
+    );
+    msg!("Residual {} 2Z tokens", residual_2z_amount);
+
+    // A mismatch is recorded instead of reverting so off-chain processes can
+    // find the distributions that need to be investigated.
+    let is_conserved = distribution.is_2z_amount_conserved(residual_2z_amount);
+
+    if !is_conserved {
+        msg!("Collected 2Z tokens do not equal distributed, burned and residual 2Z tokens");
+    }
+
+    distribution.reconciled_residual_2z_amount = residual_2z_amount;
+    distribution.set_has_reconciliation_mismatch(!is_conserved);
+    distribution.set_is_reconciled(true);
+
+    Ok(())
+}
+
+fn try_replace_distribution_rewards_root(
+    accounts: &[AccountInfo],
+    total_contributors: u32,
+    merkle_root: Hash,
+    correction_memo: Hash,
+) -> ProgramResult {
+    msg!("Replace distribution rewards root");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Rewards accountant.
+    // - 2: Distribution.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    // Account 1 must be the rewards accountant.
+    //
+    // This call ensures that the rewards accountant is a signer and is the same
+    // rewards accountant encoded in the program config.
+    let authorized_use = VerifiedProgramAuthority::try_next_accounts(
+        &mut accounts_iter,
+        Authority::RewardsAccountant,
+    )?;
+
+    // Make sure the program is not paused.
+    authorized_use.program_config.try_require_unpaused()?;
+
+    // Account 2 must be the distribution.
+    let mut distribution =
+        ZeroCopyMutAccount::<Distribution>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+    msg!("DZ epoch: {}", distribution.dz_epoch);
+
+    distribution.try_require_unfinalized_rewards_calculation()?;
+
+    if distribution.rewards_merkle_root == Hash::default() {
+        msg!("Rewards merkle root not configured");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if correction_memo == Hash::default() {
+        msg!("Correction memo cannot be empty");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if merkle_root == distribution.rewards_merkle_root {
+        msg!("Rewards merkle root is unchanged");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let rewards_root_revision = distribution
+        .rewards_root_revision
+        .checked_add(1)
+        .ok_or_else(|| {
+            msg!("Rewards root revision overflow");
+            ProgramError::ArithmeticOverflow
+        })?;
+
+    msg!(
+        "Replaced rewards_merkle_root: {}",
+        distribution.rewards_merkle_root
+    );
+    msg!("Correction memo: {}", correction_memo);
+
+    msg!("Set total_contributors: {}", total_contributors);
+    distribution.total_contributors = total_contributors;
+
+    msg!("Set rewards_merkle_root: {}", merkle_root);
+    distribution.rewards_merkle_root = merkle_root;
+
+    msg!("Set rewards_root_revision: {}", rewards_root_revision);
+    distribution.rewards_root_revision = rewards_root_revision;
+    distribution.rewards_root_correction_memo = correction_memo;
+
+    Ok(())
+}
+
+fn try_set_recipient_shares_locked(accounts: &[AccountInfo], should_lock: bool) -> ProgramResult {
+    msg!("Set recipient shares locked");
+
+    // We expect the following accounts for this instruction:
+    // - 0: Program config.
+    // - 1: Rewards accountant.
+    // - 2..: Contributor rewards.
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
@@ -2890,101 +4620,187 @@ fn try_set_distribution_economic_burn_rate(
     // Make sure the program is not paused.
     authorized_use.program_config.try_require_unpaused()?;
 
-    // Account 2 must be the distribution.
-    let mut distribution =
-        ZeroCopyMutAccount::<Distribution>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
-    msg!("DZ epoch: {}", distribution.dz_epoch);
+    msg!("is_recipient_shares_locked: {}", should_lock);
 
-    // Cannot set an economic burn rate if the rewards calculation has already
-    // been finalized.
-    distribution.try_require_unfinalized_rewards_calculation()?;
+    // Any remaining accounts must be contributor rewards.
+    let mut contributor_rewards_count = 0;
 
-    distribution.economic_burn_rate = burn_rate;
+    for (account_index, account_info) in accounts_iter {
+        let mut contributor_rewards =
+            ZeroCopyMutAccount::<ContributorRewards>::try_from_account_info(
+                account_index,
+                account_info,
+                Some(&ID),
+            )?;
+        msg!("Service key: {}", contributor_rewards.service_key);
 
-    msg!("Economic burn rate is now {}", burn_rate);
+        contributor_rewards.set_is_recipient_shares_locked(should_lock);
+        contributor_rewards_count += 1;
+    }
+
+    if contributor_rewards_count == 0 {
+        msg!("At least one contributor rewards account is required");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
 
     Ok(())
 }
 
-fn try_withdraw_solana_validator_deposit(accounts: &[AccountInfo]) -> ProgramResult {
-    msg!("Withdraw Solana validator deposit");
+fn try_assert_journal_invariants(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Assert journal invariants");
 
     // We expect the following accounts for this instruction:
     // - 0: Program config.
-    // - 1: Solana validator deposit.
-    // - 2: Validator node.
-    // - 3: Beneficiary (optional).
+    // - 1: Journal.
+    // - 2: Journal 2Z token account.
+    // - 3: Swap destination 2Z token account.
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program config.
     let program_config =
         ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
 
-    // Make sure the program is not paused.
-    program_config.try_require_unpaused()?;
+    // Account 1 must be the journal.
+    let journal = ZeroCopyAccount::<Journal>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
 
-    // Account 1 must be the Solana validator deposit.
-    let solana_validator_deposit = ZeroCopyAccount::<SolanaValidatorDeposit>::try_next_accounts(
+    // Account 2 must be the journal's 2Z token account.
+    let (_, journal_2z_token_pda_info, _) = try_next_2z_token_pda_info(
         &mut accounts_iter,
-        Some(&ID),
+        journal.info.key,
+        "journal's",
+        Some(journal.token_2z_pda_bump_seed),
     )?;
-    let node_id = solana_validator_deposit.node_id;
-    msg!("Node ID: {}", node_id);
 
-    // Account 2 must be the validator node. This account must match the
-    // node ID encoded in the Solana validator deposit.
-    let (account_index, validator_node_info) =
-        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+    // Account 3 must be the swap destination 2Z token account.
+    let swap_authority_key = program_config
+        .checked_swap_authority_address()
+        .ok_or_else(|| {
+            msg!("Swap destination is not initialized");
+            ProgramError::InvalidAccountData
+        })?;
 
-    if validator_node_info.key != &node_id {
-        msg!(
-            "Invalid address for validator node (account {})",
-            account_index
-        );
-        return Err(ProgramError::InvalidAccountData);
-    }
+    let (_, swap_destination_2z_info, _) = try_next_2z_token_pda_info(
+        &mut accounts_iter,
+        &swap_authority_key,
+        "swap destination",
+        Some(program_config.swap_destination_2z_bump_seed),
+    )?;
 
-    // Account 3 may be the beneficiary of the excess lamports. If provided, the
-    // validator node must be a signer to act as an authority for the
-    // beneficiary. Otherwise, the validator node is the destination.
-    let beneficiary_info = match try_next_enumerated_account(&mut accounts_iter, Default::default())
-    {
-        Ok((_, specified_beneficiary_info)) => {
-            if !validator_node_info.is_signer {
-                msg!("Validator node must be a signer when a beneficiary is provided");
-                return Err(ProgramError::MissingRequiredSignature);
-            }
+    let rent_exemption_lamports = Rent::get()
+        .unwrap()
+        .minimum_balance(journal.info.data_len());
+    let sol_balance = journal
+        .info
+        .lamports()
+        .saturating_sub(rent_exemption_lamports);
+
+    let drift = JournalDrift::new(
+        &journal,
+        sol_balance,
+        try_token_account_amount(journal_2z_token_pda_info)?,
+        try_token_account_amount(swap_destination_2z_info)?,
+    );
 
-            specified_beneficiary_info
-        }
-        Err(_) => validator_node_info,
-    };
+    msg!("SOL drift: {}", drift.sol_drift);
+    msg!("2Z drift: {}", drift.token_2z_drift);
+    msg!(
+        "Swap destination 2Z drift: {}",
+        drift.swap_destination_2z_drift
+    );
 
-    let written_off_sol_debt = solana_validator_deposit.written_off_sol_debt;
-    let solana_validator_deposit_info = solana_validator_deposit.info;
-    drop(solana_validator_deposit);
+    // Drift is reported instead of reverting so off-chain processes can
+    // monitor the journal's balances.
+    if !drift.is_zero() {
+        msg!("Journal balances have drifted");
+    }
 
-    let solana_validator_deposit_lamports = solana_validator_deposit_info.lamports();
+    solana_cpi::set_return_data(bytemuck::bytes_of(&drift));
 
-    // Withdraw the excess lamports beyond rent exemption and written-off debt.
-    let rent_exemption_lamports = Rent::get()
-        .unwrap()
-        .minimum_balance(zero_copy::data_end::<SolanaValidatorDeposit>());
+    Ok(())
+}
 
-    let withdrawn_lamports = solana_validator_deposit_lamports
-        .saturating_sub(rent_exemption_lamports)
-        .saturating_sub(written_off_sol_debt);
+fn try_query(accounts: &[AccountInfo], query: Query) -> ProgramResult {
+    msg!("Query");
 
-    if withdrawn_lamports == 0 {
-        msg!(
-            "No excess lamports to withdraw. Delinquent debt: {}",
-            written_off_sol_debt
-        );
-        return Err(ProgramError::InvalidAccountData);
+    // No accounts are modified, so queries are allowed while the program is
+    // paused.
+    match query {
+        Query::CommunityBurnRate => try_query_community_burn_rate(accounts),
+        Query::Token2zBalance => try_query_token_2z_balance(accounts),
     }
+}
 
-    **solana_validator_deposit_info.lamports.borrow_mut() -= withdrawn_lamports;
-    **beneficiary_info.lamports.borrow_mut() += withdrawn_lamports;
+fn try_query_community_burn_rate(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Community burn rate");
+
+    // We expect the following accounts for this query:
+    // - 0: Program config.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 must be the program config.
+    let program_config =
+        ZeroCopyAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+
+    let cbr_params = &program_config
+        .distribution_parameters
+        .community_burn_rate_parameters;
+
+    // The first projected burn rate is the one the next distribution will be
+    // initialized with.
+    let mut projected_burn_rates = cbr_params.projected_burn_rates();
+
+    let burn_rate = projected_burn_rates.next().ok_or_else(|| {
+        msg!("Community burn rate parameters are not configured");
+        ProgramError::InvalidAccountData
+    })?;
+    let next_dz_epoch_burn_rate = projected_burn_rates.next().unwrap_or(burn_rate);
+
+    let view = CommunityBurnRateView::new(
+        program_config.next_completed_dz_epoch,
+        burn_rate,
+        next_dz_epoch_burn_rate,
+        cbr_params.limit,
+    );
+
+    msg!("DZ epoch: {}", view.dz_epoch);
+    msg!("Burn rate: {}", view.burn_rate);
+    msg!("Next DZ epoch burn rate: {}", view.next_dz_epoch_burn_rate);
+
+    solana_cpi::set_return_data(bytemuck::bytes_of(&view));
+
+    Ok(())
+}
+
+fn try_query_token_2z_balance(accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("2Z token balance");
+
+    // We expect the following accounts for this query:
+    // - 0: Token owner.
+    // - 1: Token owner's 2Z token PDA.
+    let mut accounts_iter = accounts.iter().enumerate();
+
+    // Account 0 is the owner of the 2Z token PDA. Any account can be the
+    // owner because only the 2Z token PDA's address is enforced.
+    let (_, token_owner_info) =
+        try_next_enumerated_account(&mut accounts_iter, Default::default())?;
+
+    // Account 1 must be the token owner's 2Z token PDA.
+    let (_, token_2z_pda_info, _) = try_next_2z_token_pda_info(
+        &mut accounts_iter,
+        token_owner_info.key,
+        "token owner's",
+        None,
+    )?;
+
+    let view = Token2zBalanceView {
+        token_owner_key: *token_owner_info.key,
+        amount: try_token_account_amount(token_2z_pda_info)?,
+    };
+
+    msg!("Token owner: {}", view.token_owner_key);
+    msg!("2Z balance: {}", view.amount);
+
+    solana_cpi::set_return_data(bytemuck::bytes_of(&view));
 
     Ok(())
 }
@@ -2998,9 +4814,13 @@ fn try_withdraw_solana_validator_deposit(accounts: &[AccountInfo]) -> ProgramRes
 enum Authority {
     /// Configures program settings.
     Admin,
-    /// Initializes distributions, configures and finalizes distribution debt.
+    /// Initializes distributions, configures and finalizes distribution debt
+    /// and reconciles Solana validator deposit surplus. A secondary debt
+    /// accountant is also accepted until it expires.
     DebtAccountant,
-    /// Configures and finalizes distribution rewards.
+    /// Configures and finalizes distribution rewards and locks contributor
+    /// recipient shares. A secondary rewards accountant is also accepted until
+    /// it expires.
     RewardsAccountant,
     /// Sets reward managers for contributor rewards.
     ContributorManager,
@@ -3029,13 +4849,13 @@ impl Authority {
                 }
             }
             Authority::DebtAccountant => {
-                if authority_info.key != &program_config.debt_accountant_key {
+                if !program_config.is_debt_accountant(authority_info.key) {
                     msg!("Unauthorized debt accountant (account {})", index);
                     return Err(ProgramError::InvalidAccountData);
                 }
             }
             Authority::RewardsAccountant => {
-                if authority_info.key != &program_config.rewards_accountant_key {
+                if !program_config.is_rewards_accountant(authority_info.key) {
                     msg!("Unauthorized rewards accountant (account {})", index);
                     return Err(ProgramError::InvalidAccountData);
                 }
@@ -3163,6 +4983,41 @@ fn try_token_account_amount(info: &AccountInfo) -> Result<u64, ProgramError> {
     Ok(spl_token_interface::state::Account::unpack(&info.data.borrow()[..])?.amount)
 }
 
+fn try_next_withdraw_sol_authority_info(
+    accounts_iter: &mut EnumeratedAccountInfoIter,
+    program_config: &ProgramConfig,
+) -> ProgramResult {
+    // Make sure the SOL/2Z swap program ID is set by checking if the bump seed
+    // for the withdraw SOL authority is set.
+    if program_config.withdraw_sol_authority_bump_seed == 0 {
+        msg!("SOL/2Z swap program ID is not set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let (account_index, withdraw_sol_authority_info) = try_next_enumerated_account(
+        accounts_iter,
+        NextAccountOptions {
+            must_be_signer: true,
+            ..Default::default()
+        },
+    )?;
+
+    let expected_withdraw_sol_authority_key = program_config
+        .checked_withdraw_sol_authority_address()
+        .unwrap();
+
+    // Enforce this account location.
+    if withdraw_sol_authority_info.key != &expected_withdraw_sol_authority_key {
+        msg!(
+            "Invalid address for withdraw SOL authority (account {})",
+            account_index
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
 #[inline(always)]
 fn try_next_token_program_info(accounts_iter: &mut EnumeratedAccountInfoIter) -> ProgramResult {
     let (account_index, token_program_info) =
@@ -3270,26 +5125,39 @@ fn try_process_remaining_data_leaf_index(
     processed_leaf_data: &mut [u8],
     leaf_index: u32,
 ) -> ProgramResult {
-    // Calculate which byte contains the bit for this leaf index
-    // (8 bits per byte, so divide by 8)
-    let leaf_byte_index = leaf_index as usize / 8;
+    try_require_unprocessed_remaining_data_leaf_index(processed_leaf_data, leaf_index)?;
 
-    // First, we have to grab the relevant byte from the processed data.
-    let leaf_byte_ref = processed_leaf_data
-        .get_mut(leaf_byte_index)
-        .ok_or_else(|| {
-            msg!("Invalid leaf index");
-            ProgramError::InvalidInstructionData
-        })?;
+    // Calculate which byte contains the bit for this leaf index
+    // (8 bits per byte, so divide by 8). This byte was already checked to
+    // exist.
+    let leaf_byte_ref = &mut processed_leaf_data[leaf_index as usize / 8];
 
-    // Create ByteFlags from the byte value to check the bit.
+    // Create ByteFlags from the byte value to set the bit.
     let mut leaf_byte = ByteFlags::new(*leaf_byte_ref);
 
     // Calculate which bit within the byte corresponds to this leaf
     // (modulo 8 gives us the bit position within the byte: 0-7)
     let leaf_bit = leaf_index as usize % 8;
 
-    if leaf_byte.bit(leaf_bit) {
+    // Set the bit to true to indicate that the leaf has been processed.
+    // This prevents replay attacks using the same merkle proof.
+    leaf_byte.set_bit(leaf_bit, true);
+    *leaf_byte_ref = leaf_byte.into();
+
+    Ok(())
+}
+
+fn try_require_unprocessed_remaining_data_leaf_index(
+    processed_leaf_data: &[u8],
+    leaf_index: u32,
+) -> ProgramResult {
+    // First, we have to grab the relevant byte from the processed data.
+    if processed_leaf_data.get(leaf_index as usize / 8).is_none() {
+        msg!("Invalid leaf index");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if is_remaining_data_leaf_index_processed(processed_leaf_data, leaf_index) {
         msg!(
             "Merkle leaf index {} has already been processed",
             leaf_index
@@ -3297,34 +5165,119 @@ fn try_process_remaining_data_leaf_index(
         return Err(ProgramError::InvalidAccountData);
     }
 
-    // Set the bit to true to indicate that the leaf has been processed.
-    // This prevents replay attacks using the same merkle proof.
-    leaf_byte.set_bit(leaf_bit, true);
-    *leaf_byte_ref = leaf_byte.into();
-
     Ok(())
 }
 
+/// Returns whether the bit for the leaf index is set. Leaf indices beyond the
+/// processed data are treated as unprocessed.
+fn is_remaining_data_leaf_index_processed(processed_leaf_data: &[u8], leaf_index: u32) -> bool {
+    processed_leaf_data
+        .get(leaf_index as usize / 8)
+        .is_some_and(|leaf_byte| ByteFlags::new(*leaf_byte).bit(leaf_index as usize % 8))
+}
+
 //
 // Here be dragons.
 //
 
-/// This instruction processor is a special instruction that will not always be
-/// used after a program upgrade. This docstring should be updated whenever the
-/// upgrade authority must perform a special migration.
+/// Migration steps registered by account version. The step at index `i`
+/// upgrades program accounts from version `i` to version `i + 1`, so the latest
+/// account version is the number of registered steps. Accounts created before
+/// their layouts were versioned read as version 0 because their version field
+/// was padding.
 ///
-/// # Why are we migrating?
+/// Each step must only write to fields that were padding or storage gap in the
+/// previous version. If an account layout must change in size, use
+/// [zero_copy::try_upgrade_in_place] instead.
+const MIGRATION_STEPS: &[MigrationStep] = &[MigrationStep {
+    program_config: migrate_program_config_to_v1,
+    journal: skip_migration,
+    distribution: skip_migration,
+}];
+
+const LATEST_ACCOUNT_VERSION: usize = MIGRATION_STEPS.len();
+
+// New accounts are initialized at the latest account version.
+const _: () = assert!(ProgramConfig::VERSION as usize == LATEST_ACCOUNT_VERSION);
+const _: () = assert!(Journal::VERSION as usize == LATEST_ACCOUNT_VERSION);
+const _: () = assert!(Distribution::VERSION as usize == LATEST_ACCOUNT_VERSION);
+
+struct MigrationStep {
+    program_config: fn(&mut ProgramConfig),
+    journal: fn(&mut Journal),
+    distribution: fn(&mut Distribution),
+}
+
+trait MigratableAccount: VersionedAccount {
+    fn version_mut(&mut self) -> &mut u8;
+
+    fn migration(step: &MigrationStep) -> fn(&mut Self);
+}
+
+impl MigratableAccount for ProgramConfig {
+    fn version_mut(&mut self) -> &mut u8 {
+        &mut self.version
+    }
+
+    fn migration(step: &MigrationStep) -> fn(&mut Self) {
+        step.program_config
+    }
+}
+
+impl MigratableAccount for Journal {
+    fn version_mut(&mut self) -> &mut u8 {
+        &mut self.version
+    }
+
+    fn migration(step: &MigrationStep) -> fn(&mut Self) {
+        step.journal
+    }
+}
+
+impl MigratableAccount for Distribution {
+    fn version_mut(&mut self) -> &mut u8 {
+        &mut self.version
+    }
+
+    fn migration(step: &MigrationStep) -> fn(&mut Self) {
+        step.distribution
+    }
+}
+
+fn skip_migration<T>(_: &mut T) {}
+
+/// After the last one-off migration, the migrated bit was set to true. This
+/// step resets this bit to false.
+fn migrate_program_config_to_v1(program_config: &mut ProgramConfig) {
+    program_config.set_is_migrated(false);
+    msg!("Set flag is_migrated to false");
+}
+
+/// Migrates program accounts to the target account version. Accounts already
+/// at or beyond the target version are left unchanged.
 ///
-/// After the last migration, the migrated bit is set to true. This instruction
-/// will reset this bit to false.
-fn try_migrate_program_accounts(accounts: &[AccountInfo]) -> ProgramResult {
+/// Account layouts and discriminators do not change between versions, so
+/// accounts that have not been migrated remain readable. Migrating is only
+/// needed for the changes made by each step, and accounts can be migrated in
+/// any order and across any number of transactions.
+fn try_migrate_program_accounts(accounts: &[AccountInfo], target_version: u8) -> ProgramResult {
     msg!("Migrate program accounts");
 
+    if usize::from(target_version) > LATEST_ACCOUNT_VERSION {
+        msg!(
+            "Target version {} exceeds latest account version {}",
+            target_version,
+            LATEST_ACCOUNT_VERSION
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
     // We expect the following accounts for this instruction:
     // - 0: This program's program data account (BPF Loader Upgradeable
     //      program).
     // - 1: The program's owner (i.e., upgrade authority).
     // - 2: Program config.
+    // - 3..: Journal or distributions.
     let mut accounts_iter = accounts.iter().enumerate();
 
     // Account 0 must be the program data belonging to this program.
@@ -3335,9 +5288,71 @@ fn try_migrate_program_accounts(accounts: &[AccountInfo]) -> ProgramResult {
     // Account 2 must be the program config.
     let mut program_config =
         ZeroCopyMutAccount::<ProgramConfig>::try_next_accounts(&mut accounts_iter, Some(&ID))?;
+    try_migrate_account(&mut program_config, target_version);
 
-    program_config.set_is_migrated(false);
-    msg!("Set flag is_migrated to false");
+    // Any remaining accounts must be either the journal or distributions.
+    for (account_index, account_info) in accounts_iter {
+        if account_info.owner != &ID {
+            msg!(
+                "Account {} must be owned by the Revenue Distribution program",
+                account_index
+            );
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let data = account_info.try_borrow_data()?;
+
+        if Journal::has_discriminator(&data) {
+            drop(data);
+
+            let mut journal = ZeroCopyMutAccount::<Journal>::try_from_account_info(
+                account_index,
+                account_info,
+                Some(&ID),
+            )?;
+            try_migrate_account(&mut journal, target_version);
+        } else if Distribution::has_discriminator(&data) {
+            drop(data);
+
+            let mut distribution = ZeroCopyMutAccount::<Distribution>::try_from_account_info(
+                account_index,
+                account_info,
+                Some(&ID),
+            )?;
+            msg!("DZ epoch: {}", distribution.dz_epoch);
+            try_migrate_account(&mut distribution, target_version);
+        } else {
+            msg!(
+                "Account {} must be the journal or a distribution",
+                account_index
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
 
     Ok(())
 }
+
+fn try_migrate_account<T: MigratableAccount>(
+    account: &mut ZeroCopyMutAccount<T>,
+    target_version: u8,
+) {
+    let version = *account.version_mut();
+
+    if version >= target_version {
+        msg!("Account {} already at version {}", account.index, version);
+        return;
+    }
+
+    for step in &MIGRATION_STEPS[usize::from(version)..usize::from(target_version)] {
+        T::migration(step)(&mut account.data);
+    }
+
+    *account.version_mut() = target_version;
+    msg!(
+        "Migrated account {} from version {} to {}",
+        account.index,
+        version,
+        target_version
+    );
+}
@@ -6,11 +6,14 @@ pub use recipient_shares::*;
 
 use bytemuck::{Pod, Zeroable};
 use doublezero_program_tools::{
-    types::{Flags, StorageGap},
+    assert_field_offset, define_flags, remaining_storage_gap,
+    types::{storage_gap_padding_len, Flags, StorageGap},
     {Discriminator, PrecomputedDiscriminator},
 };
 use solana_pubkey::Pubkey;
 
+use crate::types::DoubleZeroEpoch;
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
 #[repr(C, align(8))]
 pub struct ContributorRewards {
@@ -20,9 +23,49 @@ pub struct ContributorRewards {
 
     pub flags: Flags,
 
+    /// The first page of recipient shares. Any additional page is stored in
+    /// this account's remaining data, which is allocated when more than
+    /// [MAX_RECIPIENTS_PER_PAGE] recipients are configured.
     pub recipient_shares: RecipientShares,
 
-    _storage_gap: StorageGap<8>,
+    /// The most recent DZ epoch whose rewards were fully distributed to this
+    /// contributor's recipients.
+    pub last_distributed_dz_epoch: DoubleZeroEpoch,
+
+    /// Number of DZ epochs whose rewards were fully distributed.
+    pub distributed_dz_epochs_count: u64,
+
+    /// Total 2Z tokens transferred to recipients across all DZ epochs.
+    pub total_distributed_2z_amount: u64,
+
+    /// Total 2Z tokens burned from this contributor's rewards across all DZ
+    /// epochs. The burn rate applied in each epoch is the greater of the
+    /// contributor's economic burn rate and the community burn rate.
+    pub total_burned_2z_amount: u64,
+
+    /// DZ epoch whose rewards have been distributed to the first page of
+    /// recipients but not to the last page yet. Only meaningful while
+    /// [ContributorRewardsFlag::IsPartiallyDistributed] is set.
+    pub partially_distributed_dz_epoch: DoubleZeroEpoch,
+
+    /// 2Z tokens transferred to the first page of recipients for the partially
+    /// distributed DZ epoch. The last page accounts for these tokens when
+    /// computing rounding dust.
+    pub partially_distributed_2z_amount: u64,
+    _padding: [u8; storage_gap_padding_len(2 * size_of::<u64>())],
+
+    _storage_gap: StorageGap<{ remaining_storage_gap!(7; DoubleZeroEpoch, u64) }>,
+}
+
+assert_field_offset!(ContributorRewards, partially_distributed_dz_epoch, 376);
+
+define_flags! {
+    /// Flag bits stored in [ContributorRewards::flags].
+    pub enum ContributorRewardsFlag: Flags {
+        IsSetRewardsManagerBlocked = 0,
+        IsRecipientSharesLocked = 1,
+        IsPartiallyDistributed = 2,
+    }
 }
 
 impl PrecomputedDiscriminator for ContributorRewards {
@@ -33,19 +76,158 @@ impl PrecomputedDiscriminator for ContributorRewards {
 impl ContributorRewards {
     pub const SEED_PREFIX: &'static [u8] = b"contributor_rewards";
 
-    pub const FLAG_IS_SET_REWARDS_MANAGER_BLOCKED_BIT: usize = 0;
+    /// Length of the remaining data storing the second page of recipient
+    /// shares.
+    pub const EXTENSION_RECIPIENT_SHARES_LEN: usize = size_of::<RecipientShares>();
 
     pub fn find_address(service_key: &Pubkey) -> (Pubkey, u8) {
         Pubkey::find_program_address(&[Self::SEED_PREFIX, service_key.as_ref()], &crate::ID)
     }
 
     pub fn is_set_rewards_manager_blocked(&self) -> bool {
-        self.flags
-            .bit(Self::FLAG_IS_SET_REWARDS_MANAGER_BLOCKED_BIT)
+        ContributorRewardsFlag::IsSetRewardsManagerBlocked.is_set(&self.flags)
     }
 
     pub fn set_is_set_rewards_manager_blocked(&mut self, should_block: bool) {
-        self.flags
-            .set_bit(Self::FLAG_IS_SET_REWARDS_MANAGER_BLOCKED_BIT, should_block);
+        ContributorRewardsFlag::IsSetRewardsManagerBlocked.set(&mut self.flags, should_block);
+    }
+
+    /// While locked, the rewards manager cannot change recipients. The rewards
+    /// accountant locks recipient shares so they cannot change between
+    /// finalizing and distributing rewards.
+    pub fn is_recipient_shares_locked(&self) -> bool {
+        ContributorRewardsFlag::IsRecipientSharesLocked.is_set(&self.flags)
+    }
+
+    pub fn set_is_recipient_shares_locked(&mut self, should_lock: bool) {
+        ContributorRewardsFlag::IsRecipientSharesLocked.set(&mut self.flags, should_lock);
+    }
+
+    /// Returns the DZ epoch whose rewards have only been distributed to the
+    /// first page of recipients, if any. Recipients cannot change until the
+    /// last page is distributed.
+    pub fn checked_partially_distributed_dz_epoch(&self) -> Option<DoubleZeroEpoch> {
+        if ContributorRewardsFlag::IsPartiallyDistributed.is_set(&self.flags) {
+            Some(self.partially_distributed_dz_epoch)
+        } else {
+            None
+        }
+    }
+
+    pub fn set_partially_distributed(&mut self, dz_epoch: DoubleZeroEpoch, amount: u64) {
+        ContributorRewardsFlag::IsPartiallyDistributed.set(&mut self.flags, true);
+        self.partially_distributed_dz_epoch = dz_epoch;
+        self.partially_distributed_2z_amount = amount;
+    }
+
+    /// Clears the partially distributed DZ epoch, returning the 2Z tokens
+    /// already transferred to the first page of recipients.
+    pub fn take_partially_distributed_2z_amount(&mut self) -> u64 {
+        ContributorRewardsFlag::IsPartiallyDistributed.set(&mut self.flags, false);
+        self.partially_distributed_dz_epoch = Default::default();
+        std::mem::take(&mut self.partially_distributed_2z_amount)
+    }
+
+    /// Returns the most recent DZ epoch whose rewards were fully distributed,
+    /// if any rewards have been distributed.
+    pub fn checked_last_distributed_dz_epoch(&self) -> Option<DoubleZeroEpoch> {
+        if self.distributed_dz_epochs_count == 0 {
+            None
+        } else {
+            Some(self.last_distributed_dz_epoch)
+        }
+    }
+
+    /// Returns the recipient shares for the given page. The second page is
+    /// read from the account's remaining data, which may not be aligned.
+    pub fn recipient_shares_page(
+        &self,
+        remaining_data: &[u8],
+        page: usize,
+    ) -> Option<RecipientShares> {
+        match page {
+            0 => Some(self.recipient_shares),
+            1 => remaining_data
+                .get(..Self::EXTENSION_RECIPIENT_SHARES_LEN)
+                .map(bytemuck::pod_read_unaligned),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of pages with at least one recipient.
+    pub fn recipient_page_count(&self, remaining_data: &[u8]) -> usize {
+        (0..MAX_RECIPIENT_PAGES)
+            .filter_map(|page| self.recipient_shares_page(remaining_data, page))
+            .filter(|shares| shares.active_iter().next().is_some())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipient_shares_pages() {
+        let recipients = (0..MAX_RECIPIENTS)
+            .map(|_| (Pubkey::new_unique(), 625))
+            .collect::<Vec<_>>();
+        let [first_page, second_page] = RecipientShares::try_new_pages(&recipients).unwrap();
+
+        let contributor_rewards = ContributorRewards {
+            recipient_shares: first_page,
+            ..Default::default()
+        };
+
+        // Without remaining data, there is only one page.
+        assert_eq!(contributor_rewards.recipient_page_count(&[]), 1);
+        assert_eq!(
+            contributor_rewards.recipient_shares_page(&[], 0),
+            Some(first_page)
+        );
+        assert_eq!(contributor_rewards.recipient_shares_page(&[], 1), None);
+
+        let mut remaining_data = vec![0; ContributorRewards::EXTENSION_RECIPIENT_SHARES_LEN + 1];
+        assert_eq!(contributor_rewards.recipient_page_count(&remaining_data), 1);
+
+        // Unaligned remaining data.
+        remaining_data[1..].copy_from_slice(bytemuck::bytes_of(&second_page));
+        assert_eq!(
+            contributor_rewards.recipient_page_count(&remaining_data[1..]),
+            2
+        );
+        assert_eq!(
+            contributor_rewards.recipient_shares_page(&remaining_data[1..], 1),
+            Some(second_page)
+        );
+        assert_eq!(
+            contributor_rewards.recipient_shares_page(&remaining_data[1..], 2),
+            None
+        );
+    }
+    #[test]
+    fn test_partially_distributed() {
+        let mut contributor_rewards = ContributorRewards::default();
+        assert_eq!(
+            contributor_rewards.checked_partially_distributed_dz_epoch(),
+            None
+        );
+
+        contributor_rewards.set_partially_distributed(DoubleZeroEpoch::new(69), 420);
+        assert_eq!(
+            contributor_rewards.checked_partially_distributed_dz_epoch(),
+            Some(DoubleZeroEpoch::new(69))
+        );
+        assert!(!contributor_rewards.is_recipient_shares_locked());
+
+        assert_eq!(
+            contributor_rewards.take_partially_distributed_2z_amount(),
+            420
+        );
+        assert_eq!(
+            contributor_rewards.checked_partially_distributed_dz_epoch(),
+            None
+        );
+        assert_eq!(contributor_rewards, ContributorRewards::default());
     }
 }
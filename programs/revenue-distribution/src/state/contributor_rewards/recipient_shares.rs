@@ -3,7 +3,13 @@ use solana_pubkey::Pubkey;
 
 use crate::types::UnitShare16;
 
-pub const MAX_RECIPIENTS: usize = 8;
+/// Maximum number of recipients whose shares are transferred by a single
+/// distribute rewards instruction.
+pub const MAX_RECIPIENTS_PER_PAGE: usize = 8;
+
+pub const MAX_RECIPIENT_PAGES: usize = 2;
+
+pub const MAX_RECIPIENTS: usize = MAX_RECIPIENTS_PER_PAGE * MAX_RECIPIENT_PAGES;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
 #[repr(C, align(2))]
@@ -14,10 +20,22 @@ pub struct RecipientShare {
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
 #[repr(C, align(8))]
-pub struct RecipientShares([RecipientShare; MAX_RECIPIENTS]);
+pub struct RecipientShares([RecipientShare; MAX_RECIPIENTS_PER_PAGE]);
 
 impl RecipientShares {
+    /// Recipient shares that fit in a single page.
     pub fn new(recipients: &[(Pubkey, u16)]) -> Option<Self> {
+        if recipients.len() > MAX_RECIPIENTS_PER_PAGE {
+            return None;
+        }
+
+        Self::try_new_pages(recipients).map(|[page, _]| page)
+    }
+
+    /// Split recipient shares into pages of at most
+    /// [MAX_RECIPIENTS_PER_PAGE] recipients. Shares across all pages must sum
+    /// to 100%. Pages without recipients are all default entries.
+    pub fn try_new_pages(recipients: &[(Pubkey, u16)]) -> Option<[Self; MAX_RECIPIENT_PAGES]> {
         if recipients.len() > MAX_RECIPIENTS {
             return None;
         }
@@ -52,7 +70,16 @@ impl RecipientShares {
             return None;
         }
 
-        Some(Self(out))
+        let mut pages = [Self::default(); MAX_RECIPIENT_PAGES];
+
+        for (page, shares) in pages
+            .iter_mut()
+            .zip(out.chunks_exact(MAX_RECIPIENTS_PER_PAGE))
+        {
+            page.0.copy_from_slice(shares);
+        }
+
+        Some(pages)
     }
 
     /// Returns an iterator over all recipient shares (including default
@@ -140,7 +167,7 @@ mod tests {
 
         let shares = RecipientShares::new(&recipients).unwrap();
 
-        assert_eq!(shares.iter().count(), MAX_RECIPIENTS);
+        assert_eq!(shares.iter().count(), MAX_RECIPIENTS_PER_PAGE);
         assert_eq!(shares.active_iter().count(), 2);
     }
 
@@ -172,4 +199,40 @@ mod tests {
 
         assert_eq!(shares.iter().count(), shares.active_iter().count());
     }
+
+    #[test]
+    fn test_recipient_shares_pages() {
+        let recipients = (0..12)
+            .map(|i| (Pubkey::new_unique(), if i < 4 { 1_000 } else { 750 }))
+            .collect::<Vec<_>>();
+
+        // Too many recipients for a single page.
+        assert!(RecipientShares::new(&recipients).is_none());
+
+        let [first_page, second_page] = RecipientShares::try_new_pages(&recipients).unwrap();
+        assert_eq!(first_page.active_iter().count(), MAX_RECIPIENTS_PER_PAGE);
+        assert_eq!(second_page.active_iter().count(), 4);
+
+        assert_eq!(first_page.0[0].recipient_key, recipients[0].0);
+        assert_eq!(second_page.0[0].recipient_key, recipients[8].0);
+        assert_eq!(second_page.0[3].recipient_key, recipients[11].0);
+        assert_eq!(second_page.0[3].share, UnitShare16::new(750).unwrap());
+
+        // Single page recipients leave the second page empty.
+        let recipients = [(Pubkey::new_unique(), 10_000)];
+        let [first_page, second_page] = RecipientShares::try_new_pages(&recipients).unwrap();
+        assert_eq!(first_page, RecipientShares::new(&recipients).unwrap());
+        assert_eq!(second_page, RecipientShares::default());
+
+        // Shares across pages must sum to 100%.
+        let recipients = (0..MAX_RECIPIENTS)
+            .map(|_| (Pubkey::new_unique(), 600))
+            .collect::<Vec<_>>();
+        assert!(RecipientShares::try_new_pages(&recipients).is_none());
+
+        let recipients = (0..MAX_RECIPIENTS + 1)
+            .map(|_| (Pubkey::new_unique(), 1))
+            .collect::<Vec<_>>();
+        assert!(RecipientShares::try_new_pages(&recipients).is_none());
+    }
 }
@@ -2,7 +2,9 @@ use std::ops::Range;
 
 use bytemuck::{Pod, Zeroable};
 use doublezero_program_tools::{
+    define_flags,
     types::{Flags, StorageGap},
+    zero_copy::VersionedAccount,
     {Discriminator, PrecomputedDiscriminator},
 };
 use ruint::Uint;
@@ -10,8 +12,8 @@ use solana_pubkey::Pubkey;
 use svm_hash::sha2::Hash;
 
 use crate::{
-    state::SolanaValidatorFeeParameters,
-    types::{BurnRate, DoubleZeroEpoch, RewardShare},
+    state::{Journal, SolanaValidatorFeeParameters},
+    types::{BurnRate, DoubleZeroEpoch, RewardShare, UnitShare16},
 };
 
 /// Account representing distribution information for a given DoubleZero epoch.
@@ -33,7 +35,11 @@ pub struct Distribution {
 
     /// Cache this seed to validate token PDA address.
     pub token_2z_pda_bump_seed: u8,
-    _padding_0: [u8; 2],
+
+    /// Account version, which is updated by the migrate program accounts
+    /// instruction.
+    pub version: u8,
+    _padding_0: [u8; 1],
 
     /// Because the validator fee can change between epochs, we will save what
     /// it was at the time this account was created.
@@ -108,72 +114,127 @@ pub struct Distribution {
 
     pub collected_2z_from_integrations: u64,
 
-    _storage_gap: StorageGap<4>,
+    /// Cumulative rounding remainder from splitting contributor rewards
+    /// across recipients. This dust is burned and is already included in
+    /// [burned_2z_amount].
+    ///
+    /// [burned_2z_amount]: Self::burned_2z_amount
+    pub burned_rounding_dust_2z_amount: u64,
+
+    /// Balance of the distribution's 2Z token account when this distribution
+    /// was last reconciled. This amount is the rounding remainder from computing
+    /// each contributor's share of the collected 2Z tokens.
+    pub reconciled_residual_2z_amount: u64,
+
+    /// Number of times the rewards merkle root was replaced after it was
+    /// first configured.
+    pub rewards_root_revision: u32,
+
+    /// Bits indicating whether the first page of recipients has been
+    /// distributed for contributors with more than one page of recipients.
+    /// This bitmap is allocated when the first of these contributors has
+    /// rewards distributed.
+    pub partially_distributed_rewards_start_index: u32,
+    pub partially_distributed_rewards_end_index: u32,
+
+    _padding_2: [u8; 4],
+
+    /// Hash of the correction reason provided with the latest rewards merkle
+    /// root replacement.
+    pub rewards_root_correction_memo: Hash,
+
+    /// 2Z tokens converted from SOL debt that were transferred to the protocol
+    /// treasury when this distribution swept tokens. This amount is not
+    /// included in [collected_2z_converted_from_sol].
+    ///
+    /// [collected_2z_converted_from_sol]: Self::collected_2z_converted_from_sol
+    pub treasury_2z_amount: u64,
+
+    _padding_3: [u8; 24],
+
+    _storage_gap: StorageGap<1>,
+}
+
+define_flags! {
+    /// Flag bits stored in [Distribution::flags]. Bit 0 is reserved.
+    pub enum DistributionFlag: Flags {
+        IsDebtCalculationFinalized = 1,
+        IsRewardsCalculationFinalized = 2,
+        HasSwept2zTokens = 3,
+        IsSolanaValidatorDebtWriteOffEnabled = 4,
+        IsReconciled = 5,
+        HasReconciliationMismatch = 6,
+    }
 }
 
 impl PrecomputedDiscriminator for Distribution {
     const DISCRIMINATOR: Discriminator<8> = Discriminator::new_sha2(b"dz::account::distribution");
 }
 
+impl VersionedAccount for Distribution {
+    const VERSION: u8 = 1;
+    const VERSION_OFFSET: usize = std::mem::offset_of!(Distribution, version);
+}
+
 impl Distribution {
     pub const SEED_PREFIX: &'static [u8] = b"distribution";
 
-    pub const FLAG_RESERVED_BIT: usize = 0;
-    pub const FLAG_IS_DEBT_CALCULATION_FINALIZED_BIT: usize = 1;
-    pub const FLAG_IS_REWARDS_CALCULATION_FINALIZED_BIT: usize = 2;
-    pub const FLAG_HAS_SWEPT_2Z_TOKENS_BIT: usize = 3;
-    pub const FLAG_IS_SOLANA_VALIDATOR_DEBT_WRITE_OFF_ENABLED_BIT: usize = 4;
-
     pub fn find_address(dz_epoch: DoubleZeroEpoch) -> (Pubkey, u8) {
         Pubkey::find_program_address(&[Self::SEED_PREFIX, &dz_epoch.as_seed()], &crate::ID)
     }
 
     #[inline]
     pub fn is_debt_calculation_finalized(&self) -> bool {
-        self.flags.bit(Self::FLAG_IS_DEBT_CALCULATION_FINALIZED_BIT)
+        DistributionFlag::IsDebtCalculationFinalized.is_set(&self.flags)
     }
 
     pub fn set_is_debt_calculation_finalized(&mut self, should_finalize: bool) {
-        self.flags.set_bit(
-            Self::FLAG_IS_DEBT_CALCULATION_FINALIZED_BIT,
-            should_finalize,
-        );
+        DistributionFlag::IsDebtCalculationFinalized.set(&mut self.flags, should_finalize);
     }
 
     #[inline]
     pub fn is_rewards_calculation_finalized(&self) -> bool {
-        self.flags
-            .bit(Self::FLAG_IS_REWARDS_CALCULATION_FINALIZED_BIT)
+        DistributionFlag::IsRewardsCalculationFinalized.is_set(&self.flags)
     }
 
     pub fn set_is_rewards_calculation_finalized(&mut self, should_finalize: bool) {
-        self.flags.set_bit(
-            Self::FLAG_IS_REWARDS_CALCULATION_FINALIZED_BIT,
-            should_finalize,
-        );
+        DistributionFlag::IsRewardsCalculationFinalized.set(&mut self.flags, should_finalize);
     }
 
     #[inline]
     pub fn is_solana_validator_debt_write_off_enabled(&self) -> bool {
-        self.flags
-            .bit(Self::FLAG_IS_SOLANA_VALIDATOR_DEBT_WRITE_OFF_ENABLED_BIT)
+        DistributionFlag::IsSolanaValidatorDebtWriteOffEnabled.is_set(&self.flags)
     }
 
     pub fn set_is_solana_validator_debt_write_off_enabled(&mut self, should_enable: bool) {
-        self.flags.set_bit(
-            Self::FLAG_IS_SOLANA_VALIDATOR_DEBT_WRITE_OFF_ENABLED_BIT,
-            should_enable,
-        );
+        DistributionFlag::IsSolanaValidatorDebtWriteOffEnabled.set(&mut self.flags, should_enable);
     }
 
     #[inline]
     pub fn has_swept_2z_tokens(&self) -> bool {
-        self.flags.bit(Self::FLAG_HAS_SWEPT_2Z_TOKENS_BIT)
+        DistributionFlag::HasSwept2zTokens.is_set(&self.flags)
     }
 
     pub fn set_has_swept_2z_tokens(&mut self, has_swept: bool) {
-        self.flags
-            .set_bit(Self::FLAG_HAS_SWEPT_2Z_TOKENS_BIT, has_swept);
+        DistributionFlag::HasSwept2zTokens.set(&mut self.flags, has_swept);
+    }
+
+    #[inline]
+    pub fn is_reconciled(&self) -> bool {
+        DistributionFlag::IsReconciled.is_set(&self.flags)
+    }
+
+    pub fn set_is_reconciled(&mut self, is_reconciled: bool) {
+        DistributionFlag::IsReconciled.set(&mut self.flags, is_reconciled);
+    }
+
+    #[inline]
+    pub fn has_reconciliation_mismatch(&self) -> bool {
+        DistributionFlag::HasReconciliationMismatch.is_set(&self.flags)
+    }
+
+    pub fn set_has_reconciliation_mismatch(&mut self, has_mismatch: bool) {
+        DistributionFlag::HasReconciliationMismatch.set(&mut self.flags, has_mismatch);
     }
 
     #[inline]
@@ -269,6 +330,12 @@ impl Distribution {
         self.processed_rewards_start_index as usize..self.processed_rewards_end_index as usize
     }
 
+    #[inline]
+    pub fn partially_distributed_rewards_bitmap_range(&self) -> Range<usize> {
+        self.partially_distributed_rewards_start_index as usize
+            ..self.partially_distributed_rewards_end_index as usize
+    }
+
     #[inline]
     pub fn processed_solana_validator_debt_write_off_bitmap_range(&self) -> Range<usize> {
         self.processed_solana_validator_debt_write_off_start_index as usize
@@ -289,6 +356,104 @@ impl Distribution {
             .saturating_sub(self.distributed_rewards_count)
             == 0
     }
+
+    /// Sum of 2Z tokens distributed to contributors, burned and remaining in
+    /// the distribution's 2Z token account. None if this sum overflows.
+    #[inline]
+    pub fn checked_accounted_2z_amount(&self, residual_2z_amount: u64) -> Option<u64> {
+        self.distributed_2z_amount
+            .checked_add(self.burned_2z_amount)?
+            .checked_add(residual_2z_amount)
+    }
+
+    /// Every 2Z token collected by this distribution must either have been
+    /// distributed, burned or still remain in its 2Z token account.
+    #[inline]
+    pub fn is_2z_amount_conserved(&self, residual_2z_amount: u64) -> bool {
+        self.checked_accounted_2z_amount(residual_2z_amount)
+            == Some(self.total_collected_2z_tokens())
+    }
+
+    /// Aggregates the settlement progress of this distribution so off-chain
+    /// processes can tell whether this DZ epoch is fully settled without
+    /// inspecting each field themselves.
+    pub fn status(&self, journal: &Journal) -> DistributionStatus {
+        DistributionStatus {
+            dz_epoch: self.dz_epoch,
+            is_debt_calculation_finalized: self.is_debt_calculation_finalized(),
+            is_rewards_calculation_finalized: self.is_rewards_calculation_finalized(),
+            has_swept_2z_tokens: self.has_swept_2z_tokens(),
+            is_next_to_sweep_2z_tokens: journal.next_dz_epoch_to_sweep_tokens == self.dz_epoch,
+            solana_validators_paid: checked_progress(
+                self.solana_validator_payments_count,
+                self.total_solana_validators,
+            ),
+            solana_validators_written_off: checked_progress(
+                self.solana_validator_write_off_count,
+                self.total_solana_validators,
+            ),
+            rewards_distributed: checked_progress(
+                self.distributed_rewards_count,
+                self.total_contributors,
+            ),
+            uncollectible_sol_debt: self.uncollectible_sol_debt,
+            is_all_solana_validator_debt_processed: self.is_all_solana_validator_debt_processed(),
+            are_all_rewards_distributed: self.are_all_rewards_distributed(),
+        }
+    }
+}
+
+/// Summary of a distribution's settlement progress, derived from the
+/// distribution and journal accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistributionStatus {
+    pub dz_epoch: DoubleZeroEpoch,
+    pub is_debt_calculation_finalized: bool,
+    pub is_rewards_calculation_finalized: bool,
+    pub has_swept_2z_tokens: bool,
+
+    /// Whether the journal expects this distribution to be the next one to
+    /// sweep 2Z tokens.
+    pub is_next_to_sweep_2z_tokens: bool,
+
+    /// Proportion of Solana validators that paid their debt. None if there
+    /// are no Solana validators.
+    pub solana_validators_paid: Option<UnitShare16>,
+
+    /// Proportion of Solana validators whose debt was written off. None if
+    /// there are no Solana validators.
+    pub solana_validators_written_off: Option<UnitShare16>,
+
+    /// Proportion of contributors that have had rewards distributed. None if
+    /// there are no contributors.
+    pub rewards_distributed: Option<UnitShare16>,
+
+    pub uncollectible_sol_debt: u64,
+    pub is_all_solana_validator_debt_processed: bool,
+    pub are_all_rewards_distributed: bool,
+}
+
+impl DistributionStatus {
+    /// A distribution is settled once both calculations are finalized, 2Z
+    /// tokens have been swept and every contributor has received rewards.
+    pub fn is_settled(&self) -> bool {
+        self.is_debt_calculation_finalized
+            && self.is_rewards_calculation_finalized
+            && self.has_swept_2z_tokens
+            && self.is_all_solana_validator_debt_processed
+            && self.are_all_rewards_distributed
+    }
+}
+
+fn checked_progress(count: u32, total: u32) -> Option<UnitShare16> {
+    if total == 0 {
+        return None;
+    }
+
+    let count = u64::from(count.min(total));
+    let value = count * u64::from(u16::from(UnitShare16::MAX)) / u64::from(total);
+
+    UnitShare16::try_from(value).ok()
 }
 
 #[cfg(test)]
@@ -345,6 +510,25 @@ mod tests {
         assert!(!distribution.has_swept_2z_tokens());
     }
 
+    #[test]
+    fn test_reconciliation_flags() {
+        let mut distribution = Distribution::default();
+        assert!(!distribution.is_reconciled());
+        assert!(!distribution.has_reconciliation_mismatch());
+
+        distribution.set_is_reconciled(true);
+        assert!(distribution.is_reconciled());
+        assert!(!distribution.has_reconciliation_mismatch());
+
+        distribution.set_has_reconciliation_mismatch(true);
+        assert!(distribution.has_reconciliation_mismatch());
+
+        distribution.set_is_reconciled(false);
+        distribution.set_has_reconciliation_mismatch(false);
+        assert!(!distribution.is_reconciled());
+        assert!(!distribution.has_reconciliation_mismatch());
+    }
+
     #[test]
     fn test_checked_total_sol_debt() {
         let mut distribution = Distribution::default();
@@ -399,6 +583,26 @@ mod tests {
         assert_eq!(distribution.total_collected_2z_tokens(), 150);
     }
 
+    #[test]
+    fn test_is_2z_amount_conserved() {
+        let mut distribution = Distribution::default();
+        assert!(distribution.is_2z_amount_conserved(0));
+        assert!(!distribution.is_2z_amount_conserved(1));
+
+        distribution.collected_prepaid_2z_payments = 1_000;
+        distribution.collected_2z_converted_from_sol = 2_000;
+        distribution.distributed_2z_amount = 1_500;
+        distribution.burned_2z_amount = 1_497;
+        assert_eq!(distribution.checked_accounted_2z_amount(3), Some(3_000));
+        assert!(distribution.is_2z_amount_conserved(3));
+        assert!(!distribution.is_2z_amount_conserved(2));
+        assert!(!distribution.is_2z_amount_conserved(4));
+
+        distribution.burned_2z_amount = u64::MAX;
+        assert!(distribution.checked_accounted_2z_amount(3).is_none());
+        assert!(!distribution.is_2z_amount_conserved(3));
+    }
+
     #[test]
     fn test_are_all_integrations_collected() {
         let mut distribution = Distribution::default();
@@ -663,6 +867,21 @@ mod tests {
         assert_eq!(distribution.processed_rewards_bitmap_range(), 10..30);
     }
 
+    #[test]
+    fn test_partially_distributed_rewards_bitmap_range() {
+        let mut distribution = Distribution::default();
+        assert!(distribution
+            .partially_distributed_rewards_bitmap_range()
+            .is_empty());
+
+        distribution.partially_distributed_rewards_start_index = 10;
+        distribution.partially_distributed_rewards_end_index = 12;
+        assert_eq!(
+            distribution.partially_distributed_rewards_bitmap_range(),
+            10..12
+        );
+    }
+
     #[test]
     fn test_processed_solana_validator_debt_write_off_bitmap_range() {
         let mut distribution = Distribution {
@@ -683,6 +902,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_status() {
+        let mut journal = Journal::default();
+        journal.next_dz_epoch_to_sweep_tokens = DoubleZeroEpoch::new(1);
+
+        let mut distribution = Distribution {
+            dz_epoch: DoubleZeroEpoch::new(1),
+            ..Default::default()
+        };
+
+        let status = distribution.status(&journal);
+        assert_eq!(
+            status,
+            DistributionStatus {
+                dz_epoch: DoubleZeroEpoch::new(1),
+                is_debt_calculation_finalized: false,
+                is_rewards_calculation_finalized: false,
+                has_swept_2z_tokens: false,
+                is_next_to_sweep_2z_tokens: true,
+                solana_validators_paid: None,
+                solana_validators_written_off: None,
+                rewards_distributed: None,
+                uncollectible_sol_debt: 0,
+                is_all_solana_validator_debt_processed: true,
+                are_all_rewards_distributed: true,
+            }
+        );
+        assert!(!status.is_settled());
+
+        distribution.set_is_debt_calculation_finalized(true);
+        distribution.set_is_rewards_calculation_finalized(true);
+        distribution.total_solana_validators = 8;
+        distribution.solana_validator_payments_count = 6;
+        distribution.solana_validator_write_off_count = 1;
+        distribution.uncollectible_sol_debt = 69;
+        distribution.total_contributors = 3;
+        distribution.distributed_rewards_count = 1;
+
+        let status = distribution.status(&journal);
+        assert_eq!(status.solana_validators_paid, UnitShare16::new(7_500));
+        assert_eq!(
+            status.solana_validators_written_off,
+            UnitShare16::new(1_250)
+        );
+        assert_eq!(status.rewards_distributed, UnitShare16::new(3_333));
+        assert_eq!(status.uncollectible_sol_debt, 69);
+        assert!(!status.is_all_solana_validator_debt_processed);
+        assert!(!status.are_all_rewards_distributed);
+        assert!(!status.is_settled());
+
+        distribution.solana_validator_write_off_count = 2;
+        distribution.distributed_rewards_count = 3;
+        distribution.set_has_swept_2z_tokens(true);
+
+        // Journal has moved on to the next DZ epoch after sweeping.
+        journal.next_dz_epoch_to_sweep_tokens = DoubleZeroEpoch::new(2);
+
+        let status = distribution.status(&journal);
+        assert!(!status.is_next_to_sweep_2z_tokens);
+        assert_eq!(status.rewards_distributed, Some(UnitShare16::MAX));
+        assert!(status.is_settled());
+    }
+
     #[test]
     fn test_is_all_solana_validator_debt_processed() {
         let mut distribution = Distribution::default();
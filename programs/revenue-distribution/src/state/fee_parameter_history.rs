@@ -0,0 +1,148 @@
+use bytemuck::{Pod, Zeroable};
+use doublezero_program_tools::{types::StorageGap, Discriminator, PrecomputedDiscriminator};
+use solana_pubkey::Pubkey;
+
+use crate::{state::SolanaValidatorFeeParameters, types::DoubleZeroEpoch};
+
+pub const FEE_PARAMETER_HISTORY_ENTRIES_LENGTH: usize = 32;
+
+/// Append-only record of Solana validator fee parameter changes. Entries are
+/// stored in a ring buffer, so only the most recent
+/// [FEE_PARAMETER_HISTORY_ENTRIES_LENGTH] changes are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C, align(8))]
+pub struct FeeParameterHistory {
+    pub bump_seed: u8,
+    _padding: [u8; 3],
+
+    /// Total number of changes recorded over the lifetime of this account.
+    pub total_entries_count: u32,
+
+    pub entries: [FeeParameterHistoryEntry; FEE_PARAMETER_HISTORY_ENTRIES_LENGTH],
+
+    _storage_gap: StorageGap<2>,
+}
+
+impl Default for FeeParameterHistory {
+    fn default() -> Self {
+        Zeroable::zeroed()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C, align(8))]
+pub struct FeeParameterHistoryEntry {
+    /// First DZ epoch whose distribution uses these fee parameters.
+    pub effective_dz_epoch: DoubleZeroEpoch,
+
+    /// Unix timestamp when the fee parameters were configured.
+    pub timestamp: i64,
+
+    pub solana_validator_fee_parameters: SolanaValidatorFeeParameters,
+}
+
+impl PrecomputedDiscriminator for FeeParameterHistory {
+    const DISCRIMINATOR: Discriminator<8> =
+        Discriminator::new_sha2(b"dz::account::fee_parameter_history");
+}
+
+impl FeeParameterHistory {
+    pub const SEED_PREFIX: &'static [u8] = b"fee_parameter_history";
+
+    pub fn find_address() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED_PREFIX], &crate::ID)
+    }
+
+    pub fn append(&mut self, entry: FeeParameterHistoryEntry) {
+        let index = self.total_entries_count as usize % FEE_PARAMETER_HISTORY_ENTRIES_LENGTH;
+        self.entries[index] = entry;
+        self.total_entries_count = self.total_entries_count.saturating_add(1);
+    }
+
+    /// Returns recorded entries from oldest to newest.
+    pub fn iter_entries(&self) -> impl DoubleEndedIterator<Item = &FeeParameterHistoryEntry> {
+        let total_entries_count = self.total_entries_count as usize;
+        let len = total_entries_count.min(FEE_PARAMETER_HISTORY_ENTRIES_LENGTH);
+        let start = total_entries_count - len;
+
+        (start..total_entries_count)
+            .map(|i| &self.entries[i % FEE_PARAMETER_HISTORY_ENTRIES_LENGTH])
+    }
+
+    /// Returns the entry whose fee parameters applied to the given DZ epoch,
+    /// if the change that set them is still recorded.
+    pub fn find_entry(&self, dz_epoch: DoubleZeroEpoch) -> Option<&FeeParameterHistoryEntry> {
+        self.iter_entries()
+            .rev()
+            .find(|entry| entry.effective_dz_epoch <= dz_epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ValidatorFee;
+
+    fn test_entry(effective_dz_epoch: u64) -> FeeParameterHistoryEntry {
+        let mut solana_validator_fee_parameters = SolanaValidatorFeeParameters::default();
+        solana_validator_fee_parameters.base_block_rewards_pct =
+            ValidatorFee::new(effective_dz_epoch as u16).unwrap();
+
+        FeeParameterHistoryEntry {
+            effective_dz_epoch: DoubleZeroEpoch::new(effective_dz_epoch),
+            timestamp: 69 + effective_dz_epoch as i64,
+            solana_validator_fee_parameters,
+        }
+    }
+
+    #[test]
+    fn test_append() {
+        let mut history = FeeParameterHistory::default();
+        assert_eq!(history.iter_entries().count(), 0);
+        assert!(history.find_entry(DoubleZeroEpoch::new(1)).is_none());
+
+        history.append(test_entry(1));
+        history.append(test_entry(1));
+        history.append(test_entry(4));
+        assert_eq!(history.total_entries_count, 3);
+
+        let entries = history.iter_entries().copied().collect::<Vec<_>>();
+        assert_eq!(entries, [test_entry(1), test_entry(1), test_entry(4)]);
+
+        assert!(history.find_entry(DoubleZeroEpoch::new(0)).is_none());
+        assert_eq!(
+            history.find_entry(DoubleZeroEpoch::new(3)),
+            Some(&test_entry(1))
+        );
+        assert_eq!(
+            history.find_entry(DoubleZeroEpoch::new(4)),
+            Some(&test_entry(4))
+        );
+        assert_eq!(
+            history.find_entry(DoubleZeroEpoch::new(100)),
+            Some(&test_entry(4))
+        );
+    }
+
+    #[test]
+    fn test_append_wraps_around() {
+        let mut history = FeeParameterHistory::default();
+
+        let total_entries_count = FEE_PARAMETER_HISTORY_ENTRIES_LENGTH as u64 + 5;
+        for dz_epoch in 0..total_entries_count {
+            history.append(test_entry(dz_epoch));
+        }
+        assert_eq!(u64::from(history.total_entries_count), total_entries_count);
+
+        // The oldest entries were overwritten.
+        let entries = history.iter_entries().copied().collect::<Vec<_>>();
+        let expected = (5..total_entries_count).map(test_entry).collect::<Vec<_>>();
+        assert_eq!(entries, expected);
+
+        assert!(history.find_entry(DoubleZeroEpoch::new(4)).is_none());
+        assert_eq!(
+            history.find_entry(DoubleZeroEpoch::new(5)),
+            Some(&test_entry(5))
+        );
+    }
+}
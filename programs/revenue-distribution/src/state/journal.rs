@@ -1,5 +1,7 @@
 use bytemuck::{Pod, Zeroable};
-use doublezero_program_tools::{Discriminator, PrecomputedDiscriminator};
+use doublezero_program_tools::{
+    zero_copy::VersionedAccount, Discriminator, PrecomputedDiscriminator,
+};
 use ruint::Uint;
 use solana_pubkey::Pubkey;
 
@@ -17,7 +19,11 @@ pub struct Journal {
     pub token_2z_pda_bump_seed: u8,
 
     pub integrations_count: u16,
-    _padding: [u8; 4],
+
+    /// Account version, which is updated by the migrate program accounts
+    /// instruction.
+    pub version: u8,
+    _padding: [u8; 3],
 
     pub total_sol_balance: u64,
 
@@ -36,12 +42,22 @@ pub struct Journal {
     pub next_dz_epoch_to_sweep_tokens: DoubleZeroEpoch,
 
     pub lifetime_swapped_2z_amount: Uint<128, 2>,
+
+    /// Total 2Z tokens swept into the reserve 2Z token account as the protocol
+    /// treasury's share. The reserve 2Z token account may hold other 2Z tokens,
+    /// so this amount tracks the treasury's portion of its balance.
+    pub total_treasury_2z_amount: u64,
 }
 
 impl PrecomputedDiscriminator for Journal {
     const DISCRIMINATOR: Discriminator<8> = Discriminator::new_sha2(b"dz::account::journal");
 }
 
+impl VersionedAccount for Journal {
+    const VERSION: u8 = 1;
+    const VERSION_OFFSET: usize = std::mem::offset_of!(Journal, version);
+}
+
 impl Journal {
     pub const SEED_PREFIX: &'static [u8] = b"journal";
 
@@ -54,6 +70,60 @@ impl Journal {
     }
 }
 
+/// Difference between the actual balances of the accounts tracked by the
+/// journal and the balances recorded in the journal. A positive drift means an
+/// account holds more than the journal expects.
+///
+/// This struct is set as the return data of the assert journal invariants
+/// instruction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct JournalDrift {
+    /// Journal lamports in excess of rent exemption compared to the journal's
+    /// total SOL balance. Swapped SOL is not included because it has already
+    /// been withdrawn from the journal.
+    pub sol_drift: i64,
+
+    /// Journal 2Z token account balance compared to the journal's total 2Z
+    /// balance.
+    pub token_2z_drift: i64,
+
+    /// Swap destination 2Z token account balance compared to the journal's
+    /// swap destination balance.
+    pub swap_destination_2z_drift: i64,
+}
+
+impl JournalDrift {
+    pub fn new(
+        journal: &Journal,
+        sol_balance: u64,
+        token_2z_balance: u64,
+        swap_destination_2z_balance: u64,
+    ) -> Self {
+        Self {
+            sol_drift: saturating_drift(sol_balance, journal.total_sol_balance),
+            token_2z_drift: saturating_drift(token_2z_balance, journal.total_2z_balance),
+            swap_destination_2z_drift: saturating_drift(
+                swap_destination_2z_balance,
+                journal.swap_2z_destination_balance,
+            ),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn try_from_return_data(data: &[u8]) -> Option<Self> {
+        bytemuck::try_pod_read_unaligned(data).ok()
+    }
+}
+
+fn saturating_drift(actual: u64, expected: u64) -> i64 {
+    let drift = i128::from(actual) - i128::from(expected);
+    drift.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,4 +136,34 @@ mod tests {
         };
         assert_eq!(journal.lifetime_swapped_2z_amount(), 69_420);
     }
+
+    #[test]
+    fn test_journal_drift() {
+        let journal = Journal {
+            total_sol_balance: 1_000,
+            total_2z_balance: 2_000,
+            swap_2z_destination_balance: 3_000,
+            ..Default::default()
+        };
+
+        let drift = JournalDrift::new(&journal, 1_000, 2_000, 3_000);
+        assert!(drift.is_zero());
+
+        let drift = JournalDrift::new(&journal, 1_100, 1_800, u64::MAX);
+        assert_eq!(
+            drift,
+            JournalDrift {
+                sol_drift: 100,
+                token_2z_drift: -200,
+                swap_destination_2z_drift: i64::MAX,
+            }
+        );
+        assert!(!drift.is_zero());
+
+        assert_eq!(
+            JournalDrift::try_from_return_data(bytemuck::bytes_of(&drift)),
+            Some(drift)
+        );
+        assert_eq!(JournalDrift::try_from_return_data(&[0; 8]), None);
+    }
 }
@@ -1,16 +1,24 @@
 mod contributor_rewards;
 mod distribution;
+mod fee_parameter_history;
 mod journal;
+mod payment_receipt;
 mod program_config;
+mod query;
 mod rewards_integration;
 mod solana_validator_deposit;
+mod swap_session;
 
 pub use contributor_rewards::*;
 pub use distribution::*;
+pub use fee_parameter_history::*;
 pub use journal::*;
+pub use payment_receipt::*;
 pub use program_config::*;
+pub use query::*;
 pub use rewards_integration::*;
 pub use solana_validator_deposit::*;
+pub use swap_session::*;
 
 //
 
@@ -0,0 +1,42 @@
+use bytemuck::{Pod, Zeroable};
+use doublezero_program_tools::{types::StorageGap, Discriminator, PrecomputedDiscriminator};
+use solana_pubkey::Pubkey;
+
+use crate::types::DoubleZeroEpoch;
+
+/// Proof that a Solana validator paid its debt for a specific DZ epoch. This
+/// account is optionally created when paying Solana validator debt and is
+/// never modified afterwards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C, align(8))]
+pub struct PaymentReceipt {
+    pub node_id: Pubkey,
+    pub dz_epoch: DoubleZeroEpoch,
+
+    /// Lamports paid from the Solana validator deposit.
+    pub amount: u64,
+
+    /// Solana slot when the debt was paid.
+    pub slot: u64,
+
+    pub bump_seed: u8,
+    _padding: [u8; 7],
+
+    _storage_gap: StorageGap<1>,
+}
+
+impl PrecomputedDiscriminator for PaymentReceipt {
+    const DISCRIMINATOR: Discriminator<8> =
+        Discriminator::new_sha2(b"dz::account::payment_receipt");
+}
+
+impl PaymentReceipt {
+    pub const SEED_PREFIX: &'static [u8] = b"payment_receipt";
+
+    pub fn find_address(dz_epoch: DoubleZeroEpoch, node_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED_PREFIX, &dz_epoch.as_seed(), node_id.as_ref()],
+            &crate::ID,
+        )
+    }
+}
@@ -0,0 +1,27 @@
+use bytemuck::{Pod, Zeroable};
+use solana_pubkey::Pubkey;
+
+use crate::types::DoubleZeroEpoch;
+
+/// An additional key accepted for an authority role while the primary key is
+/// being rotated. Without an overlap, rotating an accountant key mid-epoch can
+/// strand a calculation that the old key has already started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SecondaryAuthority {
+    pub key: Pubkey,
+
+    /// The key is accepted until the program config's next completed DZ epoch
+    /// reaches this epoch.
+    pub expiry_dz_epoch: DoubleZeroEpoch,
+}
+
+impl SecondaryAuthority {
+    pub fn is_active(&self, next_completed_dz_epoch: DoubleZeroEpoch) -> bool {
+        self.key != Pubkey::default() && next_completed_dz_epoch < self.expiry_dz_epoch
+    }
+
+    pub fn is_active_key(&self, key: &Pubkey, next_completed_dz_epoch: DoubleZeroEpoch) -> bool {
+        self.is_active(next_completed_dz_epoch) && &self.key == key
+    }
+}
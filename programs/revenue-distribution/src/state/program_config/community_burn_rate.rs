@@ -144,6 +144,15 @@ impl CommunityBurnRateParameters {
         Some(next_burn_rate)
     }
 
+    /// Returns the community burn rates that [Self::checked_compute] would
+    /// return for each subsequent DZ epoch without modifying these parameters.
+    /// Once the limit is reached, the iterator yields the limit indefinitely,
+    /// so callers should bound it with [Iterator::take].
+    pub fn projected_burn_rates(&self) -> impl Iterator<Item = BurnRate> {
+        let mut params = *self;
+        std::iter::from_fn(move || params.checked_compute())
+    }
+
     /// Update the parameters for the community burn rate calculation.
     ///
     /// If the new configured limit ends up being less than the last cached burn
@@ -543,6 +552,55 @@ mod tests {
         }
     }
 
+    //
+    // CommunityBurnRateParameters::projected_burn_rates
+    //
+
+    #[test]
+    fn test_projected_burn_rates() {
+        let initial_rate = BurnRate::new(100_000_000).unwrap(); // 10%.
+        let limit = BurnRate::new(500_000_000).unwrap(); // 50%.
+
+        let params = CommunityBurnRateParameters::new(initial_rate, limit, 2, 5).unwrap();
+
+        let projected = params.projected_burn_rates().take(8).collect::<Vec<_>>();
+        let expected = [
+            100_000_000,
+            100_000_000,
+            200_000_000,
+            300_000_000,
+            400_000_000,
+            500_000_000,
+            500_000_000,
+            500_000_000,
+        ]
+        .map(|rate| BurnRate::new(rate).unwrap());
+        assert_eq!(projected, expected);
+
+        // Projection matches computing the burn rate for each DZ epoch.
+
+        let mut computed_params = params;
+        for projected_rate in params.projected_burn_rates().take(8) {
+            assert_eq!(computed_params.checked_compute().unwrap(), projected_rate);
+        }
+
+        // The parameters themselves are unchanged.
+        assert_eq!(
+            params,
+            CommunityBurnRateParameters::new(initial_rate, limit, 2, 5).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_projected_burn_rates_uninitialized() {
+        assert_eq!(
+            CommunityBurnRateParameters::default()
+                .projected_burn_rates()
+                .next(),
+            None
+        );
+    }
+
     //
     // CommunityBurnRateParameters::checked_update
     //
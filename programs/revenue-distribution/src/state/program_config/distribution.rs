@@ -45,7 +45,14 @@ pub struct DistributionParameters {
     /// represents a proportion of SOL rewards.
     pub solana_validator_fee_parameters: SolanaValidatorFeeParameters,
 
-    _storage_gap: StorageGap<8>,
+    /// Solana validator debt below this amount (in lamports) is considered
+    /// dust. The debt accountant should treat dust as zero when calculating
+    /// debt, and dust debt that was still included in a distribution's debt
+    /// can be written off even if the validator's deposit could pay it.
+    pub minimum_solana_validator_debt: u64,
+    _padding_1: [u8; 24],
+
+    _storage_gap: StorageGap<7>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
@@ -1,7 +1,9 @@
+mod authority;
 mod community_burn_rate;
 mod distribution;
 mod relay;
 
+pub use authority::*;
 pub use community_burn_rate::*;
 pub use distribution::*;
 pub use relay::*;
@@ -9,10 +11,13 @@ pub use relay::*;
 //
 
 use bytemuck::{Pod, Zeroable};
-use doublezero_program_tools::{types::Flags, Discriminator, PrecomputedDiscriminator};
+use doublezero_program_tools::{
+    define_flags, types::Flags, zero_copy::VersionedAccount, Discriminator,
+    PrecomputedDiscriminator,
+};
 use solana_pubkey::Pubkey;
 
-use crate::types::{DoubleZeroEpoch, EpochDuration};
+use crate::types::{DoubleZeroEpoch, EpochDuration, UnitShare16};
 
 use super::checked_2z_token_pda_address;
 
@@ -40,7 +45,10 @@ pub struct ProgramConfig {
     /// the required signer of [Self::sol_2z_swap_program_id] to withdraw SOL.
     pub withdraw_sol_authority_bump_seed: u8,
 
-    _padding_0: [u8; 3],
+    /// Account version, which is updated by the migrate program accounts
+    /// instruction.
+    pub version: u8,
+    _padding_0: [u8; 2],
 
     pub admin_key: Pubkey,
 
@@ -65,25 +73,61 @@ pub struct ProgramConfig {
     pub relay_parameters: RelayParameters,
 
     pub last_initialized_distribution_timestamp: u32,
-    _padding_1: [u8; 4],
+
+    /// Share of 2Z tokens converted from SOL debt that is transferred to the
+    /// reserve 2Z token account (the protocol treasury) when distribution
+    /// tokens are swept. The rest is transferred to the distribution. The
+    /// treasury's total is tracked by the journal's
+    /// [total_treasury_2z_amount](super::Journal::total_treasury_2z_amount).
+    pub treasury_2z_share: UnitShare16,
+    _padding_1: [u8; 2],
 
     /// DoubleZero epoch when the debt write-off feature activates. For more
     /// information, please refer to [RFC-0002].
     ///
     /// [RFC-0002]: https://github.com/doublezerofoundation/doublezero-solana/blob/main/docs/rfc/0002_IMPROVED_DEBT_WRITE_OFF_TRACKING.md
     pub debt_write_off_feature_activation_epoch: DoubleZeroEpoch,
+
+    /// Relayers allowed to collect relay lamports. Unused entries are the
+    /// default pubkey. If there are no allowed relayers, any relayer can
+    /// collect relay lamports.
+    pub allowed_relayer_keys: [Pubkey; MAX_ALLOWED_RELAYERS],
+
+    /// Debt accountant accepted alongside [Self::debt_accountant_key] while
+    /// the debt accountant is being rotated.
+    pub secondary_debt_accountant: SecondaryAuthority,
+
+    /// Rewards accountant accepted alongside [Self::rewards_accountant_key]
+    /// while the rewards accountant is being rotated.
+    pub secondary_rewards_accountant: SecondaryAuthority,
+
+    /// Receives lamports and 2Z tokens (via its ATA) moved out of the program
+    /// by an emergency withdrawal. Emergency withdrawals are not allowed until
+    /// this key is configured.
+    pub emergency_recovery_key: Pubkey,
+}
+
+define_flags! {
+    /// Flag bits stored in [ProgramConfig::flags].
+    pub enum ProgramConfigFlag: Flags {
+        IsPaused = 0,
+        IsMigrated = 1,
+        HasEmergencyWithdrawn = 2,
+    }
 }
 
 impl PrecomputedDiscriminator for ProgramConfig {
     const DISCRIMINATOR: Discriminator<8> = Discriminator::new_sha2(b"dz::account::program_config");
 }
 
+impl VersionedAccount for ProgramConfig {
+    const VERSION: u8 = 1;
+    const VERSION_OFFSET: usize = std::mem::offset_of!(ProgramConfig, version);
+}
+
 impl ProgramConfig {
     pub const SEED_PREFIX: &'static [u8] = b"program_config";
 
-    pub const FLAG_IS_PAUSED_BIT: usize = 0;
-    pub const FLAG_IS_MIGRATED_BIT: usize = 1;
-
     pub fn find_address() -> (Pubkey, u8) {
         Pubkey::find_program_address(&[Self::SEED_PREFIX], &crate::ID)
     }
@@ -135,20 +179,28 @@ impl ProgramConfig {
     }
 
     pub fn is_paused(&self) -> bool {
-        self.flags.bit(Self::FLAG_IS_PAUSED_BIT)
+        ProgramConfigFlag::IsPaused.is_set(&self.flags)
     }
 
     pub fn set_is_paused(&mut self, should_pause: bool) {
-        self.flags.set_bit(Self::FLAG_IS_PAUSED_BIT, should_pause);
+        ProgramConfigFlag::IsPaused.set(&mut self.flags, should_pause);
     }
 
     pub fn is_migrated(&self) -> bool {
-        self.flags.bit(Self::FLAG_IS_MIGRATED_BIT)
+        ProgramConfigFlag::IsMigrated.is_set(&self.flags)
     }
 
     pub fn set_is_migrated(&mut self, should_migrate: bool) {
-        self.flags
-            .set_bit(Self::FLAG_IS_MIGRATED_BIT, should_migrate);
+        ProgramConfigFlag::IsMigrated.set(&mut self.flags, should_migrate);
+    }
+
+    pub fn has_emergency_withdrawn(&self) -> bool {
+        ProgramConfigFlag::HasEmergencyWithdrawn.is_set(&self.flags)
+    }
+
+    /// Once set, this flag cannot be cleared.
+    pub fn set_has_emergency_withdrawn(&mut self) {
+        ProgramConfigFlag::HasEmergencyWithdrawn.set(&mut self.flags, true);
     }
 
     // TODO: Remove this in the next zero-versioned minor release.
@@ -178,6 +230,12 @@ impl ProgramConfig {
         }
     }
 
+    /// Whether the debt amount is below the configured minimum Solana
+    /// validator debt. No debt is dust if the minimum is not configured.
+    pub fn is_dust_solana_validator_debt(&self, amount: u64) -> bool {
+        amount < self.distribution_parameters.minimum_solana_validator_debt
+    }
+
     pub fn checked_calculation_grace_period_seconds(&self) -> Option<u32> {
         let grace_period = self
             .distribution_parameters
@@ -211,12 +269,88 @@ impl ProgramConfig {
 
         activation_epoch != 0 && self.next_completed_dz_epoch >= activation_epoch
     }
+
+    pub fn is_relayer_allowed(&self, relayer_key: &Pubkey) -> bool {
+        let mut allowed_relayer_keys = self
+            .allowed_relayer_keys
+            .iter()
+            .filter(|key| key != &&Pubkey::default())
+            .peekable();
+
+        allowed_relayer_keys.peek().is_none() || allowed_relayer_keys.any(|key| key == relayer_key)
+    }
+
+    pub fn checked_emergency_recovery_key(&self) -> Option<Pubkey> {
+        if self.emergency_recovery_key == Pubkey::default() {
+            None
+        } else {
+            Some(self.emergency_recovery_key)
+        }
+    }
+
+    pub fn is_debt_accountant(&self, key: &Pubkey) -> bool {
+        key == &self.debt_accountant_key
+            || self
+                .secondary_debt_accountant
+                .is_active_key(key, self.next_completed_dz_epoch)
+    }
+
+    pub fn is_rewards_accountant(&self, key: &Pubkey) -> bool {
+        key == &self.rewards_accountant_key
+            || self
+                .secondary_rewards_accountant
+                .is_active_key(key, self.next_completed_dz_epoch)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use doublezero_program_tools::{
+        zero_copy::{self, checked_from_bytes_with_discriminator},
+        DISCRIMINATOR_LEN,
+    };
+    use solana_account_info::MAX_PERMITTED_DATA_INCREASE;
+
     use super::*;
 
+    #[test]
+    fn test_preallocated_account_data() {
+        // Program config accounts are created with the maximum data length
+        // allowed (10kb). Existing accounts only have the fields up to the
+        // debt write-off feature activation epoch initialized.
+        let initialized_len = std::mem::offset_of!(ProgramConfig, allowed_relayer_keys);
+
+        let program_config = ProgramConfig {
+            next_completed_dz_epoch: DoubleZeroEpoch::new(69),
+            admin_key: Pubkey::new_unique(),
+            debt_accountant_key: Pubkey::new_unique(),
+            rewards_accountant_key: Pubkey::new_unique(),
+            debt_write_off_feature_activation_epoch: DoubleZeroEpoch::new(420),
+            ..Default::default()
+        };
+
+        let mut data = vec![0; MAX_PERMITTED_DATA_INCREASE];
+        data[..DISCRIMINATOR_LEN].copy_from_slice(ProgramConfig::discriminator_slice());
+        data[DISCRIMINATOR_LEN..][..initialized_len]
+            .copy_from_slice(&bytemuck::bytes_of(&program_config)[..initialized_len]);
+
+        let (loaded_program_config, remaining_data) =
+            checked_from_bytes_with_discriminator::<ProgramConfig>(&data).unwrap();
+        assert_eq!(loaded_program_config, &program_config);
+        assert_eq!(
+            remaining_data.len(),
+            MAX_PERMITTED_DATA_INCREASE - zero_copy::data_end::<ProgramConfig>()
+        );
+
+        // Appended fields read as their defaults.
+        assert!(loaded_program_config.is_relayer_allowed(&Pubkey::new_unique()));
+        assert!(!loaded_program_config.is_debt_accountant(&Pubkey::default()));
+        assert!(!loaded_program_config.is_rewards_accountant(&Pubkey::default()));
+        assert!(loaded_program_config
+            .checked_emergency_recovery_key()
+            .is_none());
+    }
+
     #[test]
     fn test_is_paused() {
         let mut program_config = ProgramConfig::default();
@@ -241,6 +375,16 @@ mod tests {
         assert!(!program_config.is_migrated());
     }
 
+    #[test]
+    fn test_has_emergency_withdrawn() {
+        let mut program_config = ProgramConfig::default();
+        assert!(!program_config.has_emergency_withdrawn());
+
+        program_config.set_has_emergency_withdrawn();
+        assert!(program_config.has_emergency_withdrawn());
+        assert!(!program_config.is_paused());
+    }
+
     #[test]
     fn test_checked_solana_validator_fee_parameters() {
         const FIXED_SOL_AMOUNT: u32 = 69;
@@ -304,10 +448,25 @@ mod tests {
             program_config
                 .checked_minimum_epoch_duration_to_finalize_rewards()
                 .unwrap(),
-            MINIMUM_EPOCH_DURATION_TO_FINALIZE_REWARDS.into()
+            u32::from(MINIMUM_EPOCH_DURATION_TO_FINALIZE_REWARDS)
         );
     }
 
+    #[test]
+    fn test_is_dust_solana_validator_debt() {
+        const MINIMUM_SOLANA_VALIDATOR_DEBT: u64 = 69_420;
+
+        let mut program_config = ProgramConfig::default();
+        assert!(!program_config.is_dust_solana_validator_debt(0));
+
+        program_config
+            .distribution_parameters
+            .minimum_solana_validator_debt = MINIMUM_SOLANA_VALIDATOR_DEBT;
+        assert!(program_config.is_dust_solana_validator_debt(0));
+        assert!(program_config.is_dust_solana_validator_debt(MINIMUM_SOLANA_VALIDATOR_DEBT - 1));
+        assert!(!program_config.is_dust_solana_validator_debt(MINIMUM_SOLANA_VALIDATOR_DEBT));
+    }
+
     #[test]
     fn test_checked_calculation_grace_period_seconds() {
         const CALCULATION_GRACE_PERIOD_SECONDS: u16 = 69;
@@ -391,4 +550,67 @@ mod tests {
             .saturating_add_duration(1);
         assert!(program_config.is_debt_write_off_feature_activated());
     }
+
+    #[test]
+    fn test_checked_emergency_recovery_key() {
+        let mut program_config = ProgramConfig::default();
+        assert!(program_config.checked_emergency_recovery_key().is_none());
+
+        let emergency_recovery_key = Pubkey::new_unique();
+        program_config.emergency_recovery_key = emergency_recovery_key;
+        assert_eq!(
+            program_config.checked_emergency_recovery_key(),
+            Some(emergency_recovery_key)
+        );
+    }
+
+    #[test]
+    fn test_is_relayer_allowed() {
+        let mut program_config = ProgramConfig::default();
+
+        // Any relayer is allowed without an allowlist.
+        let relayer_key = Pubkey::new_unique();
+        assert!(program_config.is_relayer_allowed(&relayer_key));
+
+        let allowed_relayer_key = Pubkey::new_unique();
+        program_config.allowed_relayer_keys[1] = allowed_relayer_key;
+        assert!(program_config.is_relayer_allowed(&allowed_relayer_key));
+        assert!(!program_config.is_relayer_allowed(&relayer_key));
+        assert!(!program_config.is_relayer_allowed(&Pubkey::default()));
+    }
+
+    #[test]
+    fn test_is_accountant_with_secondary() {
+        let mut program_config = ProgramConfig::default();
+
+        let debt_accountant_key = Pubkey::new_unique();
+        let secondary_debt_accountant_key = Pubkey::new_unique();
+        let rewards_accountant_key = Pubkey::new_unique();
+
+        program_config.debt_accountant_key = debt_accountant_key;
+        program_config.rewards_accountant_key = rewards_accountant_key;
+        program_config.next_completed_dz_epoch = DoubleZeroEpoch::new(10);
+
+        // Without a secondary key, only the primary key is accepted.
+        assert!(program_config.is_debt_accountant(&debt_accountant_key));
+        assert!(!program_config.is_debt_accountant(&secondary_debt_accountant_key));
+        assert!(!program_config.is_debt_accountant(&Pubkey::default()));
+
+        program_config.secondary_debt_accountant = SecondaryAuthority {
+            key: secondary_debt_accountant_key,
+            expiry_dz_epoch: DoubleZeroEpoch::new(11),
+        };
+        assert!(program_config.is_debt_accountant(&debt_accountant_key));
+        assert!(program_config.is_debt_accountant(&secondary_debt_accountant_key));
+
+        // Secondary keys are specific to their role.
+        assert!(!program_config.is_rewards_accountant(&secondary_debt_accountant_key));
+        assert!(program_config.is_rewards_accountant(&rewards_accountant_key));
+
+        // The secondary key expires once the next completed DZ epoch reaches
+        // the expiry epoch.
+        program_config.next_completed_dz_epoch = DoubleZeroEpoch::new(11);
+        assert!(!program_config.is_debt_accountant(&secondary_debt_accountant_key));
+        assert!(program_config.is_debt_accountant(&debt_accountant_key));
+    }
 }
@@ -1,6 +1,10 @@
 use bytemuck::{Pod, Zeroable};
 use doublezero_program_tools::types::StorageGap;
 
+/// Maximum number of relayers that can be registered to collect relay
+/// lamports.
+pub const MAX_ALLOWED_RELAYERS: usize = 4;
+
 /// Specific amounts to pay actors that execute instructions on behalf of
 /// others.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
@@ -0,0 +1,100 @@
+use bytemuck::{Pod, Zeroable};
+use solana_pubkey::Pubkey;
+
+use crate::types::{BurnRate, DoubleZeroEpoch};
+
+/// Community burn rates derived from the program config's community burn rate
+/// parameters.
+///
+/// This struct is set as the return data of the query instruction for the
+/// community burn rate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct CommunityBurnRateView {
+    /// The DZ epoch of the next distribution to be initialized.
+    pub dz_epoch: DoubleZeroEpoch,
+
+    /// The community burn rate the next distribution will be initialized with.
+    pub burn_rate: BurnRate,
+
+    /// The projected community burn rate for the distribution following the
+    /// next one.
+    pub next_dz_epoch_burn_rate: BurnRate,
+
+    /// The configured community burn rate limit.
+    pub limit: BurnRate,
+
+    _padding: [u8; 4],
+}
+
+impl CommunityBurnRateView {
+    pub fn new(
+        dz_epoch: DoubleZeroEpoch,
+        burn_rate: BurnRate,
+        next_dz_epoch_burn_rate: BurnRate,
+        limit: BurnRate,
+    ) -> Self {
+        Self {
+            dz_epoch,
+            burn_rate,
+            next_dz_epoch_burn_rate,
+            limit,
+            _padding: Default::default(),
+        }
+    }
+
+    pub fn try_from_return_data(data: &[u8]) -> Option<Self> {
+        bytemuck::try_pod_read_unaligned(data).ok()
+    }
+}
+
+/// 2Z tokens held by a program account's 2Z token PDA.
+///
+/// This struct is set as the return data of the query instruction for a 2Z
+/// token balance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct Token2zBalanceView {
+    /// Owner of the 2Z token PDA (e.g. the journal or a distribution).
+    pub token_owner_key: Pubkey,
+
+    pub amount: u64,
+}
+
+impl Token2zBalanceView {
+    pub fn try_from_return_data(data: &[u8]) -> Option<Self> {
+        bytemuck::try_pod_read_unaligned(data).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views_from_return_data() {
+        let community_burn_rate_view = CommunityBurnRateView::new(
+            DoubleZeroEpoch::new(69),
+            BurnRate::new(100_000_000).unwrap(),
+            BurnRate::new(110_000_000).unwrap(),
+            BurnRate::new(500_000_000).unwrap(),
+        );
+        assert_eq!(
+            CommunityBurnRateView::try_from_return_data(bytemuck::bytes_of(
+                &community_burn_rate_view
+            )),
+            Some(community_burn_rate_view)
+        );
+        assert_eq!(CommunityBurnRateView::try_from_return_data(&[0; 8]), None);
+
+        let token_2z_balance_view = Token2zBalanceView {
+            token_owner_key: Pubkey::new_unique(),
+            amount: 69_420,
+        };
+        assert_eq!(
+            Token2zBalanceView::try_from_return_data(bytemuck::bytes_of(&token_2z_balance_view)),
+            Some(token_2z_balance_view)
+        );
+        assert_eq!(Token2zBalanceView::try_from_return_data(&[0; 8]), None);
+    }
+}
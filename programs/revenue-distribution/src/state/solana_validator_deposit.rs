@@ -1,18 +1,48 @@
 use bytemuck::{Pod, Zeroable};
-use doublezero_program_tools::{types::StorageGap, Discriminator, PrecomputedDiscriminator};
+use doublezero_program_tools::{
+    assert_field_offset, define_flags, types::StorageGap, Discriminator, PrecomputedDiscriminator,
+};
 use solana_pubkey::Pubkey;
 
+use crate::types::{ByteFlags, DoubleZeroEpoch};
+
+/// Number of most recent payments kept in a Solana validator deposit's
+/// payment history.
+pub const PAYMENT_HISTORY_CAPACITY: usize = 4;
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
 #[repr(C, align(8))]
 pub struct SolanaValidatorDeposit {
     pub node_id: Pubkey,
 
     pub written_off_sol_debt: u64,
-    _padding: [u8; 24],
+
+    /// Cumulative lamports deposited via `DepositSolForValidator`, plus
+    /// lamports transferred directly to this account once they are attributed
+    /// to this validator via `ReconcileDepositSurplus`.
+    pub deposited_sol_amount: u64,
+
+    /// Lamports above the rent exemption that are accounted for by deposits,
+    /// debt payments and withdrawals. Any lamports beyond this balance are
+    /// surplus, which the debt accountant attributes to this validator via
+    /// `ReconcileDepositSurplus`. Only meaningful once the balance is tracked.
+    pub recorded_sol_balance: u64,
+
+    pub flags: ByteFlags,
+    _padding_1: [u8; 7],
 
     _storage_gap: StorageGap<1>,
 }
 
+assert_field_offset!(SolanaValidatorDeposit, _storage_gap, 64);
+
+define_flags! {
+    /// Flag bits stored in [SolanaValidatorDeposit::flags].
+    pub enum SolanaValidatorDepositFlag: ByteFlags {
+        IsBalanceTracked = 0,
+    }
+}
+
 impl PrecomputedDiscriminator for SolanaValidatorDeposit {
     const DISCRIMINATOR: Discriminator<8> =
         Discriminator::new_sha2(b"dz::account::solana_validator_deposit");
@@ -21,7 +51,116 @@ impl PrecomputedDiscriminator for SolanaValidatorDeposit {
 impl SolanaValidatorDeposit {
     pub const SEED_PREFIX: &'static [u8] = b"solana_validator_deposit";
 
+    /// Length of the remaining data storing the payment history.
+    pub const PAYMENT_HISTORY_LEN: usize = size_of::<SolanaValidatorPaymentHistory>();
+
     pub fn find_address(node_id: &Pubkey) -> (Pubkey, u8) {
         Pubkey::find_program_address(&[Self::SEED_PREFIX, node_id.as_ref()], &crate::ID)
     }
+
+    /// Returns the payment history stored in the account's remaining data,
+    /// which may not be aligned. Deposits initialized before payment history
+    /// was added do not have one.
+    pub fn payment_history(remaining_data: &[u8]) -> Option<SolanaValidatorPaymentHistory> {
+        remaining_data
+            .get(..Self::PAYMENT_HISTORY_LEN)
+            .map(bytemuck::pod_read_unaligned)
+    }
+
+    /// Deposits initialized before the recorded SOL balance was added start
+    /// tracking it when surplus lamports are first reconciled.
+    pub fn is_balance_tracked(&self) -> bool {
+        SolanaValidatorDepositFlag::IsBalanceTracked.is_set(&self.flags)
+    }
+
+    pub fn set_is_balance_tracked(&mut self, should_track: bool) {
+        SolanaValidatorDepositFlag::IsBalanceTracked.set(&mut self.flags, should_track);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SolanaValidatorPayment {
+    pub dz_epoch: DoubleZeroEpoch,
+
+    /// Lamports paid from the Solana validator deposit.
+    pub amount: u64,
+
+    /// Unix timestamp when the debt was paid.
+    pub timestamp: i64,
+}
+
+/// Ring buffer of the most recent debt payments made from a Solana validator
+/// deposit, so they can be queried on-chain without an indexer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SolanaValidatorPaymentHistory {
+    /// Total number of payments recorded. The next payment is written to
+    /// index `payments_count % PAYMENT_HISTORY_CAPACITY`.
+    pub payments_count: u64,
+
+    pub payments: [SolanaValidatorPayment; PAYMENT_HISTORY_CAPACITY],
+}
+
+impl SolanaValidatorPaymentHistory {
+    pub fn record(&mut self, payment: SolanaValidatorPayment) {
+        let index = self.payments_count % PAYMENT_HISTORY_CAPACITY as u64;
+        self.payments[index as usize] = payment;
+        self.payments_count = self.payments_count.saturating_add(1);
+    }
+
+    /// Iterate over recorded payments from most recent to oldest.
+    pub fn recent_iter(&self) -> impl Iterator<Item = &SolanaValidatorPayment> {
+        let len = self.payments_count.min(PAYMENT_HISTORY_CAPACITY as u64);
+
+        (1..=len).map(move |offset| {
+            let index = (self.payments_count - offset) % PAYMENT_HISTORY_CAPACITY as u64;
+            &self.payments[index as usize]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_history() {
+        let mut payment_history = SolanaValidatorPaymentHistory::default();
+        assert_eq!(payment_history.recent_iter().count(), 0);
+
+        let payments = (1..=6)
+            .map(|i| SolanaValidatorPayment {
+                dz_epoch: DoubleZeroEpoch::new(i),
+                amount: i * 1_000,
+                timestamp: i as i64,
+            })
+            .collect::<Vec<_>>();
+
+        payment_history.record(payments[0]);
+        payment_history.record(payments[1]);
+        assert_eq!(
+            payment_history.recent_iter().copied().collect::<Vec<_>>(),
+            [payments[1], payments[0]]
+        );
+
+        // Oldest payments are overwritten once the buffer is full.
+        for payment in payments[2..].iter() {
+            payment_history.record(*payment);
+        }
+        assert_eq!(payment_history.payments_count, 6);
+        assert_eq!(
+            payment_history.recent_iter().copied().collect::<Vec<_>>(),
+            [payments[5], payments[4], payments[3], payments[2]]
+        );
+
+        // Unaligned remaining data.
+        let mut remaining_data = [0; SolanaValidatorDeposit::PAYMENT_HISTORY_LEN + 1];
+        remaining_data[1..].copy_from_slice(bytemuck::bytes_of(&payment_history));
+        assert_eq!(
+            SolanaValidatorDeposit::payment_history(&remaining_data[1..]),
+            Some(payment_history)
+        );
+        assert_eq!(SolanaValidatorDeposit::payment_history(&[]), None);
+    }
 }
@@ -0,0 +1,38 @@
+use bytemuck::{Pod, Zeroable};
+use doublezero_program_tools::{types::StorageGap, Discriminator, PrecomputedDiscriminator};
+use solana_pubkey::Pubkey;
+
+/// Opened by the SOL/2Z swap program before it transfers 2Z to the swap
+/// destination and consumed by the withdraw SOL instruction that follows in
+/// the same transaction. The withdraw SOL instruction only credits 2Z that
+/// arrived in the swap destination while this session was open, so an
+/// unrelated 2Z transfer cannot be used to withdraw SOL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C, align(8))]
+pub struct SwapSession {
+    /// Receives the session's lamports when it is consumed.
+    pub rent_beneficiary_key: Pubkey,
+
+    /// Swap destination 2Z token account balance when the session was opened.
+    pub swap_destination_2z_amount: u64,
+
+    /// Solana slot when the session was opened.
+    pub slot: u64,
+
+    pub bump_seed: u8,
+    _padding: [u8; 7],
+
+    _storage_gap: StorageGap<1>,
+}
+
+impl PrecomputedDiscriminator for SwapSession {
+    const DISCRIMINATOR: Discriminator<8> = Discriminator::new_sha2(b"dz::account::swap_session");
+}
+
+impl SwapSession {
+    pub const SEED_PREFIX: &'static [u8] = b"swap_session";
+
+    pub fn find_address() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED_PREFIX], &crate::ID)
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt::Display;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use bytemuck::{Pod, Zeroable};
+use doublezero_program_tools::flags::FlagStorage;
 use solana_pubkey::Pubkey;
 
 #[derive(
@@ -290,6 +291,18 @@ impl ByteFlags {
     }
 }
 
+impl FlagStorage for ByteFlags {
+    const BIT_COUNT: usize = 8;
+
+    fn flag_bit(&self, index: usize) -> bool {
+        self.bit(index)
+    }
+
+    fn set_flag_bit(&mut self, index: usize, value: bool) {
+        self.set_bit(index, value);
+    }
+}
+
 impl From<ByteFlags> for u8 {
     fn from(value: ByteFlags) -> Self {
         value.0
@@ -0,0 +1,110 @@
+mod common;
+
+//
+
+use doublezero_program_tools::instruction::try_build_instruction;
+use doublezero_revenue_distribution::{
+    instruction::{account::AssertJournalInvariantsAccounts, RevenueDistributionInstructionData},
+    state::{self, Journal, JournalDrift},
+    DOUBLEZERO_MINT_KEY, ID,
+};
+use solana_program_test::tokio;
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+//
+// Assert journal invariants — no drift.
+//
+
+#[tokio::test]
+async fn test_assert_journal_invariants() {
+    let mut test_setup = common::start_test().await;
+
+    test_setup.setup_configured_program().await.unwrap();
+
+    test_setup
+        .initialize_swap_destination(&DOUBLEZERO_MINT_KEY)
+        .await
+        .unwrap();
+
+    let drift = test_setup.assert_journal_invariants().await.unwrap();
+    assert!(drift.is_zero());
+}
+
+//
+// Assert journal invariants — drift.
+//
+
+#[tokio::test]
+async fn test_assert_journal_invariants_with_drift() {
+    let mut test_setup = common::start_test().await;
+
+    test_setup.setup_configured_program().await.unwrap();
+
+    let journal_key = Journal::find_address().0;
+    let journal_2z_token_pda_key = state::find_2z_token_pda_address(&journal_key).0;
+    let swap_destination_2z_key =
+        state::find_2z_token_pda_address(&state::find_swap_authority_address().0).0;
+
+    // Send SOL and 2Z directly to the accounts tracked by the journal, which
+    // the journal does not account for.
+    test_setup
+        .initialize_swap_destination(&DOUBLEZERO_MINT_KEY)
+        .await
+        .unwrap()
+        .transfer_lamports(&journal_key, 1_000)
+        .await
+        .unwrap()
+        .transfer_2z(&journal_2z_token_pda_key, 500)
+        .await
+        .unwrap()
+        .transfer_2z(&swap_destination_2z_key, 250)
+        .await
+        .unwrap();
+
+    let drift = test_setup.assert_journal_invariants().await.unwrap();
+    assert_eq!(
+        drift,
+        JournalDrift {
+            sol_drift: 1_000,
+            token_2z_drift: 500,
+            swap_destination_2z_drift: 250,
+        }
+    );
+
+    // The journal itself is not modified.
+    let (_, journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(journal.total_sol_balance, 0);
+    assert_eq!(journal.total_2z_balance, 0);
+    assert_eq!(journal.swap_2z_destination_balance, 0);
+}
+
+//
+// Assert journal invariants — swap destination not initialized.
+//
+
+#[tokio::test]
+async fn test_cannot_assert_journal_invariants_without_swap_destination() {
+    let mut test_setup = common::start_test().await;
+
+    test_setup.setup_configured_program().await.unwrap();
+
+    let assert_journal_invariants_ix = try_build_instruction(
+        &ID,
+        AssertJournalInvariantsAccounts::default(),
+        &RevenueDistributionInstructionData::AssertJournalInvariants,
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[assert_journal_invariants_ix], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Swap destination is not initialized"
+    );
+}
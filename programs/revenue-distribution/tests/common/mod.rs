@@ -20,29 +20,38 @@ fn init_logger() {
 }
 
 use doublezero_program_tools::{
-    instruction::try_build_instruction, zero_copy::checked_from_bytes_with_discriminator,
+    compute_units::{parse_consumed_compute_units, ComputeUnitBaselines},
+    instruction::try_build_instruction,
+    zero_copy::{self, checked_from_bytes_with_discriminator},
 };
 use doublezero_revenue_distribution::{
     instruction::{
         account::{
-            CollectIntegrationRewardsAccounts, ConfigureContributorRewardsAccounts,
-            ConfigureDistributionDebtAccounts, ConfigureDistributionRewardsAccounts,
-            ConfigureProgramAccounts, DistributeRewardsAccounts,
+            AssertJournalInvariantsAccounts, CollectIntegrationRewardsAccounts,
+            ConfigureContributorRewardsAccounts, ConfigureDistributionDebtAccounts,
+            ConfigureDistributionRewardsAccounts, ConfigureProgramAccounts,
+            DepositSolForValidatorAccounts, DistributeRewardsAccounts, EmergencyWithdrawAccounts,
             EnableSolanaValidatorDebtWriteOffAccounts, FinalizeDistributionDebtAccounts,
             FinalizeDistributionRewardsAccounts, InitializeContributorRewardsAccounts,
-            InitializeDistributionAccounts, InitializeJournalAccounts, InitializeProgramAccounts,
+            InitializeDistributionAccounts, InitializeFeeParameterHistoryAccounts,
+            InitializeJournalAccounts, InitializeProgramAccounts,
             InitializeRewardsIntegrationAccounts, InitializeSolanaValidatorDepositAccounts,
-            InitializeSwapDestinationAccounts, PaySolanaValidatorDebtAccounts, SetAdminAccounts,
-            SetDistributionEconomicBurnRateAccounts, SetRewardsManagerAccounts,
-            SweepDistributionTokensAccounts, VerifyDistributionMerkleRootAccounts,
-            WithdrawSolanaValidatorDepositAccounts, WriteOffSolanaValidatorDebtAccounts,
+            InitializeSwapDestinationAccounts, MigrateProgramAccountsAccounts,
+            PaySolanaValidatorDebtAccounts, ReconcileDepositSurplusAccounts,
+            ReconcileDistributionAccounts, ReplaceDistributionRewardsRootAccounts,
+            SetAdminAccounts, SetDistributionEconomicBurnRateAccounts,
+            SetRecipientSharesLockedAccounts, SetRewardsManagerAccounts,
+            SkipDistributionSweepAccounts, SweepDistributionTokensAccounts,
+            VerifyDistributionMerkleRootAccounts, WithdrawSolanaValidatorDepositAccounts,
+            WriteOffSolanaValidatorDebtAccounts,
         },
-        ContributorRewardsConfiguration, DistributionMerkleRootKind, ProgramConfiguration,
-        ProgramFlagConfiguration, RevenueDistributionInstructionData,
+        ContributorRewardsConfiguration, DistributionMerkleRootKind, EmergencyWithdrawSource,
+        ProgramConfiguration, ProgramFlagConfiguration, RevenueDistributionInstructionData,
     },
     state::{
-        self, ContributorRewards, Distribution, Journal, ProgramConfig, RewardsIntegration,
-        SolanaValidatorDeposit,
+        self, ContributorRewards, Distribution, FeeParameterHistory, Journal, JournalDrift,
+        PaymentReceipt, ProgramConfig, RewardsIntegration, SolanaValidatorDeposit,
+        SolanaValidatorPaymentHistory, SwapSession,
     },
     types::{DoubleZeroEpoch, RewardShare, SolanaValidatorDebt},
     DOUBLEZERO_MINT_KEY, ID,
@@ -57,8 +66,9 @@ use solana_sdk::{
     account::Account,
     clock::Clock,
     hash::Hash,
-    instruction::Instruction,
+    instruction::{Instruction, InstructionError},
     message::{v0::Message, VersionedMessage},
+    rent::Rent,
     signature::{Keypair, Signer},
     transaction::{TransactionError, VersionedTransaction},
 };
@@ -69,6 +79,10 @@ use spl_token_interface::{
 use svm_hash::merkle::MerkleProof;
 pub const TOTAL_2Z_SUPPLY: u64 = 10_000_000_000 * u64::pow(10, 8);
 
+/// Error the runtime reports when an instruction is not given enough accounts.
+#[allow(deprecated)]
+pub const NOT_ENOUGH_ACCOUNT_KEYS: InstructionError = InstructionError::NotEnoughAccountKeys;
+
 pub struct TestAccount {
     pub key: Pubkey,
     pub info: Account,
@@ -338,6 +352,75 @@ impl ProgramTestWithOwner {
         Ok((tx_err, simulated_tx.simulation_details.unwrap().logs))
     }
 
+    /// Simulate instructions that are expected to succeed and return the
+    /// return data set by the last instruction.
+    pub async fn simulate_return_data(
+        &mut self,
+        instructions: &[Instruction],
+    ) -> Result<Vec<u8>, BanksClientError> {
+        let recent_blockhash = self.get_latest_blockhash().await?;
+
+        let payer_signer = &self.context.payer;
+
+        let transaction = new_transaction(instructions, &[payer_signer], recent_blockhash);
+
+        let simulated_tx = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await?;
+
+        simulated_tx
+            .result
+            .ok_or(BanksClientError::ClientError(
+                "simulation returned no result",
+            ))?
+            .map_err(BanksClientError::TransactionError)?;
+
+        self.context.last_blockhash = recent_blockhash;
+
+        simulated_tx
+            .simulation_details
+            .and_then(|details| details.return_data)
+            .map(|return_data| return_data.data)
+            .ok_or(BanksClientError::ClientError("no return data"))
+    }
+
+    /// Process instructions and return the compute units consumed by each
+    /// instruction invoking the Revenue Distribution program.
+    pub async fn process_instructions_with_compute_units(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<u64>, BanksClientError> {
+        let recent_blockhash = self.get_latest_blockhash().await?;
+
+        let payer_signer = &self.context.payer;
+
+        let mut tx_signers = vec![payer_signer];
+        tx_signers.extend_from_slice(signers);
+
+        let transaction = new_transaction(instructions, &tx_signers, recent_blockhash);
+
+        let processed_tx = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        processed_tx
+            .result
+            .map_err(BanksClientError::TransactionError)?;
+
+        self.context.last_blockhash = recent_blockhash;
+
+        let logs = processed_tx
+            .metadata
+            .map(|metadata| metadata.log_messages)
+            .unwrap_or_default();
+
+        Ok(parse_consumed_compute_units(&ID, &logs))
+    }
+
     pub async fn transfer_lamports(
         &mut self,
         dst_key: &Pubkey,
@@ -464,6 +547,32 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    pub async fn migrate_program_accounts(
+        &mut self,
+        target_version: u8,
+        account_keys: &[Pubkey],
+    ) -> Result<&mut Self, BanksClientError> {
+        let owner_signer = &self.owner_signer;
+        let payer_signer = &self.context.payer;
+
+        let migrate_program_accounts_ix = try_build_instruction(
+            &ID,
+            MigrateProgramAccountsAccounts::new(&ID, &owner_signer.pubkey(), account_keys),
+            &RevenueDistributionInstructionData::MigrateProgramAccounts(target_version),
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[migrate_program_accounts_ix],
+            &[payer_signer, owner_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
     pub async fn configure_program<const N: usize>(
         &mut self,
         admin_signer: &Keypair,
@@ -476,7 +585,7 @@ impl ProgramTestWithOwner {
             .map(|setting| {
                 try_build_instruction(
                     &ID,
-                    ConfigureProgramAccounts::new(&admin_signer.pubkey()),
+                    ConfigureProgramAccounts::new(&admin_signer.pubkey(), false),
                     &RevenueDistributionInstructionData::ConfigureProgram(setting),
                 )
                 .unwrap()
@@ -519,6 +628,56 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    pub async fn initialize_fee_parameter_history(
+        &mut self,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let initialize_fee_parameter_history_ix = try_build_instruction(
+            &ID,
+            InitializeFeeParameterHistoryAccounts::new(&payer_signer.pubkey()),
+            &RevenueDistributionInstructionData::InitializeFeeParameterHistory,
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[initialize_fee_parameter_history_ix],
+            &[payer_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
+    /// Configure Solana validator fee parameters and record the change in the
+    /// fee parameter history.
+    pub async fn configure_program_with_fee_parameter_history(
+        &mut self,
+        admin_signer: &Keypair,
+        setting: ProgramConfiguration,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let configure_program_ix = try_build_instruction(
+            &ID,
+            ConfigureProgramAccounts::new(&admin_signer.pubkey(), true),
+            &RevenueDistributionInstructionData::ConfigureProgram(setting),
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[configure_program_ix],
+            &[payer_signer, admin_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
     pub async fn initialize_distribution(
         &mut self,
         accountant_signer: &Keypair,
@@ -641,6 +800,38 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    pub async fn replace_distribution_rewards_root(
+        &mut self,
+        dz_epoch: DoubleZeroEpoch,
+        accountant_signer: &Keypair,
+        total_contributors: u32,
+        merkle_root: Hash,
+        correction_memo: Hash,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let replace_distribution_rewards_root_ix = try_build_instruction(
+            &ID,
+            ReplaceDistributionRewardsRootAccounts::new(&accountant_signer.pubkey(), dz_epoch),
+            &RevenueDistributionInstructionData::ReplaceDistributionRewardsRoot {
+                total_contributors,
+                merkle_root,
+                correction_memo,
+            },
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[replace_distribution_rewards_root_ix],
+            &[payer_signer, accountant_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
     pub async fn set_distribution_economic_burn_rate(
         &mut self,
         dz_epoch: DoubleZeroEpoch,
@@ -691,6 +882,8 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    /// Distribute rewards, where the payer pays for the partially distributed
+    /// rewards bitmap if it has to be allocated.
     pub async fn distribute_rewards(
         &mut self,
         dz_epoch: DoubleZeroEpoch,
@@ -700,24 +893,58 @@ impl ProgramTestWithOwner {
         recipient_keys: &[&Pubkey],
         proof: MerkleProof,
     ) -> Result<&mut Self, BanksClientError> {
-        let payer_signer = &self.context.payer;
+        let distribute_rewards_accounts = DistributeRewardsAccounts::new(
+            dz_epoch,
+            &reward_share.contributor_key,
+            dz_mint_key,
+            relayer_key,
+            recipient_keys,
+            None, // ata_payer_key
+            Some(&self.context.payer.pubkey()),
+        );
+
+        self.process_distribute_rewards(distribute_rewards_accounts, reward_share, proof)
+            .await
+    }
+
+    /// Distribute rewards, where the payer pays for any missing recipient
+    /// ATAs.
+    pub async fn distribute_rewards_creating_recipient_atas(
+        &mut self,
+        dz_epoch: DoubleZeroEpoch,
+        reward_share: &RewardShare,
+        relayer_key: &Pubkey,
+        recipient_keys: &[&Pubkey],
+        proof: MerkleProof,
+    ) -> Result<&mut Self, BanksClientError> {
+        let distribute_rewards_accounts = DistributeRewardsAccounts::new(
+            dz_epoch,
+            &reward_share.contributor_key,
+            &DOUBLEZERO_MINT_KEY,
+            relayer_key,
+            recipient_keys,
+            Some(&self.context.payer.pubkey()),
+            Some(&self.context.payer.pubkey()),
+        );
 
-        let contributor_key = &reward_share.contributor_key;
-        let unit_share = reward_share.unit_share;
-        let economic_burn_rate = reward_share.economic_burn_rate();
+        self.process_distribute_rewards(distribute_rewards_accounts, reward_share, proof)
+            .await
+    }
+
+    async fn process_distribute_rewards(
+        &mut self,
+        distribute_rewards_accounts: DistributeRewardsAccounts,
+        reward_share: &RewardShare,
+        proof: MerkleProof,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
 
         let distribute_rewards_ix = try_build_instruction(
             &ID,
-            DistributeRewardsAccounts::new(
-                dz_epoch,
-                contributor_key,
-                dz_mint_key,
-                relayer_key,
-                recipient_keys,
-            ),
+            distribute_rewards_accounts,
             &RevenueDistributionInstructionData::DistributeRewards {
-                unit_share,
-                economic_burn_rate,
+                unit_share: reward_share.unit_share,
+                economic_burn_rate: reward_share.economic_burn_rate(),
                 proof,
             },
         )
@@ -800,6 +1027,7 @@ impl ProgramTestWithOwner {
                     ConfigureContributorRewardsAccounts::new(
                         &rewards_manager_signer.pubkey(),
                         service_key,
+                        Some(&payer_signer.pubkey()),
                     ),
                     &RevenueDistributionInstructionData::ConfigureContributorRewards(setting),
                 )
@@ -818,6 +1046,35 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    pub async fn set_recipient_shares_locked(
+        &mut self,
+        rewards_accountant_signer: &Keypair,
+        service_keys: &[&Pubkey],
+        should_lock: bool,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let set_recipient_shares_locked_ix = try_build_instruction(
+            &ID,
+            SetRecipientSharesLockedAccounts::new(
+                &rewards_accountant_signer.pubkey(),
+                service_keys,
+            ),
+            &RevenueDistributionInstructionData::SetRecipientSharesLocked(should_lock),
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[set_recipient_shares_locked_ix],
+            &[payer_signer, rewards_accountant_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
     pub async fn verify_distribution_merkle_root(
         &mut self,
         dz_epoch: DoubleZeroEpoch,
@@ -875,6 +1132,57 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    pub async fn deposit_sol_for_validator(
+        &mut self,
+        depositor_signer: &Keypair,
+        node_id: &Pubkey,
+        amount: u64,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let deposit_sol_for_validator_ix = try_build_instruction(
+            &ID,
+            DepositSolForValidatorAccounts::new(&depositor_signer.pubkey(), node_id),
+            &RevenueDistributionInstructionData::DepositSolForValidator(amount),
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[deposit_sol_for_validator_ix],
+            &[payer_signer, depositor_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn reconcile_deposit_surplus(
+        &mut self,
+        debt_accountant_signer: &Keypair,
+        node_id: &Pubkey,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let reconcile_deposit_surplus_ix = try_build_instruction(
+            &ID,
+            ReconcileDepositSurplusAccounts::new(&debt_accountant_signer.pubkey(), node_id),
+            &RevenueDistributionInstructionData::ReconcileDepositSurplus,
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[reconcile_deposit_surplus_ix],
+            &[payer_signer, debt_accountant_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
     pub async fn initialize_rewards_integration(
         &mut self,
         admin_signer: &Keypair,
@@ -943,12 +1251,18 @@ impl ProgramTestWithOwner {
         dz_epoch: DoubleZeroEpoch,
         debt: &SolanaValidatorDebt,
         proof: MerkleProof,
+        with_payment_receipt: bool,
     ) -> Result<&mut Self, BanksClientError> {
         let payer_signer = &self.context.payer;
+        let payer_key = payer_signer.pubkey();
 
         let pay_solana_validator_debt_ix = try_build_instruction(
             &ID,
-            PaySolanaValidatorDebtAccounts::new(dz_epoch, &debt.node_id),
+            PaySolanaValidatorDebtAccounts::new(
+                dz_epoch,
+                &debt.node_id,
+                with_payment_receipt.then_some(&payer_key),
+            ),
             &RevenueDistributionInstructionData::PaySolanaValidatorDebt {
                 amount: debt.amount,
                 proof,
@@ -1112,6 +1426,127 @@ impl ProgramTestWithOwner {
         Ok(self)
     }
 
+    pub async fn skip_distribution_sweep(
+        &mut self,
+        dz_epoch: DoubleZeroEpoch,
+        admin_signer: &Keypair,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let skip_distribution_sweep_ix = try_build_instruction(
+            &ID,
+            SkipDistributionSweepAccounts::new(&admin_signer.pubkey(), dz_epoch),
+            &RevenueDistributionInstructionData::SkipDistributionSweep,
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[skip_distribution_sweep_ix],
+            &[payer_signer, admin_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn emergency_withdraw(
+        &mut self,
+        admin_signer: &Keypair,
+        source: EmergencyWithdrawSource,
+        emergency_recovery_key: &Pubkey,
+    ) -> Result<&mut Self, BanksClientError> {
+        let owner_signer = &self.owner_signer;
+        let payer_signer = &self.context.payer;
+
+        let emergency_withdraw_ix = try_build_instruction(
+            &ID,
+            EmergencyWithdrawAccounts::new(
+                &ID,
+                &owner_signer.pubkey(),
+                &admin_signer.pubkey(),
+                source,
+                emergency_recovery_key,
+            ),
+            &RevenueDistributionInstructionData::EmergencyWithdraw(source),
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[emergency_withdraw_ix],
+            &[payer_signer, owner_signer, admin_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn reconcile_distribution(
+        &mut self,
+        dz_epoch: DoubleZeroEpoch,
+        rewards_accountant_signer: &Keypair,
+    ) -> Result<&mut Self, BanksClientError> {
+        let payer_signer = &self.context.payer;
+
+        let reconcile_distribution_ix = try_build_instruction(
+            &ID,
+            ReconcileDistributionAccounts::new(&rewards_accountant_signer.pubkey(), dz_epoch),
+            &RevenueDistributionInstructionData::ReconcileDistribution,
+        )
+        .unwrap();
+
+        self.context.last_blockhash = process_instructions_for_test(
+            &mut self.context.banks_client,
+            &self.context.last_blockhash,
+            &[reconcile_distribution_ix],
+            &[payer_signer, rewards_accountant_signer],
+        )
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn assert_journal_invariants(&mut self) -> Result<JournalDrift, BanksClientError> {
+        let recent_blockhash = self.get_latest_blockhash().await?;
+
+        let payer_signer = &self.context.payer;
+
+        let assert_journal_invariants_ix = try_build_instruction(
+            &ID,
+            AssertJournalInvariantsAccounts::default(),
+            &RevenueDistributionInstructionData::AssertJournalInvariants,
+        )
+        .unwrap();
+
+        let transaction = new_transaction(
+            &[assert_journal_invariants_ix],
+            &[payer_signer],
+            recent_blockhash,
+        );
+
+        let processed_tx = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        processed_tx
+            .result
+            .map_err(BanksClientError::TransactionError)?;
+
+        self.context.last_blockhash = recent_blockhash;
+
+        let return_data = processed_tx
+            .metadata
+            .and_then(|metadata| metadata.return_data)
+            .ok_or(BanksClientError::ClientError("no return data"))?;
+
+        JournalDrift::try_from_return_data(&return_data.data)
+            .ok_or(BanksClientError::ClientError("invalid journal drift"))
+    }
+
     //
     // Mock Swap SOL/2Z integration.
     //
@@ -1149,6 +1584,14 @@ impl ProgramTestWithOwner {
         let payer_signer = &self.context.payer;
         let fills_tracker_key = self.sol_2z_swap_fills_registry_key;
 
+        // The transfer authority pays for the swap session, which is refunded
+        // when SOL is withdrawn.
+        let fund_swap_session_ix = solana_system_interface::instruction::transfer(
+            &payer_signer.pubkey(),
+            &transfer_authority_signer.pubkey(),
+            Rent::default().minimum_balance(zero_copy::data_end::<SwapSession>()),
+        );
+
         let buy_sol_ix = mock_swap_sol_2z::instruction::buy_sol(
             &fills_tracker_key,
             source_2z_token_account_key,
@@ -1161,7 +1604,7 @@ impl ProgramTestWithOwner {
         self.context.last_blockhash = process_instructions_for_test(
             &mut self.context.banks_client,
             &self.context.last_blockhash,
-            &[buy_sol_ix],
+            &[fund_swap_session_ix, buy_sol_ix],
             &[payer_signer, transfer_authority_signer],
         )
         .await?;
@@ -1358,6 +1801,79 @@ impl ProgramTestWithOwner {
                 .0,
         )
     }
+
+    pub async fn fetch_solana_validator_payment_history(
+        &self,
+        node_id: &Pubkey,
+    ) -> Option<SolanaValidatorPaymentHistory> {
+        let solana_validator_deposit_account_data = self
+            .context
+            .banks_client
+            .get_account(SolanaValidatorDeposit::find_address(node_id).0)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        let (_, remaining_data) = checked_from_bytes_with_discriminator::<SolanaValidatorDeposit>(
+            &solana_validator_deposit_account_data,
+        )
+        .unwrap();
+
+        SolanaValidatorDeposit::payment_history(remaining_data)
+    }
+
+    pub async fn fetch_fee_parameter_history(&self) -> (Pubkey, FeeParameterHistory) {
+        let fee_parameter_history_key = FeeParameterHistory::find_address().0;
+
+        let fee_parameter_history_account_data = self
+            .context
+            .banks_client
+            .get_account(fee_parameter_history_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        (
+            fee_parameter_history_key,
+            *checked_from_bytes_with_discriminator(&fee_parameter_history_account_data)
+                .unwrap()
+                .0,
+        )
+    }
+
+    pub async fn fetch_payment_receipt(
+        &self,
+        dz_epoch: DoubleZeroEpoch,
+        node_id: &Pubkey,
+    ) -> (Pubkey, PaymentReceipt) {
+        let payment_receipt_key = PaymentReceipt::find_address(dz_epoch, node_id).0;
+
+        let payment_receipt_account_data = self
+            .context
+            .banks_client
+            .get_account(payment_receipt_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        (
+            payment_receipt_key,
+            *checked_from_bytes_with_discriminator(&payment_receipt_account_data)
+                .unwrap()
+                .0,
+        )
+    }
+}
+
+/// Fail if the compute units consumed by a measured instruction regress beyond
+/// the baseline recorded in `tests/compute_units`, or if there is no recorded
+/// baseline. Run with `UPDATE_COMPUTE_UNIT_BASELINES=1` to record baselines.
+pub fn assert_compute_units_within_baseline(name: &str, consumed: u64) {
+    ComputeUnitBaselines::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/compute_units"))
+        .assert_within_threshold(name, consumed);
 }
 
 pub async fn process_instructions_for_test(
@@ -2,15 +2,21 @@ mod common;
 
 //
 
+use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
 use doublezero_revenue_distribution::{
     instruction::{
-        ContributorRewardsConfiguration, ProgramConfiguration, ProgramFlagConfiguration,
+        account::ConfigureContributorRewardsAccounts, ContributorRewardsConfiguration,
+        ProgramConfiguration, ProgramFlagConfiguration, RevenueDistributionInstructionData,
     },
-    state::{ContributorRewards, RecipientShares},
+    state::{ContributorRewards, RecipientShares, MAX_RECIPIENTS},
+    ID,
 };
 use solana_program_test::tokio;
 use solana_pubkey::Pubkey;
-use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
 
 //
 // Setup.
@@ -107,3 +113,144 @@ async fn test_initialize_contributor_rewards() {
     expected_contributor_rewards.recipient_shares = RecipientShares::new(&recipients).unwrap();
     assert_eq!(contributor_rewards, expected_contributor_rewards);
 }
+
+//
+// Configure contributor rewards — two pages of recipients.
+//
+
+#[tokio::test]
+async fn test_configure_contributor_rewards_two_pages() {
+    let ConfigureContributorRewardsSetup {
+        mut test_setup,
+        rewards_manager_signer,
+        service_key,
+    } = setup_for_configure_contributor_rewards().await;
+
+    let recipients = (0..MAX_RECIPIENTS)
+        .map(|_| (Pubkey::new_unique(), 625))
+        .collect::<Vec<_>>();
+
+    test_setup
+        .configure_contributor_rewards(
+            &service_key,
+            &rewards_manager_signer,
+            [ContributorRewardsConfiguration::Recipients(
+                recipients.clone(),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let [first_page, second_page] = RecipientShares::try_new_pages(&recipients).unwrap();
+
+    let (contributor_rewards, remaining_data) =
+        fetch_contributor_rewards_with_remaining_data(&mut test_setup, &service_key).await;
+    assert_eq!(contributor_rewards.recipient_shares, first_page);
+    assert_eq!(
+        remaining_data.len(),
+        ContributorRewards::EXTENSION_RECIPIENT_SHARES_LEN
+    );
+    assert_eq!(
+        contributor_rewards.recipient_shares_page(&remaining_data, 1),
+        Some(second_page)
+    );
+    assert_eq!(contributor_rewards.recipient_page_count(&remaining_data), 2);
+
+    // Configuring one page of recipients clears the second page without
+    // shrinking the account.
+    let recipients = vec![(Pubkey::new_unique(), 10_000)];
+
+    test_setup
+        .configure_contributor_rewards(
+            &service_key,
+            &rewards_manager_signer,
+            [ContributorRewardsConfiguration::Recipients(
+                recipients.clone(),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let (contributor_rewards, remaining_data) =
+        fetch_contributor_rewards_with_remaining_data(&mut test_setup, &service_key).await;
+    assert_eq!(
+        contributor_rewards.recipient_shares,
+        RecipientShares::new(&recipients).unwrap()
+    );
+    assert_eq!(
+        remaining_data,
+        vec![0; ContributorRewards::EXTENSION_RECIPIENT_SHARES_LEN]
+    );
+    assert_eq!(contributor_rewards.recipient_page_count(&remaining_data), 1);
+}
+
+//
+// Configure contributor rewards — two pages of recipients without payer.
+//
+
+#[tokio::test]
+async fn test_cannot_configure_contributor_rewards_two_pages_without_payer() {
+    let ConfigureContributorRewardsSetup {
+        mut test_setup,
+        rewards_manager_signer,
+        service_key,
+    } = setup_for_configure_contributor_rewards().await;
+
+    let recipients = (0..MAX_RECIPIENTS)
+        .map(|_| (Pubkey::new_unique(), 625))
+        .collect::<Vec<_>>();
+
+    let configure_contributor_rewards_ix = try_build_instruction(
+        &ID,
+        ConfigureContributorRewardsAccounts::new(
+            &rewards_manager_signer.pubkey(),
+            &service_key,
+            None,
+        ),
+        &RevenueDistributionInstructionData::ConfigureContributorRewards(
+            ContributorRewardsConfiguration::Recipients(recipients),
+        ),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(
+            &[configure_contributor_rewards_ix],
+            &[&rewards_manager_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, common::NOT_ENOUGH_ACCOUNT_KEYS)
+    );
+    assert_eq!(
+        program_logs.get(4 + MAX_RECIPIENTS).unwrap(),
+        "Program log: Payer required to configure more than 8 recipients"
+    );
+}
+
+//
+// Helpers.
+//
+
+async fn fetch_contributor_rewards_with_remaining_data(
+    test_setup: &mut common::ProgramTestWithOwner,
+    service_key: &Pubkey,
+) -> (ContributorRewards, Vec<u8>) {
+    let contributor_rewards_data = test_setup
+        .context
+        .banks_client
+        .get_account(ContributorRewards::find_address(service_key).0)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+
+    let (contributor_rewards, remaining_data) = zero_copy::checked_from_bytes_with_discriminator::<
+        ContributorRewards,
+    >(&contributor_rewards_data)
+    .unwrap();
+
+    (*contributor_rewards, remaining_data.to_vec())
+}
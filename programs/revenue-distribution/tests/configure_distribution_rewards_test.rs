@@ -2,12 +2,21 @@ mod common;
 
 //
 
+use doublezero_program_tools::instruction::try_build_instruction;
 use doublezero_revenue_distribution::{
+    instruction::{
+        account::ConfigureDistributionRewardsAccounts, RevenueDistributionInstructionData,
+    },
     state::{self, Distribution},
     types::{BurnRate, DoubleZeroEpoch, ValidatorFee},
+    ID,
 };
 use solana_program_test::tokio;
-use solana_sdk::signature::Keypair;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
 use svm_hash::sha2::Hash;
 
 //
@@ -99,3 +108,53 @@ async fn test_configure_distribution_rewards() {
         test_setup.get_clock().await.unix_timestamp as u32;
     assert_eq!(distribution, expected_distribution);
 }
+
+//
+// Configure distribution rewards — root already configured.
+//
+
+#[tokio::test]
+async fn test_cannot_configure_distribution_rewards_twice() {
+    let ConfigureDistributionRewardsSetup {
+        mut test_setup,
+        rewards_accountant_signer,
+        dz_epoch,
+        ..
+    } = setup_for_configure_distribution_rewards().await;
+
+    test_setup
+        .configure_distribution_rewards(
+            dz_epoch,
+            &rewards_accountant_signer,
+            69,
+            Hash::new_unique(),
+        )
+        .await
+        .unwrap();
+
+    let configure_distribution_rewards_ix = try_build_instruction(
+        &ID,
+        ConfigureDistributionRewardsAccounts::new(&rewards_accountant_signer.pubkey(), dz_epoch),
+        &RevenueDistributionInstructionData::ConfigureDistributionRewards {
+            total_contributors: 69,
+            merkle_root: Hash::new_unique(),
+        },
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(
+            &[configure_distribution_rewards_ix],
+            &[&rewards_accountant_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Rewards merkle root already configured"
+    );
+}
@@ -2,16 +2,27 @@ mod common;
 
 //
 
+use doublezero_program_tools::instruction::try_build_instruction;
 use doublezero_revenue_distribution::{
-    instruction::{ProgramConfiguration, ProgramFeatureConfiguration, ProgramFlagConfiguration},
+    instruction::{
+        account::{ConfigureProgramAccounts, InitializeDistributionAccounts},
+        AccountantRole, ProgramConfiguration, ProgramFeatureConfiguration,
+        ProgramFlagConfiguration, RevenueDistributionInstructionData,
+    },
     state::{
         self, find_withdraw_sol_authority_address, CommunityBurnRateParameters, ProgramConfig,
+        SecondaryAuthority, MAX_ALLOWED_RELAYERS,
     },
-    types::{BurnRate, DoubleZeroEpoch, ValidatorFee},
+    types::{BurnRate, DoubleZeroEpoch, UnitShare16, ValidatorFee},
+    DOUBLEZERO_MINT_KEY, ID,
 };
 use solana_program_test::tokio;
 use solana_pubkey::Pubkey;
-use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
 
 //
 // Setup.
@@ -86,6 +97,8 @@ async fn test_configure_program() {
     // Feature activation.
     let debt_write_off_feature_activation_epoch = DoubleZeroEpoch::new(1);
 
+    let allowed_relayer_keys = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
     test_setup
         .configure_program(
             &admin_signer,
@@ -122,6 +135,7 @@ async fn test_configure_program() {
                     feature: ProgramFeatureConfiguration::SolanaValidatorDebtWriteOff,
                     activation_epoch: debt_write_off_feature_activation_epoch,
                 },
+                ProgramConfiguration::AllowedRelayers(allowed_relayer_keys.clone()),
             ],
         )
         .await
@@ -145,6 +159,7 @@ async fn test_configure_program() {
     expected_program_config.sol_2z_swap_program_id = sol_2z_swap_program_id;
     expected_program_config.debt_write_off_feature_activation_epoch =
         debt_write_off_feature_activation_epoch;
+    expected_program_config.allowed_relayer_keys[..2].copy_from_slice(&allowed_relayer_keys);
 
     let expected_distribution_params = &mut expected_program_config.distribution_parameters;
     expected_distribution_params.calculation_grace_period_minutes =
@@ -175,3 +190,349 @@ async fn test_configure_program() {
     expected_relay_params.distribute_rewards_lamports = distribute_rewards_relay_lamports;
     assert_eq!(program_config, expected_program_config);
 }
+
+//
+// Configure program — allowed relayers.
+//
+
+#[tokio::test]
+async fn test_configure_program_allowed_relayers() {
+    let ConfigureProgramSetup {
+        mut test_setup,
+        admin_signer,
+    } = setup_for_configure_program().await;
+
+    let allowed_relayer_keys = (0..MAX_ALLOWED_RELAYERS)
+        .map(|_| Pubkey::new_unique())
+        .collect::<Vec<_>>();
+
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::AllowedRelayers(
+                allowed_relayer_keys.clone(),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(
+        program_config.allowed_relayer_keys.to_vec(),
+        allowed_relayer_keys
+    );
+
+    // Clearing the allowlist allows any relayer.
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::AllowedRelayers(vec![])],
+        )
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(
+        program_config.allowed_relayer_keys,
+        [Pubkey::default(); MAX_ALLOWED_RELAYERS]
+    );
+    assert!(program_config.is_relayer_allowed(&Pubkey::new_unique()));
+
+    // Cannot exceed the maximum number of allowed relayers.
+    let (tx_err, program_logs) = simulate_configure_program_revert(
+        &mut test_setup,
+        &admin_signer,
+        ProgramConfiguration::AllowedRelayers(vec![Pubkey::new_unique(); MAX_ALLOWED_RELAYERS + 1]),
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        &format!("Program log: Cannot exceed {MAX_ALLOWED_RELAYERS} allowed relayers")
+    );
+
+    // Cannot allow the default pubkey.
+    let (tx_err, program_logs) = simulate_configure_program_revert(
+        &mut test_setup,
+        &admin_signer,
+        ProgramConfiguration::AllowedRelayers(vec![Pubkey::default()]),
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Allowed relayer cannot be the default pubkey"
+    );
+}
+
+//
+// Configure program — secondary accountant.
+//
+
+#[tokio::test]
+async fn test_configure_program_secondary_accountant() {
+    let ConfigureProgramSetup {
+        mut test_setup,
+        admin_signer,
+    } = setup_for_configure_program().await;
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    let next_completed_dz_epoch = program_config.next_completed_dz_epoch;
+
+    let secondary_debt_accountant = SecondaryAuthority {
+        key: Pubkey::new_unique(),
+        expiry_dz_epoch: next_completed_dz_epoch.saturating_add_duration(1),
+    };
+    let secondary_rewards_accountant = SecondaryAuthority {
+        key: Pubkey::new_unique(),
+        expiry_dz_epoch: next_completed_dz_epoch.saturating_add_duration(2),
+    };
+
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [
+                ProgramConfiguration::SecondaryAccountant {
+                    role: AccountantRole::Debt,
+                    key: secondary_debt_accountant.key,
+                    expiry_dz_epoch: secondary_debt_accountant.expiry_dz_epoch,
+                },
+                ProgramConfiguration::SecondaryAccountant {
+                    role: AccountantRole::Rewards,
+                    key: secondary_rewards_accountant.key,
+                    expiry_dz_epoch: secondary_rewards_accountant.expiry_dz_epoch,
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(
+        program_config.secondary_debt_accountant,
+        secondary_debt_accountant
+    );
+    assert_eq!(
+        program_config.secondary_rewards_accountant,
+        secondary_rewards_accountant
+    );
+    assert!(program_config.is_debt_accountant(&secondary_debt_accountant.key));
+    assert!(program_config.is_rewards_accountant(&secondary_rewards_accountant.key));
+
+    // The default pubkey removes the secondary accountant.
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::SecondaryAccountant {
+                role: AccountantRole::Debt,
+                key: Pubkey::default(),
+                expiry_dz_epoch: Default::default(),
+            }],
+        )
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(
+        program_config.secondary_debt_accountant,
+        SecondaryAuthority::default()
+    );
+    assert!(!program_config.is_debt_accountant(&secondary_debt_accountant.key));
+    assert_eq!(
+        program_config.secondary_rewards_accountant,
+        secondary_rewards_accountant
+    );
+
+    // Cannot set a secondary accountant that has already expired.
+    let (tx_err, program_logs) = simulate_configure_program_revert(
+        &mut test_setup,
+        &admin_signer,
+        ProgramConfiguration::SecondaryAccountant {
+            role: AccountantRole::Debt,
+            key: Pubkey::new_unique(),
+            expiry_dz_epoch: next_completed_dz_epoch,
+        },
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        &format!(
+            "Program log: Secondary accountant expiry must be after DZ epoch {next_completed_dz_epoch}"
+        )
+    );
+}
+
+//
+// Configure program — secondary debt accountant expires.
+//
+
+#[tokio::test]
+async fn test_secondary_debt_accountant_expires() {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+
+    let secondary_debt_accountant_signer = Keypair::new();
+
+    // The secondary debt accountant can initialize one distribution before it
+    // expires.
+    test_setup
+        .configure_program(
+            &configured.admin_signer,
+            [ProgramConfiguration::SecondaryAccountant {
+                role: AccountantRole::Debt,
+                key: secondary_debt_accountant_signer.pubkey(),
+                expiry_dz_epoch: program_config
+                    .next_completed_dz_epoch
+                    .saturating_add_duration(1),
+            }],
+        )
+        .await
+        .unwrap()
+        .initialize_distribution(&secondary_debt_accountant_signer)
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+
+    let initialize_distribution_ix = try_build_instruction(
+        &ID,
+        InitializeDistributionAccounts::new(
+            &secondary_debt_accountant_signer.pubkey(),
+            &test_setup.payer_signer().pubkey(),
+            program_config.next_completed_dz_epoch,
+            &DOUBLEZERO_MINT_KEY,
+        ),
+        &RevenueDistributionInstructionData::InitializeDistribution,
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(
+            &[initialize_distribution_ix],
+            &[&secondary_debt_accountant_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Unauthorized debt accountant (account 1)"
+    );
+
+    // The primary debt accountant is still accepted.
+    test_setup
+        .initialize_distribution(&configured.debt_accountant_signer)
+        .await
+        .unwrap();
+}
+
+//
+// Configure program — treasury 2Z share.
+//
+
+#[tokio::test]
+async fn test_configure_program_treasury_2z_share() {
+    let ConfigureProgramSetup {
+        mut test_setup,
+        admin_signer,
+    } = setup_for_configure_program().await;
+
+    test_setup
+        .configure_program(&admin_signer, [ProgramConfiguration::Treasury2zShare(420)])
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(
+        program_config.treasury_2z_share,
+        UnitShare16::new(420).unwrap()
+    );
+
+    // Cannot exceed 100%.
+    let (tx_err, program_logs) = simulate_configure_program_revert(
+        &mut test_setup,
+        &admin_signer,
+        ProgramConfiguration::Treasury2zShare(10_001),
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Invalid treasury 2Z share: 10001"
+    );
+}
+
+//
+// Configure program — minimum Solana validator debt.
+//
+
+#[tokio::test]
+async fn test_configure_program_minimum_solana_validator_debt() {
+    let ConfigureProgramSetup {
+        mut test_setup,
+        admin_signer,
+    } = setup_for_configure_program().await;
+
+    let minimum_solana_validator_debt = 69_420;
+
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::MinimumSolanaValidatorDebt(
+                minimum_solana_validator_debt,
+            )],
+        )
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(
+        program_config
+            .distribution_parameters
+            .minimum_solana_validator_debt,
+        minimum_solana_validator_debt
+    );
+    assert!(program_config.is_dust_solana_validator_debt(minimum_solana_validator_debt - 1));
+    assert!(!program_config.is_dust_solana_validator_debt(minimum_solana_validator_debt));
+}
+
+//
+// Helpers.
+//
+
+async fn simulate_configure_program_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    admin_signer: &Keypair,
+    setting: ProgramConfiguration,
+) -> (TransactionError, Vec<String>) {
+    let configure_program_ix = try_build_instruction(
+        &ID,
+        ConfigureProgramAccounts::new(&admin_signer.pubkey(), false),
+        &RevenueDistributionInstructionData::ConfigureProgram(setting),
+    )
+    .unwrap();
+
+    test_setup
+        .unwrap_simulation_error(&[configure_program_ix], &[admin_signer])
+        .await
+        .unwrap()
+}
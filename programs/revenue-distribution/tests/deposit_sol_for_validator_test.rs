@@ -0,0 +1,203 @@
+mod common;
+
+//
+
+use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
+use doublezero_revenue_distribution::{
+    instruction::{
+        account::DepositSolForValidatorAccounts, ProgramConfiguration, ProgramFlagConfiguration,
+        RevenueDistributionInstructionData,
+    },
+    state::SolanaValidatorDeposit,
+    ID,
+};
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+//
+// Setup.
+//
+
+struct DepositSolForValidatorSetup {
+    test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
+    depositor_signer: Keypair,
+    node_id: Pubkey,
+}
+
+/// Set up a configured program with a single validator deposit account
+/// initialized and a funded depositor.
+async fn setup_for_deposit_sol_for_validator() -> DepositSolForValidatorSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    let depositor_signer = Keypair::new();
+    let node_id = Pubkey::new_unique();
+
+    test_setup
+        .initialize_solana_validator_deposit(&node_id)
+        .await
+        .unwrap()
+        .transfer_lamports(&depositor_signer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+
+    DepositSolForValidatorSetup {
+        test_setup,
+        admin_signer: configured.admin_signer,
+        depositor_signer,
+        node_id,
+    }
+}
+
+//
+// Deposit SOL for validator — happy path.
+//
+
+#[tokio::test]
+async fn test_deposit_sol_for_validator() {
+    let DepositSolForValidatorSetup {
+        mut test_setup,
+        depositor_signer,
+        node_id,
+        ..
+    } = setup_for_deposit_sol_for_validator().await;
+
+    let deposit_key = SolanaValidatorDeposit::find_address(&node_id).0;
+    let deposit_rent_exemption = (128
+        + (zero_copy::data_end::<SolanaValidatorDeposit>()
+            + SolanaValidatorDeposit::PAYMENT_HISTORY_LEN) as u64)
+        * 6_960;
+
+    let first_deposit_amount = 1_000_000_000;
+    let second_deposit_amount = 500_000_000;
+    let direct_transfer_amount = 69_420;
+
+    // Lamports transferred directly to the deposit are not counted.
+    test_setup
+        .deposit_sol_for_validator(&depositor_signer, &node_id, first_deposit_amount)
+        .await
+        .unwrap()
+        .transfer_lamports(&deposit_key, direct_transfer_amount)
+        .await
+        .unwrap()
+        .deposit_sol_for_validator(&depositor_signer, &node_id, second_deposit_amount)
+        .await
+        .unwrap();
+
+    let (_, solana_validator_deposit) = test_setup.fetch_solana_validator_deposit(&node_id).await;
+    assert_eq!(solana_validator_deposit.node_id, node_id);
+    assert_eq!(
+        solana_validator_deposit.deposited_sol_amount,
+        first_deposit_amount + second_deposit_amount
+    );
+
+    let deposit_balance = test_setup
+        .context
+        .banks_client
+        .get_balance(deposit_key)
+        .await
+        .unwrap();
+    assert_eq!(
+        deposit_balance,
+        deposit_rent_exemption
+            + first_deposit_amount
+            + second_deposit_amount
+            + direct_transfer_amount
+    );
+}
+
+//
+// Deposit SOL for validator — zero amount.
+//
+
+#[tokio::test]
+async fn test_cannot_deposit_sol_for_validator_zero_amount() {
+    let DepositSolForValidatorSetup {
+        mut test_setup,
+        depositor_signer,
+        node_id,
+        ..
+    } = setup_for_deposit_sol_for_validator().await;
+
+    let (tx_err, program_logs) =
+        simulate_deposit_sol_for_validator_revert(&mut test_setup, &depositor_signer, &node_id, 0)
+            .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Deposit amount must be greater than zero"
+    );
+}
+
+//
+// Deposit SOL for validator — paused.
+//
+
+#[tokio::test]
+async fn test_cannot_deposit_sol_for_validator_when_paused() {
+    let DepositSolForValidatorSetup {
+        mut test_setup,
+        admin_signer,
+        depositor_signer,
+        node_id,
+    } = setup_for_deposit_sol_for_validator().await;
+
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::Flag(
+                ProgramFlagConfiguration::IsPaused(true),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let (tx_err, program_logs) = simulate_deposit_sol_for_validator_revert(
+        &mut test_setup,
+        &depositor_signer,
+        &node_id,
+        1_000_000_000,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Program is paused"
+    );
+}
+
+//
+// Helpers.
+//
+
+async fn simulate_deposit_sol_for_validator_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    depositor_signer: &Keypair,
+    node_id: &Pubkey,
+    amount: u64,
+) -> (TransactionError, Vec<String>) {
+    let deposit_sol_for_validator_ix = try_build_instruction(
+        &ID,
+        DepositSolForValidatorAccounts::new(&depositor_signer.pubkey(), node_id),
+        &RevenueDistributionInstructionData::DepositSolForValidator(amount),
+    )
+    .unwrap();
+
+    test_setup
+        .unwrap_simulation_error(&[deposit_sol_for_validator_ix], &[depositor_signer])
+        .await
+        .unwrap()
+}
@@ -7,14 +7,15 @@ use std::collections::HashMap;
 use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
 use doublezero_revenue_distribution::{
     instruction::{
-        account::DistributeRewardsAccounts, ContributorRewardsConfiguration,
-        DistributionMerkleRootKind, ProgramConfiguration, ProgramFeatureConfiguration,
-        ProgramFlagConfiguration, RevenueDistributionInstructionData,
+        account::{ConfigureContributorRewardsAccounts, DistributeRewardsAccounts},
+        ContributorRewardsConfiguration, DistributionMerkleRootKind, ProgramConfiguration,
+        ProgramFeatureConfiguration, ProgramFlagConfiguration, RevenueDistributionInstructionData,
     },
-    state::{self, Distribution, Journal, SolanaValidatorDeposit},
+    state::{self, Distribution, Journal, SolanaValidatorDeposit, MAX_RECIPIENTS_PER_PAGE},
     types::{BurnRate, DoubleZeroEpoch, RewardShare, SolanaValidatorDebt, ValidatorFee},
     DOUBLEZERO_MINT_KEY, ID,
 };
+use solana_program_pack::Pack;
 use solana_program_test::{tokio, BanksClientError};
 use solana_pubkey::Pubkey;
 use solana_sdk::{
@@ -23,6 +24,7 @@ use solana_sdk::{
     transaction::TransactionError,
 };
 use spl_associated_token_account_interface::address::get_associated_token_address;
+use spl_token_interface::state::Account as TokenAccount;
 use svm_hash::merkle::{merkle_root_from_indexed_pod_leaves, MerkleProof};
 
 //
@@ -69,7 +71,9 @@ struct DistributeRewardsBaseSetup {
 /// - Distribution 0 finalized and swept (prerequisite)
 ///
 /// Stops BEFORE contributor rewards setup and rewards finalization.
-async fn setup_distributions_with_debt() -> DistributeRewardsBaseSetup {
+async fn setup_distributions_with_debt(
+    distribute_rewards_relay_lamports: u32,
+) -> DistributeRewardsBaseSetup {
     let transfer_authority_signer = Keypair::new();
 
     let bootstrapped_accounts = common::generate_token_accounts_for_test(
@@ -153,7 +157,7 @@ async fn setup_distributions_with_debt() -> DistributeRewardsBaseSetup {
                     initial_rate: Some(INITIAL_CBR),
                 },
                 ProgramConfiguration::DistributeRewardsRelayLamports(
-                    DISTRIBUTE_REWARDS_RELAY_LAMPORTS,
+                    distribute_rewards_relay_lamports,
                 ),
                 ProgramConfiguration::MinimumEpochDurationToFinalizeRewards(1),
                 ProgramConfiguration::CalculationGracePeriodMinutes(1),
@@ -250,7 +254,7 @@ async fn setup_distributions_with_debt() -> DistributeRewardsBaseSetup {
                 .transfer_lamports(&deposit_key, amount)
                 .await
                 .unwrap()
-                .pay_solana_validator_debt(next_dz_epoch, debt, proof.clone())
+                .pay_solana_validator_debt(next_dz_epoch, debt, proof.clone(), false)
                 .await
                 .unwrap()
                 .write_off_solana_validator_debt(
@@ -270,10 +274,10 @@ async fn setup_distributions_with_debt() -> DistributeRewardsBaseSetup {
                 .transfer_lamports(&deposit_key, 2 * amount)
                 .await
                 .unwrap()
-                .pay_solana_validator_debt(dz_epoch, debt, proof.clone())
+                .pay_solana_validator_debt(dz_epoch, debt, proof.clone(), false)
                 .await
                 .unwrap()
-                .pay_solana_validator_debt(next_dz_epoch, debt, proof)
+                .pay_solana_validator_debt(next_dz_epoch, debt, proof, false)
                 .await
                 .unwrap();
         }
@@ -325,6 +329,7 @@ struct DistributeRewardsReadySetup {
     test_setup: common::ProgramTestWithOwner,
     debt_accountant_signer: Keypair,
     rewards_accountant_signer: Keypair,
+    rewards_manager_signer: Keypair,
     total_solana_validators: u32,
     total_solana_validator_debt: u64,
     solana_validator_debt_merkle_root: svm_hash::sha2::Hash,
@@ -343,8 +348,12 @@ struct DistributeRewardsReadySetup {
 /// merkle root for both epochs.
 ///
 /// Stops BEFORE finalize/sweep so the caller can optionally set
-/// economic burn rate before finalizing.
-async fn setup_ready_to_distribute() -> DistributeRewardsReadySetup {
+/// economic burn rate before finalizing. Recipient ATAs are only created if
+/// `with_recipient_atas` is true.
+async fn setup_ready_to_distribute(
+    distribute_rewards_relay_lamports: u32,
+    with_recipient_atas: bool,
+) -> DistributeRewardsReadySetup {
     let DistributeRewardsBaseSetup {
         mut test_setup,
         contributor_manager_signer,
@@ -356,7 +365,7 @@ async fn setup_ready_to_distribute() -> DistributeRewardsReadySetup {
         uncollectible_debt,
         dz_epoch,
         next_dz_epoch,
-    } = setup_distributions_with_debt().await;
+    } = setup_distributions_with_debt(distribute_rewards_relay_lamports).await;
 
     // 5 contributors with clean proportions (no rounding issues).
     let rewards_data = vec![
@@ -388,10 +397,11 @@ async fn setup_ready_to_distribute() -> DistributeRewardsReadySetup {
 
         recipient_shares.insert(*contributor_key, recipients.clone());
 
+        if with_recipient_atas {
+            test_setup.create_2z_ata(&recipient_key).await.unwrap();
+        }
+
         test_setup
-            .create_2z_ata(&recipient_key)
-            .await
-            .unwrap()
             .initialize_contributor_rewards(contributor_key)
             .await
             .unwrap()
@@ -466,6 +476,7 @@ async fn setup_ready_to_distribute() -> DistributeRewardsReadySetup {
         test_setup,
         debt_accountant_signer,
         rewards_accountant_signer,
+        rewards_manager_signer,
         total_solana_validators,
         total_solana_validator_debt,
         solana_validator_debt_merkle_root,
@@ -500,7 +511,7 @@ async fn test_distribute_rewards() {
         rewards_merkle_root,
         recipient_shares,
         ..
-    } = setup_ready_to_distribute().await;
+    } = setup_ready_to_distribute(DISTRIBUTE_REWARDS_RELAY_LAMPORTS, true).await;
 
     // Finalize and sweep both epochs.
     test_setup
@@ -768,6 +779,32 @@ async fn test_distribute_rewards() {
 
     assert_eq!(distribution_2z_token_pda.amount, 0);
 
+    // Each contributor's distributed and burned 2Z totals span both epochs.
+    let total_pool_amount = SWEPT_2Z_AMOUNT_1 + DIRECT_2Z_PAYMENT_AMOUNT + SWEPT_2Z_AMOUNT_2;
+
+    for share in rewards_data.iter() {
+        let (_, contributor_rewards) = test_setup
+            .fetch_contributor_rewards(&share.contributor_key)
+            .await;
+
+        let total_share_amount = (total_pool_amount / 1_000_000_000) * share.unit_share as u64;
+        let total_burned_amount = total_share_amount / 10;
+
+        assert_eq!(
+            contributor_rewards.checked_last_distributed_dz_epoch(),
+            Some(next_dz_epoch)
+        );
+        assert_eq!(contributor_rewards.distributed_dz_epochs_count, 2);
+        assert_eq!(
+            contributor_rewards.total_burned_2z_amount,
+            total_burned_amount
+        );
+        assert_eq!(
+            contributor_rewards.total_distributed_2z_amount,
+            total_share_amount - total_burned_amount
+        );
+    }
+
     // Cannot distribute rewards again for either epoch.
     for (share, proof) in rewards_data.iter().copied().zip(proofs.iter()) {
         let contributor_key = &share.contributor_key;
@@ -826,7 +863,7 @@ async fn test_distribute_rewards_with_economic_burn_rate() {
         rewards_merkle_root,
         recipient_shares,
         ..
-    } = setup_ready_to_distribute().await;
+    } = setup_ready_to_distribute(DISTRIBUTE_REWARDS_RELAY_LAMPORTS, true).await;
 
     let distribution_economic_burn_rate = 250_000_000; // 25%.
 
@@ -941,6 +978,646 @@ async fn test_distribute_rewards_with_economic_burn_rate() {
     assert_eq!(distribution_2z_token_pda.amount, 0);
 }
 
+//
+// Distribute rewards with recipient ATA creation.
+//
+
+#[tokio::test]
+async fn test_distribute_rewards_create_recipient_atas() {
+    let DistributeRewardsReadySetup {
+        mut test_setup,
+        dz_epoch,
+        next_dz_epoch,
+        rewards_data,
+        proofs,
+        recipient_shares,
+        ..
+    } = setup_ready_to_distribute(DISTRIBUTE_REWARDS_RELAY_LAMPORTS, false).await;
+
+    // Finalize and sweep both epochs.
+    test_setup
+        .finalize_distribution_rewards(dz_epoch)
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(next_dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(next_dz_epoch)
+        .await
+        .unwrap();
+
+    let token_account_rent = test_setup
+        .context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TokenAccount::LEN);
+
+    for (share, proof) in rewards_data.iter().copied().zip(proofs.iter()) {
+        let contributor_key = &share.contributor_key;
+        let recipient_keys = recipient_shares[contributor_key]
+            .iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        let relayer_key = Pubkey::new_unique();
+
+        // Cannot distribute rewards to a recipient without an ATA unless the
+        // ATA is created.
+        let (tx_err, _) = simulate_distribute_rewards_revert(
+            &mut test_setup,
+            dz_epoch,
+            &share,
+            &relayer_key,
+            &recipient_keys,
+            proof.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            tx_err,
+            TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+        );
+
+        test_setup
+            .distribute_rewards_creating_recipient_atas(
+                dz_epoch,
+                &share,
+                &relayer_key,
+                &recipient_keys,
+                proof.clone(),
+            )
+            .await
+            .unwrap();
+
+        // Rent for the new ATA is paid by the payer, so the relayer is paid in
+        // full even though the relay lamports do not cover this rent.
+        assert!(u64::from(DISTRIBUTE_REWARDS_RELAY_LAMPORTS) < token_account_rent);
+
+        let relayer_balance = test_setup
+            .context
+            .banks_client
+            .get_balance(relayer_key)
+            .await
+            .unwrap();
+        assert_eq!(relayer_balance, DISTRIBUTE_REWARDS_RELAY_LAMPORTS as u64);
+
+        let recipient_ata_key =
+            get_associated_token_address(recipient_keys[0], &DOUBLEZERO_MINT_KEY);
+        let recipient_ata_balance = test_setup
+            .context
+            .banks_client
+            .get_balance(recipient_ata_key)
+            .await
+            .unwrap();
+        assert_eq!(recipient_ata_balance, token_account_rent);
+
+        // The ATA already exists for the second epoch.
+        test_setup
+            .distribute_rewards_creating_recipient_atas(
+                next_dz_epoch,
+                &share,
+                &relayer_key,
+                &recipient_keys,
+                proof.clone(),
+            )
+            .await
+            .unwrap();
+
+        let relayer_balance = test_setup
+            .context
+            .banks_client
+            .get_balance(relayer_key)
+            .await
+            .unwrap();
+        assert_eq!(
+            relayer_balance,
+            2 * DISTRIBUTE_REWARDS_RELAY_LAMPORTS as u64
+        );
+
+        // 900,000,000,000 2Z are distributed for dz_epoch and 450,000,000,000
+        // 2Z for next_dz_epoch.
+        let recipient_ata = test_setup
+            .fetch_token_account(&recipient_ata_key)
+            .await
+            .unwrap();
+        assert_eq!(recipient_ata.owner, *recipient_keys[0]);
+        assert_eq!(recipient_ata.amount, u64::from(share.unit_share) * 1_350);
+    }
+}
+
+//
+// Distribute rewards — two pages of recipients.
+//
+
+#[tokio::test]
+async fn test_distribute_rewards_two_pages() {
+    let DistributeRewardsReadySetup {
+        mut test_setup,
+        rewards_manager_signer,
+        dz_epoch,
+        next_dz_epoch,
+        total_contributors,
+        rewards_data,
+        proofs,
+        ..
+    } = setup_ready_to_distribute(DISTRIBUTE_REWARDS_RELAY_LAMPORTS, false).await;
+
+    let share = rewards_data[0];
+    let proof = proofs[0].clone();
+
+    // Replace the contributor's recipients with 12 recipients, which spans two
+    // pages.
+    let recipients = (0..12)
+        .map(|i| (Pubkey::new_unique(), if i < 4 { 1_000 } else { 750 }))
+        .collect::<Vec<_>>();
+
+    for (recipient_key, _) in recipients.iter() {
+        test_setup.create_2z_ata(recipient_key).await.unwrap();
+    }
+
+    test_setup
+        .configure_contributor_rewards(
+            &share.contributor_key,
+            &rewards_manager_signer,
+            [ContributorRewardsConfiguration::Recipients(
+                recipients.clone(),
+            )],
+        )
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(dz_epoch)
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(next_dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(next_dz_epoch)
+        .await
+        .unwrap();
+
+    let recipient_keys = recipients.iter().map(|(key, _)| key).collect::<Vec<_>>();
+    let (first_page_keys, second_page_keys) = recipient_keys.split_at(MAX_RECIPIENTS_PER_PAGE);
+
+    let relayer_key = Pubkey::new_unique();
+
+    // Rent for the partially distributed rewards bitmap (one byte for five
+    // contributors) is paid by the payer, so the relayer is paid in full.
+    let relay_lamports_per_page = DISTRIBUTE_REWARDS_RELAY_LAMPORTS as u64 / 2;
+    let bitmap_rent = 6_960;
+
+    // Cannot distribute the first page without a payer for the bitmap.
+
+    let (tx_err, program_logs) = simulate_distribute_rewards_revert(
+        &mut test_setup,
+        dz_epoch,
+        &share,
+        &relayer_key,
+        first_page_keys,
+        proof.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, common::NOT_ENOUGH_ACCOUNT_KEYS)
+    );
+    assert!(program_logs.contains(
+        &"Program log: Payer required to allocate partially distributed rewards bitmap".to_string()
+    ));
+
+    let distribution_lamports_before = test_setup
+        .context
+        .banks_client
+        .get_balance(Distribution::find_address(dz_epoch).0)
+        .await
+        .unwrap();
+
+    test_setup
+        .distribute_rewards(
+            dz_epoch,
+            &share,
+            &DOUBLEZERO_MINT_KEY,
+            &relayer_key,
+            first_page_keys,
+            proof.clone(),
+        )
+        .await
+        .unwrap();
+
+    let (_, distribution, remaining_distribution_data, _, _) =
+        test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(distribution.distributed_rewards_count, 0);
+    assert_eq!(distribution.burned_2z_amount, 0);
+    assert_eq!(
+        distribution
+            .partially_distributed_rewards_bitmap_range()
+            .len(),
+        (total_contributors / 8 + 1) as usize
+    );
+    assert_eq!(
+        remaining_distribution_data[distribution.partially_distributed_rewards_bitmap_range()],
+        [0b00000001]
+    );
+    assert_eq!(
+        remaining_distribution_data[distribution.processed_rewards_bitmap_range()],
+        [0b00000000]
+    );
+
+    let relayer_balance = test_setup
+        .context
+        .banks_client
+        .get_balance(relayer_key)
+        .await
+        .unwrap();
+    assert_eq!(relayer_balance, relay_lamports_per_page);
+
+    let distribution_lamports = test_setup
+        .context
+        .banks_client
+        .get_balance(Distribution::find_address(dz_epoch).0)
+        .await
+        .unwrap();
+    assert_eq!(
+        distribution_lamports,
+        distribution_lamports_before + bitmap_rent - relay_lamports_per_page
+    );
+
+    let mut recipient_balances = Vec::with_capacity(recipients.len());
+
+    for (i, recipient_key) in recipient_keys.iter().enumerate() {
+        let amount = test_setup
+            .fetch_token_account(&get_associated_token_address(
+                recipient_key,
+                &DOUBLEZERO_MINT_KEY,
+            ))
+            .await
+            .unwrap()
+            .amount;
+
+        // Only the first page of recipients has been distributed.
+        assert_eq!(amount != 0, i < MAX_RECIPIENTS_PER_PAGE);
+        recipient_balances.push(amount);
+    }
+
+    let (_, contributor_rewards) = test_setup
+        .fetch_contributor_rewards(&share.contributor_key)
+        .await;
+    assert_eq!(
+        contributor_rewards.checked_partially_distributed_dz_epoch(),
+        Some(dz_epoch)
+    );
+    assert_eq!(
+        contributor_rewards.partially_distributed_2z_amount,
+        recipient_balances.iter().sum::<u64>()
+    );
+
+    // Cannot change recipients until the last page is distributed.
+    let configure_contributor_rewards_ix = try_build_instruction(
+        &ID,
+        ConfigureContributorRewardsAccounts::new(
+            &rewards_manager_signer.pubkey(),
+            &share.contributor_key,
+            None,
+        ),
+        &RevenueDistributionInstructionData::ConfigureContributorRewards(
+            ContributorRewardsConfiguration::Recipients(vec![(Pubkey::new_unique(), 10_000)]),
+        ),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(
+            &[configure_contributor_rewards_ix],
+            &[&rewards_manager_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        &format!(
+            "Program log: Cannot change recipients while rewards for DZ epoch {} are partially distributed",
+            dz_epoch
+        )
+    );
+
+    // Cannot start distributing another DZ epoch's rewards until the last page
+    // is distributed.
+    let (tx_err, program_logs) = simulate_distribute_rewards_revert(
+        &mut test_setup,
+        next_dz_epoch,
+        &share,
+        &relayer_key,
+        first_page_keys,
+        proof.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(6).unwrap(),
+        &format!(
+            "Program log: Rewards for DZ epoch {} are partially distributed",
+            dz_epoch
+        )
+    );
+
+    // The second page must be distributed with the second page of recipients.
+    let (tx_err, program_logs) = simulate_distribute_rewards_revert(
+        &mut test_setup,
+        dz_epoch,
+        &share,
+        &relayer_key,
+        first_page_keys,
+        proof.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(5).unwrap(),
+        &format!(
+            "Program log: Expected ATA for recipient {} (account 7)",
+            second_page_keys[0]
+        )
+    );
+
+    test_setup
+        .distribute_rewards(
+            dz_epoch,
+            &share,
+            &DOUBLEZERO_MINT_KEY,
+            &relayer_key,
+            second_page_keys,
+            proof.clone(),
+        )
+        .await
+        .unwrap();
+
+    let (_, distribution, remaining_distribution_data, _, _) =
+        test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(distribution.distributed_rewards_count, 1);
+    assert_eq!(
+        remaining_distribution_data[distribution.processed_rewards_bitmap_range()],
+        [0b00000001]
+    );
+
+    let relayer_balance = test_setup
+        .context
+        .banks_client
+        .get_balance(relayer_key)
+        .await
+        .unwrap();
+    assert_eq!(relayer_balance, DISTRIBUTE_REWARDS_RELAY_LAMPORTS as u64);
+
+    for (i, recipient_key) in second_page_keys.iter().enumerate() {
+        recipient_balances[MAX_RECIPIENTS_PER_PAGE + i] = test_setup
+            .fetch_token_account(&get_associated_token_address(
+                recipient_key,
+                &DOUBLEZERO_MINT_KEY,
+            ))
+            .await
+            .unwrap()
+            .amount;
+    }
+
+    // Recipients across both pages are paid in proportion to their shares.
+    for ((_, share), balance) in recipients.iter().zip(recipient_balances.iter()) {
+        assert_eq!(balance * 1_000, recipient_balances[0] * u64::from(*share));
+    }
+    assert_eq!(
+        recipient_balances.iter().sum::<u64>(),
+        distribution.distributed_2z_amount
+    );
+
+    let (_, contributor_rewards) = test_setup
+        .fetch_contributor_rewards(&share.contributor_key)
+        .await;
+    assert_eq!(
+        contributor_rewards.checked_partially_distributed_dz_epoch(),
+        None
+    );
+    assert_eq!(contributor_rewards.partially_distributed_2z_amount, 0);
+
+    // Cannot distribute rewards again for the same contributor.
+    let (tx_err, program_logs) = simulate_distribute_rewards_revert(
+        &mut test_setup,
+        dz_epoch,
+        &share,
+        &relayer_key,
+        second_page_keys,
+        proof,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Merkle leaf index 0 has already been processed"
+    );
+}
+
+//
+// Distribute rewards — relayer not allowed.
+//
+
+#[tokio::test]
+async fn test_cannot_distribute_rewards_relayer_not_allowed() {
+    let DistributeRewardsReadySetup {
+        mut test_setup,
+        dz_epoch,
+        next_dz_epoch,
+        rewards_data,
+        proofs,
+        recipient_shares,
+        ..
+    } = setup_ready_to_distribute(DISTRIBUTE_REWARDS_RELAY_LAMPORTS, true).await;
+
+    let admin_signer = Keypair::new();
+    let allowed_relayer_key = Pubkey::new_unique();
+
+    test_setup
+        .set_admin(&admin_signer.pubkey())
+        .await
+        .unwrap()
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::AllowedRelayers(vec![
+                allowed_relayer_key,
+            ])],
+        )
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(dz_epoch)
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(next_dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(next_dz_epoch)
+        .await
+        .unwrap();
+
+    let share = rewards_data[0];
+    let proof = proofs[0].clone();
+    let recipient_keys = recipient_shares[&share.contributor_key]
+        .iter()
+        .map(|(key, _)| key)
+        .collect::<Vec<_>>();
+
+    let (tx_err, program_logs) = simulate_distribute_rewards_revert(
+        &mut test_setup,
+        dz_epoch,
+        &share,
+        &Pubkey::new_unique(),
+        &recipient_keys,
+        proof.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(4).unwrap(),
+        "Program log: Relayer is not allowed (account 5)"
+    );
+
+    // The allowed relayer collects the relay lamports.
+    test_setup
+        .distribute_rewards(
+            dz_epoch,
+            &share,
+            &DOUBLEZERO_MINT_KEY,
+            &allowed_relayer_key,
+            &recipient_keys,
+            proof,
+        )
+        .await
+        .unwrap();
+
+    let relayer_balance = test_setup
+        .context
+        .banks_client
+        .get_balance(allowed_relayer_key)
+        .await
+        .unwrap();
+    assert_eq!(relayer_balance, DISTRIBUTE_REWARDS_RELAY_LAMPORTS as u64);
+}
+
+//
+// Distribute rewards — compute units with max recipients.
+//
+
+#[tokio::test]
+async fn test_distribute_rewards_compute_units() {
+    let DistributeRewardsReadySetup {
+        mut test_setup,
+        rewards_manager_signer,
+        dz_epoch,
+        next_dz_epoch,
+        rewards_data,
+        proofs,
+        ..
+    } = setup_ready_to_distribute(DISTRIBUTE_REWARDS_RELAY_LAMPORTS, false).await;
+
+    let share = rewards_data[0];
+    let proof = proofs[0].clone();
+
+    // Replace the contributor's recipients with the maximum number of
+    // recipients, which is the most expensive case to distribute.
+    let recipients = (0..MAX_RECIPIENTS_PER_PAGE)
+        .map(|_| {
+            (
+                Pubkey::new_unique(),
+                (10_000 / MAX_RECIPIENTS_PER_PAGE) as u16,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (recipient_key, _) in recipients.iter() {
+        test_setup.create_2z_ata(recipient_key).await.unwrap();
+    }
+
+    test_setup
+        .configure_contributor_rewards(
+            &share.contributor_key,
+            &rewards_manager_signer,
+            [ContributorRewardsConfiguration::Recipients(
+                recipients.clone(),
+            )],
+        )
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(dz_epoch)
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(next_dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(next_dz_epoch)
+        .await
+        .unwrap();
+
+    let recipient_keys = recipients.iter().map(|(key, _)| key).collect::<Vec<_>>();
+
+    let distribute_rewards_ix = try_build_instruction(
+        &ID,
+        DistributeRewardsAccounts::new(
+            dz_epoch,
+            &share.contributor_key,
+            &DOUBLEZERO_MINT_KEY,
+            &Pubkey::new_unique(),
+            &recipient_keys,
+            None, // ata_payer_key
+            None, // bitmap_payer_key
+        ),
+        &RevenueDistributionInstructionData::DistributeRewards {
+            unit_share: share.unit_share,
+            economic_burn_rate: share.economic_burn_rate(),
+            proof,
+        },
+    )
+    .unwrap();
+
+    let consumed_units = test_setup
+        .process_instructions_with_compute_units(&[distribute_rewards_ix], &[])
+        .await
+        .unwrap();
+    assert_eq!(consumed_units.len(), 1);
+
+    common::assert_compute_units_within_baseline(
+        "distribute_rewards_max_recipients",
+        consumed_units[0],
+    );
+}
+
 //
 // Helpers.
 //
@@ -961,6 +1638,8 @@ async fn simulate_distribute_rewards_revert(
             &DOUBLEZERO_MINT_KEY,
             relayer_key,
             recipient_keys,
+            None, // ata_payer_key
+            None, // bitmap_payer_key
         ),
         &RevenueDistributionInstructionData::DistributeRewards {
             unit_share: share.unit_share,
@@ -0,0 +1,401 @@
+mod common;
+
+//
+
+use doublezero_program_tools::instruction::try_build_instruction;
+use doublezero_revenue_distribution::{
+    instruction::{
+        account::{ConfigureProgramAccounts, EmergencyWithdrawAccounts},
+        EmergencyWithdrawSource, ProgramConfiguration, ProgramFlagConfiguration,
+        RevenueDistributionInstructionData,
+    },
+    state::{self, Journal},
+    DOUBLEZERO_MINT_KEY, ID,
+};
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_associated_token_account_interface::address::get_associated_token_address;
+
+//
+// Setup.
+//
+
+struct EmergencyWithdrawSetup {
+    test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
+    emergency_recovery_key: Pubkey,
+}
+
+async fn setup_for_emergency_withdraw() -> EmergencyWithdrawSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+    let admin_signer = configured.admin_signer;
+
+    let emergency_recovery_key = Pubkey::new_unique();
+
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::EmergencyRecovery(
+                emergency_recovery_key,
+            )],
+        )
+        .await
+        .unwrap();
+
+    EmergencyWithdrawSetup {
+        test_setup,
+        admin_signer,
+        emergency_recovery_key,
+    }
+}
+
+//
+// Emergency withdraw — journal 2Z tokens.
+//
+
+#[tokio::test]
+async fn test_emergency_withdraw_journal_2z() {
+    let EmergencyWithdrawSetup {
+        mut test_setup,
+        admin_signer,
+        emergency_recovery_key,
+    } = setup_for_emergency_withdraw().await;
+
+    let journal_2z_token_pda_key = state::find_2z_token_pda_address(&Journal::find_address().0).0;
+    let recovery_2z_key =
+        get_associated_token_address(&emergency_recovery_key, &DOUBLEZERO_MINT_KEY);
+
+    let transfer_amount = 69_420;
+
+    test_setup
+        .create_2z_ata(&emergency_recovery_key)
+        .await
+        .unwrap()
+        .transfer_2z(&journal_2z_token_pda_key, transfer_amount)
+        .await
+        .unwrap();
+
+    let recovery_2z_amount_before = test_setup
+        .fetch_token_account(&recovery_2z_key)
+        .await
+        .unwrap()
+        .amount;
+
+    test_setup
+        .emergency_withdraw(
+            &admin_signer,
+            EmergencyWithdrawSource::Journal2z,
+            &emergency_recovery_key,
+        )
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert!(program_config.is_paused());
+    assert!(program_config.has_emergency_withdrawn());
+
+    let (_, _, journal_2z_token_pda) = test_setup.fetch_journal().await;
+    assert_eq!(journal_2z_token_pda.amount, 0);
+
+    let recovery_2z_amount_after = test_setup
+        .fetch_token_account(&recovery_2z_key)
+        .await
+        .unwrap()
+        .amount;
+    assert_eq!(
+        recovery_2z_amount_after,
+        recovery_2z_amount_before + transfer_amount
+    );
+
+    // The program cannot be unpaused after an emergency withdrawal.
+    let configure_program_ix = try_build_instruction(
+        &ID,
+        ConfigureProgramAccounts::new(&admin_signer.pubkey(), false),
+        &RevenueDistributionInstructionData::ConfigureProgram(ProgramConfiguration::Flag(
+            ProgramFlagConfiguration::IsPaused(false),
+        )),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[configure_program_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Cannot unpause after emergency withdrawal"
+    );
+}
+
+//
+// Emergency withdraw — journal SOL.
+//
+
+#[tokio::test]
+async fn test_emergency_withdraw_journal_sol() {
+    let EmergencyWithdrawSetup {
+        mut test_setup,
+        admin_signer,
+        emergency_recovery_key,
+    } = setup_for_emergency_withdraw().await;
+
+    let journal_key = Journal::find_address().0;
+
+    let transfer_amount = 4_200_000_000;
+
+    test_setup
+        .transfer_lamports(&journal_key, transfer_amount)
+        .await
+        .unwrap()
+        .emergency_withdraw(
+            &admin_signer,
+            EmergencyWithdrawSource::JournalSol,
+            &emergency_recovery_key,
+        )
+        .await
+        .unwrap();
+
+    let banks_client = &mut test_setup.context.banks_client;
+
+    let journal_account = banks_client
+        .get_account(journal_key)
+        .await
+        .unwrap()
+        .unwrap();
+    let journal_rent_exemption = banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(journal_account.data.len());
+    assert_eq!(journal_account.lamports, journal_rent_exemption);
+
+    let recovery_balance = banks_client
+        .get_balance(emergency_recovery_key)
+        .await
+        .unwrap();
+    assert!(recovery_balance >= transfer_amount);
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert!(program_config.is_paused());
+    assert!(program_config.has_emergency_withdrawn());
+}
+
+//
+// Emergency withdraw — unauthorized admin.
+//
+
+#[tokio::test]
+async fn test_cannot_emergency_withdraw_unauthorized_admin() {
+    let EmergencyWithdrawSetup {
+        mut test_setup,
+        emergency_recovery_key,
+        ..
+    } = setup_for_emergency_withdraw().await;
+
+    let owner_signer = test_setup.owner_signer.insecure_clone();
+    let unauthorized_signer = Keypair::new();
+
+    let emergency_withdraw_ix = try_build_instruction(
+        &ID,
+        EmergencyWithdrawAccounts::new(
+            &ID,
+            &owner_signer.pubkey(),
+            &unauthorized_signer.pubkey(),
+            EmergencyWithdrawSource::Journal2z,
+            &emergency_recovery_key,
+        ),
+        &RevenueDistributionInstructionData::EmergencyWithdraw(EmergencyWithdrawSource::Journal2z),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(
+            &[emergency_withdraw_ix],
+            &[&owner_signer, &unauthorized_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Unauthorized admin (account 3)"
+    );
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert!(!program_config.has_emergency_withdrawn());
+}
+
+//
+// Emergency withdraw — unauthorized upgrade authority.
+//
+
+#[tokio::test]
+async fn test_cannot_emergency_withdraw_unauthorized_upgrade_authority() {
+    let EmergencyWithdrawSetup {
+        mut test_setup,
+        admin_signer,
+        emergency_recovery_key,
+    } = setup_for_emergency_withdraw().await;
+
+    // The admin cannot act as the upgrade authority.
+    let emergency_withdraw_ix = try_build_instruction(
+        &ID,
+        EmergencyWithdrawAccounts::new(
+            &ID,
+            &admin_signer.pubkey(),
+            &admin_signer.pubkey(),
+            EmergencyWithdrawSource::Journal2z,
+            &emergency_recovery_key,
+        ),
+        &RevenueDistributionInstructionData::EmergencyWithdraw(EmergencyWithdrawSource::Journal2z),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[emergency_withdraw_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Owner (account 1) must match upgrade authority from program data (account 0)"
+    );
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert!(!program_config.has_emergency_withdrawn());
+}
+
+//
+// Emergency withdraw — emergency recovery key not configured.
+//
+
+#[tokio::test]
+async fn test_cannot_emergency_withdraw_without_emergency_recovery_key() {
+    let mut test_setup = common::start_test().await;
+
+    let admin_signer = test_setup
+        .setup_configured_program()
+        .await
+        .unwrap()
+        .admin_signer;
+    let owner_signer = test_setup.owner_signer.insecure_clone();
+
+    let emergency_withdraw_ix = try_build_instruction(
+        &ID,
+        EmergencyWithdrawAccounts::new(
+            &ID,
+            &owner_signer.pubkey(),
+            &admin_signer.pubkey(),
+            EmergencyWithdrawSource::JournalSol,
+            &Pubkey::new_unique(),
+        ),
+        &RevenueDistributionInstructionData::EmergencyWithdraw(EmergencyWithdrawSource::JournalSol),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[emergency_withdraw_ix], &[&owner_signer, &admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Emergency recovery key is not configured"
+    );
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert!(!program_config.is_paused());
+    assert!(!program_config.has_emergency_withdrawn());
+}
+
+//
+// Emergency withdraw — invalid recovery account.
+//
+
+#[tokio::test]
+async fn test_cannot_emergency_withdraw_to_invalid_recovery_account() {
+    let EmergencyWithdrawSetup {
+        mut test_setup,
+        admin_signer,
+        ..
+    } = setup_for_emergency_withdraw().await;
+
+    let owner_signer = test_setup.owner_signer.insecure_clone();
+
+    // Journal SOL.
+    let emergency_withdraw_ix = try_build_instruction(
+        &ID,
+        EmergencyWithdrawAccounts::new(
+            &ID,
+            &owner_signer.pubkey(),
+            &admin_signer.pubkey(),
+            EmergencyWithdrawSource::JournalSol,
+            &Pubkey::new_unique(),
+        ),
+        &RevenueDistributionInstructionData::EmergencyWithdraw(EmergencyWithdrawSource::JournalSol),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[emergency_withdraw_ix], &[&owner_signer, &admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Invalid address for emergency recovery account (account 5)"
+    );
+
+    // Journal 2Z tokens.
+    let emergency_withdraw_ix = try_build_instruction(
+        &ID,
+        EmergencyWithdrawAccounts::new(
+            &ID,
+            &owner_signer.pubkey(),
+            &admin_signer.pubkey(),
+            EmergencyWithdrawSource::Journal2z,
+            &Pubkey::new_unique(),
+        ),
+        &RevenueDistributionInstructionData::EmergencyWithdraw(EmergencyWithdrawSource::Journal2z),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[emergency_withdraw_ix], &[&owner_signer, &admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Invalid address for emergency recovery 2Z token account (account 6)"
+    );
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert!(!program_config.has_emergency_withdrawn());
+}
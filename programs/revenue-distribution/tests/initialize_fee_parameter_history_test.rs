@@ -0,0 +1,214 @@
+mod common;
+
+//
+
+use doublezero_program_tools::instruction::try_build_instruction;
+use doublezero_revenue_distribution::{
+    instruction::{
+        account::ConfigureProgramAccounts, ProgramConfiguration, RevenueDistributionInstructionData,
+    },
+    state::{FeeParameterHistory, FeeParameterHistoryEntry, SolanaValidatorFeeParameters},
+    types::ValidatorFee,
+    ID,
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+//
+// Setup.
+//
+
+struct InitializeFeeParameterHistorySetup {
+    test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
+    debt_accountant_signer: Keypair,
+}
+
+async fn setup_for_initialize_fee_parameter_history() -> InitializeFeeParameterHistorySetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    InitializeFeeParameterHistorySetup {
+        test_setup,
+        admin_signer: configured.admin_signer,
+        debt_accountant_signer: configured.debt_accountant_signer,
+    }
+}
+
+//
+// Initialize fee parameter history — happy path.
+//
+
+#[tokio::test]
+async fn test_initialize_fee_parameter_history() {
+    let InitializeFeeParameterHistorySetup {
+        mut test_setup,
+        admin_signer,
+        debt_accountant_signer,
+    } = setup_for_initialize_fee_parameter_history().await;
+
+    test_setup.initialize_fee_parameter_history().await.unwrap();
+
+    let (_, fee_parameter_history) = test_setup.fetch_fee_parameter_history().await;
+
+    let mut expected_fee_parameter_history = FeeParameterHistory::default();
+    expected_fee_parameter_history.bump_seed = FeeParameterHistory::find_address().1;
+    assert_eq!(fee_parameter_history, expected_fee_parameter_history);
+
+    // Configuring fee parameters with the history records the change.
+
+    test_setup
+        .configure_program_with_fee_parameter_history(
+            &admin_signer,
+            ProgramConfiguration::SolanaValidatorFeeParameters {
+                base_block_rewards_pct: 420,
+                priority_block_rewards_pct: 69,
+                inflation_rewards_pct: 0,
+                jito_tips_pct: 0,
+                fixed_sol_amount: 0,
+                _unused: Default::default(),
+            },
+        )
+        .await
+        .unwrap()
+        .initialize_distribution(&debt_accountant_signer)
+        .await
+        .unwrap()
+        .configure_program_with_fee_parameter_history(
+            &admin_signer,
+            ProgramConfiguration::SolanaValidatorFeeParameters {
+                base_block_rewards_pct: 500,
+                priority_block_rewards_pct: 0,
+                inflation_rewards_pct: 0,
+                jito_tips_pct: 20,
+                fixed_sol_amount: 1,
+                _unused: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+    let current_timestamp = test_setup.get_clock().await.unix_timestamp;
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    let latest_fee_parameters = program_config
+        .distribution_parameters
+        .solana_validator_fee_parameters;
+
+    let (_, fee_parameter_history) = test_setup.fetch_fee_parameter_history().await;
+    assert_eq!(fee_parameter_history.total_entries_count, 2);
+
+    let entries = fee_parameter_history
+        .iter_entries()
+        .copied()
+        .collect::<Vec<_>>();
+    assert!(entries[0].timestamp <= entries[1].timestamp);
+    assert!(entries[1].timestamp <= current_timestamp);
+
+    let mut initial_fee_parameters = SolanaValidatorFeeParameters::default();
+    initial_fee_parameters.base_block_rewards_pct = ValidatorFee::new(420).unwrap();
+    initial_fee_parameters.priority_block_rewards_pct = ValidatorFee::new(69).unwrap();
+
+    let expected_entries = [
+        FeeParameterHistoryEntry {
+            effective_dz_epoch: program_config
+                .next_completed_dz_epoch
+                .checked_sub_duration(1)
+                .unwrap(),
+            timestamp: entries[0].timestamp,
+            solana_validator_fee_parameters: initial_fee_parameters,
+        },
+        FeeParameterHistoryEntry {
+            effective_dz_epoch: program_config.next_completed_dz_epoch,
+            timestamp: entries[1].timestamp,
+            solana_validator_fee_parameters: latest_fee_parameters,
+        },
+    ];
+    assert_eq!(entries, expected_entries);
+
+    // The distribution initialized between the changes used the first entry's
+    // fee parameters.
+
+    let (_, distribution, _, _, _) = test_setup
+        .fetch_distribution(expected_entries[0].effective_dz_epoch)
+        .await;
+    assert_eq!(
+        fee_parameter_history
+            .find_entry(distribution.dz_epoch)
+            .unwrap()
+            .solana_validator_fee_parameters,
+        distribution.solana_validator_fee_parameters
+    );
+}
+
+//
+// Initialize fee parameter history — optional history account.
+//
+
+#[tokio::test]
+async fn test_configure_fee_parameters_with_optional_history() {
+    let InitializeFeeParameterHistorySetup {
+        mut test_setup,
+        admin_signer,
+        ..
+    } = setup_for_initialize_fee_parameter_history().await;
+
+    let fee_parameters = ProgramConfiguration::SolanaValidatorFeeParameters {
+        base_block_rewards_pct: 420,
+        priority_block_rewards_pct: 69,
+        inflation_rewards_pct: 0,
+        jito_tips_pct: 0,
+        fixed_sol_amount: 0,
+        _unused: Default::default(),
+    };
+
+    // Cannot pass the fee parameter history before it is initialized.
+
+    let configure_program_ix = try_build_instruction(
+        &ID,
+        ConfigureProgramAccounts::new(&admin_signer.pubkey(), true),
+        &RevenueDistributionInstructionData::ConfigureProgram(fee_parameters.clone()),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[configure_program_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::UninitializedAccount)
+    );
+    assert_eq!(
+        program_logs.get(8).unwrap(),
+        "Program log: Fee parameter history is not initialized"
+    );
+
+    // Clients that do not pass the fee parameter history can still configure
+    // fee parameters, which are not recorded.
+
+    test_setup
+        .initialize_fee_parameter_history()
+        .await
+        .unwrap()
+        .configure_program(&admin_signer, [fee_parameters])
+        .await
+        .unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(
+        program_config
+            .distribution_parameters
+            .solana_validator_fee_parameters
+            .base_block_rewards_pct,
+        ValidatorFee::new(420).unwrap()
+    );
+
+    let (_, fee_parameter_history) = test_setup.fetch_fee_parameter_history().await;
+    assert_eq!(fee_parameter_history.total_entries_count, 0);
+}
@@ -39,5 +39,6 @@ async fn test_initialize_solana_validator_deposit() {
 
     let mut expected_solana_validator_deposit = SolanaValidatorDeposit::default();
     expected_solana_validator_deposit.node_id = node_id;
+    expected_solana_validator_deposit.set_is_balance_tracked(true);
     assert_eq!(solana_validator_deposit, expected_solana_validator_deposit);
 }
@@ -0,0 +1,251 @@
+mod common;
+
+//
+
+use bytemuck::Pod;
+use doublezero_program_tools::{
+    instruction::try_build_instruction, zero_copy, PrecomputedDiscriminator,
+};
+use doublezero_revenue_distribution::{
+    instruction::{account::MigrateProgramAccountsAccounts, RevenueDistributionInstructionData},
+    state::{self, Distribution, Journal, ProgramConfig},
+    types::DoubleZeroEpoch,
+    ID,
+};
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    account::AccountSharedData,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    signer::Signer,
+    transaction::TransactionError,
+};
+
+//
+// Setup.
+//
+
+struct MigrateProgramAccountsSetup {
+    test_setup: common::ProgramTestWithOwner,
+    dz_epoch: DoubleZeroEpoch,
+}
+
+/// Set up a configured program with one initialized distribution.
+async fn setup_for_migrate_program_accounts() -> MigrateProgramAccountsSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    test_setup
+        .initialize_distribution(&configured.debt_accountant_signer)
+        .await
+        .unwrap();
+
+    MigrateProgramAccountsSetup {
+        test_setup,
+        dz_epoch: DoubleZeroEpoch::new(0),
+    }
+}
+
+/// Overwrite the account data of an existing account, keeping its
+/// discriminator and any remaining data.
+async fn set_account_data<T: Pod + PrecomputedDiscriminator>(
+    test_setup: &mut common::ProgramTestWithOwner,
+    account_key: &Pubkey,
+    account_data: &T,
+) {
+    let mut account = test_setup
+        .context
+        .banks_client
+        .get_account(*account_key)
+        .await
+        .unwrap()
+        .unwrap();
+
+    account.data[zero_copy::data_range::<T>()].copy_from_slice(bytemuck::bytes_of(account_data));
+
+    test_setup
+        .context
+        .set_account(account_key, &AccountSharedData::from(account));
+}
+
+//
+// Migrate program accounts — happy path with sequential error checks.
+//
+
+#[tokio::test]
+async fn test_migrate_program_accounts() {
+    let MigrateProgramAccountsSetup {
+        mut test_setup,
+        dz_epoch,
+    } = setup_for_migrate_program_accounts().await;
+
+    let owner_signer = test_setup.owner_signer.insecure_clone();
+    let program_config_key = ProgramConfig::find_address().0;
+    let journal_key = Journal::find_address().0;
+    let distribution_key = Distribution::find_address(dz_epoch).0;
+
+    // New accounts are initialized at the latest account version.
+
+    let (_, mut program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(program_config.version, 1);
+
+    let (_, journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(journal.version, 1);
+
+    let (_, distribution, distribution_remaining_data, _, _) =
+        test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(distribution.version, 1);
+
+    // Simulate accounts written before account versioning. The last one-off
+    // migration left the program config's migrated flag set.
+
+    program_config.set_is_migrated(true);
+    program_config.version = 0;
+    set_account_data(&mut test_setup, &program_config_key, &program_config).await;
+
+    let mut unmigrated_journal = journal;
+    unmigrated_journal.version = 0;
+    set_account_data(&mut test_setup, &journal_key, &unmigrated_journal).await;
+
+    let mut unmigrated_distribution = distribution;
+    unmigrated_distribution.version = 0;
+    set_account_data(&mut test_setup, &distribution_key, &unmigrated_distribution).await;
+
+    // Unmigrated accounts are still readable.
+
+    let (_, fetched_journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(fetched_journal, unmigrated_journal);
+
+    let (_, fetched_distribution, _, _, _) = test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(fetched_distribution, unmigrated_distribution);
+
+    // Cannot migrate beyond the latest account version.
+
+    let migrate_program_accounts_ix = try_build_instruction(
+        &ID,
+        MigrateProgramAccountsAccounts::new(&ID, &owner_signer.pubkey(), &[]),
+        &RevenueDistributionInstructionData::MigrateProgramAccounts(2),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[migrate_program_accounts_ix], &[&owner_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Target version 2 exceeds latest account version 1"
+    );
+
+    // Cannot migrate accounts not owned by this program.
+
+    let journal_2z_token_pda_key = state::find_2z_token_pda_address(&journal_key).0;
+
+    let migrate_program_accounts_ix = try_build_instruction(
+        &ID,
+        MigrateProgramAccountsAccounts::new(
+            &ID,
+            &owner_signer.pubkey(),
+            &[journal_2z_token_pda_key],
+        ),
+        &RevenueDistributionInstructionData::MigrateProgramAccounts(1),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[migrate_program_accounts_ix], &[&owner_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountOwner)
+    );
+    assert_eq!(
+        program_logs.get(4).unwrap(),
+        "Program log: Account 3 must be owned by the Revenue Distribution program"
+    );
+
+    // Cannot migrate accounts that are neither the journal nor a distribution.
+
+    let migrate_program_accounts_ix = try_build_instruction(
+        &ID,
+        MigrateProgramAccountsAccounts::new(&ID, &owner_signer.pubkey(), &[program_config_key]),
+        &RevenueDistributionInstructionData::MigrateProgramAccounts(1),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[migrate_program_accounts_ix], &[&owner_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(4).unwrap(),
+        "Program log: Account 3 must be the journal or a distribution"
+    );
+
+    // Migrate with the previous selector, which has no target version.
+
+    let account_metas: Vec<AccountMeta> = MigrateProgramAccountsAccounts::new(
+        &ID,
+        &owner_signer.pubkey(),
+        &[journal_key, distribution_key],
+    )
+    .into();
+    let migrate_program_accounts_ix = Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: borsh::to_vec(&RevenueDistributionInstructionData::MIGRATE_PROGRAM_ACCOUNTS).unwrap(),
+    };
+
+    test_setup.context.last_blockhash = common::process_instructions_for_test(
+        &mut test_setup.context.banks_client,
+        &test_setup.context.last_blockhash,
+        &[migrate_program_accounts_ix],
+        &[&test_setup.context.payer, &owner_signer],
+    )
+    .await
+    .unwrap();
+
+    let (_, migrated_program_config, _) = test_setup.fetch_program_config().await;
+    assert_eq!(migrated_program_config.version, 1);
+    assert!(!migrated_program_config.is_migrated());
+
+    program_config.set_is_migrated(false);
+    program_config.version = 1;
+    assert_eq!(migrated_program_config, program_config);
+
+    let (_, migrated_journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(migrated_journal, journal);
+
+    let (_, migrated_distribution, migrated_distribution_remaining_data, _, _) =
+        test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(migrated_distribution, distribution);
+    assert_eq!(
+        migrated_distribution_remaining_data,
+        distribution_remaining_data
+    );
+
+    // Migrating to the same version again leaves accounts unchanged.
+    test_setup
+        .migrate_program_accounts(1, &[journal_key, distribution_key])
+        .await
+        .unwrap();
+
+    let (_, program_config_after, _) = test_setup.fetch_program_config().await;
+    assert_eq!(program_config_after, migrated_program_config);
+
+    let (_, journal_after, _) = test_setup.fetch_journal().await;
+    assert_eq!(journal_after, migrated_journal);
+
+    let (_, distribution_after, _, _, _) = test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(distribution_after, migrated_distribution);
+}
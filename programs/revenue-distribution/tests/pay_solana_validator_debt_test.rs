@@ -8,13 +8,15 @@ use doublezero_revenue_distribution::{
         account::PaySolanaValidatorDebtAccounts, DistributionMerkleRootKind,
         RevenueDistributionInstructionData,
     },
-    state::{self, Distribution, SolanaValidatorDeposit},
+    state::{self, Distribution, PaymentReceipt, SolanaValidatorDeposit, SolanaValidatorPayment},
     types::{BurnRate, DoubleZeroEpoch, SolanaValidatorDebt, ValidatorFee},
     ID,
 };
 use solana_program_test::tokio;
 use solana_pubkey::Pubkey;
-use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+use solana_sdk::{
+    account::AccountSharedData, instruction::InstructionError, transaction::TransactionError,
+};
 use svm_hash::merkle::{merkle_root_from_indexed_pod_leaves, MerkleProof};
 
 //
@@ -129,8 +131,10 @@ async fn test_pay_solana_validator_debt() {
         .map(|(_, proof)| proof.clone())
         .collect::<Vec<_>>();
 
-    let deposit_rent_exemption =
-        (128 + zero_copy::data_end::<SolanaValidatorDeposit>() as u64) * 6_960;
+    let deposit_rent_exemption = (128
+        + (zero_copy::data_end::<SolanaValidatorDeposit>()
+            + SolanaValidatorDeposit::PAYMENT_HISTORY_LEN) as u64)
+        * 6_960;
 
     // Initialize Solana validator deposit accounts and transfer an amount one
     // less than the debt amount.
@@ -159,7 +163,7 @@ async fn test_pay_solana_validator_debt() {
 
         let pay_solana_validator_debt_ix = try_build_instruction(
             &ID,
-            PaySolanaValidatorDebtAccounts::new(dz_epoch, &debt.node_id),
+            PaySolanaValidatorDebtAccounts::new(dz_epoch, &debt.node_id, None),
             &RevenueDistributionInstructionData::PaySolanaValidatorDebt {
                 amount: debt.amount - 1,
                 proof: proof.clone(),
@@ -184,7 +188,7 @@ async fn test_pay_solana_validator_debt() {
 
         let pay_solana_validator_debt_ix = try_build_instruction(
             &ID,
-            PaySolanaValidatorDebtAccounts::new(dz_epoch, &debt.node_id),
+            PaySolanaValidatorDebtAccounts::new(dz_epoch, &debt.node_id, None),
             &RevenueDistributionInstructionData::PaySolanaValidatorDebt {
                 amount: debt.amount,
                 proof,
@@ -239,11 +243,17 @@ async fn test_pay_solana_validator_debt() {
     let (_, journal, _) = test_setup.fetch_journal().await;
     assert_eq!(journal.total_sol_balance, 0);
 
-    // Pay debt.
-    for ((debt, balance_before), proof) in debt_data.iter().zip(balances_before).zip(proofs.clone())
+    // Pay debt. Only request payment receipts for every other validator.
+    for (i, ((debt, balance_before), proof)) in debt_data
+        .iter()
+        .zip(balances_before)
+        .zip(proofs.clone())
+        .enumerate()
     {
+        let with_payment_receipt = i % 2 == 1;
+
         test_setup
-            .pay_solana_validator_debt(dz_epoch, debt, proof)
+            .pay_solana_validator_debt(dz_epoch, debt, proof, with_payment_receipt)
             .await
             .unwrap();
 
@@ -255,6 +265,45 @@ async fn test_pay_solana_validator_debt() {
             .unwrap();
 
         assert_eq!(balance_before - balance_after, debt.amount);
+
+        let payment_history = test_setup
+            .fetch_solana_validator_payment_history(&debt.node_id)
+            .await
+            .unwrap();
+        assert_eq!(payment_history.payments_count, 1);
+        assert_eq!(
+            payment_history.recent_iter().copied().collect::<Vec<_>>(),
+            [SolanaValidatorPayment {
+                dz_epoch,
+                amount: debt.amount,
+                timestamp: test_setup.get_clock().await.unix_timestamp,
+            }]
+        );
+
+        let (payment_receipt_key, payment_receipt_bump) =
+            PaymentReceipt::find_address(dz_epoch, &debt.node_id);
+
+        if with_payment_receipt {
+            let (_, payment_receipt) = test_setup
+                .fetch_payment_receipt(dz_epoch, &debt.node_id)
+                .await;
+
+            let mut expected_payment_receipt = PaymentReceipt::default();
+            expected_payment_receipt.node_id = debt.node_id;
+            expected_payment_receipt.dz_epoch = dz_epoch;
+            expected_payment_receipt.amount = debt.amount;
+            expected_payment_receipt.slot = test_setup.get_clock().await.slot;
+            expected_payment_receipt.bump_seed = payment_receipt_bump;
+            assert_eq!(payment_receipt, expected_payment_receipt);
+        } else {
+            let payment_receipt_account = test_setup
+                .context
+                .banks_client
+                .get_account(payment_receipt_key)
+                .await
+                .unwrap();
+            assert!(payment_receipt_account.is_none());
+        }
     }
 
     let (distribution_key, distribution, remaining_distribution_data, _, _) =
@@ -295,7 +344,7 @@ async fn test_pay_solana_validator_debt() {
 
         let pay_solana_validator_debt_ix = try_build_instruction(
             &ID,
-            PaySolanaValidatorDebtAccounts::new(dz_epoch, &debt.node_id),
+            PaySolanaValidatorDebtAccounts::new(dz_epoch, &debt.node_id, None),
             &RevenueDistributionInstructionData::PaySolanaValidatorDebt {
                 amount: debt.amount,
                 proof,
@@ -321,3 +370,129 @@ async fn test_pay_solana_validator_debt() {
         )
     }
 }
+
+//
+// Pay Solana validator debt — deposit without payment history.
+//
+
+#[tokio::test]
+async fn test_pay_solana_validator_debt_resizes_for_payment_history() {
+    let PaySolanaValidatorDebtSetup {
+        mut test_setup,
+        dz_epoch,
+        debt_data,
+        ..
+    } = setup_for_pay_solana_validator_debt().await;
+
+    let data_end = zero_copy::data_end::<SolanaValidatorDeposit>();
+    let legacy_rent_exemption = (128 + data_end as u64) * 6_960;
+    let payment_history_rent = SolanaValidatorDeposit::PAYMENT_HISTORY_LEN as u64 * 6_960;
+
+    let proofs = (0..2)
+        .map(|i| {
+            MerkleProof::from_indexed_pod_leaves(
+                &debt_data,
+                i,
+                Some(SolanaValidatorDebt::LEAF_PREFIX),
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // Simulate deposits initialized before payment history was added by
+    // truncating the payment history from the first two deposits.
+    for SolanaValidatorDebt { node_id, amount } in debt_data.iter().take(2) {
+        let (deposit_key, _) = SolanaValidatorDeposit::find_address(node_id);
+
+        test_setup
+            .transfer_lamports(&deposit_key, *amount)
+            .await
+            .unwrap()
+            .initialize_solana_validator_deposit(node_id)
+            .await
+            .unwrap();
+
+        let mut deposit_account = test_setup
+            .context
+            .banks_client
+            .get_account(deposit_key)
+            .await
+            .unwrap()
+            .unwrap();
+        deposit_account.data.truncate(data_end);
+        deposit_account.lamports = amount + legacy_rent_exemption;
+
+        test_setup
+            .context
+            .set_account(&deposit_key, &AccountSharedData::from(deposit_account));
+
+        assert!(test_setup
+            .fetch_solana_validator_payment_history(node_id)
+            .await
+            .is_none());
+    }
+
+    // Without a payer, the payment is not recorded.
+    let debt = &debt_data[0];
+    let (deposit_key, _) = SolanaValidatorDeposit::find_address(&debt.node_id);
+
+    test_setup
+        .pay_solana_validator_debt(dz_epoch, debt, proofs[0].clone(), false)
+        .await
+        .unwrap();
+
+    let deposit_account = test_setup
+        .context
+        .banks_client
+        .get_account(deposit_key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(deposit_account.data.len(), data_end);
+    assert_eq!(deposit_account.lamports, legacy_rent_exemption);
+
+    assert!(test_setup
+        .fetch_solana_validator_payment_history(&debt.node_id)
+        .await
+        .is_none());
+
+    // With a payer, the deposit is resized to store its payment history, which
+    // the payer funds.
+    let debt = &debt_data[1];
+    let (deposit_key, _) = SolanaValidatorDeposit::find_address(&debt.node_id);
+
+    test_setup
+        .pay_solana_validator_debt(dz_epoch, debt, proofs[1].clone(), true)
+        .await
+        .unwrap();
+
+    let deposit_account = test_setup
+        .context
+        .banks_client
+        .get_account(deposit_key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        deposit_account.data.len(),
+        data_end + SolanaValidatorDeposit::PAYMENT_HISTORY_LEN
+    );
+    assert_eq!(
+        deposit_account.lamports,
+        legacy_rent_exemption + payment_history_rent
+    );
+
+    let payment_history = test_setup
+        .fetch_solana_validator_payment_history(&debt.node_id)
+        .await
+        .unwrap();
+    assert_eq!(payment_history.payments_count, 1);
+    assert_eq!(
+        payment_history.recent_iter().copied().collect::<Vec<_>>(),
+        [SolanaValidatorPayment {
+            dz_epoch,
+            amount: debt.amount,
+            timestamp: test_setup.get_clock().await.unix_timestamp,
+        }]
+    );
+}
@@ -0,0 +1,163 @@
+mod common;
+
+//
+
+use doublezero_program_tools::instruction::try_build_instruction;
+use doublezero_revenue_distribution::{
+    instruction::{
+        account::{QueryCommunityBurnRateAccounts, QueryToken2zBalanceAccounts},
+        ProgramConfiguration, ProgramFlagConfiguration, Query, RevenueDistributionInstructionData,
+    },
+    state::{self, CommunityBurnRateView, Journal, Token2zBalanceView},
+    types::BurnRate,
+    ID,
+};
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+//
+// Query — community burn rate.
+//
+
+#[tokio::test]
+async fn test_query_community_burn_rate() {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    // Queries are allowed while the program is paused.
+    test_setup
+        .configure_program(
+            &configured.admin_signer,
+            [ProgramConfiguration::Flag(
+                ProgramFlagConfiguration::IsPaused(true),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let query_ix = try_build_instruction(
+        &ID,
+        QueryCommunityBurnRateAccounts::default(),
+        &RevenueDistributionInstructionData::Query(Query::CommunityBurnRate),
+    )
+    .unwrap();
+
+    let return_data = test_setup.simulate_return_data(&[query_ix]).await.unwrap();
+
+    let (_, program_config, _) = test_setup.fetch_program_config().await;
+
+    // The community burn rate is static until it starts increasing after 10
+    // DZ epochs.
+    let initial_cbr = BurnRate::new(100_000_000).unwrap();
+    assert_eq!(
+        CommunityBurnRateView::try_from_return_data(&return_data).unwrap(),
+        CommunityBurnRateView::new(
+            program_config.next_completed_dz_epoch,
+            initial_cbr,
+            initial_cbr,
+            BurnRate::new(500_000_000).unwrap(),
+        )
+    );
+}
+
+//
+// Query — community burn rate not configured.
+//
+
+#[tokio::test]
+async fn test_cannot_query_community_burn_rate_not_configured() {
+    let mut test_setup = common::start_test().await;
+
+    test_setup.initialize_program().await.unwrap();
+
+    let query_ix = try_build_instruction(
+        &ID,
+        QueryCommunityBurnRateAccounts::default(),
+        &RevenueDistributionInstructionData::Query(Query::CommunityBurnRate),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[query_ix], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Community burn rate parameters are not configured"
+    );
+}
+
+//
+// Query — 2Z token balance.
+//
+
+#[tokio::test]
+async fn test_query_token_2z_balance() {
+    let mut test_setup = common::start_test().await;
+
+    test_setup.setup_configured_program().await.unwrap();
+
+    let journal_key = Journal::find_address().0;
+    let journal_2z_token_pda_key = state::find_2z_token_pda_address(&journal_key).0;
+
+    test_setup
+        .transfer_2z(&journal_2z_token_pda_key, 69_420)
+        .await
+        .unwrap();
+
+    let query_ix = try_build_instruction(
+        &ID,
+        QueryToken2zBalanceAccounts::new(&journal_key),
+        &RevenueDistributionInstructionData::Query(Query::Token2zBalance),
+    )
+    .unwrap();
+
+    let return_data = test_setup.simulate_return_data(&[query_ix]).await.unwrap();
+    assert_eq!(
+        Token2zBalanceView::try_from_return_data(&return_data).unwrap(),
+        Token2zBalanceView {
+            token_owner_key: journal_key,
+            amount: 69_420,
+        }
+    );
+}
+
+//
+// Query — 2Z token balance with invalid 2Z token PDA.
+//
+
+#[tokio::test]
+async fn test_cannot_query_token_2z_balance_invalid_token_pda() {
+    let mut test_setup = common::start_test().await;
+
+    test_setup.setup_configured_program().await.unwrap();
+
+    let mut query_accounts = QueryToken2zBalanceAccounts::new(&Journal::find_address().0);
+    query_accounts.token_2z_pda_key = Pubkey::new_unique();
+
+    let query_ix = try_build_instruction(
+        &ID,
+        query_accounts,
+        &RevenueDistributionInstructionData::Query(Query::Token2zBalance),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[query_ix], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidSeeds)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Invalid seeds for token owner's 2Z token PDA (account 1)"
+    );
+}
@@ -0,0 +1,175 @@
+mod common;
+
+//
+
+use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
+use doublezero_revenue_distribution::{
+    instruction::{account::ReconcileDepositSurplusAccounts, RevenueDistributionInstructionData},
+    state::SolanaValidatorDeposit,
+    ID,
+};
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+//
+// Setup.
+//
+
+struct ReconcileDepositSurplusSetup {
+    test_setup: common::ProgramTestWithOwner,
+    debt_accountant_signer: Keypair,
+    depositor_signer: Keypair,
+    node_id: Pubkey,
+}
+
+/// Set up a configured program with a single validator deposit account
+/// initialized and a funded depositor.
+async fn setup_for_reconcile_deposit_surplus() -> ReconcileDepositSurplusSetup {
+    let mut test_setup = common::start_test().await;
+
+    let common::ConfiguredProgramState {
+        debt_accountant_signer,
+        ..
+    } = test_setup.setup_configured_program().await.unwrap();
+
+    let depositor_signer = Keypair::new();
+    let node_id = Pubkey::new_unique();
+
+    test_setup
+        .initialize_solana_validator_deposit(&node_id)
+        .await
+        .unwrap()
+        .transfer_lamports(&depositor_signer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+
+    ReconcileDepositSurplusSetup {
+        test_setup,
+        debt_accountant_signer,
+        depositor_signer,
+        node_id,
+    }
+}
+
+fn reconcile_deposit_surplus_ix(debt_accountant_key: &Pubkey, node_id: &Pubkey) -> Instruction {
+    try_build_instruction(
+        &ID,
+        ReconcileDepositSurplusAccounts::new(debt_accountant_key, node_id),
+        &RevenueDistributionInstructionData::ReconcileDepositSurplus,
+    )
+    .unwrap()
+}
+
+//
+// Reconcile deposit surplus — happy path.
+//
+
+#[tokio::test]
+async fn test_reconcile_deposit_surplus() {
+    let ReconcileDepositSurplusSetup {
+        mut test_setup,
+        debt_accountant_signer,
+        depositor_signer,
+        node_id,
+    } = setup_for_reconcile_deposit_surplus().await;
+
+    let deposit_key = SolanaValidatorDeposit::find_address(&node_id).0;
+    let deposit_rent_exemption = (128
+        + (zero_copy::data_end::<SolanaValidatorDeposit>()
+            + SolanaValidatorDeposit::PAYMENT_HISTORY_LEN) as u64)
+        * 6_960;
+
+    let deposit_amount = 1_000_000_000;
+    let direct_transfer_amount = 69_420;
+
+    test_setup
+        .deposit_sol_for_validator(&depositor_signer, &node_id, deposit_amount)
+        .await
+        .unwrap()
+        .transfer_lamports(&deposit_key, direct_transfer_amount)
+        .await
+        .unwrap()
+        .reconcile_deposit_surplus(&debt_accountant_signer, &node_id)
+        .await
+        .unwrap();
+
+    // The directly transferred lamports are attributed to the validator.
+    let (_, solana_validator_deposit) = test_setup.fetch_solana_validator_deposit(&node_id).await;
+    assert_eq!(
+        solana_validator_deposit.recorded_sol_balance,
+        deposit_amount + direct_transfer_amount
+    );
+    assert_eq!(
+        solana_validator_deposit.deposited_sol_amount,
+        deposit_amount + direct_transfer_amount
+    );
+
+    // These lamports stay in the deposit.
+    let deposit_balance = test_setup
+        .context
+        .banks_client
+        .get_balance(deposit_key)
+        .await
+        .unwrap();
+    assert_eq!(
+        deposit_balance,
+        deposit_rent_exemption + deposit_amount + direct_transfer_amount
+    );
+
+    // There is no more surplus to reconcile.
+    let reconcile_ix = reconcile_deposit_surplus_ix(&debt_accountant_signer.pubkey(), &node_id);
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[reconcile_ix], &[&debt_accountant_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: No surplus lamports in Solana validator deposit"
+    );
+}
+
+//
+// Reconcile deposit surplus — unauthorized debt accountant.
+//
+
+#[tokio::test]
+async fn test_cannot_reconcile_deposit_surplus_unauthorized() {
+    let ReconcileDepositSurplusSetup {
+        mut test_setup,
+        node_id,
+        ..
+    } = setup_for_reconcile_deposit_surplus().await;
+
+    let deposit_key = SolanaValidatorDeposit::find_address(&node_id).0;
+
+    test_setup
+        .transfer_lamports(&deposit_key, 69_420)
+        .await
+        .unwrap();
+
+    let unauthorized_signer = Keypair::new();
+    let reconcile_ix = reconcile_deposit_surplus_ix(&unauthorized_signer.pubkey(), &node_id);
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[reconcile_ix], &[&unauthorized_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Unauthorized debt accountant (account 1)"
+    );
+}
@@ -0,0 +1,460 @@
+mod common;
+
+//
+
+use doublezero_program_tools::instruction::try_build_instruction;
+use doublezero_revenue_distribution::{
+    instruction::{
+        account::ReconcileDistributionAccounts, ContributorRewardsConfiguration,
+        ProgramConfiguration, ProgramFlagConfiguration, RevenueDistributionInstructionData,
+    },
+    state::Journal,
+    types::{DoubleZeroEpoch, RewardShare, UnitShare16},
+    DOUBLEZERO_MINT_KEY, ID,
+};
+use solana_program_test::{tokio, BanksClientError};
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_associated_token_account_interface::address::get_associated_token_address;
+use svm_hash::merkle::{merkle_root_from_indexed_pod_leaves, MerkleProof};
+
+//
+// Constants (odd numbers to force rounding).
+//
+
+const PREPAID_2Z_AMOUNT: u64 = 1_000 * 100_000_000 + 7;
+
+// Recipient shares do not divide evenly, so each distribution leaves dust.
+const RECIPIENT_SHARES: [u16; 3] = [3_333, 3_333, 3_334];
+
+//
+// Setup.
+//
+
+struct ReconcileDistributionSetup {
+    test_setup: common::ProgramTestWithOwner,
+    dz_epoch: DoubleZeroEpoch,
+    rewards_data: Vec<RewardShare>,
+    proofs: Vec<MerkleProof>,
+    recipient_keys: Vec<Vec<Pubkey>>,
+    rewards_accountant_signer: Keypair,
+}
+
+/// Set up a distribution funded only by prepaid 2Z with zero SOL debt and
+/// rewards finalized. Stops BEFORE sweeping 2Z tokens. Contributor unit shares
+/// do not add up to 100%, so some 2Z tokens remain in the distribution's 2Z
+/// token account after all rewards are distributed.
+async fn setup_for_reconcile_distribution() -> ReconcileDistributionSetup {
+    let mut test_setup = common::start_test().await;
+
+    let admin_signer = Keypair::new();
+    let contributor_manager_signer = Keypair::new();
+    let debt_accountant_signer = Keypair::new();
+    let rewards_accountant_signer = Keypair::new();
+
+    let dz_epoch = DoubleZeroEpoch::new(0);
+
+    let rewards_data = vec![
+        RewardShare::new(Pubkey::new_unique(), 333_333_333, false, 0).unwrap(),
+        RewardShare::new(Pubkey::new_unique(), 333_333_333, false, 0).unwrap(),
+    ];
+
+    let total_contributors = rewards_data.len() as u32;
+    let rewards_merkle_root =
+        merkle_root_from_indexed_pod_leaves(&rewards_data, Some(RewardShare::LEAF_PREFIX)).unwrap();
+
+    let proofs = (0..rewards_data.len())
+        .map(|i| {
+            MerkleProof::from_indexed_pod_leaves(
+                &rewards_data,
+                i.try_into().unwrap(),
+                Some(RewardShare::LEAF_PREFIX),
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let (journal_key, _) = Journal::find_address();
+    let journal_ata_key = get_associated_token_address(&journal_key, &DOUBLEZERO_MINT_KEY);
+
+    test_setup
+        .initialize_program()
+        .await
+        .unwrap()
+        .initialize_journal()
+        .await
+        .unwrap()
+        .create_2z_ata(&journal_key)
+        .await
+        .unwrap()
+        .set_admin(&admin_signer.pubkey())
+        .await
+        .unwrap()
+        .configure_program(
+            &admin_signer,
+            [
+                ProgramConfiguration::ContributorManager(contributor_manager_signer.pubkey()),
+                ProgramConfiguration::DebtAccountant(debt_accountant_signer.pubkey()),
+                ProgramConfiguration::RewardsAccountant(rewards_accountant_signer.pubkey()),
+                ProgramConfiguration::CommunityBurnRateParameters {
+                    limit: 500_000_000,
+                    dz_epochs_to_increasing: 10,
+                    dz_epochs_to_limit: 20,
+                    initial_rate: Some(100_000_000),
+                },
+                ProgramConfiguration::DistributeRewardsRelayLamports(10_000),
+                ProgramConfiguration::MinimumEpochDurationToFinalizeRewards(1),
+                ProgramConfiguration::CalculationGracePeriodMinutes(1),
+                ProgramConfiguration::DistributionInitializationGracePeriodMinutes(1),
+                ProgramConfiguration::Flag(ProgramFlagConfiguration::IsPaused(false)),
+            ],
+        )
+        .await
+        .unwrap()
+        .transfer_2z(&journal_ata_key, PREPAID_2Z_AMOUNT)
+        .await
+        .unwrap()
+        .initialize_distribution(&debt_accountant_signer)
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap()
+        // Initialize another distribution to allow rewards to be finalized.
+        .initialize_distribution(&debt_accountant_signer)
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap()
+        .finalize_distribution_debt(dz_epoch, &debt_accountant_signer)
+        .await
+        .unwrap()
+        .configure_distribution_rewards(
+            dz_epoch,
+            &rewards_accountant_signer,
+            total_contributors,
+            rewards_merkle_root,
+        )
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(dz_epoch)
+        .await
+        .unwrap();
+
+    let rewards_manager_signer = Keypair::new();
+    let mut recipient_keys = Vec::new();
+
+    for RewardShare {
+        contributor_key, ..
+    } in rewards_data.iter()
+    {
+        let recipients = RECIPIENT_SHARES
+            .iter()
+            .map(|share| (Pubkey::new_unique(), *share))
+            .collect::<Vec<_>>();
+
+        for (recipient_key, _) in recipients.iter() {
+            test_setup.create_2z_ata(recipient_key).await.unwrap();
+        }
+
+        recipient_keys.push(recipients.iter().map(|(key, _)| *key).collect());
+
+        test_setup
+            .initialize_contributor_rewards(contributor_key)
+            .await
+            .unwrap()
+            .set_rewards_manager(
+                contributor_key,
+                &contributor_manager_signer,
+                &rewards_manager_signer.pubkey(),
+            )
+            .await
+            .unwrap()
+            .configure_contributor_rewards(
+                contributor_key,
+                &rewards_manager_signer,
+                [ContributorRewardsConfiguration::Recipients(recipients)],
+            )
+            .await
+            .unwrap();
+    }
+
+    ReconcileDistributionSetup {
+        test_setup,
+        dz_epoch,
+        rewards_data,
+        proofs,
+        recipient_keys,
+        rewards_accountant_signer,
+    }
+}
+
+impl ReconcileDistributionSetup {
+    async fn distribute_rewards(&mut self, index: usize) -> Result<&mut Self, BanksClientError> {
+        let relayer_key = self.test_setup.payer_signer().pubkey();
+        let recipient_keys = self.recipient_keys[index].iter().collect::<Vec<_>>();
+
+        self.test_setup
+            .distribute_rewards(
+                self.dz_epoch,
+                &self.rewards_data[index],
+                &DOUBLEZERO_MINT_KEY,
+                &relayer_key,
+                &recipient_keys,
+                self.proofs[index].clone(),
+            )
+            .await?;
+
+        Ok(self)
+    }
+}
+
+//
+// Reconcile distribution — happy path.
+//
+
+#[tokio::test]
+async fn test_reconcile_distribution() {
+    let mut setup = setup_for_reconcile_distribution().await;
+    let dz_epoch = setup.dz_epoch;
+
+    let test_setup = &mut setup.test_setup;
+    test_setup
+        .sweep_distribution_tokens(dz_epoch)
+        .await
+        .unwrap();
+
+    // Cannot reconcile until all rewards have been distributed.
+    let (tx_err, program_logs) =
+        simulate_reconcile_revert(test_setup, dz_epoch, &setup.rewards_accountant_signer)
+            .await
+            .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Not all rewards have been distributed"
+    );
+
+    setup
+        .distribute_rewards(0)
+        .await
+        .unwrap()
+        .distribute_rewards(1)
+        .await
+        .unwrap();
+
+    let test_setup = &mut setup.test_setup;
+
+    let (_, distribution_before, _, _, distribution_2z_token_pda) =
+        test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(
+        distribution_before.total_collected_2z_tokens(),
+        PREPAID_2Z_AMOUNT
+    );
+
+    // Compute the rounding dust expected from splitting each contributor's
+    // share across its recipients.
+    let expected_rounding_dust = setup
+        .rewards_data
+        .iter()
+        .map(|reward_share| {
+            let (_, remaining_share_amount) =
+                distribution_before.split_2z_amount(reward_share).unwrap();
+            let transferred_amount = RECIPIENT_SHARES
+                .iter()
+                .map(|share| {
+                    UnitShare16::new(*share)
+                        .unwrap()
+                        .mul_scalar(remaining_share_amount)
+                })
+                .sum::<u64>();
+
+            remaining_share_amount - transferred_amount
+        })
+        .sum::<u64>();
+    assert_ne!(expected_rounding_dust, 0);
+    assert_eq!(
+        distribution_before.burned_rounding_dust_2z_amount,
+        expected_rounding_dust
+    );
+
+    // Contributor unit shares do not add up to 100%, so 2Z tokens remain.
+    let expected_residual_2z_amount = distribution_2z_token_pda.amount;
+    assert_ne!(expected_residual_2z_amount, 0);
+
+    setup
+        .test_setup
+        .reconcile_distribution(dz_epoch, &setup.rewards_accountant_signer)
+        .await
+        .unwrap();
+
+    let test_setup = &mut setup.test_setup;
+
+    let (_, distribution, _, _, distribution_2z_token_pda) =
+        test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(
+        distribution_2z_token_pda.amount,
+        expected_residual_2z_amount
+    );
+
+    let mut expected_distribution = distribution_before;
+    expected_distribution.set_is_reconciled(true);
+    expected_distribution.reconciled_residual_2z_amount = expected_residual_2z_amount;
+    assert_eq!(distribution, expected_distribution);
+
+    assert!(!distribution.has_reconciliation_mismatch());
+    assert_eq!(
+        distribution.distributed_2z_amount
+            + distribution.burned_2z_amount
+            + distribution.reconciled_residual_2z_amount,
+        PREPAID_2Z_AMOUNT
+    );
+
+    // Reconciling again records the same outcome.
+    test_setup
+        .reconcile_distribution(dz_epoch, &setup.rewards_accountant_signer)
+        .await
+        .unwrap();
+
+    let (_, distribution, _, _, _) = setup.test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(distribution, expected_distribution);
+}
+
+//
+// Reconcile distribution — mismatch.
+//
+
+#[tokio::test]
+async fn test_reconcile_distribution_flags_mismatch() {
+    let mut setup = setup_for_reconcile_distribution().await;
+    let dz_epoch = setup.dz_epoch;
+
+    // Cannot reconcile before 2Z tokens are swept.
+    let (tx_err, program_logs) = simulate_reconcile_revert(
+        &mut setup.test_setup,
+        dz_epoch,
+        &setup.rewards_accountant_signer,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Distribution has not swept 2Z tokens"
+    );
+
+    setup
+        .test_setup
+        .sweep_distribution_tokens(dz_epoch)
+        .await
+        .unwrap();
+    setup
+        .distribute_rewards(0)
+        .await
+        .unwrap()
+        .distribute_rewards(1)
+        .await
+        .unwrap();
+
+    // 2Z tokens sent directly to the distribution's 2Z token account were
+    // never collected by the distribution.
+    let donated_2z_amount = 69;
+
+    let distribution_2z_token_pda_key =
+        ReconcileDistributionAccounts::new(&setup.rewards_accountant_signer.pubkey(), dz_epoch)
+            .distribution_2z_token_pda_key;
+    let (_, _, _, _, distribution_2z_token_pda_before) =
+        setup.test_setup.fetch_distribution(dz_epoch).await;
+
+    setup
+        .test_setup
+        .transfer_2z(&distribution_2z_token_pda_key, donated_2z_amount)
+        .await
+        .unwrap()
+        .reconcile_distribution(dz_epoch, &setup.rewards_accountant_signer)
+        .await
+        .unwrap();
+
+    let (_, distribution, _, _, _) = setup.test_setup.fetch_distribution(dz_epoch).await;
+    assert!(distribution.is_reconciled());
+    assert!(distribution.has_reconciliation_mismatch());
+    assert_eq!(
+        distribution.reconciled_residual_2z_amount,
+        distribution_2z_token_pda_before.amount + donated_2z_amount
+    );
+    assert!(!distribution.is_2z_amount_conserved(distribution.reconciled_residual_2z_amount));
+    assert!(distribution.is_2z_amount_conserved(distribution_2z_token_pda_before.amount));
+}
+
+//
+// Reconcile distribution — unauthorized rewards accountant.
+//
+
+#[tokio::test]
+async fn test_cannot_reconcile_distribution_unauthorized() {
+    let mut setup = setup_for_reconcile_distribution().await;
+    let dz_epoch = setup.dz_epoch;
+
+    setup
+        .test_setup
+        .sweep_distribution_tokens(dz_epoch)
+        .await
+        .unwrap();
+    setup
+        .distribute_rewards(0)
+        .await
+        .unwrap()
+        .distribute_rewards(1)
+        .await
+        .unwrap();
+
+    let unauthorized_signer = Keypair::new();
+
+    let (tx_err, program_logs) =
+        simulate_reconcile_revert(&mut setup.test_setup, dz_epoch, &unauthorized_signer)
+            .await
+            .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Unauthorized rewards accountant (account 1)"
+    );
+
+    let (_, distribution, _, _, _) = setup.test_setup.fetch_distribution(dz_epoch).await;
+    assert!(!distribution.is_reconciled());
+}
+
+//
+// Helpers.
+//
+
+async fn simulate_reconcile_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    dz_epoch: DoubleZeroEpoch,
+    rewards_accountant_signer: &Keypair,
+) -> Result<(TransactionError, Vec<String>), BanksClientError> {
+    let reconcile_distribution_ix = try_build_instruction(
+        &ID,
+        ReconcileDistributionAccounts::new(&rewards_accountant_signer.pubkey(), dz_epoch),
+        &RevenueDistributionInstructionData::ReconcileDistribution,
+    )
+    .unwrap();
+
+    test_setup
+        .unwrap_simulation_error(&[reconcile_distribution_ix], &[rewards_accountant_signer])
+        .await
+}
@@ -0,0 +1,268 @@
+mod common;
+
+//
+
+use doublezero_program_tools::instruction::try_build_instruction;
+use doublezero_revenue_distribution::{
+    instruction::{
+        account::ReplaceDistributionRewardsRootAccounts, RevenueDistributionInstructionData,
+    },
+    types::DoubleZeroEpoch,
+    ID,
+};
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use svm_hash::sha2::Hash;
+
+//
+// Setup.
+//
+
+struct ReplaceDistributionRewardsRootSetup {
+    test_setup: common::ProgramTestWithOwner,
+    rewards_accountant_signer: Keypair,
+    dz_epoch: DoubleZeroEpoch,
+    rewards_merkle_root: Hash,
+}
+
+/// Set up a configured program with two distributions (epoch 0 and 1).
+/// Epoch 1 has its rewards merkle root configured.
+async fn setup_for_replace_distribution_rewards_root(
+    should_configure_rewards: bool,
+) -> ReplaceDistributionRewardsRootSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    test_setup
+        .initialize_distribution(&configured.debt_accountant_signer)
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap()
+        .initialize_distribution(&configured.debt_accountant_signer)
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap();
+
+    let dz_epoch = DoubleZeroEpoch::new(1);
+    let rewards_merkle_root = Hash::new_unique();
+
+    if should_configure_rewards {
+        test_setup
+            .configure_distribution_rewards(
+                dz_epoch,
+                &configured.rewards_accountant_signer,
+                69,
+                rewards_merkle_root,
+            )
+            .await
+            .unwrap();
+    }
+
+    ReplaceDistributionRewardsRootSetup {
+        test_setup,
+        rewards_accountant_signer: configured.rewards_accountant_signer,
+        dz_epoch,
+        rewards_merkle_root,
+    }
+}
+
+//
+// Replace distribution rewards root — happy path.
+//
+
+#[tokio::test]
+async fn test_replace_distribution_rewards_root() {
+    let ReplaceDistributionRewardsRootSetup {
+        mut test_setup,
+        rewards_accountant_signer,
+        dz_epoch,
+        rewards_merkle_root,
+    } = setup_for_replace_distribution_rewards_root(true).await;
+
+    let (_, distribution, _, _, _) = test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(distribution.rewards_merkle_root, rewards_merkle_root);
+    assert_eq!(distribution.rewards_root_revision, 0);
+    assert_eq!(distribution.rewards_root_correction_memo, Hash::default());
+
+    let total_contributors = 420;
+    let replaced_rewards_merkle_root = Hash::new_unique();
+    let correction_memo = Hash::new_unique();
+
+    test_setup
+        .replace_distribution_rewards_root(
+            dz_epoch,
+            &rewards_accountant_signer,
+            total_contributors,
+            replaced_rewards_merkle_root,
+            correction_memo,
+        )
+        .await
+        .unwrap();
+
+    let (_, distribution, _, _, _) = test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(distribution.total_contributors, total_contributors);
+    assert_eq!(
+        distribution.rewards_merkle_root,
+        replaced_rewards_merkle_root
+    );
+    assert_eq!(distribution.rewards_root_revision, 1);
+    assert_eq!(distribution.rewards_root_correction_memo, correction_memo);
+
+    // The root can be replaced again before finalization.
+    let replaced_rewards_merkle_root = Hash::new_unique();
+    let correction_memo = Hash::new_unique();
+
+    test_setup
+        .replace_distribution_rewards_root(
+            dz_epoch,
+            &rewards_accountant_signer,
+            total_contributors,
+            replaced_rewards_merkle_root,
+            correction_memo,
+        )
+        .await
+        .unwrap();
+
+    let (_, distribution, _, _, _) = test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(
+        distribution.rewards_merkle_root,
+        replaced_rewards_merkle_root
+    );
+    assert_eq!(distribution.rewards_root_revision, 2);
+    assert_eq!(distribution.rewards_root_correction_memo, correction_memo);
+}
+
+//
+// Replace distribution rewards root — root not configured.
+//
+
+#[tokio::test]
+async fn test_cannot_replace_distribution_rewards_root_not_configured() {
+    let ReplaceDistributionRewardsRootSetup {
+        mut test_setup,
+        rewards_accountant_signer,
+        dz_epoch,
+        ..
+    } = setup_for_replace_distribution_rewards_root(false).await;
+
+    let (tx_err, program_logs) = simulate_replace_distribution_rewards_root_revert(
+        &mut test_setup,
+        &rewards_accountant_signer,
+        dz_epoch,
+        Hash::new_unique(),
+        Hash::new_unique(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Rewards merkle root not configured"
+    );
+}
+
+//
+// Replace distribution rewards root — empty correction memo.
+//
+
+#[tokio::test]
+async fn test_cannot_replace_distribution_rewards_root_without_memo() {
+    let ReplaceDistributionRewardsRootSetup {
+        mut test_setup,
+        rewards_accountant_signer,
+        dz_epoch,
+        ..
+    } = setup_for_replace_distribution_rewards_root(true).await;
+
+    let (tx_err, program_logs) = simulate_replace_distribution_rewards_root_revert(
+        &mut test_setup,
+        &rewards_accountant_signer,
+        dz_epoch,
+        Hash::new_unique(),
+        Hash::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Correction memo cannot be empty"
+    );
+}
+
+//
+// Replace distribution rewards root — unchanged root.
+//
+
+#[tokio::test]
+async fn test_cannot_replace_distribution_rewards_root_unchanged() {
+    let ReplaceDistributionRewardsRootSetup {
+        mut test_setup,
+        rewards_accountant_signer,
+        dz_epoch,
+        rewards_merkle_root,
+    } = setup_for_replace_distribution_rewards_root(true).await;
+
+    let (tx_err, program_logs) = simulate_replace_distribution_rewards_root_revert(
+        &mut test_setup,
+        &rewards_accountant_signer,
+        dz_epoch,
+        rewards_merkle_root,
+        Hash::new_unique(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Rewards merkle root is unchanged"
+    );
+}
+
+//
+// Helpers.
+//
+
+async fn simulate_replace_distribution_rewards_root_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    rewards_accountant_signer: &Keypair,
+    dz_epoch: DoubleZeroEpoch,
+    merkle_root: Hash,
+    correction_memo: Hash,
+) -> Result<(TransactionError, Vec<String>), BanksClientError> {
+    let replace_distribution_rewards_root_ix = try_build_instruction(
+        &ID,
+        ReplaceDistributionRewardsRootAccounts::new(&rewards_accountant_signer.pubkey(), dz_epoch),
+        &RevenueDistributionInstructionData::ReplaceDistributionRewardsRoot {
+            total_contributors: 69,
+            merkle_root,
+            correction_memo,
+        },
+    )
+    .unwrap();
+
+    test_setup
+        .unwrap_simulation_error(
+            &[replace_distribution_rewards_root_ix],
+            &[rewards_accountant_signer],
+        )
+        .await
+}
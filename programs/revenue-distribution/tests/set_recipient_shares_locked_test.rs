@@ -0,0 +1,280 @@
+mod common;
+
+//
+
+use doublezero_program_tools::instruction::try_build_instruction;
+use doublezero_revenue_distribution::{
+    instruction::{
+        account::{ConfigureContributorRewardsAccounts, SetRecipientSharesLockedAccounts},
+        ContributorRewardsConfiguration, ProgramConfiguration, RevenueDistributionInstructionData,
+    },
+    state::RecipientShares,
+    ID,
+};
+use solana_program_test::tokio;
+use solana_pubkey::Pubkey;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+//
+// Setup.
+//
+
+struct SetRecipientSharesLockedSetup {
+    test_setup: common::ProgramTestWithOwner,
+    rewards_accountant_signer: Keypair,
+    rewards_manager_signer: Keypair,
+    service_keys: [Pubkey; 2],
+}
+
+async fn setup_for_set_recipient_shares_locked() -> SetRecipientSharesLockedSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    let contributor_manager_signer = Keypair::new();
+    let rewards_manager_signer = Keypair::new();
+    let service_keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    test_setup
+        .configure_program(
+            &configured.admin_signer,
+            [ProgramConfiguration::ContributorManager(
+                contributor_manager_signer.pubkey(),
+            )],
+        )
+        .await
+        .unwrap();
+
+    for service_key in service_keys.iter() {
+        test_setup
+            .initialize_contributor_rewards(service_key)
+            .await
+            .unwrap()
+            .set_rewards_manager(
+                service_key,
+                &contributor_manager_signer,
+                &rewards_manager_signer.pubkey(),
+            )
+            .await
+            .unwrap()
+            .configure_contributor_rewards(
+                service_key,
+                &rewards_manager_signer,
+                [ContributorRewardsConfiguration::Recipients(vec![(
+                    Pubkey::new_unique(),
+                    10_000,
+                )])],
+            )
+            .await
+            .unwrap();
+    }
+
+    SetRecipientSharesLockedSetup {
+        test_setup,
+        rewards_accountant_signer: configured.rewards_accountant_signer,
+        rewards_manager_signer,
+        service_keys,
+    }
+}
+
+//
+// Set recipient shares locked — happy path.
+//
+
+#[tokio::test]
+async fn test_set_recipient_shares_locked() {
+    let SetRecipientSharesLockedSetup {
+        mut test_setup,
+        rewards_accountant_signer,
+        rewards_manager_signer,
+        service_keys,
+    } = setup_for_set_recipient_shares_locked().await;
+
+    let [service_key_1, service_key_2] = service_keys;
+
+    test_setup
+        .set_recipient_shares_locked(
+            &rewards_accountant_signer,
+            &[&service_key_1, &service_key_2],
+            true,
+        )
+        .await
+        .unwrap();
+
+    for service_key in service_keys.iter() {
+        let (_, contributor_rewards) = test_setup.fetch_contributor_rewards(service_key).await;
+        assert!(contributor_rewards.is_recipient_shares_locked());
+    }
+
+    // Cannot change recipients while locked.
+    let (tx_err, program_logs) = simulate_configure_recipients_revert(
+        &mut test_setup,
+        &rewards_manager_signer,
+        &service_key_1,
+    )
+    .await;
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Recipient shares are locked"
+    );
+
+    // Other settings can still be configured while locked.
+    test_setup
+        .configure_contributor_rewards(
+            &service_key_1,
+            &rewards_manager_signer,
+            [ContributorRewardsConfiguration::IsSetRewardsManagerBlocked(
+                true,
+            )],
+        )
+        .await
+        .unwrap();
+
+    let (_, contributor_rewards) = test_setup.fetch_contributor_rewards(&service_key_1).await;
+    assert!(contributor_rewards.is_recipient_shares_locked());
+    assert!(contributor_rewards.is_set_rewards_manager_blocked());
+
+    // Unlock only the first contributor's recipient shares.
+    test_setup
+        .set_recipient_shares_locked(&rewards_accountant_signer, &[&service_key_1], false)
+        .await
+        .unwrap();
+
+    let (_, contributor_rewards) = test_setup.fetch_contributor_rewards(&service_key_2).await;
+    assert!(contributor_rewards.is_recipient_shares_locked());
+
+    let recipients = vec![(Pubkey::new_unique(), 10_000)];
+
+    test_setup
+        .configure_contributor_rewards(
+            &service_key_1,
+            &rewards_manager_signer,
+            [ContributorRewardsConfiguration::Recipients(
+                recipients.clone(),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let (_, contributor_rewards) = test_setup.fetch_contributor_rewards(&service_key_1).await;
+    assert!(!contributor_rewards.is_recipient_shares_locked());
+    assert_eq!(
+        contributor_rewards.recipient_shares,
+        RecipientShares::new(&recipients).unwrap()
+    );
+}
+
+//
+// Set recipient shares locked — unauthorized.
+//
+
+#[tokio::test]
+async fn test_cannot_set_recipient_shares_locked_unauthorized() {
+    let SetRecipientSharesLockedSetup {
+        mut test_setup,
+        rewards_manager_signer,
+        service_keys,
+        ..
+    } = setup_for_set_recipient_shares_locked().await;
+
+    let set_recipient_shares_locked_ix = try_build_instruction(
+        &ID,
+        SetRecipientSharesLockedAccounts::new(
+            &rewards_manager_signer.pubkey(),
+            &[&service_keys[0]],
+        ),
+        &RevenueDistributionInstructionData::SetRecipientSharesLocked(true),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(
+            &[set_recipient_shares_locked_ix],
+            &[&rewards_manager_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(2).unwrap(),
+        "Program log: Unauthorized rewards accountant (account 1)"
+    );
+}
+
+//
+// Set recipient shares locked — no contributor rewards.
+//
+
+#[tokio::test]
+async fn test_cannot_set_recipient_shares_locked_without_contributor_rewards() {
+    let SetRecipientSharesLockedSetup {
+        mut test_setup,
+        rewards_accountant_signer,
+        ..
+    } = setup_for_set_recipient_shares_locked().await;
+
+    let set_recipient_shares_locked_ix = try_build_instruction(
+        &ID,
+        SetRecipientSharesLockedAccounts::new(&rewards_accountant_signer.pubkey(), &[]),
+        &RevenueDistributionInstructionData::SetRecipientSharesLocked(true),
+    )
+    .unwrap();
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(
+            &[set_recipient_shares_locked_ix],
+            &[&rewards_accountant_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, common::NOT_ENOUGH_ACCOUNT_KEYS)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: At least one contributor rewards account is required"
+    );
+}
+
+//
+// Helpers.
+//
+
+async fn simulate_configure_recipients_revert(
+    test_setup: &mut common::ProgramTestWithOwner,
+    rewards_manager_signer: &Keypair,
+    service_key: &Pubkey,
+) -> (TransactionError, Vec<String>) {
+    let configure_contributor_rewards_ix = try_build_instruction(
+        &ID,
+        ConfigureContributorRewardsAccounts::new(
+            &rewards_manager_signer.pubkey(),
+            service_key,
+            None,
+        ),
+        &RevenueDistributionInstructionData::ConfigureContributorRewards(
+            ContributorRewardsConfiguration::Recipients(vec![(Pubkey::new_unique(), 10_000)]),
+        ),
+    )
+    .unwrap();
+
+    test_setup
+        .unwrap_simulation_error(
+            &[configure_contributor_rewards_ix],
+            &[rewards_manager_signer],
+        )
+        .await
+        .unwrap()
+}
@@ -0,0 +1,239 @@
+mod common;
+
+//
+
+use doublezero_program_tools::instruction::try_build_instruction;
+use doublezero_revenue_distribution::{
+    instruction::{account::SkipDistributionSweepAccounts, RevenueDistributionInstructionData},
+    types::DoubleZeroEpoch,
+    ID,
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use svm_hash::sha2::Hash;
+
+//
+// Setup.
+//
+
+struct SkipDistributionSweepSetup {
+    test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
+    debt_accountant_signer: Keypair,
+}
+
+/// Set up a configured program with two distributions (epoch 0 and 1), both
+/// ready for debt calculation.
+async fn setup_for_skip_distribution_sweep() -> SkipDistributionSweepSetup {
+    let mut test_setup = common::start_test().await;
+
+    let configured = test_setup.setup_configured_program().await.unwrap();
+
+    test_setup
+        .initialize_distribution(&configured.debt_accountant_signer)
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap()
+        .initialize_distribution(&configured.debt_accountant_signer)
+        .await
+        .unwrap()
+        .warp_timestamp_by(60)
+        .await
+        .unwrap();
+
+    SkipDistributionSweepSetup {
+        test_setup,
+        admin_signer: configured.admin_signer,
+        debt_accountant_signer: configured.debt_accountant_signer,
+    }
+}
+
+fn skip_distribution_sweep_ix(admin_signer: &Keypair, dz_epoch: DoubleZeroEpoch) -> Instruction {
+    try_build_instruction(
+        &ID,
+        SkipDistributionSweepAccounts::new(&admin_signer.pubkey(), dz_epoch),
+        &RevenueDistributionInstructionData::SkipDistributionSweep,
+    )
+    .unwrap()
+}
+
+//
+// Skip distribution sweep — happy path.
+//
+
+#[tokio::test]
+async fn test_skip_distribution_sweep() {
+    let SkipDistributionSweepSetup {
+        mut test_setup,
+        admin_signer,
+        debt_accountant_signer,
+    } = setup_for_skip_distribution_sweep().await;
+
+    let dz_epoch = DoubleZeroEpoch::new(0);
+
+    test_setup
+        .finalize_distribution_debt(dz_epoch, &debt_accountant_signer)
+        .await
+        .unwrap()
+        .skip_distribution_sweep(dz_epoch, &admin_signer)
+        .await
+        .unwrap();
+
+    let (_, distribution, _, _, distribution_2z_token_pda) =
+        test_setup.fetch_distribution(dz_epoch).await;
+    assert!(distribution.has_swept_2z_tokens());
+    assert_eq!(distribution.checked_total_sol_debt(), Some(0));
+    assert_eq!(distribution_2z_token_pda.amount, 0);
+
+    let (_, journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(
+        journal.next_dz_epoch_to_sweep_tokens,
+        DoubleZeroEpoch::new(1)
+    );
+
+    // The next distribution can be skipped now.
+    let next_dz_epoch = dz_epoch.saturating_add_duration(1);
+
+    test_setup
+        .finalize_distribution_debt(next_dz_epoch, &debt_accountant_signer)
+        .await
+        .unwrap()
+        .skip_distribution_sweep(next_dz_epoch, &admin_signer)
+        .await
+        .unwrap();
+
+    let (_, journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(
+        journal.next_dz_epoch_to_sweep_tokens,
+        DoubleZeroEpoch::new(2)
+    );
+
+    // Cannot skip the same distribution twice.
+    let skip_ix = skip_distribution_sweep_ix(&admin_signer, next_dz_epoch);
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[skip_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Distribution has already swept 2Z tokens"
+    );
+}
+
+//
+// Skip distribution sweep — debt calculation not finalized.
+//
+
+#[tokio::test]
+async fn test_cannot_skip_distribution_sweep_debt_not_finalized() {
+    let SkipDistributionSweepSetup {
+        mut test_setup,
+        admin_signer,
+        ..
+    } = setup_for_skip_distribution_sweep().await;
+
+    let skip_ix = skip_distribution_sweep_ix(&admin_signer, DoubleZeroEpoch::new(0));
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[skip_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Distribution debt calculation is not finalized yet"
+    );
+}
+
+//
+// Skip distribution sweep — SOL debt.
+//
+
+#[tokio::test]
+async fn test_cannot_skip_distribution_sweep_with_sol_debt() {
+    let SkipDistributionSweepSetup {
+        mut test_setup,
+        admin_signer,
+        debt_accountant_signer,
+    } = setup_for_skip_distribution_sweep().await;
+
+    let dz_epoch = DoubleZeroEpoch::new(0);
+
+    test_setup
+        .configure_distribution_debt(
+            dz_epoch,
+            &debt_accountant_signer,
+            1,
+            u64::pow(10, 9),
+            Hash::new_unique(),
+        )
+        .await
+        .unwrap()
+        .finalize_distribution_debt(dz_epoch, &debt_accountant_signer)
+        .await
+        .unwrap();
+
+    let skip_ix = skip_distribution_sweep_ix(&admin_signer, dz_epoch);
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[skip_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Cannot skip sweep with SOL debt"
+    );
+}
+
+//
+// Skip distribution sweep — out of order.
+//
+
+#[tokio::test]
+async fn test_cannot_skip_distribution_sweep_out_of_order() {
+    let SkipDistributionSweepSetup {
+        mut test_setup,
+        admin_signer,
+        debt_accountant_signer,
+    } = setup_for_skip_distribution_sweep().await;
+
+    let dz_epoch = DoubleZeroEpoch::new(1);
+
+    test_setup
+        .finalize_distribution_debt(dz_epoch, &debt_accountant_signer)
+        .await
+        .unwrap();
+
+    let skip_ix = skip_distribution_sweep_ix(&admin_signer, dz_epoch);
+
+    let (tx_err, program_logs) = test_setup
+        .unwrap_simulation_error(&[skip_ix], &[&admin_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(3).unwrap(),
+        "Program log: Can only sweep tokens for DZ epoch 0"
+    );
+}
@@ -9,7 +9,7 @@ use doublezero_revenue_distribution::{
         ProgramFeatureConfiguration, ProgramFlagConfiguration, RevenueDistributionInstructionData,
     },
     state::{
-        self, find_2z_token_pda_address, find_swap_authority_address, Distribution,
+        self, find_2z_token_pda_address, find_swap_authority_address, Distribution, ProgramConfig,
         SolanaValidatorDeposit,
     },
     types::{BurnRate, DoubleZeroEpoch, SolanaValidatorDebt, ValidatorFee},
@@ -33,6 +33,7 @@ use svm_hash::{
 
 struct SweepDistributionTokensSetup {
     test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
     debt_accountant_signer: Keypair,
     rewards_accountant_signer: Keypair,
     src_token_account_key: Pubkey,
@@ -222,7 +223,7 @@ async fn setup_for_sweep_distribution_tokens() -> SweepDistributionTokensSetup {
             .transfer_lamports(&deposit_key, debt.amount)
             .await
             .unwrap()
-            .pay_solana_validator_debt(next_dz_epoch, debt, proof)
+            .pay_solana_validator_debt(next_dz_epoch, debt, proof, false)
             .await
             .unwrap();
     }
@@ -231,6 +232,7 @@ async fn setup_for_sweep_distribution_tokens() -> SweepDistributionTokensSetup {
 
     SweepDistributionTokensSetup {
         test_setup,
+        admin_signer,
         debt_accountant_signer,
         rewards_accountant_signer,
         src_token_account_key,
@@ -422,7 +424,7 @@ async fn test_sweep_distribution_tokens() {
             .transfer_lamports(&deposit_key, debt.amount)
             .await
             .unwrap()
-            .pay_solana_validator_debt(and_another_dz_epoch, debt, proof)
+            .pay_solana_validator_debt(and_another_dz_epoch, debt, proof, false)
             .await
             .unwrap();
     }
@@ -674,3 +676,107 @@ async fn test_sweep_distribution_tokens() {
         &remaining_distribution_data[distribution.processed_rewards_bitmap_range()];
     assert_eq!(rewards_bitmap, [0]);
 }
+
+//
+// Sweep distribution tokens — treasury 2Z share.
+//
+
+#[tokio::test]
+async fn test_sweep_distribution_tokens_with_treasury_2z_share() {
+    let SweepDistributionTokensSetup {
+        mut test_setup,
+        admin_signer,
+        debt_accountant_signer,
+        src_token_account_key,
+        transfer_authority_signer,
+        debt_data,
+        total_solana_validator_debt,
+        uncollectible_debt,
+        expected_swept_2z_amount_1,
+        dz_epoch,
+        next_dz_epoch,
+        ..
+    } = setup_for_sweep_distribution_tokens().await;
+
+    // 10% of swept 2Z tokens go to the treasury.
+    let treasury_2z_share = 1_000;
+
+    // Pay the remaining debt so the journal has enough SOL to swap for the
+    // total SOL debt.
+    let uncollectible_index = 2;
+    let proof = MerkleProof::from_indexed_pod_leaves(
+        &debt_data,
+        uncollectible_index,
+        Some(SolanaValidatorDebt::LEAF_PREFIX),
+    )
+    .unwrap();
+
+    let (deposit_key, _) = SolanaValidatorDeposit::find_address(&uncollectible_debt.node_id);
+
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::Treasury2zShare(treasury_2z_share)],
+        )
+        .await
+        .unwrap()
+        .transfer_lamports(&deposit_key, uncollectible_debt.amount)
+        .await
+        .unwrap()
+        .pay_solana_validator_debt(next_dz_epoch, &uncollectible_debt, proof, false)
+        .await
+        .unwrap()
+        .finalize_distribution_debt(dz_epoch, &debt_accountant_signer)
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(dz_epoch)
+        .await
+        .unwrap()
+        .finalize_distribution_rewards(next_dz_epoch)
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(dz_epoch)
+        .await
+        .unwrap()
+        .mock_buy_sol(
+            &src_token_account_key,
+            &transfer_authority_signer,
+            &Pubkey::new_unique(),
+            expected_swept_2z_amount_1,
+            total_solana_validator_debt,
+        )
+        .await
+        .unwrap()
+        .sweep_distribution_tokens(next_dz_epoch)
+        .await
+        .unwrap();
+
+    let expected_treasury_2z_amount = expected_swept_2z_amount_1 / 10;
+    let expected_distribution_2z_amount = expected_swept_2z_amount_1 - expected_treasury_2z_amount;
+
+    let (_, distribution, _, _, distribution_2z_token_pda) =
+        test_setup.fetch_distribution(next_dz_epoch).await;
+    assert_eq!(
+        distribution.collected_2z_converted_from_sol,
+        expected_distribution_2z_amount
+    );
+    assert_eq!(distribution.treasury_2z_amount, expected_treasury_2z_amount);
+    assert_eq!(
+        distribution_2z_token_pda.amount,
+        expected_distribution_2z_amount
+    );
+
+    let reserve_2z_key = find_2z_token_pda_address(&ProgramConfig::find_address().0).0;
+    let reserve_2z_token_account = test_setup
+        .fetch_token_account(&reserve_2z_key)
+        .await
+        .unwrap();
+    assert_eq!(reserve_2z_token_account.amount, expected_treasury_2z_amount);
+
+    let (_, journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(journal.swap_2z_destination_balance, 0);
+    assert_eq!(
+        journal.total_treasury_2z_amount,
+        expected_treasury_2z_amount
+    );
+}
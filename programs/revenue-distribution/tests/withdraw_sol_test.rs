@@ -2,15 +2,23 @@ mod common;
 
 //
 
+use doublezero_program_tools::{zero_copy, PrecomputedDiscriminator};
 use doublezero_revenue_distribution::{
     instruction::{ProgramConfiguration, ProgramFlagConfiguration},
-    state::SolanaValidatorDeposit,
+    state::{SolanaValidatorDeposit, SwapSession},
     types::{DoubleZeroEpoch, SolanaValidatorDebt},
-    DOUBLEZERO_MINT_KEY,
+    DOUBLEZERO_MINT_KEY, ID,
 };
 use solana_program_test::tokio;
 use solana_pubkey::Pubkey;
-use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    clock::Clock,
+    instruction::InstructionError,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
 use svm_hash::merkle::{merkle_root_from_indexed_pod_leaves, MerkleProof};
 
 //
@@ -127,7 +135,7 @@ async fn setup_for_withdraw_sol() -> WithdrawSolSetup {
         )
         .await
         .unwrap()
-        .pay_solana_validator_debt(dz_epoch, &debt, proof)
+        .pay_solana_validator_debt(dz_epoch, &debt, proof, false)
         .await
         .unwrap();
 
@@ -204,6 +212,27 @@ async fn test_withdraw_sol() {
         .amount;
     assert_eq!(swap_destination_balance, amount_2z_in);
 
+    // The swap session is closed and its rent is refunded to the transfer
+    // authority.
+    let swap_session_info = test_setup
+        .context
+        .banks_client
+        .get_account(SwapSession::find_address().0)
+        .await
+        .unwrap();
+    assert!(swap_session_info.is_none());
+
+    let transfer_authority_balance = test_setup
+        .context
+        .banks_client
+        .get_balance(transfer_authority_signer.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        transfer_authority_balance,
+        Rent::default().minimum_balance(zero_copy::data_end::<SwapSession>())
+    );
+
     // Second swap.
 
     test_setup
@@ -228,3 +257,144 @@ async fn test_withdraw_sol() {
         2 * amount_2z_in as u128
     );
 }
+
+//
+// Withdraw SOL — cannot withdraw without a swap session.
+//
+// This test uses the mock SOL/2Z Swap program.
+//
+
+#[tokio::test]
+async fn test_cannot_withdraw_sol_without_swap_session() {
+    let WithdrawSolSetup {
+        mut test_setup,
+        src_token_account_key,
+        transfer_authority_signer,
+        total_solana_validator_debt,
+    } = setup_for_withdraw_sol().await;
+
+    let amount_2z_in = 2_500 * u64::pow(10, 8); // 2,500 2Z.
+    let amount_sol_out = 2 * u64::pow(10, 9); // 2 SOL.
+
+    let sol_destination_key = Pubkey::new_unique();
+
+    test_setup
+        .transfer_2z(&src_token_account_key, amount_2z_in)
+        .await
+        .unwrap();
+
+    let buy_sol_ix = mock_swap_sol_2z::instruction::buy_sol_without_swap_session(
+        &test_setup.sol_2z_swap_fills_registry_key,
+        &src_token_account_key,
+        &transfer_authority_signer.pubkey(),
+        &sol_destination_key,
+        amount_2z_in,
+        amount_sol_out,
+    );
+
+    // The swap session was never opened, so the account passed to withdraw
+    // SOL is not owned by the Revenue Distribution program.
+    let (tx_err, _) = test_setup
+        .unwrap_simulation_error(&[buy_sol_ix], &[&transfer_authority_signer])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountOwner)
+    );
+
+    let (_, journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(journal.total_sol_balance, total_solana_validator_debt);
+    assert_eq!(journal.swap_2z_destination_balance, 0);
+}
+
+//
+// Withdraw SOL — stale swap session is reopened.
+//
+// This test uses the mock SOL/2Z Swap program.
+//
+
+#[tokio::test]
+async fn test_withdraw_sol_reopens_stale_swap_session() {
+    let WithdrawSolSetup {
+        mut test_setup,
+        src_token_account_key,
+        transfer_authority_signer,
+        total_solana_validator_debt,
+    } = setup_for_withdraw_sol().await;
+
+    let amount_2z_in = 2_500 * u64::pow(10, 8); // 2,500 2Z.
+    let amount_sol_out = 2 * u64::pow(10, 9); // 2 SOL.
+
+    let sol_destination_key = Pubkey::new_unique();
+
+    test_setup
+        .transfer_2z(&src_token_account_key, amount_2z_in)
+        .await
+        .unwrap();
+
+    // Simulate a swap session that was opened but never consumed, then move
+    // to the next slot.
+
+    let mut clock = test_setup.get_clock().await;
+
+    let stale_rent_beneficiary_key = Pubkey::new_unique();
+    let swap_session_rent = Rent::default().minimum_balance(zero_copy::data_end::<SwapSession>());
+
+    let mut swap_session = SwapSession::default();
+    swap_session.rent_beneficiary_key = stale_rent_beneficiary_key;
+    swap_session.slot = clock.slot;
+    swap_session.bump_seed = SwapSession::find_address().1;
+
+    let mut swap_session_data = SwapSession::discriminator_slice().to_vec();
+    swap_session_data.extend_from_slice(bytemuck::bytes_of(&swap_session));
+
+    test_setup.context.set_account(
+        &SwapSession::find_address().0,
+        &AccountSharedData::from(Account {
+            lamports: swap_session_rent,
+            data: swap_session_data,
+            owner: ID,
+            ..Default::default()
+        }),
+    );
+
+    clock.slot += 1;
+    test_setup.context.set_sysvar::<Clock>(&clock);
+
+    test_setup
+        .mock_buy_sol(
+            &src_token_account_key,
+            &transfer_authority_signer,
+            &sol_destination_key,
+            amount_2z_in,
+            amount_sol_out,
+        )
+        .await
+        .unwrap();
+
+    let (_, journal, _) = test_setup.fetch_journal().await;
+    assert_eq!(
+        journal.total_sol_balance,
+        total_solana_validator_debt - amount_sol_out
+    );
+    assert_eq!(journal.swap_2z_destination_balance, amount_2z_in);
+
+    // The reopened swap session is consumed and its rent is refunded to the
+    // stale session's rent beneficiary, which funded it.
+    let swap_session_info = test_setup
+        .context
+        .banks_client
+        .get_account(SwapSession::find_address().0)
+        .await
+        .unwrap();
+    assert!(swap_session_info.is_none());
+
+    let stale_rent_beneficiary_balance = test_setup
+        .context
+        .banks_client
+        .get_balance(stale_rent_beneficiary_key)
+        .await
+        .unwrap();
+    assert_eq!(stale_rent_beneficiary_balance, swap_session_rent);
+}
@@ -50,8 +50,10 @@ async fn setup_for_withdraw_solana_validator_deposit() -> WithdrawSolanaValidato
         .await
         .unwrap();
 
-    let deposit_rent_exemption =
-        (128 + zero_copy::data_end::<SolanaValidatorDeposit>() as u64) * 6_960;
+    let deposit_rent_exemption = (128
+        + (zero_copy::data_end::<SolanaValidatorDeposit>()
+            + SolanaValidatorDeposit::PAYMENT_HISTORY_LEN) as u64)
+        * 6_960;
 
     WithdrawSolanaValidatorDepositSetup {
         test_setup,
@@ -27,6 +27,7 @@ use svm_hash::merkle::{merkle_root_from_indexed_pod_leaves, MerkleProof};
 
 struct WriteOffSolanaValidatorDebtSetup {
     test_setup: common::ProgramTestWithOwner,
+    admin_signer: Keypair,
     debt_accountant_signer: Keypair,
     dz_epoch: DoubleZeroEpoch,
     next_dz_epoch: DoubleZeroEpoch,
@@ -124,6 +125,7 @@ async fn setup_for_write_off_solana_validator_debt() -> WriteOffSolanaValidatorD
 
     WriteOffSolanaValidatorDebtSetup {
         test_setup,
+        admin_signer: configured.admin_signer,
         debt_accountant_signer: configured.debt_accountant_signer,
         dz_epoch,
         next_dz_epoch,
@@ -149,6 +151,7 @@ async fn test_write_off_solana_validator_debt() {
         total_solana_validators,
         total_solana_validator_debt,
         solana_validator_debt_merkle_root,
+        ..
     } = setup_for_write_off_solana_validator_debt().await;
 
     let initial_cbr = 100_000_000;
@@ -293,7 +296,7 @@ async fn test_write_off_solana_validator_debt() {
     );
 
     test_setup
-        .pay_solana_validator_debt(dz_epoch, &paid_debt, paid_proof)
+        .pay_solana_validator_debt(dz_epoch, &paid_debt, paid_proof, false)
         .await
         .unwrap();
 
@@ -490,6 +493,100 @@ async fn test_write_off_solana_validator_debt() {
     }
 }
 
+//
+// Write off Solana validator debt — dust debt.
+//
+
+#[tokio::test]
+async fn test_write_off_solana_validator_dust_debt() {
+    let WriteOffSolanaValidatorDebtSetup {
+        mut test_setup,
+        admin_signer,
+        debt_accountant_signer,
+        dz_epoch,
+        debt_data,
+        ..
+    } = setup_for_write_off_solana_validator_debt().await;
+
+    let dust_debt_index = 0;
+    let dust_debt = debt_data[dust_debt_index];
+    let proof = MerkleProof::from_indexed_pod_leaves(
+        &debt_data,
+        dust_debt_index.try_into().unwrap(),
+        Some(SolanaValidatorDebt::LEAF_PREFIX),
+    )
+    .unwrap();
+
+    // Fund the deposit so it can cover the debt.
+    test_setup
+        .transfer_lamports(
+            &SolanaValidatorDeposit::find_address(&dust_debt.node_id).0,
+            dust_debt.amount,
+        )
+        .await
+        .unwrap()
+        .finalize_distribution_debt(dz_epoch, &debt_accountant_signer)
+        .await
+        .unwrap()
+        .enable_solana_validator_debt_write_off(dz_epoch)
+        .await
+        .unwrap();
+
+    // Cannot write off debt for a deposit that has enough lamports when the
+    // debt is not dust.
+    let (tx_err, program_logs) = simulate_write_off_revert(
+        &mut test_setup,
+        &debt_accountant_signer,
+        dz_epoch,
+        &dust_debt,
+        dz_epoch,
+        proof.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tx_err,
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    assert_eq!(
+        program_logs.get(4).unwrap(),
+        "Program log: Lamports balance in deposit account is enough to cover debt amount"
+    );
+
+    let minimum_solana_validator_debt = dust_debt.amount + 1;
+
+    test_setup
+        .configure_program(
+            &admin_signer,
+            [ProgramConfiguration::MinimumSolanaValidatorDebt(
+                minimum_solana_validator_debt,
+            )],
+        )
+        .await
+        .unwrap()
+        .write_off_solana_validator_debt(
+            dz_epoch,
+            dz_epoch,
+            &debt_accountant_signer,
+            &dust_debt,
+            proof,
+        )
+        .await
+        .unwrap();
+
+    let (_, distribution, _, _, _) = test_setup.fetch_distribution(dz_epoch).await;
+    assert_eq!(distribution.uncollectible_sol_debt, dust_debt.amount);
+    assert_eq!(distribution.solana_validator_write_off_count, 1);
+
+    let (_, solana_validator_deposit) = test_setup
+        .fetch_solana_validator_deposit(&dust_debt.node_id)
+        .await;
+    assert_eq!(
+        solana_validator_deposit.written_off_sol_debt,
+        dust_debt.amount
+    );
+}
+
 //
 // Helpers.
 //
@@ -0,0 +1,164 @@
+// @generated by `cargo xtask gen-state-layout`. Do not edit.
+//
+// Byte ranges of account fields in account data, which starts with the
+// 8-byte discriminator.
+
+pub mod doublezero_passport {
+    pub mod program_config {
+        pub const DATA_LEN: usize = 352;
+        pub const FLAGS: core::ops::Range<usize> = 8..16;
+        pub const ADMIN_KEY: core::ops::Range<usize> = 16..48;
+        pub const SENTINEL_KEY: core::ops::Range<usize> = 48..80;
+        pub const REQUEST_DEPOSIT_LAMPORTS: core::ops::Range<usize> = 80..88;
+        pub const REQUEST_FEE_LAMPORTS: core::ops::Range<usize> = 88..96;
+        pub const SOLANA_VALIDATOR_BACKUP_IDS_LIMIT: core::ops::Range<usize> = 96..98;
+        pub const DENY_REFUND_BASIS_POINTS: core::ops::Range<usize> = 98..100;
+        pub const ACCESS_REQUEST_EXPIRY_SECONDS: core::ops::Range<usize> = 100..104;
+        pub const ONBOARDING_LAMPORTS: core::ops::Range<usize> = 104..112;
+        pub const TOP_UP_THRESHOLD_LAMPORTS: core::ops::Range<usize> = 112..120;
+        pub const SENTINEL_QUORUM: core::ops::Range<usize> = 120..121;
+        pub const QUORUM_SENTINELS_VERSION: core::ops::Range<usize> = 124..128;
+        pub const QUORUM_SENTINEL_KEYS: core::ops::Range<usize> = 128..288;
+    }
+    pub mod access_request {
+        pub const DATA_LEN: usize = 4176;
+        pub const SERVICE_KEY: core::ops::Range<usize> = 8..40;
+        pub const RENT_BENEFICIARY_KEY: core::ops::Range<usize> = 40..72;
+        pub const REQUEST_FEE_LAMPORTS: core::ops::Range<usize> = 72..80;
+        pub const ENCODED_ACCESS_MODE: core::ops::Range<usize> = 80..4176;
+    }
+}
+
+pub mod doublezero_revenue_distribution {
+    pub mod program_config {
+        pub const DATA_LEN: usize = 848;
+        pub const FLAGS: core::ops::Range<usize> = 8..16;
+        pub const NEXT_COMPLETED_DZ_EPOCH: core::ops::Range<usize> = 16..24;
+        pub const BUMP_SEED: core::ops::Range<usize> = 24..25;
+        pub const RESERVE_2Z_BUMP_SEED: core::ops::Range<usize> = 25..26;
+        pub const SWAP_AUTHORITY_BUMP_SEED: core::ops::Range<usize> = 26..27;
+        pub const SWAP_DESTINATION_2Z_BUMP_SEED: core::ops::Range<usize> = 27..28;
+        pub const WITHDRAW_SOL_AUTHORITY_BUMP_SEED: core::ops::Range<usize> = 28..29;
+        pub const VERSION: core::ops::Range<usize> = 29..30;
+        pub const ADMIN_KEY: core::ops::Range<usize> = 32..64;
+        pub const DEBT_ACCOUNTANT_KEY: core::ops::Range<usize> = 64..96;
+        pub const REWARDS_ACCOUNTANT_KEY: core::ops::Range<usize> = 96..128;
+        pub const CONTRIBUTOR_MANAGER_KEY: core::ops::Range<usize> = 128..160;
+        pub const SOL_2Z_SWAP_PROGRAM_ID: core::ops::Range<usize> = 192..224;
+        pub const DISTRIBUTION_PARAMETERS: core::ops::Range<usize> = 224..552;
+        pub const RELAY_PARAMETERS: core::ops::Range<usize> = 552..592;
+        pub const LAST_INITIALIZED_DISTRIBUTION_TIMESTAMP: core::ops::Range<usize> = 592..596;
+        pub const TREASURY_2Z_SHARE: core::ops::Range<usize> = 596..598;
+        pub const DEBT_WRITE_OFF_FEATURE_ACTIVATION_EPOCH: core::ops::Range<usize> = 600..608;
+        pub const ALLOWED_RELAYER_KEYS: core::ops::Range<usize> = 608..736;
+        pub const SECONDARY_DEBT_ACCOUNTANT: core::ops::Range<usize> = 736..776;
+        pub const SECONDARY_REWARDS_ACCOUNTANT: core::ops::Range<usize> = 776..816;
+        pub const EMERGENCY_RECOVERY_KEY: core::ops::Range<usize> = 816..848;
+    }
+    pub mod journal {
+        pub const DATA_LEN: usize = 80;
+        pub const BUMP_SEED: core::ops::Range<usize> = 8..9;
+        pub const TOKEN_2Z_PDA_BUMP_SEED: core::ops::Range<usize> = 9..10;
+        pub const INTEGRATIONS_COUNT: core::ops::Range<usize> = 10..12;
+        pub const VERSION: core::ops::Range<usize> = 12..13;
+        pub const TOTAL_SOL_BALANCE: core::ops::Range<usize> = 16..24;
+        pub const TOTAL_2Z_BALANCE: core::ops::Range<usize> = 24..32;
+        pub const SWAP_2Z_DESTINATION_BALANCE: core::ops::Range<usize> = 32..40;
+        pub const SWAPPED_SOL_AMOUNT: core::ops::Range<usize> = 40..48;
+        pub const NEXT_DZ_EPOCH_TO_SWEEP_TOKENS: core::ops::Range<usize> = 48..56;
+        pub const LIFETIME_SWAPPED_2Z_AMOUNT: core::ops::Range<usize> = 56..72;
+        pub const TOTAL_TREASURY_2Z_AMOUNT: core::ops::Range<usize> = 72..80;
+    }
+    pub mod distribution {
+        pub const DATA_LEN: usize = 456;
+        pub const DZ_EPOCH: core::ops::Range<usize> = 8..16;
+        pub const FLAGS: core::ops::Range<usize> = 16..24;
+        pub const COMMUNITY_BURN_RATE: core::ops::Range<usize> = 24..28;
+        pub const BUMP_SEED: core::ops::Range<usize> = 28..29;
+        pub const TOKEN_2Z_PDA_BUMP_SEED: core::ops::Range<usize> = 29..30;
+        pub const VERSION: core::ops::Range<usize> = 30..31;
+        pub const SOLANA_VALIDATOR_FEE_PARAMETERS: core::ops::Range<usize> = 32..72;
+        pub const SOLANA_VALIDATOR_DEBT_MERKLE_ROOT: core::ops::Range<usize> = 72..104;
+        pub const TOTAL_SOLANA_VALIDATORS: core::ops::Range<usize> = 104..108;
+        pub const SOLANA_VALIDATOR_PAYMENTS_COUNT: core::ops::Range<usize> = 108..112;
+        pub const TOTAL_SOLANA_VALIDATOR_DEBT: core::ops::Range<usize> = 112..120;
+        pub const COLLECTED_SOLANA_VALIDATOR_PAYMENTS: core::ops::Range<usize> = 120..128;
+        pub const REWARDS_MERKLE_ROOT: core::ops::Range<usize> = 128..160;
+        pub const TOTAL_CONTRIBUTORS: core::ops::Range<usize> = 160..164;
+        pub const DISTRIBUTED_REWARDS_COUNT: core::ops::Range<usize> = 164..168;
+        pub const COLLECTED_PREPAID_2Z_PAYMENTS: core::ops::Range<usize> = 168..176;
+        pub const COLLECTED_2Z_CONVERTED_FROM_SOL: core::ops::Range<usize> = 176..184;
+        pub const UNCOLLECTIBLE_SOL_DEBT: core::ops::Range<usize> = 184..192;
+        pub const PROCESSED_SOLANA_VALIDATOR_DEBT_START_INDEX: core::ops::Range<usize> = 192..196;
+        pub const PROCESSED_SOLANA_VALIDATOR_DEBT_END_INDEX: core::ops::Range<usize> = 196..200;
+        pub const PROCESSED_REWARDS_START_INDEX: core::ops::Range<usize> = 200..204;
+        pub const PROCESSED_REWARDS_END_INDEX: core::ops::Range<usize> = 204..208;
+        pub const DISTRIBUTE_REWARDS_RELAY_LAMPORTS: core::ops::Range<usize> = 208..212;
+        pub const CALCULATION_ALLOWED_TIMESTAMP: core::ops::Range<usize> = 212..216;
+        pub const DISTRIBUTED_2Z_AMOUNT: core::ops::Range<usize> = 216..224;
+        pub const BURNED_2Z_AMOUNT: core::ops::Range<usize> = 224..232;
+        pub const PROCESSED_SOLANA_VALIDATOR_DEBT_WRITE_OFF_START_INDEX: core::ops::Range<usize> = 232..236;
+        pub const PROCESSED_SOLANA_VALIDATOR_DEBT_WRITE_OFF_END_INDEX: core::ops::Range<usize> = 236..240;
+        pub const SOLANA_VALIDATOR_WRITE_OFF_COUNT: core::ops::Range<usize> = 240..244;
+        pub const ECONOMIC_BURN_RATE: core::ops::Range<usize> = 244..248;
+        pub const INTEGRATIONS_COUNT_SNAPSHOT: core::ops::Range<usize> = 248..250;
+        pub const INTEGRATIONS_COLLECTED_COUNT: core::ops::Range<usize> = 250..252;
+        pub const COLLECTED_INTEGRATIONS_BITMAP: core::ops::Range<usize> = 256..320;
+        pub const COLLECTED_2Z_FROM_INTEGRATIONS: core::ops::Range<usize> = 320..328;
+        pub const BURNED_ROUNDING_DUST_2Z_AMOUNT: core::ops::Range<usize> = 328..336;
+        pub const RECONCILED_RESIDUAL_2Z_AMOUNT: core::ops::Range<usize> = 336..344;
+        pub const REWARDS_ROOT_REVISION: core::ops::Range<usize> = 344..348;
+        pub const PARTIALLY_DISTRIBUTED_REWARDS_START_INDEX: core::ops::Range<usize> = 348..352;
+        pub const PARTIALLY_DISTRIBUTED_REWARDS_END_INDEX: core::ops::Range<usize> = 352..356;
+        pub const REWARDS_ROOT_CORRECTION_MEMO: core::ops::Range<usize> = 360..392;
+        pub const TREASURY_2Z_AMOUNT: core::ops::Range<usize> = 392..400;
+    }
+    pub mod contributor_rewards {
+        pub const DATA_LEN: usize = 608;
+        pub const REWARDS_MANAGER_KEY: core::ops::Range<usize> = 8..40;
+        pub const SERVICE_KEY: core::ops::Range<usize> = 40..72;
+        pub const FLAGS: core::ops::Range<usize> = 72..80;
+        pub const RECIPIENT_SHARES: core::ops::Range<usize> = 80..352;
+        pub const LAST_DISTRIBUTED_DZ_EPOCH: core::ops::Range<usize> = 352..360;
+        pub const DISTRIBUTED_DZ_EPOCHS_COUNT: core::ops::Range<usize> = 360..368;
+        pub const TOTAL_DISTRIBUTED_2Z_AMOUNT: core::ops::Range<usize> = 368..376;
+        pub const TOTAL_BURNED_2Z_AMOUNT: core::ops::Range<usize> = 376..384;
+        pub const PARTIALLY_DISTRIBUTED_DZ_EPOCH: core::ops::Range<usize> = 384..392;
+        pub const PARTIALLY_DISTRIBUTED_2Z_AMOUNT: core::ops::Range<usize> = 392..400;
+    }
+    pub mod solana_validator_deposit {
+        pub const DATA_LEN: usize = 104;
+        pub const NODE_ID: core::ops::Range<usize> = 8..40;
+        pub const WRITTEN_OFF_SOL_DEBT: core::ops::Range<usize> = 40..48;
+        pub const DEPOSITED_SOL_AMOUNT: core::ops::Range<usize> = 48..56;
+        pub const RECORDED_SOL_BALANCE: core::ops::Range<usize> = 56..64;
+        pub const FLAGS: core::ops::Range<usize> = 64..65;
+    }
+    pub mod payment_receipt {
+        pub const DATA_LEN: usize = 104;
+        pub const NODE_ID: core::ops::Range<usize> = 8..40;
+        pub const DZ_EPOCH: core::ops::Range<usize> = 40..48;
+        pub const AMOUNT: core::ops::Range<usize> = 48..56;
+        pub const SLOT: core::ops::Range<usize> = 56..64;
+        pub const BUMP_SEED: core::ops::Range<usize> = 64..65;
+    }
+    pub mod swap_session {
+        pub const DATA_LEN: usize = 96;
+        pub const RENT_BENEFICIARY_KEY: core::ops::Range<usize> = 8..40;
+        pub const SWAP_DESTINATION_2Z_AMOUNT: core::ops::Range<usize> = 40..48;
+        pub const SLOT: core::ops::Range<usize> = 48..56;
+        pub const BUMP_SEED: core::ops::Range<usize> = 56..57;
+    }
+    pub mod rewards_integration {
+        pub const DATA_LEN: usize = 184;
+        pub const PROGRAM_ID: core::ops::Range<usize> = 8..40;
+        pub const BUMP_SEED: core::ops::Range<usize> = 40..41;
+        pub const REGISTRATION_INDEX: core::ops::Range<usize> = 42..44;
+    }
+    pub mod fee_parameter_history {
+        pub const DATA_LEN: usize = 1872;
+        pub const BUMP_SEED: core::ops::Range<usize> = 8..9;
+        pub const TOTAL_ENTRIES_COUNT: core::ops::Range<usize> = 12..16;
+        pub const ENTRIES: core::ops::Range<usize> = 16..1808;
+    }
+}
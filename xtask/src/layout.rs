@@ -0,0 +1,541 @@
+use std::fmt::Write;
+
+use bytemuck::Pod;
+use doublezero_passport::instruction::PassportInstructionData;
+use doublezero_program_tools::{
+    zero_copy, Discriminator, PrecomputedDiscriminator, DISCRIMINATOR_LEN,
+};
+use doublezero_revenue_distribution::instruction::RevenueDistributionInstructionData;
+use solana_pubkey::Pubkey;
+
+/// Program config and journal accounts are allocated with 10kb of data when
+/// they are initialized.
+const PREALLOCATED_ACCOUNT_DATA_LEN: usize = 10_240;
+
+pub struct InstructionLayout {
+    pub name: &'static str,
+    pub discriminator: Discriminator<8>,
+}
+
+/// Location of a public account field in account data, which starts with the
+/// discriminator. Private padding and storage gaps are not listed.
+pub struct FieldLayout {
+    pub name: &'static str,
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl FieldLayout {
+    /// Field length is inferred from the field accessor.
+    fn new<T, F>(name: &'static str, offset_in_struct: usize, _accessor: fn(&T) -> &F) -> Self {
+        Self {
+            name,
+            offset: DISCRIMINATOR_LEN + offset_in_struct,
+            len: size_of::<F>(),
+        }
+    }
+}
+
+/// Build instruction layouts for the listed instruction variants. The listed
+/// variants are matched exhaustively, so a new instruction fails to compile
+/// until it is listed here.
+macro_rules! instruction_layouts {
+    ($ix:ident { $($variant:ident => $discriminator:ident),* $(,)? }) => {{
+        let _ = |ix: &$ix| match ix {
+            $($ix::$variant { .. } => (),)*
+        };
+
+        vec![$(
+            InstructionLayout {
+                name: stringify!($variant),
+                discriminator: $ix::$discriminator,
+            }
+        ),*]
+    }};
+}
+
+/// Build field layouts for the listed fields of a Pod account, in declaration
+/// order.
+macro_rules! field_layouts {
+    ($account:ty { $($field:ident),* $(,)? }) => {
+        vec![$(
+            FieldLayout::new(
+                stringify!($field),
+                std::mem::offset_of!($account, $field),
+                |account: &$account| &account.$field,
+            )
+        ),*]
+    };
+}
+
+pub struct AccountLayout {
+    pub name: &'static str,
+    pub discriminator: Discriminator<8>,
+    pub data_len: usize,
+    pub max_data_len: Option<usize>,
+    pub fields: Vec<FieldLayout>,
+}
+
+impl AccountLayout {
+    fn new<T: Pod + PrecomputedDiscriminator>(
+        name: &'static str,
+        fields: Vec<FieldLayout>,
+    ) -> Self {
+        Self {
+            name,
+            discriminator: T::DISCRIMINATOR,
+            data_len: zero_copy::data_end::<T>(),
+            max_data_len: None,
+            fields,
+        }
+    }
+
+    fn new_preallocated<T: Pod + PrecomputedDiscriminator>(
+        name: &'static str,
+        fields: Vec<FieldLayout>,
+    ) -> Self {
+        Self {
+            max_data_len: Some(PREALLOCATED_ACCOUNT_DATA_LEN),
+            ..Self::new::<T>(name, fields)
+        }
+    }
+}
+
+pub struct ProgramLayout {
+    pub name: &'static str,
+    pub program_id: Pubkey,
+    pub instructions: Vec<InstructionLayout>,
+    pub accounts: Vec<AccountLayout>,
+}
+
+impl ProgramLayout {
+    pub fn all() -> Vec<Self> {
+        vec![passport_layout(), revenue_distribution_layout()]
+    }
+
+    /// Returns a description of each inconsistency found in this layout.
+    pub fn verify(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            if let Some(other) = self.instructions[..i]
+                .iter()
+                .find(|other| other.discriminator == instruction.discriminator)
+            {
+                errors.push(format!(
+                    "{}: instructions {} and {} share discriminator {}",
+                    self.name, other.name, instruction.name, instruction.discriminator
+                ));
+            }
+        }
+
+        for (i, account) in self.accounts.iter().enumerate() {
+            if let Some(other) = self.accounts[..i]
+                .iter()
+                .find(|other| other.discriminator == account.discriminator)
+            {
+                errors.push(format!(
+                    "{}: accounts {} and {} share discriminator {}",
+                    self.name, other.name, account.name, account.discriminator
+                ));
+            }
+
+            if account.data_len % 8 != 0 {
+                errors.push(format!(
+                    "{}: account {} data length {} is not 8-byte aligned",
+                    self.name, account.name, account.data_len
+                ));
+            }
+
+            if let Some(max_data_len) = account.max_data_len {
+                if account.data_len > max_data_len {
+                    errors.push(format!(
+                        "{}: account {} data length {} exceeds {} allocated bytes",
+                        self.name, account.name, account.data_len, max_data_len
+                    ));
+                }
+            }
+
+            // Fields are listed in declaration order, so each field must end
+            // before the next one starts.
+            let mut field_start = DISCRIMINATOR_LEN;
+
+            for field in account.fields.iter() {
+                if field.offset < field_start {
+                    errors.push(format!(
+                        "{}: account {} field {} at offset {} overlaps the previous field",
+                        self.name, account.name, field.name, field.offset
+                    ));
+                }
+
+                field_start = field.offset + field.len;
+
+                if field_start > account.data_len {
+                    errors.push(format!(
+                        "{}: account {} field {} ends past data length {}",
+                        self.name, account.name, field.name, account.data_len
+                    ));
+                }
+            }
+        }
+
+        errors
+    }
+
+    pub fn to_json(&self) -> String {
+        let instructions = self
+            .instructions
+            .iter()
+            .map(|instruction| {
+                format!(
+                    "    {{ \"name\": \"{}\", \"discriminator\": \"{}\" }}",
+                    instruction.name, instruction.discriminator
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                let fields = account
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "        {{ \"name\": \"{}\", \"offset\": {}, \"len\": {} }}",
+                            field.name, field.offset, field.len
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",\n");
+
+                format!(
+                    "    {{\n      \"name\": \"{}\",\n      \"discriminator\": \"{}\",\n      \"dataLen\": {},\n      \"fields\": [\n{}\n      ]\n    }}",
+                    account.name, account.discriminator, account.data_len, fields
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        format!(
+            "{{\n  \"name\": \"{}\",\n  \"programId\": \"{}\",\n  \"instructions\": [\n{}\n  ],\n  \"accounts\": [\n{}\n  ]\n}}\n",
+            self.name, self.program_id, instructions, accounts
+        )
+    }
+
+    /// Render the account layouts as a Rust module of data ranges, which can
+    /// be used to read account fields directly from account data.
+    pub fn to_rust_module(&self) -> String {
+        let mut module = format!("pub mod {} {{\n", self.name);
+
+        for account in self.accounts.iter() {
+            writeln!(module, "    pub mod {} {{", to_snake_case(account.name)).unwrap();
+            writeln!(
+                module,
+                "        pub const DATA_LEN: usize = {};",
+                account.data_len
+            )
+            .unwrap();
+
+            for field in account.fields.iter() {
+                writeln!(
+                    module,
+                    "        pub const {}: core::ops::Range<usize> = {}..{};",
+                    field.name.to_uppercase(),
+                    field.offset,
+                    field.offset + field.len
+                )
+                .unwrap();
+            }
+
+            module.push_str("    }\n");
+        }
+
+        module.push_str("}\n");
+        module
+    }
+}
+
+/// Contents of programs/state_layout.rs, which is generated by
+/// `cargo xtask gen-state-layout`.
+pub fn state_layout_module(program_layouts: &[ProgramLayout]) -> String {
+    let modules = program_layouts
+        .iter()
+        .map(ProgramLayout::to_rust_module)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "// @generated by `cargo xtask gen-state-layout`. Do not edit.\n//\n// Byte ranges of account fields in account data, which starts with the\n// {DISCRIMINATOR_LEN}-byte discriminator.\n\n{modules}"
+    )
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::with_capacity(name.len() + 4);
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i != 0 {
+            snake_case.push('_');
+        }
+        snake_case.push(c.to_ascii_lowercase());
+    }
+
+    snake_case
+}
+
+fn passport_layout() -> ProgramLayout {
+    use doublezero_passport::state::{AccessRequest, ProgramConfig};
+
+    type Ix = PassportInstructionData;
+
+    ProgramLayout {
+        name: "doublezero_passport",
+        program_id: doublezero_passport::ID,
+        instructions: instruction_layouts!(Ix {
+            InitializeProgram => INITIALIZE_PROGRAM,
+            SetAdmin => SET_ADMIN,
+            ConfigureProgram => CONFIGURE_PROGRAM,
+            RequestAccess => REQUEST_ACCESS,
+            GrantAccess => GRANT_ACCESS,
+            DenyAccess => DENY_ACCESS,
+            ExpireAccessRequest => EXPIRE_ACCESS_REQUEST,
+            UpdateValidatorId => UPDATE_VALIDATOR_ID,
+            ApproveAccess => APPROVE_ACCESS,
+        }),
+        accounts: vec![
+            AccountLayout::new::<ProgramConfig>(
+                "ProgramConfig",
+                field_layouts!(ProgramConfig {
+                    flags,
+                    admin_key,
+                    sentinel_key,
+                    request_deposit_lamports,
+                    request_fee_lamports,
+                    solana_validator_backup_ids_limit,
+                    deny_refund_basis_points,
+                    access_request_expiry_seconds,
+                    onboarding_lamports,
+                    top_up_threshold_lamports,
+                    sentinel_quorum,
+                    quorum_sentinels_version,
+                    quorum_sentinel_keys,
+                }),
+            ),
+            AccountLayout::new::<AccessRequest>(
+                "AccessRequest",
+                field_layouts!(AccessRequest {
+                    service_key,
+                    rent_beneficiary_key,
+                    request_fee_lamports,
+                    encoded_access_mode,
+                }),
+            ),
+        ],
+    }
+}
+
+fn revenue_distribution_layout() -> ProgramLayout {
+    use doublezero_revenue_distribution::state::{
+        ContributorRewards, Distribution, FeeParameterHistory, Journal, PaymentReceipt,
+        ProgramConfig, RewardsIntegration, SolanaValidatorDeposit, SwapSession,
+    };
+
+    type Ix = RevenueDistributionInstructionData;
+
+    ProgramLayout {
+        name: "doublezero_revenue_distribution",
+        program_id: doublezero_revenue_distribution::ID,
+        instructions: instruction_layouts!(Ix {
+            InitializeProgram => INITIALIZE_PROGRAM,
+            MigrateProgramAccounts => MIGRATE_PROGRAM_ACCOUNTS_V1,
+            SetAdmin => SET_ADMIN,
+            ConfigureProgram => CONFIGURE_PROGRAM,
+            InitializeJournal => INITIALIZE_JOURNAL,
+            InitializeDistribution => INITIALIZE_DISTRIBUTION,
+            ConfigureDistributionDebt => CONFIGURE_DISTRIBUTION_DEBT,
+            FinalizeDistributionDebt => FINALIZE_DISTRIBUTION_DEBT,
+            ConfigureDistributionRewards => CONFIGURE_DISTRIBUTION_REWARDS,
+            FinalizeDistributionRewards => FINALIZE_DISTRIBUTION_REWARDS,
+            DistributeRewards => DISTRIBUTE_REWARDS_V1,
+            InitializeContributorRewards => INITIALIZE_CONTRIBUTOR_REWARDS,
+            SetRewardsManager => SET_REWARDS_MANAGER,
+            ConfigureContributorRewards => CONFIGURE_CONTRIBUTOR_REWARDS,
+            VerifyDistributionMerkleRoot => VERIFY_DISTRIBUTION_MERKLE_ROOT,
+            InitializeSolanaValidatorDeposit => INITIALIZE_SOLANA_VALIDATOR_DEPOSIT,
+            PaySolanaValidatorDebt => PAY_SOLANA_VALIDATOR_DEBT_V1,
+            EnableSolanaValidatorDebtWriteOff => ENABLE_SOLANA_VALIDATOR_DEBT_WRITE_OFF,
+            WriteOffSolanaValidatorDebt => WRITE_OFF_SOLANA_VALIDATOR_DEBT,
+            InitializeSwapDestination => INITIALIZE_SWAP_DESTINATION,
+            SweepDistributionTokens => SWEEP_DISTRIBUTION_TOKENS_V1,
+            WithdrawSol => WITHDRAW_SOL,
+            SetDistributionEconomicBurnRate => SET_DISTRIBUTION_ECONOMIC_BURN_RATE,
+            WithdrawSolanaValidatorDeposit => WITHDRAW_SOLANA_VALIDATOR_DEPOSIT,
+            InitializeRewardsIntegration => INITIALIZE_REWARDS_INTEGRATION,
+            CollectIntegrationRewards => COLLECT_INTEGRATION_REWARDS,
+            InitializeFeeParameterHistory => INITIALIZE_FEE_PARAMETER_HISTORY,
+            ReconcileDistribution => RECONCILE_DISTRIBUTION,
+            ReplaceDistributionRewardsRoot => REPLACE_DISTRIBUTION_REWARDS_ROOT,
+            SetRecipientSharesLocked => SET_RECIPIENT_SHARES_LOCKED,
+            AssertJournalInvariants => ASSERT_JOURNAL_INVARIANTS,
+            DepositSolForValidator => DEPOSIT_SOL_FOR_VALIDATOR,
+            Query => QUERY,
+            SkipDistributionSweep => SKIP_DISTRIBUTION_SWEEP,
+            EmergencyWithdraw => EMERGENCY_WITHDRAW,
+            ReconcileDepositSurplus => RECONCILE_DEPOSIT_SURPLUS,
+            OpenSwapSession => OPEN_SWAP_SESSION,
+        }),
+        accounts: vec![
+            AccountLayout::new_preallocated::<ProgramConfig>(
+                "ProgramConfig",
+                field_layouts!(ProgramConfig {
+                    flags,
+                    next_completed_dz_epoch,
+                    bump_seed,
+                    reserve_2z_bump_seed,
+                    swap_authority_bump_seed,
+                    swap_destination_2z_bump_seed,
+                    withdraw_sol_authority_bump_seed,
+                    version,
+                    admin_key,
+                    debt_accountant_key,
+                    rewards_accountant_key,
+                    contributor_manager_key,
+                    sol_2z_swap_program_id,
+                    distribution_parameters,
+                    relay_parameters,
+                    last_initialized_distribution_timestamp,
+                    treasury_2z_share,
+                    debt_write_off_feature_activation_epoch,
+                    allowed_relayer_keys,
+                    secondary_debt_accountant,
+                    secondary_rewards_accountant,
+                    emergency_recovery_key,
+                }),
+            ),
+            AccountLayout::new_preallocated::<Journal>(
+                "Journal",
+                field_layouts!(Journal {
+                    bump_seed,
+                    token_2z_pda_bump_seed,
+                    integrations_count,
+                    version,
+                    total_sol_balance,
+                    total_2z_balance,
+                    swap_2z_destination_balance,
+                    swapped_sol_amount,
+                    next_dz_epoch_to_sweep_tokens,
+                    lifetime_swapped_2z_amount,
+                    total_treasury_2z_amount,
+                }),
+            ),
+            AccountLayout::new::<Distribution>(
+                "Distribution",
+                field_layouts!(Distribution {
+                    dz_epoch,
+                    flags,
+                    community_burn_rate,
+                    bump_seed,
+                    token_2z_pda_bump_seed,
+                    version,
+                    solana_validator_fee_parameters,
+                    solana_validator_debt_merkle_root,
+                    total_solana_validators,
+                    solana_validator_payments_count,
+                    total_solana_validator_debt,
+                    collected_solana_validator_payments,
+                    rewards_merkle_root,
+                    total_contributors,
+                    distributed_rewards_count,
+                    collected_prepaid_2z_payments,
+                    collected_2z_converted_from_sol,
+                    uncollectible_sol_debt,
+                    processed_solana_validator_debt_start_index,
+                    processed_solana_validator_debt_end_index,
+                    processed_rewards_start_index,
+                    processed_rewards_end_index,
+                    distribute_rewards_relay_lamports,
+                    calculation_allowed_timestamp,
+                    distributed_2z_amount,
+                    burned_2z_amount,
+                    processed_solana_validator_debt_write_off_start_index,
+                    processed_solana_validator_debt_write_off_end_index,
+                    solana_validator_write_off_count,
+                    economic_burn_rate,
+                    integrations_count_snapshot,
+                    integrations_collected_count,
+                    collected_integrations_bitmap,
+                    collected_2z_from_integrations,
+                    burned_rounding_dust_2z_amount,
+                    reconciled_residual_2z_amount,
+                    rewards_root_revision,
+                    partially_distributed_rewards_start_index,
+                    partially_distributed_rewards_end_index,
+                    rewards_root_correction_memo,
+                    treasury_2z_amount,
+                }),
+            ),
+            AccountLayout::new::<ContributorRewards>(
+                "ContributorRewards",
+                field_layouts!(ContributorRewards {
+                    rewards_manager_key,
+                    service_key,
+                    flags,
+                    recipient_shares,
+                    last_distributed_dz_epoch,
+                    distributed_dz_epochs_count,
+                    total_distributed_2z_amount,
+                    total_burned_2z_amount,
+                    partially_distributed_dz_epoch,
+                    partially_distributed_2z_amount,
+                }),
+            ),
+            AccountLayout::new::<SolanaValidatorDeposit>(
+                "SolanaValidatorDeposit",
+                field_layouts!(SolanaValidatorDeposit {
+                    node_id,
+                    written_off_sol_debt,
+                    deposited_sol_amount,
+                    recorded_sol_balance,
+                    flags,
+                }),
+            ),
+            AccountLayout::new::<PaymentReceipt>(
+                "PaymentReceipt",
+                field_layouts!(PaymentReceipt {
+                    node_id,
+                    dz_epoch,
+                    amount,
+                    slot,
+                    bump_seed,
+                }),
+            ),
+            AccountLayout::new::<SwapSession>(
+                "SwapSession",
+                field_layouts!(SwapSession {
+                    rent_beneficiary_key,
+                    swap_destination_2z_amount,
+                    slot,
+                    bump_seed,
+                }),
+            ),
+            AccountLayout::new::<RewardsIntegration>(
+                "RewardsIntegration",
+                field_layouts!(RewardsIntegration {
+                    program_id,
+                    bump_seed,
+                    registration_index,
+                }),
+            ),
+            AccountLayout::new::<FeeParameterHistory>(
+                "FeeParameterHistory",
+                field_layouts!(FeeParameterHistory {
+                    bump_seed,
+                    total_entries_count,
+                    entries,
+                }),
+            ),
+        ],
+    }
+}
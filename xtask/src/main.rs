@@ -0,0 +1,240 @@
+mod layout;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+use layout::ProgramLayout;
+
+/// Generated account field ranges, checked by verify-layouts.
+const STATE_LAYOUT_PATH: &str = "programs/state_layout.rs";
+
+const USAGE: &str = "\
+Usage: cargo xtask <COMMAND> [OPTIONS]
+
+Commands:
+  build-programs     Build the SBF programs
+  package-artifacts  Build verifiable program artifacts with Docker
+  gen-idl            Write instruction and account layouts as JSON
+  gen-state-layout   Write account field ranges to programs/state_layout.rs
+  verify-layouts     Check instruction and account layouts for consistency
+
+Options:
+  --network <NETWORK>  mainnet-beta (default) or development
+  --out-dir <DIR>      Output directory for gen-idl (default: target/idl)
+";
+
+/// Programs deployed from this workspace. Mock programs are excluded.
+const PROGRAM_MANIFEST_PATHS: [&str; 2] = [
+    "programs/passport/Cargo.toml",
+    "programs/revenue-distribution/Cargo.toml",
+];
+
+type XtaskResult = Result<(), String>;
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let Some((command, options)) = args.split_first() else {
+        eprint!("{USAGE}");
+        process::exit(1);
+    };
+
+    let result = Options::try_parse(options).and_then(|options| match command.as_str() {
+        "build-programs" => try_build_programs(&options),
+        "package-artifacts" => try_package_artifacts(&options),
+        "gen-idl" => try_gen_idl(&options),
+        "gen-state-layout" => try_gen_state_layout(),
+        "verify-layouts" => try_verify_layouts(),
+        "help" | "--help" | "-h" => {
+            print!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(format!("Unknown command: {command}\n\n{USAGE}")),
+    });
+
+    if let Err(err) = result {
+        eprintln!("error: {err}");
+        process::exit(1);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Network {
+    MainnetBeta,
+    Development,
+}
+
+impl Network {
+    fn try_parse(value: &str) -> Result<Self, String> {
+        match value {
+            "mainnet-beta" => Ok(Self::MainnetBeta),
+            "development" => Ok(Self::Development),
+            _ => Err(format!(
+                "Network must be either \"mainnet-beta\" or \"development\". Got \"{value}\""
+            )),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::MainnetBeta => "mainnet-beta",
+            Self::Development => "development",
+        }
+    }
+
+    fn cargo_features(&self) -> &'static str {
+        match self {
+            Self::MainnetBeta => "entrypoint",
+            Self::Development => "development,entrypoint",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Options {
+    network: Network,
+    out_dir: Option<PathBuf>,
+}
+
+impl Options {
+    fn try_parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self {
+            network: Network::MainnetBeta,
+            out_dir: None,
+        };
+
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+            let mut next_value = || {
+                args_iter
+                    .next()
+                    .ok_or_else(|| format!("Missing value for {arg}"))
+            };
+
+            match arg.as_str() {
+                "--network" => options.network = Network::try_parse(next_value()?)?,
+                "--out-dir" => options.out_dir = Some(next_value()?.into()),
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+fn try_run(command: &mut Command) -> XtaskResult {
+    eprintln!("Running {command:?}");
+
+    let status = command
+        .current_dir(workspace_root())
+        .status()
+        .map_err(|err| format!("Failed to run {command:?}: {err}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{command:?} exited with {status}"))
+    }
+}
+
+fn try_build_programs(options: &Options) -> XtaskResult {
+    for manifest_path in PROGRAM_MANIFEST_PATHS {
+        try_run(Command::new("cargo").args([
+            "build-sbf",
+            "--features",
+            options.network.cargo_features(),
+            "--manifest-path",
+            manifest_path,
+        ]))?;
+    }
+
+    Ok(())
+}
+
+/// Artifacts are built in Docker so their checksums can be compared against
+/// programs/sha256sums_*.txt (see `make verify-checksums`).
+fn try_package_artifacts(options: &Options) -> XtaskResult {
+    let network = options.network.as_str();
+
+    try_run(
+        Command::new("docker")
+            .env("DOCKER_BUILDKIT", "1")
+            .args(["build", "--build-arg"])
+            .arg(format!("NETWORK={network}"))
+            .args(["--platform", "linux/amd64", "--output"])
+            .arg(format!("type=local,dest=./artifacts-{network}"))
+            .arg("."),
+    )
+}
+
+fn try_gen_idl(options: &Options) -> XtaskResult {
+    let out_dir = options
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| workspace_root().join("target").join("idl"));
+
+    fs::create_dir_all(&out_dir)
+        .map_err(|err| format!("Failed to create {}: {err}", out_dir.display()))?;
+
+    for program_layout in ProgramLayout::all() {
+        let path = out_dir.join(format!("{}.json", program_layout.name));
+
+        fs::write(&path, program_layout.to_json())
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+
+        eprintln!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn try_gen_state_layout() -> XtaskResult {
+    let path = workspace_root().join(STATE_LAYOUT_PATH);
+
+    fs::write(&path, layout::state_layout_module(&ProgramLayout::all()))
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+
+    eprintln!("Wrote {}", path.display());
+
+    Ok(())
+}
+
+fn try_verify_layouts() -> XtaskResult {
+    let program_layouts = ProgramLayout::all();
+
+    let mut errors = program_layouts
+        .iter()
+        .flat_map(ProgramLayout::verify)
+        .collect::<Vec<_>>();
+
+    // Field offsets are only guarded by account sizes in the programs, so a
+    // reordered or resized field is caught by comparing against the
+    // generated state layout.
+    let path = workspace_root().join(STATE_LAYOUT_PATH);
+    let state_layout = fs::read_to_string(&path).unwrap_or_default();
+
+    if state_layout != layout::state_layout_module(&program_layouts) {
+        errors.push(format!(
+            "{} is out of date. Run `cargo xtask gen-state-layout` and review the diff",
+            path.display()
+        ));
+    }
+
+    if errors.is_empty() {
+        eprintln!("All layouts OK");
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}